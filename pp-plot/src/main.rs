@@ -115,7 +115,6 @@ impl Evaluator {
         self.pp.push(difference(data.inner.pp, attrs.pp()));
 
         match attrs {
-            PerformanceAttributes::Fruits(_) => {}
             PerformanceAttributes::Mania(attrs) => {
                 if let Some(acc) = data.inner.accuracy {
                     let values = self.accuracy.get_or_insert_with(Vec::new);
@@ -169,6 +168,7 @@ impl Evaluator {
                     values.push(entry);
                 }
             }
+            _ => {}
         }
     }
 
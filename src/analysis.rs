@@ -0,0 +1,715 @@
+use crate::{
+    AnyPP, Beatmap, BeatmapExt, DifficultyAttributes, Mods, PerformanceAttributes, ScoreState,
+};
+
+use std::iter;
+
+/// Amount of steps per dimension for the initial grid of [`estimate_max_pp`].
+const MAX_PP_GRID_STEPS: usize = 32;
+
+/// Amount of score states that [`estimate_max_pp`] evaluates at once in an exhaustive search.
+const MAX_PP_CHUNK_SIZE: usize = 4096;
+
+/// Weight decay of consecutive strain peaks for the skill ratings of [`compare_maps`].
+const SKILL_DECAY_WEIGHT: f64 = 0.9;
+
+/// The difference between the difficulty of a map for two sets of mods.
+///
+/// See [`compare`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModComparison {
+    /// The attributes for the first set of mods.
+    pub attributes_a: DifficultyAttributes,
+    /// The attributes for the second set of mods.
+    pub attributes_b: DifficultyAttributes,
+    /// Time in ms inbetween two sections, unaffected by the clock rate.
+    pub section_length: f64,
+    /// The summed strains of each section for both sets of mods.
+    pub sections: Vec<SectionDelta>,
+}
+
+impl ModComparison {
+    /// How many more stars the second set of mods has than the first one.
+    #[inline]
+    pub fn stars_delta(&self) -> f64 {
+        self.attributes_b.stars() - self.attributes_a.stars()
+    }
+
+    /// The section in which the second set of mods increased the strain the most.
+    pub fn largest_increase(&self) -> Option<&SectionDelta> {
+        self.sections
+            .iter()
+            .max_by(|a, b| a.delta().total_cmp(&b.delta()))
+    }
+}
+
+/// The summed strains of a single section for two sets of mods.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SectionDelta {
+    /// The start of the section in ms relative to the first section,
+    /// unaffected by the clock rate.
+    pub time: f64,
+    /// The strain for the first set of mods.
+    pub strain_a: f64,
+    /// The strain for the second set of mods.
+    pub strain_b: f64,
+}
+
+impl SectionDelta {
+    /// How much higher the strain of the second set of mods is.
+    #[inline]
+    pub fn delta(&self) -> f64 {
+        self.strain_b - self.strain_a
+    }
+}
+
+/// Compare the difficulty of a map between two sets of mods, e.g. to find out how
+/// much harder HR is than NM and which parts of the map are affected the most.
+///
+/// The sections are those of `mods_a`. If the clock rates of both mod sets differ,
+/// the sections of `mods_b` are of a different length so each section of `mods_a`
+/// is compared with the section of `mods_b` that contains its start time.
+///
+/// ```
+/// use akatsuki_pp::{analysis, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// let comparison = analysis::compare(&map, 0, 16); // NM vs HR
+///
+/// println!("HR adds {:.2} stars", comparison.stars_delta());
+///
+/// if let Some(section) = comparison.largest_increase() {
+///     println!("mostly around {}ms", section.time);
+/// }
+/// ```
+pub fn compare(map: &Beatmap, mods_a: impl Mods, mods_b: impl Mods) -> ModComparison {
+    let strains_a = map.strains(mods_a, None);
+    let strains_b = map.strains(mods_b, None);
+
+    let same_grid = (strains_a.section_length - strains_b.section_length).abs() <= f64::EPSILON;
+
+    let sections = strains_a
+        .strains
+        .iter()
+        .enumerate()
+        .map(|(i, &strain_a)| {
+            let time = i as f64 * strains_a.section_length;
+
+            let idx_b = if same_grid || strains_b.section_length <= 0.0 {
+                i
+            } else {
+                (time / strains_b.section_length) as usize
+            };
+
+            SectionDelta {
+                time,
+                strain_a,
+                strain_b: strains_b.strains.get(idx_b).copied().unwrap_or(0.0),
+            }
+        })
+        .collect();
+
+    ModComparison {
+        attributes_a: map.stars(mods_a, None),
+        attributes_b: map.stars(mods_b, None),
+        section_length: strains_a.section_length,
+        sections,
+    }
+}
+
+/// The difficulty profiles of multiple maps, see [`compare_maps`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MapPoolComparison {
+    /// The profile of each map in the order the maps were given.
+    pub profiles: Vec<DifficultyProfile>,
+}
+
+impl MapPoolComparison {
+    /// The index of the map with the most stars.
+    pub fn hardest(&self) -> Option<usize> {
+        self.profiles
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.stars.value.total_cmp(&b.stars.value))
+            .map(|(idx, _)| idx)
+    }
+}
+
+/// The difficulty profile of a single map within a [`MapPoolComparison`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DifficultyProfile {
+    /// The difficulty attributes of the map.
+    pub attributes: DifficultyAttributes,
+    /// The star rating.
+    pub stars: ProfileValue,
+    /// The rating of each skill of the map's mode.
+    ///
+    /// Ratings are the strain peaks of the skill weighted by `0.9^i` in descending
+    /// order. Unlike the star rating they are not scaled so they are only comparable
+    /// among ratings of the same skill.
+    pub skills: Vec<SkillRating>,
+    /// The amount of objects per second of playable time.
+    pub density: ProfileValue,
+    /// The highest amount of objects within a single second.
+    pub peak_density: ProfileValue,
+}
+
+impl DifficultyProfile {
+    /// The rating of the skill with the given name, e.g. `"aim"`.
+    pub fn skill(&self, name: &str) -> Option<&SkillRating> {
+        self.skills.iter().find(|skill| skill.name == name)
+    }
+}
+
+/// A value of a [`DifficultyProfile`] alongside its normalized counterpart.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ProfileValue {
+    /// The raw value.
+    pub value: f64,
+    /// The value divided by the highest value of all compared maps,
+    /// i.e. `1.0` for the highest one and `0.0` if all values are zero.
+    pub normalized: f64,
+}
+
+/// The rating of a single skill, see [`DifficultyProfile::skills`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SkillRating {
+    /// The name of the skill, e.g. `"aim"` or `"stamina_left"`.
+    pub name: &'static str,
+    /// The rating alongside its normalization across all maps that have this skill.
+    pub rating: ProfileValue,
+}
+
+/// Calculate comparable difficulty profiles of multiple maps, e.g. for a tournament mappool.
+///
+/// The maps are calculated in parallel and each value is normalized by the highest
+/// value among all maps so that `1.0` denotes the hardest map in that regard.
+/// Skill ratings are normalized only among the maps that have that skill,
+/// which matters when comparing maps of different modes.
+///
+/// ```
+/// use akatsuki_pp::{analysis, Beatmap};
+///
+/// # let (map_a, map_b) = (Beatmap::default(), Beatmap::default());
+/// let comparison = analysis::compare_maps(&[&map_a, &map_b], 16); // HR
+///
+/// for profile in comparison.profiles.iter() {
+///     println!("{:.2} stars ({:.0}%)", profile.stars.value, profile.stars.normalized * 100.0);
+/// }
+/// ```
+pub fn compare_maps(maps: &[&Beatmap], mods: impl Mods + Send) -> MapPoolComparison {
+    let mut profiles = crate::par_map(maps, move |map| profile(map, mods));
+
+    normalize(profiles.iter_mut().map(|profile| &mut profile.stars));
+    normalize(profiles.iter_mut().map(|profile| &mut profile.density));
+    normalize(profiles.iter_mut().map(|profile| &mut profile.peak_density));
+
+    let mut names: Vec<_> = profiles
+        .iter()
+        .flat_map(|profile| profile.skills.iter().map(|skill| skill.name))
+        .collect();
+
+    names.sort_unstable();
+    names.dedup();
+
+    for name in names {
+        normalize(profiles.iter_mut().flat_map(|profile| {
+            profile
+                .skills
+                .iter_mut()
+                .filter(move |skill| skill.name == name)
+                .map(|skill| &mut skill.rating)
+        }));
+    }
+
+    MapPoolComparison { profiles }
+}
+
+fn profile(map: &Beatmap, mods: impl Mods) -> DifficultyProfile {
+    let attributes = map.stars(mods, None);
+    let clock_rate = map.attributes().mods(mods).clock_rate;
+
+    let playable_seconds = map.length(clock_rate).playable_time / 1000.0;
+
+    let density = if playable_seconds > 0.0 {
+        map.hit_objects.len() as f64 / playable_seconds
+    } else {
+        0.0
+    };
+
+    let skills = match attributes {
+        #[cfg(feature = "fruits")]
+        DifficultyAttributes::Fruits(_) => {
+            let strains = crate::fruits::skill_strains(map, mods, None);
+
+            vec![skill_rating("movement", &strains.movement)]
+        }
+        #[cfg(feature = "mania")]
+        DifficultyAttributes::Mania(_) => {
+            let strains = crate::mania::skill_strains(map, mods, None);
+
+            vec![skill_rating("strain", &strains.strain)]
+        }
+        #[cfg(feature = "osu")]
+        DifficultyAttributes::Osu(_) => {
+            let strains = crate::osu::skill_strains(map, mods, None);
+
+            let mut skills = vec![
+                skill_rating("aim", &strains.aim),
+                skill_rating("speed", &strains.speed),
+            ];
+
+            if mods.fl() {
+                skills.push(skill_rating("flashlight", &strains.flashlight));
+            }
+
+            skills
+        }
+        #[cfg(feature = "taiko")]
+        DifficultyAttributes::Taiko(_) => {
+            let strains = crate::taiko::skill_strains(map, mods, None);
+
+            vec![
+                skill_rating("color", &strains.color),
+                skill_rating("rhythm", &strains.rhythm),
+                skill_rating("stamina_right", &strains.stamina_right),
+                skill_rating("stamina_left", &strains.stamina_left),
+            ]
+        }
+    };
+
+    DifficultyProfile {
+        stars: ProfileValue {
+            value: attributes.stars(),
+            normalized: 0.0,
+        },
+        attributes,
+        skills,
+        density: ProfileValue {
+            value: density,
+            normalized: 0.0,
+        },
+        peak_density: ProfileValue {
+            value: crate::features::peak_density(map, clock_rate),
+            normalized: 0.0,
+        },
+    }
+}
+
+/// Weight the descendingly sorted strain peaks of a skill.
+fn skill_rating(name: &'static str, peaks: &[f64]) -> SkillRating {
+    let weights = iter::successors(Some(1.0), |weight| Some(weight * SKILL_DECAY_WEIGHT));
+    let value = peaks
+        .iter()
+        .zip(weights)
+        .map(|(peak, weight)| peak * weight)
+        .sum();
+
+    SkillRating {
+        name,
+        rating: ProfileValue {
+            value,
+            normalized: 0.0,
+        },
+    }
+}
+
+fn normalize<'a>(values: impl Iterator<Item = &'a mut ProfileValue>) {
+    let mut values: Vec<_> = values.collect();
+    let max = values.iter().map(|value| value.value).fold(0.0, f64::max);
+
+    for value in values.iter_mut() {
+        value.normalized = if max > 0.0 { value.value / max } else { 0.0 };
+    }
+}
+
+/// The highest performance that [`estimate_max_pp`] found on a map.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaxPerformance {
+    /// The score state that achieves the performance.
+    pub state: ScoreState,
+    /// The performance attributes of that score state.
+    pub attributes: PerformanceAttributes,
+}
+
+impl MaxPerformance {
+    /// Return the performance point value.
+    #[inline]
+    pub fn pp(&self) -> f64 {
+        self.attributes.pp()
+    }
+}
+
+/// Search the valid score states of a map for the one with the highest performance.
+///
+/// [`BeatmapExt::max_pp`] assumes that a perfect hitresult distribution gives the most pp
+/// which does not hold for every map and set of mods. For osu!mania for example, the
+/// maximum score is capped by score multiplier mods so the default score of a perfect
+/// play is not achievable. This function only considers states that a play could
+/// actually have which makes it suited for thresholds such as pp caps.
+///
+/// The search varies the worse hitresults, i.e. 100s and 50s for osu!standard, 100s for
+/// osu!taiko, and tiny droplet misses for osu!ctb:
+///
+/// - For osu!taiko and osu!ctb, this is a single dimension which is searched exhaustively.
+/// - For osu!standard, the two dimensions are too large to evaluate every state.
+///   Instead, a coarse grid is refined around its best state until no neighbor
+///   improves it. This is a heuristic that may end up in a local maximum.
+/// - For osu!mania, the performance only increases with the score so the maximum
+///   score that the mods allow is evaluated directly.
+///
+/// States with misses are not searched. For the current performance formulas, a miss
+/// is never better than the worst hitresult that could replace it but this is an
+/// assumption about the formulas, not something the search verifies.
+///
+/// Since every evaluated state is valid, the result is always a lower bound of the
+/// actual maximum. The difficulty attributes are calculated only once.
+///
+/// ```
+/// use akatsuki_pp::{analysis, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// let max = analysis::estimate_max_pp(&map, 2); // EZ
+///
+/// println!("a valid score can reach {:.2}pp", max.pp());
+/// ```
+pub fn estimate_max_pp(map: &Beatmap, mods: impl Mods) -> MaxPerformance {
+    let difficulty = map.stars(mods, None);
+    let space = SearchSpace::new(map, mods, &difficulty);
+
+    let evaluate = |points: &[(usize, usize)]| -> Option<(usize, usize, MaxPerformance)> {
+        let states: Vec<_> = points
+            .iter()
+            .map(|&(a, b)| space.state(a, b, &difficulty))
+            .collect();
+
+        let performances = AnyPP::new(map)
+            .attributes(difficulty.clone())
+            .lazer_mods(crate::mods::lazer_mods(mods))
+            .calculate_many(&states);
+
+        points
+            .iter()
+            .zip(states)
+            .zip(performances)
+            .map(|((&(a, b), state), attributes)| (a, b, MaxPerformance { state, attributes }))
+            .reduce(prefer_first_max)
+    };
+
+    // A single dimension is small enough to evaluate every state
+    if space.max_b == 0 {
+        let (.., best) = (0..=space.max_a)
+            .step_by(MAX_PP_CHUNK_SIZE)
+            .filter_map(|start| {
+                let end = (start + MAX_PP_CHUNK_SIZE).min(space.max_a + 1);
+                let chunk: Vec<_> = (start..end).map(|a| (a, 0)).collect();
+
+                evaluate(&chunk)
+            })
+            .reduce(prefer_first_max)
+            .expect("space contains the perfect state");
+
+        return best;
+    }
+
+    let mut step = (space.max_a.max(space.max_b) / MAX_PP_GRID_STEPS).max(1);
+
+    let grid: Vec<_> = (0..=space.max_a)
+        .step_by(step)
+        .flat_map(|a| (0..=space.max_b).step_by(step).map(move |b| (a, b)))
+        .filter(|&(a, b)| space.is_valid(a, b))
+        .collect();
+
+    let (mut a, mut b, mut best) = evaluate(&grid).expect("grid contains the perfect state");
+
+    loop {
+        let neighbors: Vec<_> = [-1, 0, 1]
+            .iter()
+            .flat_map(|&da| [-1, 0, 1].iter().map(move |&db| (da, db)))
+            .filter(|&offset| offset != (0, 0))
+            .filter_map(|(da, db)| Some((offset(a, da, step)?, offset(b, db, step)?)))
+            .filter(|&(a, b)| space.is_valid(a, b))
+            .collect();
+
+        match evaluate(&neighbors) {
+            Some((next_a, next_b, next)) if next.pp() > best.pp() => {
+                a = next_a;
+                b = next_b;
+                best = next;
+            }
+            _ if step > 1 => step /= 2,
+            _ => return best,
+        }
+    }
+}
+
+/// Keep the earlier of two equally good states, i.e. the one closer to a perfect play.
+fn prefer_first_max(
+    best: (usize, usize, MaxPerformance),
+    next: (usize, usize, MaxPerformance),
+) -> (usize, usize, MaxPerformance) {
+    if next.2.pp() > best.2.pp() {
+        next
+    } else {
+        best
+    }
+}
+
+fn offset(value: usize, direction: isize, step: usize) -> Option<usize> {
+    match direction {
+        -1 => value.checked_sub(step),
+        1 => value.checked_add(step),
+        _ => Some(value),
+    }
+}
+
+/// The valid score states of a map as two dimensions that are zero for a perfect play.
+struct SearchSpace {
+    max_a: usize,
+    max_b: usize,
+    max_sum: usize,
+    /// The score of every osu!mania state.
+    #[cfg(feature = "mania")]
+    max_score: u32,
+}
+
+impl SearchSpace {
+    fn new(map: &Beatmap, mods: impl Mods, difficulty: &DifficultyAttributes) -> Self {
+        let (max_a, max_b) = match difficulty {
+            #[cfg(feature = "fruits")]
+            DifficultyAttributes::Fruits(attributes) => (attributes.n_tiny_droplets, 0),
+            #[cfg(feature = "mania")]
+            DifficultyAttributes::Mania(_) => (0, 0),
+            #[cfg(feature = "osu")]
+            DifficultyAttributes::Osu(attributes) => {
+                let n_objects = attributes.n_circles + attributes.n_sliders + attributes.n_spinners;
+
+                (n_objects, n_objects)
+            }
+            #[cfg(feature = "taiko")]
+            DifficultyAttributes::Taiko(attributes) => (attributes.max_combo, 0),
+        };
+
+        #[cfg(not(feature = "mania"))]
+        let _ = (map, mods);
+
+        Self {
+            max_a,
+            max_b,
+            max_sum: max_a.max(max_b),
+            #[cfg(feature = "mania")]
+            max_score: crate::mania::max_score(map, mods),
+        }
+    }
+
+    fn is_valid(&self, a: usize, b: usize) -> bool {
+        a <= self.max_a && b <= self.max_b && a + b <= self.max_sum
+    }
+
+    fn state(&self, a: usize, b: usize, difficulty: &DifficultyAttributes) -> ScoreState {
+        let mut state = ScoreState::max_for(difficulty);
+
+        #[cfg(not(feature = "osu"))]
+        let _ = b;
+
+        #[cfg(not(any(feature = "osu", feature = "taiko", feature = "fruits")))]
+        let _ = a;
+
+        match difficulty {
+            #[cfg(feature = "fruits")]
+            DifficultyAttributes::Fruits(_) => {
+                state.n_katu = a;
+                state.n50 -= a;
+            }
+            #[cfg(feature = "mania")]
+            DifficultyAttributes::Mania(_) => state.score = self.max_score,
+            #[cfg(feature = "osu")]
+            DifficultyAttributes::Osu(_) => {
+                state.n300 -= a + b;
+                state.n100 = a;
+                state.n50 = b;
+            }
+            #[cfg(feature = "taiko")]
+            DifficultyAttributes::Taiko(_) => {
+                state.n300 -= a;
+                state.n100 = a;
+            }
+        }
+
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn compare_hr() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let comparison = compare(&map, 0, 16);
+
+        let nomod = map.stars(0, None).stars();
+        let hr = map.stars(16, None).stars();
+        assert!((comparison.stars_delta() - (hr - nomod)).abs() < 1e-9);
+
+        let strains = map.strains(0, None);
+        assert_eq!(comparison.sections.len(), strains.strains.len());
+        assert!(comparison.largest_increase().unwrap().delta() > 0.0);
+
+        let dt = compare(&map, 0, 64);
+        assert_eq!(dt.sections.len(), strains.strains.len());
+        assert!(dt.stars_delta() > 0.0);
+
+        let same = compare(&map, 0, 0);
+        assert!(same.sections.iter().all(|section| section.delta() == 0.0));
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn estimate_max_osu() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        for mods in [0, 16, 64] {
+            let max = estimate_max_pp(&map, mods);
+
+            assert!(max.pp() >= map.max_pp(mods).pp());
+            assert_eq!(max.state.misses, 0);
+        }
+
+        // Lazer-only mods apply to the performance too
+        let mods = crate::LazerMods {
+            traceable: true,
+            ..Default::default()
+        };
+
+        let max = estimate_max_pp(&map, mods);
+        let expected = crate::OsuPP::new(&map)
+            .lazer_mods(mods)
+            .state(max.state.clone().into())
+            .calculate();
+
+        assert_eq!(max.pp(), expected.pp);
+        assert!(max.pp() > estimate_max_pp(&map, 0).pp());
+    }
+
+    #[cfg(all(
+        feature = "taiko",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn estimate_max_taiko_exhaustive() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let max = estimate_max_pp(&map, 0);
+
+        let difficulty = map.stars(0, None);
+        let n_objects = ScoreState::max_for(&difficulty).n300;
+
+        let states: Vec<_> = (0..=n_objects)
+            .map(|n100| ScoreState {
+                max_combo: n_objects,
+                n300: n_objects - n100,
+                n100,
+                ..Default::default()
+            })
+            .collect();
+
+        let best = AnyPP::new(&map)
+            .attributes(difficulty)
+            .calculate_many(&states)
+            .into_iter()
+            .map(|attributes| attributes.pp())
+            .fold(0.0, f64::max);
+
+        assert!((max.pp() - best).abs() < 1e-9);
+    }
+
+    #[cfg(all(
+        feature = "fruits",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn estimate_max_fruits_exhaustive() {
+        let map = Beatmap::from_path("./maps/2118524.osu").expect("failed to parse map");
+        let max = estimate_max_pp(&map, 64);
+
+        let difficulty = map.stars(64, None);
+        let perfect = ScoreState::max_for(&difficulty);
+
+        let states: Vec<_> = (0..=perfect.n50)
+            .map(|n_katu| ScoreState {
+                n50: perfect.n50 - n_katu,
+                n_katu,
+                ..perfect
+            })
+            .collect();
+
+        let best = AnyPP::new(&map)
+            .attributes(difficulty)
+            .mods(64)
+            .calculate_many(&states)
+            .into_iter()
+            .map(|attributes| attributes.pp())
+            .fold(0.0, f64::max);
+
+        assert_eq!(max.pp(), best);
+    }
+
+    #[cfg(all(
+        feature = "mania",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn estimate_max_mania_score_cap() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let max = estimate_max_pp(&map, 2);
+
+        let max_score = crate::mania::max_score(&map, 2);
+        assert_eq!(max.state.score, max_score);
+        assert!((max.pp() - map.max_pp(2).pp()).abs() < 1e-9);
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        feature = "taiko",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn compare_map_pool() {
+        let osu = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let taiko = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let empty = Beatmap::default();
+
+        let comparison = compare_maps(&[&osu, &taiko, &osu, &empty], 0);
+        let profiles = &comparison.profiles;
+        assert_eq!(profiles.len(), 4);
+
+        assert_eq!(profiles[0], profiles[2]);
+        assert_eq!(profiles[0].stars.value, osu.stars(0, None).stars());
+        assert_eq!(profiles[1].stars.value, taiko.stars(0, None).stars());
+        assert_eq!(profiles[3].stars, ProfileValue::default());
+
+        let hardest = comparison.hardest().unwrap();
+        assert_eq!(profiles[hardest].stars.normalized, 1.0);
+        assert!(profiles
+            .iter()
+            .all(|profile| (0.0..=1.0).contains(&profile.stars.normalized)));
+
+        // Skills are only normalized among maps of the same mode
+        assert_eq!(profiles[0].skill("aim").unwrap().rating.normalized, 1.0);
+        assert_eq!(profiles[1].skill("color").unwrap().rating.normalized, 1.0);
+        assert!(profiles[1].skill("aim").is_none());
+        assert!(profiles[0].skill("flashlight").is_none());
+
+        let fl = compare_maps(&[&osu], 1024);
+        assert!(fl.profiles[0].skill("flashlight").unwrap().rating.value > 0.0);
+    }
+}
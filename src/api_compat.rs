@@ -0,0 +1,257 @@
+use std::fmt;
+
+use crate::{json::JsonError, Beatmap, BeatmapExt, DifficultyAttributes, Mods};
+
+/// Difficulty attributes as returned by the osu!api v2 endpoint
+/// `POST /beatmaps/{beatmap}/attributes`.
+///
+/// Only the numeric fields are kept. Their names are those of the API,
+/// e.g. `star_rating` or `aim_difficulty`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ApiAttributes {
+    fields: Vec<(String, f64)>,
+}
+
+impl ApiAttributes {
+    /// Read the attributes from the body of an API response.
+    ///
+    /// Both the full response, i.e. `{"attributes": {...}}`, and
+    /// the inner attributes object are accepted.
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let object = crate::json::Object::parse(json)?;
+        let attributes = object.object("attributes").unwrap_or(&object);
+
+        let fields = attributes
+            .numbers()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect();
+
+        Ok(Self { fields })
+    }
+
+    /// The value of the field with the given API name.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Compare the API's values against attributes calculated by this crate.
+    ///
+    /// Attributes that the API did not provide, e.g. `flashlight_difficulty`
+    /// without FL, are skipped.
+    pub fn compare(&self, attributes: &DifficultyAttributes) -> ApiComparison {
+        let calculated = calculated_fields(attributes);
+
+        let deltas = calculated
+            .iter()
+            .filter_map(|&(name, calculated)| {
+                let api = self.get(name)?;
+
+                Some(AttributeDelta {
+                    name,
+                    api,
+                    calculated,
+                })
+            })
+            .collect();
+
+        let unmatched = self
+            .fields
+            .iter()
+            .filter(|(key, _)| calculated.iter().all(|(name, _)| name != key))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        ApiComparison { deltas, unmatched }
+    }
+}
+
+/// Calculate the attributes of a map for the given mods and
+/// compare them against the body of an osu!api v2 response.
+///
+/// ```
+/// use akatsuki_pp::{api_compat, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// # /*
+/// let response = ...
+/// # */
+/// # let response = r#"{"attributes":{"star_rating":0.0,"max_combo":0}}"#;
+/// let comparison = api_compat::compare(&map, 64, response).unwrap();
+///
+/// for delta in comparison.exceeding(0.01) {
+///     println!("{}: bancho {} - ours {}", delta.name, delta.api, delta.calculated);
+/// }
+/// ```
+pub fn compare(map: &Beatmap, mods: impl Mods, response: &str) -> Result<ApiComparison, JsonError> {
+    let api = ApiAttributes::from_json(response)?;
+
+    Ok(api.compare(&map.stars(mods, None)))
+}
+
+/// The calculated attributes of the given mode under their API names.
+fn calculated_fields(attributes: &DifficultyAttributes) -> Vec<(&'static str, f64)> {
+    match attributes {
+        #[cfg(feature = "osu")]
+        DifficultyAttributes::Osu(attributes) => vec![
+            ("star_rating", attributes.stars),
+            ("max_combo", attributes.max_combo as f64),
+            ("aim_difficulty", attributes.aim_strain),
+            ("speed_difficulty", attributes.speed_strain),
+            ("flashlight_difficulty", attributes.flashlight_rating),
+            ("slider_factor", attributes.slider_factor),
+            (
+                "aim_difficult_strain_count",
+                attributes.aim_difficult_strain_count,
+            ),
+            (
+                "speed_difficult_strain_count",
+                attributes.speed_difficult_strain_count,
+            ),
+            ("approach_rate", attributes.ar),
+            ("overall_difficulty", attributes.od),
+        ],
+        #[cfg(feature = "taiko")]
+        DifficultyAttributes::Taiko(attributes) => vec![
+            ("star_rating", attributes.stars),
+            ("max_combo", attributes.max_combo as f64),
+        ],
+        #[cfg(feature = "fruits")]
+        DifficultyAttributes::Fruits(attributes) => vec![
+            ("star_rating", attributes.stars),
+            ("max_combo", attributes.max_combo() as f64),
+            ("approach_rate", attributes.ar),
+        ],
+        #[cfg(feature = "mania")]
+        DifficultyAttributes::Mania(attributes) => vec![("star_rating", attributes.stars)],
+    }
+}
+
+/// The difference of a single attribute between the API and this crate.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AttributeDelta {
+    /// The API name of the attribute, e.g. `"star_rating"`.
+    pub name: &'static str,
+    /// The value provided by the API.
+    pub api: f64,
+    /// The value calculated by this crate.
+    pub calculated: f64,
+}
+
+impl AttributeDelta {
+    /// By how much the calculated value exceeds the API's value.
+    #[inline]
+    pub fn delta(&self) -> f64 {
+        self.calculated - self.api
+    }
+}
+
+/// The result of comparing [`ApiAttributes`] against calculated attributes,
+/// see [`compare`] and [`ApiAttributes::compare`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ApiComparison {
+    /// The attributes that were provided by the API and calculated by this crate.
+    pub deltas: Vec<AttributeDelta>,
+    /// Numeric fields of the API response that have no counterpart in this crate,
+    /// e.g. `speed_note_count`.
+    pub unmatched: Vec<String>,
+}
+
+impl ApiComparison {
+    /// The attribute whose delta has the largest magnitude.
+    pub fn max_delta(&self) -> Option<&AttributeDelta> {
+        self.deltas
+            .iter()
+            .max_by(|a, b| a.delta().abs().total_cmp(&b.delta().abs()))
+    }
+
+    /// The attributes whose delta has a larger magnitude than `tolerance`.
+    pub fn exceeding(&self, tolerance: f64) -> impl Iterator<Item = &AttributeDelta> {
+        self.deltas
+            .iter()
+            .filter(move |delta| delta.delta().abs() > tolerance)
+    }
+
+    /// Whether all deltas are within `tolerance`.
+    #[inline]
+    pub fn is_within(&self, tolerance: f64) -> bool {
+        self.exceeding(tolerance).next().is_none()
+    }
+}
+
+impl fmt::Display for ApiComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, delta) in self.deltas.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+
+            write!(
+                f,
+                "{}: {} (api) vs {} (calculated), {:+}",
+                delta.name,
+                delta.api,
+                delta.calculated,
+                delta.delta()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn osu_response() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let attributes = map.stars(64, None);
+        let stars = attributes.stars();
+
+        let response = format!(
+            r#"{{
+                "attributes": {{
+                    "star_rating": {},
+                    "max_combo": {},
+                    "aim_difficulty": {},
+                    "speed_note_count": 120.5,
+                    "mode": "osu"
+                }}
+            }}"#,
+            stars + 0.02,
+            attributes.max_combo().unwrap(),
+            attributes.stars() * 2.0,
+        );
+
+        let comparison = compare(&map, 64, &response).unwrap();
+        let names: Vec<_> = comparison.deltas.iter().map(|delta| delta.name).collect();
+        assert_eq!(names, ["star_rating", "max_combo", "aim_difficulty"]);
+        assert_eq!(comparison.unmatched, ["speed_note_count"]);
+
+        let max = comparison.max_delta().unwrap();
+        assert_eq!(max.name, "aim_difficulty");
+
+        let exceeding: Vec<_> = comparison.exceeding(0.01).map(|delta| delta.name).collect();
+        assert_eq!(exceeding, ["star_rating", "aim_difficulty"]);
+        assert!(!comparison.is_within(0.01));
+
+        let star_delta = comparison.deltas[0];
+        assert!((star_delta.delta() + 0.02).abs() < 1e-9);
+        assert_eq!(star_delta.calculated, stars);
+        assert_eq!(comparison.deltas[1].delta(), 0.0);
+
+        let inner = ApiAttributes::from_json(&format!(r#"{{"star_rating": {}}}"#, stars)).unwrap();
+        assert!(inner.compare(&attributes).is_within(0.0));
+
+        assert!(compare(&map, 64, "[]").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Command line companion of `akatsuki-pp` to quickly check a map's difficulty and performance.
+//!
+//! Requires the `cli` feature, e.g. `cargo run --features cli -- map.osu --mods HDDT --acc 98.5`.
+
+use std::{env, fmt::Write, process};
+
+use akatsuki_pp::{analysis, Beatmap, BeatmapExt, LazerMods, PerformanceAttributes};
+
+const USAGE: &str = "\
+Usage: akatsuki-pp <PATH> [OPTIONS]
+
+Calculate the star rating and performance of a .osu file.
+
+Options:
+    --mods <MODS>     Mods as bits or acronyms, e.g. 72 or HDDT
+    --acc <ACC>       Accuracy in percent
+    --combo <COMBO>   Maximum combo of the score
+    --misses <N>      Amount of misses
+    --n300 <N>        Amount of 300s
+    --n100 <N>        Amount of 100s
+    --n50 <N>         Amount of 50s
+    --score <SCORE>   Score, only relevant for osu!mania
+    --json            Print the result as JSON
+    -h, --help        Print this message";
+
+#[derive(Default)]
+struct Args {
+    path: String,
+    mods: u32,
+    acc: Option<f64>,
+    combo: Option<usize>,
+    misses: Option<usize>,
+    n300: Option<usize>,
+    n100: Option<usize>,
+    n50: Option<usize>,
+    score: Option<u32>,
+    json: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut path = None;
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("missing value for `{}`", name))
+            };
+
+            match arg.as_str() {
+                "--mods" => parsed.mods = parse_mods(&value("--mods")?)?,
+                "--acc" => parsed.acc = Some(parse_value("--acc", &value("--acc")?)?),
+                "--combo" => parsed.combo = Some(parse_value("--combo", &value("--combo")?)?),
+                "--misses" => parsed.misses = Some(parse_value("--misses", &value("--misses")?)?),
+                "--n300" => parsed.n300 = Some(parse_value("--n300", &value("--n300")?)?),
+                "--n100" => parsed.n100 = Some(parse_value("--n100", &value("--n100")?)?),
+                "--n50" => parsed.n50 = Some(parse_value("--n50", &value("--n50")?)?),
+                "--score" => parsed.score = Some(parse_value("--score", &value("--score")?)?),
+                "--json" => parsed.json = true,
+                "-h" | "--help" => return Err(String::new()),
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                _ if path.is_none() => path = Some(arg),
+                _ => return Err(format!("unexpected argument `{}`", arg)),
+            }
+        }
+
+        parsed.path = path.ok_or("missing path to a .osu file")?;
+
+        Ok(parsed)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, name))
+}
+
+fn parse_mods(mods: &str) -> Result<u32, String> {
+    if let Ok(bits) = mods.parse() {
+        return Ok(bits);
+    }
+
+    let acronyms: Vec<_> = mods
+        .as_bytes()
+        .chunks(2)
+        .map(std::str::from_utf8)
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid mods `{}`", mods))?;
+
+    if acronyms.iter().any(|acronym| acronym.len() != 2) {
+        return Err(format!("invalid mods `{}`", mods));
+    }
+
+    LazerMods::from_acronyms(acronyms)
+        .map(|mods| mods.legacy)
+        .map_err(|err| err.to_string())
+}
+
+fn main() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) if err.is_empty() => {
+            println!("{}", USAGE);
+
+            return;
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let map = match Beatmap::from_path(&args.path) {
+        Ok(map) => map,
+        Err(err) => {
+            eprintln!("error: failed to parse `{}`: {}", args.path, err);
+            process::exit(1);
+        }
+    };
+
+    let difficulty = map.stars(args.mods, None);
+    let mut calculator = map.pp().attributes(difficulty.clone()).mods(args.mods);
+
+    if let Some(acc) = args.acc {
+        calculator = calculator.accuracy(acc);
+    }
+
+    if let Some(combo) = args.combo {
+        calculator = calculator.combo(combo);
+    }
+
+    if let Some(misses) = args.misses {
+        calculator = calculator.misses(misses);
+    }
+
+    if let Some(n300) = args.n300 {
+        calculator = calculator.n300(n300);
+    }
+
+    if let Some(n100) = args.n100 {
+        calculator = calculator.n100(n100);
+    }
+
+    if let Some(n50) = args.n50 {
+        calculator = calculator.n50(n50);
+    }
+
+    if let Some(score) = args.score {
+        calculator = calculator.score(score);
+    }
+
+    let performance = match calculator.try_calculate() {
+        Ok(performance) => performance,
+        Err(err) => {
+            eprintln!("error: invalid score: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let max_pp = analysis::estimate_max_pp(&map, args.mods).pp();
+
+    if args.json {
+        println!("{}", to_json(&map, args.mods, &performance, max_pp));
+    } else {
+        println!("Mode:      {}", map.mode);
+        println!("Mods:      {}", args.mods);
+        println!("Stars:     {:.2}", performance.stars());
+
+        if let Some(max_combo) = performance.max_combo() {
+            println!("Max combo: {}", max_combo);
+        }
+
+        println!("PP:        {:.2}", performance.pp());
+        println!("Max PP:    {:.2}", max_pp);
+    }
+}
+
+fn to_json(map: &Beatmap, mods: u32, performance: &PerformanceAttributes, max_pp: f64) -> String {
+    let mut json = String::new();
+
+    let _ = write!(
+        json,
+        r#"{{"mode":"{}","mods":{},"stars":{},"pp":{},"max_pp":{}"#,
+        map.mode,
+        mods,
+        performance.stars(),
+        performance.pp(),
+        max_pp,
+    );
+
+    if let Some(max_combo) = performance.max_combo() {
+        let _ = write!(json, r#","max_combo":{}"#, max_combo);
+    }
+
+    json.push('}');
+
+    json
+}
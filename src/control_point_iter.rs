@@ -98,24 +98,29 @@ mod test {
                 TimingPoint {
                     time: 1.0,
                     beat_len: 10.0,
+                    ..Default::default()
                 },
                 TimingPoint {
                     time: 3.0,
                     beat_len: 10.0,
+                    ..Default::default()
                 },
                 TimingPoint {
                     time: 4.0,
                     beat_len: 10.0,
+                    ..Default::default()
                 },
             ],
             difficulty_points: vec![
                 DifficultyPoint {
                     time: 2.0,
                     speed_multiplier: 10.0,
+                    ..Default::default()
                 },
                 DifficultyPoint {
                     time: 5.0,
                     speed_multiplier: 10.0,
+                    ..Default::default()
                 },
             ],
             ..Default::default()
@@ -128,6 +133,6 @@ mod test {
         assert!(matches!(iter.next(), Some(ControlPoint::Timing { .. })));
         assert!(matches!(iter.next(), Some(ControlPoint::Timing { .. })));
         assert!(matches!(iter.next(), Some(ControlPoint::Difficulty { .. })));
-        assert!(matches!(iter.next(), None));
+        assert!(iter.next().is_none());
     }
 }
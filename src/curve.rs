@@ -31,16 +31,10 @@ impl BezierBuffers {
             return;
         }
 
-        let additional = len - self.buf1.len();
-
-        self.buf1
-            .extend(iter::repeat(Pos2::zero()).take(additional));
-        self.buf2
-            .extend(iter::repeat(Pos2::zero()).take(additional));
-        self.buf3
-            .extend(iter::repeat(Pos2::zero()).take(additional));
-        self.buf4
-            .extend(iter::repeat(Pos2::zero()).take(additional));
+        self.buf1.resize(len, Pos2::zero());
+        self.buf2.resize(len, Pos2::zero());
+        self.buf3.resize(len, Pos2::zero());
+        self.buf4.resize(len, Pos2::zero());
     }
 }
 
@@ -69,6 +63,16 @@ impl Curve {
         Self { path, lengths }
     }
 
+    /// The length of the path given by the control points, regardless of the expected length.
+    pub(crate) fn path_len(points: &[PathControlPoint], bufs: &mut CurveBuffers) -> f64 {
+        let path = Self::calculate_path(points, bufs);
+
+        path.iter()
+            .zip(path.iter().skip(1))
+            .map(|(&curr, &next)| (next - curr).length() as f64)
+            .sum()
+    }
+
     pub(crate) fn position_at(&self, progress: f64) -> Pos2 {
         let d = self.progress_to_dist(progress);
         let i = self.idx_of_dist(d);
@@ -444,30 +448,28 @@ impl Curve {
 
         let catmull_detail = CATMULL_DETAIL as f32;
 
-        let subpath = (0..CATMULL_DETAIL)
-            .map(|c| {
-                let c = c as f32;
-                let t1 = c / catmull_detail;
-                let t2 = t1 * t1;
-                let t3 = t2 * t1;
-
-                let pos1 = Pos2 {
-                    x: 0.5 * (x1 + x2 * t1 + x3 * t2 + x4 * t3),
-                    y: 0.5 * (y1 + y2 * t1 + y3 * t2 + y4 * t3),
-                };
-
-                let t1 = (c + 1.0) / catmull_detail;
-                let t2 = t1 * t1;
-                let t3 = t2 * t1;
-
-                let pos2 = Pos2 {
-                    x: 0.5 * (x1 + x2 * t1 + x3 * t2 + x4 * t3),
-                    y: 0.5 * (y1 + y2 * t1 + y3 * t2 + y4 * t3),
-                };
-
-                iter::once(pos1).chain(iter::once(pos2))
-            })
-            .flatten();
+        let subpath = (0..CATMULL_DETAIL).flat_map(|c| {
+            let c = c as f32;
+            let t1 = c / catmull_detail;
+            let t2 = t1 * t1;
+            let t3 = t2 * t1;
+
+            let pos1 = Pos2 {
+                x: 0.5 * (x1 + x2 * t1 + x3 * t2 + x4 * t3),
+                y: 0.5 * (y1 + y2 * t1 + y3 * t2 + y4 * t3),
+            };
+
+            let t1 = (c + 1.0) / catmull_detail;
+            let t2 = t1 * t1;
+            let t3 = t2 * t1;
+
+            let pos2 = Pos2 {
+                x: 0.5 * (x1 + x2 * t1 + x3 * t2 + x4 * t3),
+                y: 0.5 * (y1 + y2 * t1 + y3 * t2 + y4 * t3),
+            };
+
+            iter::once(pos1).chain(iter::once(pos2))
+        });
 
         path.extend(subpath);
     }
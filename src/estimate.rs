@@ -0,0 +1,269 @@
+use crate::{parse::HitObject, Beatmap, BeatmapExt, GameMode, Mods};
+
+/// Length of the sections in ms whose strain peaks are considered.
+const SECTION_LEN: f64 = 400.0;
+
+/// Lower bound for the time between objects in ms so that stacked or
+/// simultaneous objects don't explode the strain.
+const MIN_DELTA: f64 = 25.0;
+
+/// The fraction of the strain that remains after a second.
+const STRAIN_DECAY: f64 = 0.3;
+
+/// Estimate the star rating of a map for the given mods.
+///
+/// Instead of running the actual skills, the estimation only looks at the object
+/// density and spacing so it skips slider paths, difficulty objects, and strains.
+/// On the osu!standard test map it is about 40 times faster than
+/// [`BeatmapExt::stars`] which makes it suited to pre-filter large map
+/// collections before calculating the remaining maps exactly.
+///
+/// The result is **approximate** and its coefficients are **uncalibrated**:
+/// they were only fitted to the four test maps of this crate, one per mode,
+/// so there is no held-out measurement of the error on other maps. Before
+/// relying on the estimate, check its error on a sample of your own maps
+/// through [`error_distribution`] and choose the pre-filter's margins accordingly.
+///
+/// Maps whose difficulty stems from patterns rather than density and spacing,
+/// e.g. taiko colors or catch hyperdashes, are estimated less accurately.
+/// Converted maps are estimated in the map's own mode.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{estimate, Beatmap};
+///
+/// # /*
+/// let maps: Vec<Beatmap> = ...
+/// # */
+/// # let maps = vec![Beatmap::default()];
+/// // Only calculate maps that are roughly within the requested range
+/// let candidates = maps.iter().filter(|map| {
+///     let stars = estimate::approximate_stars(map, 0);
+///
+///     (4.5..=6.5).contains(&stars)
+/// });
+/// # assert_eq!(candidates.count(), 0);
+/// ```
+pub fn approximate_stars(map: &Beatmap, mods: impl Mods) -> f64 {
+    let attributes = map.attributes().mods(mods);
+    let clock_rate = attributes.clock_rate;
+    let objects = map.hit_objects.iter();
+
+    let stars = match map.mode {
+        GameMode::STD => {
+            let radius = 54.4 - 4.48 * attributes.cs;
+
+            let (aim, speed) = sectioned_strains(objects, clock_rate, |prev, curr| {
+                prev.pos.distance(curr.pos) as f64 / radius
+            });
+
+            let aim = 0.139 * aim.sqrt();
+            let speed = 0.017 * speed.sqrt();
+
+            (aim.powf(1.1) + speed.powf(1.1)).powf(1.0 / 1.1)
+        }
+        GameMode::TKO => {
+            let circles = objects.filter(|h| h.is_circle());
+            let (_, density) = sectioned_strains(circles, clock_rate, |_, _| 0.0);
+
+            0.1185 * density.powf(0.513)
+        }
+        GameMode::CTB => {
+            let catcher_width = 106.75 * (1.0 - 0.7 * (attributes.cs - 5.0) / 5.0);
+
+            let (movement, density) = sectioned_strains(objects, clock_rate, |prev, curr| {
+                (curr.pos.x - prev.pos.x).abs() as f64 / catcher_width
+            });
+
+            0.155 * movement.sqrt() + 0.035 * density.sqrt()
+        }
+        GameMode::MNA => {
+            let keys = (map.cs.round() as f64).max(1.0);
+            let (_, density) = sectioned_strains(objects, clock_rate, |_, _| 0.0);
+
+            0.00895 * (density / keys.sqrt()).powf(0.823)
+        }
+    };
+
+    if stars.is_finite() {
+        stars
+    } else {
+        0.0
+    }
+}
+
+/// Accumulate the spacing and density of consecutive objects into
+/// decaying strains and sum up the weighted section peaks of both.
+fn sectioned_strains<'m>(
+    mut objects: impl Iterator<Item = &'m HitObject>,
+    clock_rate: f64,
+    spacing: impl Fn(&HitObject, &HitObject) -> f64,
+) -> (f64, f64) {
+    let mut spacing_strain = Strain::default();
+    let mut density_strain = Strain::default();
+
+    let mut prev = match objects.next() {
+        Some(h) => h,
+        None => return (0.0, 0.0),
+    };
+
+    for curr in objects {
+        let time = curr.start_time / clock_rate;
+        let delta = ((curr.start_time - prev.start_time) / clock_rate).max(MIN_DELTA);
+
+        spacing_strain.process(time, delta, 1000.0 * spacing(prev, curr) / delta);
+        density_strain.process(time, delta, 1000.0 / delta);
+        prev = curr;
+    }
+
+    (spacing_strain.difficulty(), density_strain.difficulty())
+}
+
+#[derive(Default)]
+struct Strain {
+    current: f64,
+    section_end: f64,
+    section_peak: f64,
+    peaks: Vec<f64>,
+}
+
+impl Strain {
+    fn process(&mut self, time: f64, delta: f64, value: f64) {
+        while time > self.section_end {
+            if self.section_end > 0.0 {
+                self.peaks.push(self.section_peak);
+            }
+
+            self.section_end = (time / SECTION_LEN).ceil().max(1.0) * SECTION_LEN;
+            self.section_peak = 0.0;
+        }
+
+        self.current = self.current * STRAIN_DECAY.powf(delta / 1000.0) + value;
+        self.section_peak = self.section_peak.max(self.current);
+    }
+
+    fn difficulty(mut self) -> f64 {
+        self.peaks.push(self.section_peak);
+        self.peaks.sort_by(|a, b| b.total_cmp(a));
+
+        let mut weight = 1.0;
+        let mut difficulty = 0.0;
+
+        for peak in self.peaks {
+            difficulty += peak * weight;
+            weight *= 0.9;
+        }
+
+        difficulty
+    }
+}
+
+/// How far the estimates of [`approximate_stars`] deviate from the actual star ratings,
+/// see [`error_distribution`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct EstimationError {
+    /// The amount of compared maps.
+    pub n_maps: usize,
+    /// The mean of the estimated minus the actual stars, i.e. whether
+    /// the estimates tend to be too high or too low.
+    pub mean: f64,
+    /// The mean absolute difference in stars.
+    pub mean_abs: f64,
+    /// The absolute difference in stars that 90% of the estimates stay within.
+    pub p90_abs: f64,
+    /// The largest absolute difference in stars.
+    pub max_abs: f64,
+}
+
+/// Measure the error of [`approximate_stars`] against the actual
+/// star ratings of the given maps, e.g. to check its accuracy on
+/// a sample of one's own map collection.
+pub fn error_distribution(maps: &[&Beatmap], mods: impl Mods) -> EstimationError {
+    let errors: Vec<_> = maps
+        .iter()
+        .map(|map| approximate_stars(map, mods) - map.stars(mods, None).stars())
+        .collect();
+
+    EstimationError::from_errors(errors)
+}
+
+impl EstimationError {
+    fn from_errors(errors: Vec<f64>) -> Self {
+        if errors.is_empty() {
+            return Self::default();
+        }
+
+        let n = errors.len() as f64;
+        let mean = errors.iter().sum::<f64>() / n;
+
+        let mut abs: Vec<_> = errors.iter().map(|error| error.abs()).collect();
+        abs.sort_by(f64::total_cmp);
+
+        let p90_idx = (0.9 * (abs.len() - 1) as f64).round() as usize;
+
+        Self {
+            n_maps: errors.len(),
+            mean,
+            mean_abs: abs.iter().sum::<f64>() / n,
+            p90_abs: abs[p90_idx],
+            max_abs: abs[abs.len() - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        feature = "taiko",
+        feature = "fruits",
+        feature = "mania",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn estimation_error() {
+        let maps: Vec<_> = [2785319, 1028484, 2118524, 1974394]
+            .iter()
+            .map(|map_id| Beatmap::from_path(format!("./maps/{}.osu", map_id)).unwrap())
+            .collect();
+
+        let maps: Vec<_> = maps.iter().collect();
+
+        // The test maps are the ones that the coefficients were fitted to so
+        // these bounds only guard against regressions, not the general accuracy
+        let max_error = |mode| match mode {
+            GameMode::STD => 0.25,
+            GameMode::TKO => 0.6,
+            GameMode::CTB => 0.65,
+            GameMode::MNA => 0.16,
+        };
+
+        for mods in [0, 2, 16, 64, 256] {
+            for map in maps.iter() {
+                let error = approximate_stars(map, mods) - map.stars(mods, None).stars();
+
+                assert!(
+                    error.abs() < max_error(map.mode),
+                    "{:?} {}: {}",
+                    map.mode,
+                    mods,
+                    error
+                );
+            }
+
+            let error = error_distribution(&maps, mods);
+
+            assert_eq!(error.n_maps, 4);
+            assert!(error.mean_abs < 0.32, "{}: {:?}", mods, error);
+            assert!(error.max_abs < 0.65, "{}: {:?}", mods, error);
+        }
+
+        let empty = Beatmap::default();
+        assert_eq!(approximate_stars(&empty, 0), 0.0);
+        assert_eq!(error_distribution(&[], 0), EstimationError::default());
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// A named portion of the final pp value, see [`PerformanceAttributes::components`](crate::PerformanceAttributes::components).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PpComponent {
+    /// The name of the component, e.g. `"aim"` or `"acc"`.
+    pub name: &'static str,
+    /// The pp of the component.
+    pub pp: f64,
+}
+
+impl PpComponent {
+    #[cfg(any(feature = "osu", feature = "taiko", feature = "mania"))]
+    #[inline]
+    pub(crate) fn new(name: &'static str, pp: f64) -> Self {
+        Self { name, pp }
+    }
+}
+
+/// The difference of a single [`PpComponent`] between two scores.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ComponentDelta {
+    /// The name of the component, e.g. `"aim"` or `"acc"`.
+    pub name: &'static str,
+    /// The pp of the component for the explained score.
+    pub pp: f64,
+    /// The pp of the component for the score it is compared against.
+    pub other: f64,
+}
+
+impl ComponentDelta {
+    /// How many more pp the explained score got for this component.
+    #[inline]
+    pub fn delta(&self) -> f64 {
+        self.pp - self.other
+    }
+}
+
+/// Explanation why one score gave more or less pp than another,
+/// see [`PerformanceAttributes::explain_diff`](crate::PerformanceAttributes::explain_diff).
+///
+/// The components are not simply added up to the final pp value so their
+/// deltas generally do not sum up to the [`delta`](PerformanceDiff::delta).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerformanceDiff {
+    /// The final pp of the explained score.
+    pub pp: f64,
+    /// The final pp of the score it is compared against.
+    pub other: f64,
+    /// The components of both scores, in the order of
+    /// [`PerformanceAttributes::components`](crate::PerformanceAttributes::components).
+    pub components: Vec<ComponentDelta>,
+}
+
+impl PerformanceDiff {
+    pub(crate) fn new(pp: f64, other: f64, a: &[PpComponent], b: &[PpComponent]) -> Self {
+        let components = a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| ComponentDelta {
+                name: a.name,
+                pp: a.pp,
+                other: b.pp,
+            })
+            .collect();
+
+        Self {
+            pp,
+            other,
+            components,
+        }
+    }
+
+    /// How many more pp the explained score gave in total.
+    #[inline]
+    pub fn delta(&self) -> f64 {
+        self.pp - self.other
+    }
+
+    /// The component whose delta has the largest magnitude.
+    pub fn biggest_change(&self) -> Option<&ComponentDelta> {
+        self.components
+            .iter()
+            .max_by(|a, b| a.delta().abs().total_cmp(&b.delta().abs()))
+    }
+}
+
+impl fmt::Display for PerformanceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+.2}pp", self.delta())?;
+
+        let mut components = self.components.iter();
+
+        if let Some(component) = components.next() {
+            write!(f, " ({} {:+.2}", component.name, component.delta())?;
+
+            for component in components {
+                write!(f, ", {} {:+.2}", component.name, component.delta())?;
+            }
+
+            f.write_str(")")?;
+        }
+
+        Ok(())
+    }
+}
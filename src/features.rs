@@ -0,0 +1,220 @@
+use crate::{rhythm, Beatmap, BeatmapExt, Mods};
+
+/// The version of the feature definition.
+///
+/// Increased whenever features are added, removed, reordered, or calculated differently
+/// so that models trained on an older version can detect incompatible input.
+pub const FEATURES_VERSION: u32 = 1;
+
+/// The amount of features in [`MapFeatures::values`].
+pub const N_FEATURES: usize = 18;
+
+/// The names of the features in the same order as [`MapFeatures::values`].
+pub const FEATURE_NAMES: [&str; N_FEATURES] = [
+    "mode",
+    "stars",
+    "clock_rate",
+    "ar",
+    "od",
+    "cs",
+    "hp",
+    "n_objects",
+    "playable_seconds",
+    "object_density",
+    "peak_density",
+    "strain_mean",
+    "strain_std_dev",
+    "strain_median",
+    "strain_p90",
+    "strain_max",
+    "delta_median",
+    "rhythm_complexity",
+];
+
+/// Window in ms, adjusted by the clock rate, in which the peak density is counted.
+const DENSITY_WINDOW: f64 = 1000.0;
+
+/// A numeric description of a map's difficulty for the given mods, see [`extract`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapFeatures {
+    /// The [`FEATURES_VERSION`] with which the features were extracted.
+    pub version: u32,
+    /// The feature values, named by [`FEATURE_NAMES`].
+    pub values: [f64; N_FEATURES],
+}
+
+impl MapFeatures {
+    /// The value of the feature with the given name.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        FEATURE_NAMES
+            .iter()
+            .position(|feature| *feature == name)
+            .map(|idx| self.values[idx])
+    }
+
+    /// Iterate over the names and values of all features.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, f64)> + '_ {
+        FEATURE_NAMES
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+}
+
+/// Extract a feature vector of the map for the given mods,
+/// e.g. as input for machine learning models.
+///
+/// The features consist of the map's attributes, statistics of its strains,
+/// and statistics of its object density and rhythm, see [`FEATURE_NAMES`].
+/// All times are adjusted by the clock rate. The definition only changes alongside
+/// [`FEATURES_VERSION`] so that values stay comparable across consumers.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{features, Beatmap};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+///
+/// let features = features::extract(&map, 64);
+///
+/// assert_eq!(features.version, features::FEATURES_VERSION);
+/// assert_eq!(features.get("clock_rate"), Some(1.5));
+/// ```
+pub fn extract(map: &Beatmap, mods: impl Mods) -> MapFeatures {
+    let attributes = map.attributes().mods(mods);
+    let stars = map.stars(mods, None).stars();
+    let strains = map.strains(mods, None).strains;
+    let rhythm = rhythm::analyze(map, mods);
+
+    let n_objects = map.hit_objects.len();
+    let playable_seconds = map.length(attributes.clock_rate).playable_time / 1000.0;
+
+    let object_density = if playable_seconds > 0.0 {
+        n_objects as f64 / playable_seconds
+    } else {
+        0.0
+    };
+
+    let strain_mean = mean(&strains);
+
+    let strain_std_dev = if strains.is_empty() {
+        0.0
+    } else {
+        let variance = strains
+            .iter()
+            .map(|strain| (strain - strain_mean) * (strain - strain_mean))
+            .sum::<f64>()
+            / strains.len() as f64;
+
+        variance.sqrt()
+    };
+
+    let mut sorted_strains = strains;
+    sorted_strains.sort_by(f64::total_cmp);
+
+    let mut sorted_deltas = rhythm.deltas;
+    sorted_deltas.sort_by(f64::total_cmp);
+
+    let values = [
+        map.mode as u8 as f64,
+        stars,
+        attributes.clock_rate,
+        attributes.ar,
+        attributes.od,
+        attributes.cs,
+        attributes.hp,
+        n_objects as f64,
+        playable_seconds,
+        object_density,
+        peak_density(map, attributes.clock_rate),
+        strain_mean,
+        strain_std_dev,
+        percentile(&sorted_strains, 0.5),
+        percentile(&sorted_strains, 0.9),
+        sorted_strains.last().copied().unwrap_or(0.0),
+        percentile(&sorted_deltas, 0.5),
+        rhythm.complexity,
+    ];
+
+    MapFeatures {
+        version: FEATURES_VERSION,
+        values,
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// The value at the given percentile of sorted values, `0.0` if there are none.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let idx = (p * (sorted.len() - 1) as f64).round() as usize;
+
+    sorted[idx]
+}
+
+/// The highest amount of objects starting within a window of [`DENSITY_WINDOW`].
+pub(crate) fn peak_density(map: &Beatmap, clock_rate: f64) -> f64 {
+    let mut start = 0;
+    let mut peak = 0;
+
+    for (end, h) in map.hit_objects.iter().enumerate() {
+        let window_start = h.start_time / clock_rate - DENSITY_WINDOW;
+
+        while map.hit_objects[start].start_time / clock_rate <= window_start {
+            start += 1;
+        }
+
+        peak = peak.max(end + 1 - start);
+    }
+
+    peak as f64
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn percentiles() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+        assert_eq!(percentile(&sorted, 0.5), 3.0);
+        assert_eq!(percentile(&sorted, 0.9), 5.0);
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn osu_features() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        let nomod = extract(&map, 0);
+        let dt = extract(&map, 64);
+
+        assert!(nomod.values.iter().all(|value| value.is_finite()));
+        assert_eq!(nomod.get("stars"), Some(map.stars(0, None).stars()));
+        assert_eq!(nomod.get("n_objects"), Some(map.hit_objects.len() as f64));
+        assert!(nomod.get("strain_max") >= nomod.get("strain_p90"));
+        assert!(dt.get("object_density") > nomod.get("object_density"));
+        assert!(dt.get("peak_density") >= nomod.get("peak_density"));
+        assert_eq!(nomod.iter().count(), N_FEATURES);
+        assert!(nomod.get("unknown").is_none());
+    }
+}
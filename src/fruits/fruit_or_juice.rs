@@ -38,7 +38,7 @@ impl FruitOrJuice {
                 let mut h = CatchObject::new((h.pos, h.start_time));
 
                 if params.with_hr {
-                    h = h.with_hr(params);
+                    h = h.with_hr(&mut params.last_pos, &mut params.last_time);
                 }
 
                 params.attributes.n_fruits += 1;
@@ -62,10 +62,8 @@ impl FruitOrJuice {
                 let mut tick_dist = 100.0 * params.map.slider_mult / params.map.tick_rate;
 
                 if params.map.version >= 8 {
-                    tick_dist /= (100.0 / params.slider_state.slider_velocity)
-                        .max(10.0)
-                        .min(1000.0)
-                        / 100.0;
+                    tick_dist /=
+                        (100.0 / params.slider_state.slider_velocity).clamp(10.0, 1000.0) / 100.0;
                 }
 
                 // Build the curve w.r.t. the control points
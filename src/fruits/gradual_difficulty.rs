@@ -1,4 +1,4 @@
-use std::{iter, slice::Iter};
+use std::slice::Iter;
 
 use crate::{
     curve::CurveBuffers,
@@ -7,7 +7,8 @@ use crate::{
         STAR_SCALING_FACTOR,
     },
     parse::{HitObject, Pos2},
-    Beatmap, Mods,
+    research::FruitsParameters,
+    Beatmap, Mods, Strains,
 };
 
 use super::{
@@ -32,7 +33,7 @@ use super::{
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, fruits::FruitsGradualDifficultyAttributes};
+/// use akatsuki_pp::{Beatmap, fruits::FruitsGradualDifficultyAttributes};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -81,7 +82,7 @@ impl<'map> FruitsGradualDifficultyAttributes<'map> {
         let last_direction = 0;
         let last_excess = half_catcher_width;
 
-        let movement = Movement::new(map_attributes.cs as f32);
+        let movement = Movement::new(map_attributes.cs as f32, FruitsParameters::DEFAULT.movement);
         let prev = CatchObject::new((Pos2::zero(), 0.0));
 
         Self {
@@ -106,6 +107,34 @@ impl<'map> FruitsGradualDifficultyAttributes<'map> {
             &mut self.last_excess,
         );
     }
+
+    /// The strains of all processed hit objects, including the section
+    /// of the current object, as [`strains`](super::strains) would return them.
+    ///
+    /// Suitable to draw the evolving strain graph during a play
+    /// without calculating the strains from scratch.
+    pub fn current_strains(&self) -> Strains {
+        let mut strains = Vec::new();
+
+        // The first object has no strain
+        if self.idx >= 2 {
+            strains.extend(crate::peaks_with_current(
+                &self.movement.strain_peaks,
+                self.movement.curr_section_peak,
+            ));
+        }
+
+        Strains {
+            section_length: SECTION_LENGTH * self.clock_rate,
+            strains,
+        }
+    }
+
+    /// The maximum combo of all processed hit objects.
+    #[inline]
+    pub(crate) fn max_combo(&self) -> usize {
+        self.hit_objects.params.attributes.max_combo()
+    }
 }
 
 impl Iterator for FruitsGradualDifficultyAttributes<'_> {
@@ -147,18 +176,21 @@ impl Iterator for FruitsGradualDifficultyAttributes<'_> {
         self.prev = curr;
 
         let len = self.movement.strain_peaks.len();
-        let missing = len + 1 - self.strain_peak_buf.len();
-        self.strain_peak_buf.extend(iter::repeat(0.0).take(missing));
+        self.strain_peak_buf.resize(len + 1, 0.0);
 
-        self.strain_peak_buf[..len].copy_from_slice(&self.movement.strain_peaks);
+        crate::copy_strains(
+            &mut self.strain_peak_buf[..len],
+            &self.movement.strain_peaks,
+        );
 
         if let Some(last) = self.strain_peak_buf.last_mut() {
-            *last = self.movement.curr_section_peak;
+            *last = crate::round_strain(self.movement.curr_section_peak);
         }
 
         let mut attributes = self.hit_objects.attributes();
         attributes.stars =
-            Movement::difficulty_value(&mut self.strain_peak_buf).sqrt() * STAR_SCALING_FACTOR;
+            Movement::difficulty_value(&mut self.strain_peak_buf, &self.movement.params).sqrt()
+                * STAR_SCALING_FACTOR;
 
         Some(attributes)
     }
@@ -1,6 +1,8 @@
 use crate::{Beatmap, FruitsPP};
 
-use super::{FruitsGradualDifficultyAttributes, FruitsPerformanceAttributes};
+use super::{
+    FruitsDifficultyAttributes, FruitsGradualDifficultyAttributes, FruitsPerformanceAttributes,
+};
 
 /// Aggregation for a score's current state i.e. what was the
 /// maximum combo so far and what are the current hitresults.
@@ -30,6 +32,45 @@ impl FruitsScoreState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Create the score state of a perfect play, i.e. an SS with full combo,
+    /// for the given difficulty attributes.
+    pub fn max_for(attributes: &FruitsDifficultyAttributes) -> Self {
+        Self {
+            max_combo: attributes.max_combo(),
+            n_fruits: attributes.n_fruits,
+            n_droplets: attributes.n_droplets,
+            n_tiny_droplets: attributes.n_tiny_droplets,
+            n_tiny_droplet_misses: 0,
+            misses: 0,
+        }
+    }
+
+    /// The amount of hitresults, i.e. the amount of objects that were judged so far.
+    ///
+    /// Includes tiny droplets and their misses.
+    #[inline]
+    pub fn total_hits(&self) -> usize {
+        self.n_fruits
+            + self.n_droplets
+            + self.n_tiny_droplets
+            + self.n_tiny_droplet_misses
+            + self.misses
+    }
+
+    /// The amount of objects that are yet to be judged out of `n_objects`.
+    #[inline]
+    pub fn remaining(&self, n_objects: usize) -> usize {
+        n_objects.saturating_sub(self.total_hits())
+    }
+
+    /// Whether all objects of the given difficulty attributes were judged.
+    ///
+    /// The objects are counted by [`FruitsDifficultyAttributes::n_accuracy_objects`].
+    #[inline]
+    pub fn is_complete(&self, attributes: &FruitsDifficultyAttributes) -> bool {
+        self.remaining(attributes.n_accuracy_objects()) == 0
+    }
 }
 
 /// Gradually calculate the performance attributes of an osu!ctb map.
@@ -52,7 +93,7 @@ impl FruitsScoreState {
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, fruits::{FruitsGradualPerformanceAttributes, FruitsScoreState}};
+/// use akatsuki_pp::{Beatmap, fruits::{FruitsGradualPerformanceAttributes, FruitsScoreState}};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -115,10 +156,10 @@ impl FruitsScoreState {
 /// state.n_tiny_droplets = ...
 /// state.n_tiny_droplet_misses = ...
 /// state.misses = ...
-/// let final_performance = gradual_perf.process_next_n_objects(state.clone(), usize::MAX).unwrap();
+/// let final_performance = gradual_perf.process_remaining(state.clone()).unwrap();
 /// println!("PP: {}", performance.pp);
 /// # */
-/// # let _ = gradual_perf.process_next_n_objects(state.clone(), usize::MAX);
+/// # let _ = gradual_perf.process_remaining(state.clone());
 ///
 /// // Once the final performance was calculated,
 /// // attempting to process further objects will return `None`.
@@ -186,12 +227,49 @@ impl<'map> FruitsGradualPerformanceAttributes<'map> {
 
         Some(performance)
     }
+
+    /// Process all remaining hit objects at once and calculate the
+    /// performance attributes for the final score state.
+    ///
+    /// Suitable to finish off a play, e.g. after a fail, without having to know
+    /// how many objects are left. Returns `None` if all objects were processed already.
+    pub fn process_remaining(
+        &mut self,
+        state: FruitsScoreState,
+    ) -> Option<FruitsPerformanceAttributes> {
+        self.process_next_n_objects(state, usize::MAX)
+    }
+
+    /// The maximum combo that could have been achieved up to the last processed hit object.
+    ///
+    /// Suitable to display a play's combo alongside the combo that was possible so far.
+    #[inline]
+    pub fn max_combo(&self) -> usize {
+        self.difficulty.max_combo()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[allow(unused_imports)]
     use super::*;
 
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn max_state_eq_default() {
+        let map = Beatmap::from_path("./maps/2118524.osu").expect("failed to parse map");
+        let attributes = crate::fruits::stars(&map, 0, None);
+        let state = FruitsScoreState::max_for(&attributes);
+
+        assert_eq!(state.max_combo, attributes.max_combo());
+        assert_eq!(state.n_fruits, attributes.n_fruits);
+
+        let regular = FruitsPP::new(&map).calculate();
+        let max = FruitsPP::new(&map).state(state).calculate();
+
+        assert_eq!(regular, max);
+    }
+
     #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
     #[test]
     fn correct_empty() {
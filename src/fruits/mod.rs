@@ -7,6 +7,7 @@ mod gradual_difficulty;
 mod gradual_performance;
 mod movement;
 mod pp;
+mod preprocessed;
 mod slider_state;
 
 use catch_object::CatchObject;
@@ -15,10 +16,23 @@ use fruit_or_juice::FruitOrJuice;
 pub use gradual_difficulty::*;
 pub use gradual_performance::*;
 use movement::Movement;
+pub(crate) use movement::MOVEMENT_PARAMETERS;
 pub use pp::*;
+pub use preprocessed::FruitsPreprocessedMap;
 use slider_state::SliderState;
 
-use crate::{curve::CurveBuffers, fruits::fruit_or_juice::FruitParams, Beatmap, Mods, Strains};
+use std::fmt;
+
+#[cfg(feature = "research")]
+use crate::research::ParameterError;
+
+use crate::{
+    curve::CurveBuffers,
+    fruits::fruit_or_juice::FruitParams,
+    research::FruitsParameters,
+    timings::{Phase, Timer},
+    Beatmap, Mods, Strains,
+};
 
 const SECTION_LENGTH: f64 = 750.0;
 const STAR_SCALING_FACTOR: f64 = 0.153;
@@ -34,30 +48,124 @@ pub fn stars(
     mods: impl Mods,
     passed_objects: Option<usize>,
 ) -> FruitsDifficultyAttributes {
-    let (mut movement, mut attributes) = calculate_movement(map, mods, passed_objects);
-    attributes.stars =
-        Movement::difficulty_value(&mut movement.strain_peaks).sqrt() * STAR_SCALING_FACTOR;
+    calculate_stars(map, mods, passed_objects, &FruitsParameters::DEFAULT)
+}
+
+/// Same as [`stars`] but the skills use the given parameters instead of the regular ones.
+///
+/// Returns an error if any parameter is outside of its valid range.
+#[cfg(feature = "research")]
+#[cfg_attr(docsrs, doc(cfg(feature = "research")))]
+pub fn stars_with_parameters(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &FruitsParameters,
+) -> Result<FruitsDifficultyAttributes, ParameterError> {
+    params.validate()?;
+
+    Ok(calculate_stars(map, mods, passed_objects, params))
+}
+
+fn calculate_stars(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &FruitsParameters,
+) -> FruitsDifficultyAttributes {
+    trace_span!(
+        "difficulty",
+        mode = "fruits",
+        map = format_args!("{:032x}", crate::CalcKey::fingerprint(map)),
+        mods = crate::mods::legacy_bits(mods),
+        passed_objects = ?passed_objects,
+        n_objects = map.hit_objects.len(),
+    );
+
+    let (mut movement, mut attributes) = calculate_movement(map, mods, passed_objects, params);
+    attributes.stars = star_rating(&mut movement);
 
     attributes
 }
 
+fn star_rating(movement: &mut Movement) -> f64 {
+    let timer = Timer::start();
+    let mut strain_peaks = crate::widen_strains(std::mem::take(&mut movement.strain_peaks));
+    let stars = Movement::difficulty_value(&mut strain_peaks, &movement.params).sqrt()
+        * STAR_SCALING_FACTOR;
+    timer.finish(Phase::Evaluation);
+
+    stars
+}
+
 /// Essentially the same as the [`stars`] function but instead of
 /// evaluating the final strains, it just returns them as is.
 ///
 /// Suitable to plot the difficulty of a map over time.
-pub fn strains(map: &Beatmap, mods: impl Mods) -> Strains {
-    let (movement, _) = calculate_movement(map, mods, None);
+///
+/// In case of a partial play, e.g. a live play that is still ongoing,
+/// one can specify the amount of passed objects.
+pub fn strains(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize>) -> Strains {
+    let (movement, _) = calculate_movement(map, mods, passed_objects, &FruitsParameters::DEFAULT);
 
     Strains {
         section_length: SECTION_LENGTH * mods.speed(),
-        strains: movement.strain_peaks,
+        strains: crate::widen_strains(movement.strain_peaks),
     }
 }
 
+/// The strain peaks of each skill of an osu!ctb map, see [`skill_strains`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FruitsSkillStrains {
+    /// Strain peaks of the movement skill.
+    pub movement: Vec<f64>,
+}
+
+/// Calculate the strain peaks of each skill of an osu!ctb map.
+///
+/// The peaks of each skill are sorted in descending order and not weighted,
+/// so that the star rating can be recomputed with alternative weightings.
+///
+/// In case of a partial play, e.g. a fail, one can specify the amount of passed objects.
+pub fn skill_strains(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+) -> FruitsSkillStrains {
+    let (movement, _) = calculate_movement(map, mods, passed_objects, &FruitsParameters::DEFAULT);
+    let mut peaks = crate::widen_strains(movement.strain_peaks);
+    crate::sort_strain_peaks(&mut peaks);
+
+    FruitsSkillStrains { movement: peaks }
+}
+
+/// The amount of fruits and droplets, i.e. the objects that calculations consider
+/// as passed objects, that start at or before the given time.
+pub(crate) fn passed_objects_until(map: &Beatmap, time: f64) -> usize {
+    let mut params = FruitParams {
+        attributes: FruitsDifficultyAttributes::default(),
+        curve_bufs: CurveBuffers::default(),
+        last_pos: None,
+        last_time: 0.0,
+        map,
+        slider_state: SliderState::new(map),
+        ticks: Vec::new(),
+        with_hr: false,
+    };
+
+    map.hit_objects
+        .iter()
+        .filter_map(|h| FruitOrJuice::new(h, &mut params))
+        .flatten()
+        .take_while(|h| h.time <= time)
+        .count()
+}
+
 fn calculate_movement(
     map: &Beatmap,
     mods: impl Mods,
     passed_objects: Option<usize>,
+    skill_params: &FruitsParameters,
 ) -> (Movement, FruitsDifficultyAttributes) {
     let take = passed_objects.unwrap_or(usize::MAX);
 
@@ -80,13 +188,29 @@ fn calculate_movement(
     };
 
     // BUG: Incorrect object order on 2B maps that have fruits within sliders
-    let mut hit_objects = map
+    let hit_objects = map
         .hit_objects
         .iter()
         .filter_map(|h| FruitOrJuice::new(h, &mut params))
         .flatten()
         .take(take);
 
+    let movement = process_movement(map, mods, hit_objects, skill_params);
+
+    (movement, params.attributes)
+}
+
+/// Process the objects with the movement skill.
+///
+/// The objects must already be adjusted for HR.
+fn process_movement(
+    map: &Beatmap,
+    mods: impl Mods,
+    mut hit_objects: impl Iterator<Item = CatchObject>,
+    skill_params: &FruitsParameters,
+) -> Movement {
+    let map_attributes = map.attributes().mods(mods);
+
     // Hyper dash business
     let half_catcher_width =
         (calculate_catch_width(map_attributes.cs as f32) / 2.0 / ALLOWED_CATCH_RANGE) as f64;
@@ -94,11 +218,11 @@ fn calculate_movement(
     let mut last_excess = half_catcher_width;
 
     // Strain business
-    let mut movement = Movement::new(map_attributes.cs as f32);
+    let mut movement = Movement::new(map_attributes.cs as f32, skill_params.movement);
 
     let (mut prev, curr) = match (hit_objects.next(), hit_objects.next()) {
         (Some(prev), Some(curr)) => (prev, curr),
-        (Some(_), None) | (None, None) => return (movement, params.attributes),
+        (Some(_), None) | (None, None) => return movement,
         (None, Some(_)) => unreachable!(),
     };
 
@@ -153,7 +277,7 @@ fn calculate_movement(
 
     movement.save_current_peak();
 
-    (movement, params.attributes)
+    movement
 }
 
 #[inline]
@@ -176,6 +300,8 @@ pub struct FruitsDifficultyAttributes {
     pub n_droplets: usize,
     /// The amount of tiny droplets.
     pub n_tiny_droplets: usize,
+    /// The release and formulas that calculated the attributes.
+    pub provenance: crate::Provenance,
 }
 
 impl FruitsDifficultyAttributes {
@@ -184,6 +310,42 @@ impl FruitsDifficultyAttributes {
     pub fn max_combo(&self) -> usize {
         self.n_fruits + self.n_droplets
     }
+
+    /// The amount of judgements that the accuracy is divided by,
+    /// i.e. the amount of fruits, droplets, and tiny droplets.
+    ///
+    /// Missed tiny droplets count towards the accuracy as well.
+    #[inline]
+    pub fn n_accuracy_objects(&self) -> usize {
+        self.n_fruits + self.n_droplets + self.n_tiny_droplets
+    }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let eq = |a, b| crate::approx_eq_f64(a, b, epsilon);
+
+        eq(self.stars, other.stars)
+            && eq(self.ar, other.ar)
+            && self.n_fruits == other.n_fruits
+            && self.n_droplets == other.n_droplets
+            && self.n_tiny_droplets == other.n_tiny_droplets
+    }
+
+    /// Adjust the attributes that were calculated with `old_mods` so that they
+    /// apply to `new_mods` without recalculating the difficulty.
+    ///
+    /// Mods such as HD or NF do not affect the difficulty so the attributes stay the same.
+    ///
+    /// Returns `None` if a full recalculation is required, i.e. if EZ, HR, or the clock rate changed.
+    #[inline]
+    pub fn adjust_mods(&self, old_mods: impl Mods, new_mods: impl Mods) -> Option<Self> {
+        let unchanged = old_mods.ez() == new_mods.ez()
+            && old_mods.hr() == new_mods.hr()
+            && old_mods.speed() == new_mods.speed();
+
+        unchanged.then(|| self.clone())
+    }
 }
 
 /// The result of a performance calculation on an osu!ctb map.
@@ -213,6 +375,26 @@ impl FruitsPerformanceAttributes {
     pub fn max_combo(&self) -> usize {
         self.difficulty.max_combo()
     }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.difficulty.approx_eq(&other.difficulty, epsilon)
+            && crate::approx_eq_f64(self.pp, other.pp, epsilon)
+    }
+}
+
+impl fmt::Display for FruitsDifficultyAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}★ max combo {}", self.stars, self.max_combo())
+    }
+}
+
+impl fmt::Display for FruitsPerformanceAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.0}pp", self.pp)
+    }
 }
 
 impl From<FruitsPerformanceAttributes> for FruitsDifficultyAttributes {
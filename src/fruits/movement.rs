@@ -1,4 +1,9 @@
 use super::DifficultyObject;
+use crate::{
+    research::SkillParameters,
+    timings::{Phase, Timer},
+    StrainValue,
+};
 
 use std::cmp::Ordering;
 
@@ -6,9 +11,13 @@ const ABSOLUTE_PLAYER_POSITIONING_ERROR: f32 = 16.0;
 const NORMALIZED_HITOBJECT_RADIUS: f32 = 41.0;
 const POSITION_EPSILON: f32 = NORMALIZED_HITOBJECT_RADIUS - ABSOLUTE_PLAYER_POSITIONING_ERROR;
 const DIRECTION_CHANGE_BONUS: f64 = 21.0;
-const SKILL_MULTIPLIER: f64 = 900.0;
-const STRAIN_DECAY_BASE: f64 = 0.2;
-const DECAY_WEIGHT: f64 = 0.94;
+
+pub(crate) const MOVEMENT_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 900.0,
+    strain_decay_base: 0.2,
+    decay_weight: 0.94,
+    difficulty_multiplier: 1.0,
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct Movement {
@@ -21,13 +30,15 @@ pub(crate) struct Movement {
     current_strain: f64,
     pub(crate) curr_section_peak: f64,
 
-    pub(crate) strain_peaks: Vec<f64>,
+    pub(crate) strain_peaks: Vec<StrainValue>,
     prev_time: Option<f64>,
+
+    pub(crate) params: SkillParameters,
 }
 
 impl Movement {
     #[inline]
-    pub(crate) fn new(cs: f32) -> Self {
+    pub(crate) fn new(cs: f32, params: SkillParameters) -> Self {
         let mut half_catcher_width = super::calculate_catch_width(cs) * 0.5;
         half_catcher_width *= 1.0 - ((cs - 5.5).max(0.0) * 0.0625);
 
@@ -43,12 +54,15 @@ impl Movement {
 
             strain_peaks: Vec::with_capacity(128),
             prev_time: None,
+
+            params,
         }
     }
 
     #[inline]
     pub(crate) fn save_current_peak(&mut self) {
-        self.strain_peaks.push(self.curr_section_peak);
+        self.strain_peaks
+            .push(self.curr_section_peak as StrainValue);
     }
 
     #[inline]
@@ -57,13 +71,15 @@ impl Movement {
     }
 
     pub(crate) fn process(&mut self, current: &DifficultyObject<'_>) {
-        self.current_strain *= strain_decay(current.delta);
-        self.current_strain += self.strain_value_of(current) * SKILL_MULTIPLIER;
+        let timer = Timer::start();
+        self.current_strain *= self.strain_decay(current.delta);
+        self.current_strain += self.strain_value_of(current) * self.params.skill_multiplier;
         self.curr_section_peak = self.current_strain.max(self.curr_section_peak);
         self.prev_time.replace(current.start_time);
+        timer.finish(Phase::Skill("movement"));
     }
 
-    pub(crate) fn difficulty_value(strain_peaks: &mut [f64]) -> f64 {
+    pub(crate) fn difficulty_value(strain_peaks: &mut [f64], params: &SkillParameters) -> f64 {
         let mut difficulty = 0.0;
         let mut weight = 1.0;
 
@@ -71,10 +87,10 @@ impl Movement {
 
         for &strain in strain_peaks.iter() {
             difficulty += strain * weight;
-            weight *= DECAY_WEIGHT;
+            weight *= params.decay_weight;
         }
 
-        difficulty
+        difficulty * params.difficulty_multiplier
     }
 
     fn strain_value_of(&mut self, current: &DifficultyObject<'_>) -> f64 {
@@ -134,11 +150,11 @@ impl Movement {
 
     #[inline]
     fn peak_strain(&self, delta_time: f64) -> f64 {
-        self.current_strain * strain_decay(delta_time)
+        self.current_strain * self.strain_decay(delta_time)
     }
-}
 
-#[inline]
-fn strain_decay(ms: f64) -> f64 {
-    STRAIN_DECAY_BASE.powf(ms / 1000.0)
+    #[inline]
+    fn strain_decay(&self, ms: f64) -> f64 {
+        self.params.strain_decay_base.powf(ms / 1000.0)
+    }
 }
@@ -1,12 +1,15 @@
 use super::{stars, FruitsDifficultyAttributes, FruitsPerformanceAttributes, FruitsScoreState};
-use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
+use crate::{
+    timings::{Phase, Timer},
+    Beatmap, DifficultyAttributes, InputError, Mods, PerformanceAttributes,
+};
 
 /// Performance calculator on osu!ctb maps.
 ///
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{FruitsPP, Beatmap};
+/// use akatsuki_pp::{FruitsPP, Beatmap};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -33,7 +36,7 @@ use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
 #[derive(Clone, Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct FruitsPP<'map> {
-    map: &'map Beatmap,
+    pub(crate) map: &'map Beatmap,
     attributes: Option<FruitsDifficultyAttributes>,
     mods: u32,
     combo: Option<usize>,
@@ -44,6 +47,7 @@ pub struct FruitsPP<'map> {
     pub(crate) n_tiny_droplet_misses: Option<usize>,
     pub(crate) n_misses: usize,
     passed_objects: Option<usize>,
+    strict: bool,
 }
 
 impl<'map> FruitsPP<'map> {
@@ -62,6 +66,7 @@ impl<'map> FruitsPP<'map> {
             n_tiny_droplet_misses: None,
             n_misses: 0,
             passed_objects: None,
+            strict: false,
         }
     }
 
@@ -147,6 +152,21 @@ impl<'map> FruitsPP<'map> {
         self
     }
 
+    /// Enable or disable strict mode.
+    ///
+    /// In strict mode, input that can not stem from an actual play, e.g. incompatible mods
+    /// like DT and HT or more hitresults than objects, is reported as [`InputError`]
+    /// by [`try_calculate`](FruitsPP::try_calculate) and causes [`calculate`](FruitsPP::calculate)
+    /// to panic instead of silently producing meaningless values.
+    ///
+    /// Disabled by default.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+
+        self
+    }
+
     /// Provide parameters through an [`FruitsScoreState`].
     #[inline]
     pub fn state(mut self, state: FruitsScoreState) -> Self {
@@ -281,14 +301,99 @@ impl<'map> FruitsPP<'map> {
         }
     }
 
+    /// Calculate all performance related values just like [`calculate`](FruitsPP::calculate)
+    /// while also measuring how much time each calculation phase took.
+    #[cfg(feature = "timings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+    pub fn calculate_with_timings(self) -> (FruitsPerformanceAttributes, crate::CalcTimings) {
+        crate::timings::measure(|| self.calculate())
+    }
+
+    /// Calculate the performance of each given score state on the same map.
+    ///
+    /// The difficulty attributes are calculated only once and shared across all
+    /// score states instead of paying for them on each calculation.
+    /// All other parameters, e.g. mods or passed objects, apply to every score state.
+    pub fn calculate_many(
+        mut self,
+        states: &[FruitsScoreState],
+    ) -> Vec<FruitsPerformanceAttributes> {
+        let attributes = self
+            .attributes
+            .take()
+            .unwrap_or_else(|| stars(self.map, self.mods, self.passed_objects));
+        self.attributes = Some(attributes);
+
+        states
+            .iter()
+            .map(|state| self.clone().state(state.clone()).calculate())
+            .collect()
+    }
+
     /// Calculate all performance related values, including pp and stars.
-    pub fn calculate(mut self) -> FruitsPerformanceAttributes {
+    ///
+    /// # Panics
+    ///
+    /// Panics in [strict](FruitsPP::strict) mode if the input is invalid.
+    #[track_caller]
+    pub fn calculate(self) -> FruitsPerformanceAttributes {
+        match self.try_calculate() {
+            Ok(performance) => performance,
+            Err(err) => panic!("invalid osu!ctb input: {}", err),
+        }
+    }
+
+    /// Calculate all performance related values just like [`calculate`](FruitsPP::calculate)
+    /// but in [strict](FruitsPP::strict) mode, return an error if the input is invalid.
+    ///
+    /// Outside of strict mode, this never fails.
+    pub fn try_calculate(mut self) -> Result<FruitsPerformanceAttributes, InputError> {
+        trace_span!(
+            "performance",
+            mode = "fruits",
+            map = format_args!("{:032x}", crate::CalcKey::fingerprint(self.map)),
+            mods = self.mods,
+        );
+
+        // Incompatible mods are reported before spending time on the difficulty
+        if self.strict {
+            InputError::check_mods(self.mods)?;
+        }
+
         let attributes = self
             .attributes
             .take()
             .unwrap_or_else(|| stars(self.map, self.mods, self.passed_objects));
 
-        self.assert_hitresults(attributes).calculate()
+        if self.strict {
+            self.validate(&attributes)?;
+        }
+
+        // Maps with fewer than two objects have no difficulty to reward
+        if !self.map.is_calculable_until(self.passed_objects) {
+            return Ok(FruitsPerformanceAttributes {
+                difficulty: attributes,
+                ..Default::default()
+            });
+        }
+
+        let timer = Timer::start();
+        let performance = self.assert_hitresults(attributes).calculate();
+        timer.finish(Phase::Evaluation);
+
+        Ok(performance)
+    }
+
+    fn validate(&self, attributes: &FruitsDifficultyAttributes) -> Result<(), InputError> {
+        let max_combo = attributes.max_combo();
+        let given = self.n_fruits.unwrap_or(0) + self.n_droplets.unwrap_or(0) + self.n_misses;
+        InputError::check_hitresults(given, max_combo)?;
+
+        let given_tiny =
+            self.n_tiny_droplets.unwrap_or(0) + self.n_tiny_droplet_misses.unwrap_or(0);
+        InputError::check_hitresults(given_tiny, attributes.n_tiny_droplets)?;
+
+        InputError::check_combo(self.combo, max_combo)
     }
 }
 
@@ -393,9 +498,7 @@ impl FruitsPPInner {
         if total_hits == 0 {
             1.0
         } else {
-            (self.successful_hits() as f64 / total_hits as f64)
-                .max(0.0)
-                .min(1.0)
+            (self.successful_hits() as f64 / total_hits as f64).clamp(0.0, 1.0)
         }
     }
 }
@@ -458,6 +561,31 @@ mod test {
         }
     }
 
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn completion_counts_catch_objects() {
+        use crate::AnyPP;
+
+        let map = Beatmap::from_path("./maps/2118524.osu").expect("failed to parse map");
+        let full = AnyPP::new(&map).calculate();
+
+        let almost = AnyPP::new(&map).completion(99.9).calculate();
+        assert!((almost.stars() - full.stars()).abs() < 0.01);
+        assert!((almost.pp() - full.pp()).abs() < 1.0);
+
+        assert_eq!(AnyPP::new(&map).completion(100.0).calculate(), full);
+
+        let half = AnyPP::new(&map).completion(50.0).calculate();
+        let fruits = FruitsPP::new(&map)
+            .passed_objects(crate::fruits::passed_objects_until(
+                &map,
+                map.completion_time(50.0).unwrap(),
+            ))
+            .calculate();
+        assert_eq!(half.pp(), fruits.pp);
+        assert!(half.stars() < full.stars());
+    }
+
     #[test]
     fn fruits_only_accuracy() {
         let map = Beatmap::default();
@@ -0,0 +1,208 @@
+use super::{
+    catch_object::CatchObject,
+    fruit_or_juice::{FruitOrJuice, FruitParams},
+    process_movement, star_rating, FruitsDifficultyAttributes, FruitsGradualDifficultyAttributes,
+    FruitsPP, SliderState,
+};
+
+use crate::{
+    curve::CurveBuffers,
+    parse::HitObjectKind,
+    research::FruitsParameters,
+    timings::{Phase, Timer},
+    Beatmap, Mods,
+};
+
+/// An osu!ctb map whose fruits, droplets, and tiny droplets have been generated once.
+///
+/// Generating the objects, in particular the juice streams of sliders, takes up
+/// a large share of the difficulty calculation. Since it only depends on whether
+/// HR is enabled, both variants are generated once and reused for all mods.
+///
+/// ```
+/// use akatsuki_pp::{fruits::FruitsPreprocessedMap, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// let preprocessed = FruitsPreprocessedMap::new(&map);
+///
+/// for mods in [0, 16, 64, 16 + 64] {
+///     let stars = preprocessed.stars(mods, None).stars;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FruitsPreprocessedMap<'map> {
+    map: &'map Beatmap,
+    hit_objects: Vec<CatchObject>,
+    hr_hit_objects: Vec<CatchObject>,
+    counts: Vec<ObjectCounts>,
+}
+
+/// The amount of objects that a single hit object generated.
+#[derive(Copy, Clone, Debug)]
+struct ObjectCounts {
+    first_idx: usize,
+    n_fruits: usize,
+    n_droplets: usize,
+    n_tiny_droplets: usize,
+}
+
+impl<'map> FruitsPreprocessedMap<'map> {
+    /// Prepare the map.
+    pub fn new(map: &'map Beatmap) -> Self {
+        let mut params = FruitParams {
+            attributes: FruitsDifficultyAttributes::default(),
+            curve_bufs: CurveBuffers::default(),
+            last_pos: None,
+            last_time: 0.0,
+            map,
+            slider_state: SliderState::new(map),
+            ticks: Vec::new(),
+            with_hr: false,
+        };
+
+        let timer = Timer::start();
+
+        let mut hit_objects = Vec::with_capacity(map.hit_objects.len());
+        let mut hr_hit_objects = Vec::with_capacity(map.hit_objects.len());
+        let mut counts = Vec::new();
+
+        // HR offsets fruits w.r.t. the previous fruit or slider
+        let mut hr_last_pos = None;
+        let mut hr_last_time = 0.0;
+
+        for h in map.hit_objects.iter() {
+            let prev = params.attributes.clone();
+
+            let objects = match FruitOrJuice::new(h, &mut params) {
+                Some(objects) => objects,
+                None => continue,
+            };
+
+            counts.push(ObjectCounts {
+                first_idx: hit_objects.len(),
+                n_fruits: params.attributes.n_fruits - prev.n_fruits,
+                n_droplets: params.attributes.n_droplets - prev.n_droplets,
+                n_tiny_droplets: params.attributes.n_tiny_droplets - prev.n_tiny_droplets,
+            });
+
+            match objects {
+                FruitOrJuice::Fruit(fruit) => {
+                    if let Some(fruit) = fruit {
+                        hit_objects.push(fruit.clone());
+                        hr_hit_objects.push(fruit.with_hr(&mut hr_last_pos, &mut hr_last_time));
+                    }
+                }
+                FruitOrJuice::Juice(juice) => {
+                    if let HitObjectKind::Slider { control_points, .. } = &h.kind {
+                        hr_last_pos =
+                            Some(h.pos.x + control_points[control_points.len() - 1].pos.x);
+                        hr_last_time = h.start_time;
+                    }
+
+                    for droplet in juice {
+                        hit_objects.push(droplet.clone());
+                        hr_hit_objects.push(droplet);
+                    }
+                }
+            }
+        }
+
+        timer.finish(Phase::Preprocessing);
+
+        Self {
+            map,
+            hit_objects,
+            hr_hit_objects,
+            counts,
+        }
+    }
+
+    /// The map that was prepared.
+    #[inline]
+    pub fn map(&self) -> &'map Beatmap {
+        self.map
+    }
+
+    /// Calculate the difficulty attributes for the given mods.
+    ///
+    /// The result is the same as the one of [`stars`](super::stars).
+    pub fn stars(
+        &self,
+        mods: impl Mods,
+        passed_objects: Option<usize>,
+    ) -> FruitsDifficultyAttributes {
+        let take = passed_objects.unwrap_or(usize::MAX);
+
+        let mut attributes = FruitsDifficultyAttributes {
+            ar: self.map.attributes().mods(mods).ar,
+            ..Default::default()
+        };
+
+        // Just like the regular calculation, a hit object counts
+        // as soon as any of its generated objects is processed
+        for counts in self.counts.iter().take_while(|c| c.first_idx < take) {
+            attributes.n_fruits += counts.n_fruits;
+            attributes.n_droplets += counts.n_droplets;
+            attributes.n_tiny_droplets += counts.n_tiny_droplets;
+        }
+
+        let hit_objects = if mods.hr() {
+            &self.hr_hit_objects
+        } else {
+            &self.hit_objects
+        };
+
+        let hit_objects = hit_objects.iter().take(take).cloned();
+        let mut movement =
+            process_movement(self.map, mods, hit_objects, &FruitsParameters::DEFAULT);
+        attributes.stars = star_rating(&mut movement);
+
+        attributes
+    }
+
+    /// Return a performance calculator for the given mods
+    /// whose difficulty attributes are calculated on the prepared objects.
+    ///
+    /// The attributes are those of the full map so for partial plays
+    /// use [`stars`](FruitsPreprocessedMap::stars) with the amount of passed objects instead.
+    #[inline]
+    pub fn pp(&self, mods: u32) -> FruitsPP<'map> {
+        FruitsPP::new(self.map)
+            .mods(mods)
+            .attributes(self.stars(mods, None))
+    }
+
+    /// Return an iterator that gives the difficulty attributes after each fruit or droplet,
+    /// see [`FruitsGradualDifficultyAttributes`].
+    ///
+    /// The iterator generates its objects on its own and does not reuse the prepared ones.
+    #[inline]
+    pub fn gradual_difficulty(&self, mods: impl Mods) -> FruitsGradualDifficultyAttributes<'map> {
+        FruitsGradualDifficultyAttributes::new(self.map, mods)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn preprocessed_matches_regular() {
+        let map = Beatmap::from_path("./maps/2118524.osu").expect("failed to parse map");
+        let preprocessed = FruitsPreprocessedMap::new(&map);
+
+        for mods in [0, 2, 16, 64, 256, 16 + 64] {
+            for passed_objects in [None, Some(1), Some(3), Some(100), Some(101)] {
+                let expected = super::super::stars(&map, mods, passed_objects);
+                let actual = preprocessed.stars(mods, passed_objects);
+
+                assert_eq!(actual, expected, "+{} {:?}", mods, passed_objects);
+            }
+        }
+
+        let expected = FruitsPP::new(&map).mods(16).calculate();
+        assert_eq!(preprocessed.pp(16).calculate(), expected);
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{Beatmap, DifficultyAttributes, GameMode, Mods, PerformanceAttributes};
+use std::marker::PhantomData;
+
+use crate::{Beatmap, DifficultyAttributes, GameMode, Mods, PerformanceAttributes, Strains};
+
+#[cfg(any(feature = "fruits", feature = "taiko"))]
+use crate::InputError;
 
 #[cfg(feature = "fruits")]
 use crate::fruits::{
@@ -28,7 +33,7 @@ use crate::taiko::{
 /// # Example
 ///
 /// ```no_run
-/// use rosu_pp::{Beatmap, GradualDifficultyAttributes};
+/// use akatsuki_pp::{Beatmap, GradualDifficultyAttributes};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -78,6 +83,49 @@ impl<'map> GradualDifficultyAttributes<'map> {
             _ => panic!("feature for mode {:?} is not enabled", map.mode),
         }
     }
+
+    /// The strains of all processed hit objects, including the section of the
+    /// current object, as [`BeatmapExt::strains`](crate::BeatmapExt::strains) would return them.
+    ///
+    /// Suitable to draw the evolving strain graph during a play
+    /// without calculating the strains from scratch.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, GradualDifficultyAttributes};
+    ///
+    /// # let map = Beatmap::default();
+    /// let mut iter = GradualDifficultyAttributes::new(&map, 0);
+    ///
+    /// while let Some(attributes) = iter.next() {
+    ///     let strains = iter.current_strains();
+    ///     // redraw the strain graph...
+    /// }
+    /// ```
+    #[inline]
+    pub fn current_strains(&self) -> Strains {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => f.current_strains(),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => m.current_strains(),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => o.current_strains(),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => t.current_strains(),
+        }
+    }
+
+    /// Turn this iterator into one that yields how much each hit object changed the star rating.
+    ///
+    /// See [`StarDeltas`].
+    #[inline]
+    pub fn star_deltas(self) -> StarDeltas<'map> {
+        StarDeltas {
+            difficulty: self,
+            prev_stars: 0.0,
+            _map: PhantomData,
+        }
+    }
 }
 
 impl Iterator for GradualDifficultyAttributes<'_> {
@@ -112,6 +160,142 @@ impl Iterator for GradualDifficultyAttributes<'_> {
     }
 }
 
+/// Yields the change in star rating caused by each hit object of a map.
+/// For osu!ctb, each fruit and droplet counts as an object.
+///
+/// Since strain peaks are re-weighted after every hit object, an object's delta
+/// includes its effect on the weighting of previous peaks and may be negative.
+/// The sum of all deltas is the map's final star rating.
+///
+/// Suitable to find the patterns that drive a map's difficulty.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{Beatmap, GradualDifficultyAttributes};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+///
+/// let deltas = GradualDifficultyAttributes::new(&map, 0).star_deltas();
+///
+/// // Index of the hit object that increased the stars the most
+/// let hardest = deltas
+///     .enumerate()
+///     .max_by(|(_, a), (_, b)| a.total_cmp(b))
+///     .map(|(idx, _)| idx);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StarDeltas<'map> {
+    difficulty: GradualDifficultyAttributes<'map>,
+    prev_stars: f64,
+    // The osu!standard calculator does not borrow the map
+    _map: PhantomData<&'map ()>,
+}
+
+impl Iterator for StarDeltas<'_> {
+    type Item = f64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let stars = self.difficulty.next()?.stars();
+        let delta = stars - self.prev_stars;
+        self.prev_stars = stars;
+
+        Some(delta)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.difficulty.size_hint()
+    }
+}
+
+/// The difficulty attributes after some amount of passed objects, alongside
+/// the state required to continue the calculation from there on.
+///
+/// Calculating the attributes of a partial play through [`BeatmapExt::stars`]
+/// always starts at the first hit object. When tracking ongoing plays at
+/// multiple checkpoints, resuming a [`DifficultyCheckpoint`] instead only
+/// processes the objects that have been passed since the previous checkpoint.
+///
+/// For osu!ctb, each fruit and droplet counts as an object.
+///
+/// [`BeatmapExt::stars`]: crate::BeatmapExt::stars
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{Beatmap, DifficultyCheckpoint};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+///
+/// let mut checkpoint = DifficultyCheckpoint::new(&map, 0);
+///
+/// // The attributes after the first 100 objects
+/// let attrs = checkpoint.stars_resume(100).cloned();
+///
+/// // Only processes objects 101 to 150
+/// let attrs = checkpoint.stars_resume(50).cloned();
+/// assert!(checkpoint.passed_objects() <= 150);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DifficultyCheckpoint<'map> {
+    difficulty: GradualDifficultyAttributes<'map>,
+    attributes: Option<DifficultyAttributes>,
+    passed_objects: usize,
+    // The osu!standard calculator does not borrow the map
+    _map: PhantomData<&'map ()>,
+}
+
+impl<'map> DifficultyCheckpoint<'map> {
+    /// Create a new checkpoint before the first hit object.
+    #[inline]
+    pub fn new(map: &'map Beatmap, mods: impl Mods) -> Self {
+        Self {
+            difficulty: GradualDifficultyAttributes::new(map, mods),
+            attributes: None,
+            passed_objects: 0,
+            _map: PhantomData,
+        }
+    }
+
+    /// Process the next `additional_objects` many objects and
+    /// return the resulting attributes.
+    ///
+    /// If fewer objects remain, all of them are processed.
+    /// Returns `None` if no object has been processed yet.
+    pub fn stars_resume(&mut self, additional_objects: usize) -> Option<&DifficultyAttributes> {
+        for _ in 0..additional_objects {
+            match self.difficulty.next() {
+                Some(attributes) => self.attributes = Some(attributes),
+                None => break,
+            }
+
+            self.passed_objects += 1;
+        }
+
+        self.attributes.as_ref()
+    }
+
+    /// The attributes of the current checkpoint.
+    #[inline]
+    pub fn attributes(&self) -> Option<&DifficultyAttributes> {
+        self.attributes.as_ref()
+    }
+
+    /// The amount of objects that have been processed so far.
+    #[inline]
+    pub fn passed_objects(&self) -> usize {
+        self.passed_objects
+    }
+}
+
 /// Aggregation for a score's current state i.e. what is
 /// the maximum combo so far, what are the current
 /// hitresults and what is the current score.
@@ -157,12 +341,129 @@ impl ScoreState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Create the score state of a perfect play, i.e. an SS with full combo,
+    /// for the given difficulty attributes.
+    ///
+    /// For osu!mania the score will be 1,000,000 so it must still be adjusted
+    /// if score reducing mods such as EZ, NF, or HT are used.
+    ///
+    /// Paired with the gradual calculators, this gives the performance of a play
+    /// that is perfect up to the current object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, GradualDifficultyAttributes, GradualPerformanceAttributes, ScoreState};
+    ///
+    /// # /*
+    /// let map: Beatmap = ...
+    /// # */
+    /// # let map = Beatmap::default();
+    ///
+    /// let mut difficulty = GradualDifficultyAttributes::new(&map, 0);
+    /// let mut performance = GradualPerformanceAttributes::new(&map, 0);
+    ///
+    /// while let Some(attributes) = difficulty.next() {
+    ///     let ss = performance.process_next_object(ScoreState::max_for(&attributes));
+    /// }
+    /// ```
+    pub fn max_for(attributes: &DifficultyAttributes) -> Self {
+        match attributes {
+            #[cfg(feature = "fruits")]
+            DifficultyAttributes::Fruits(attributes) => Self {
+                max_combo: attributes.max_combo(),
+                n300: attributes.n_fruits,
+                n100: attributes.n_droplets,
+                n50: attributes.n_tiny_droplets,
+                ..Default::default()
+            },
+            #[cfg(feature = "mania")]
+            DifficultyAttributes::Mania(_) => Self {
+                score: 1_000_000,
+                ..Default::default()
+            },
+            #[cfg(feature = "osu")]
+            DifficultyAttributes::Osu(attributes) => Self {
+                max_combo: attributes.max_combo,
+                n300: attributes.n_circles + attributes.n_sliders + attributes.n_spinners,
+                ..Default::default()
+            },
+            #[cfg(feature = "taiko")]
+            DifficultyAttributes::Taiko(attributes) => Self {
+                max_combo: attributes.max_combo,
+                n300: attributes.max_combo,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// The amount of hitresults, i.e. the amount of objects that were judged so far.
+    ///
+    /// Always zero for osu!mania since only the score is relevant.
+    #[inline]
+    pub fn total_hits(&self) -> usize {
+        self.n300 + self.n100 + self.n50 + self.n_katu + self.misses
+    }
+
+    /// The amount of objects that are yet to be judged out of `n_objects`.
+    #[inline]
+    pub fn remaining(&self, n_objects: usize) -> usize {
+        n_objects.saturating_sub(self.total_hits())
+    }
+
+    /// Whether all objects of the given difficulty attributes were judged.
+    ///
+    /// The objects are counted by [`DifficultyAttributes::n_accuracy_objects`]
+    /// so this is always `false` for osu!mania.
+    #[inline]
+    pub fn is_complete(&self, attributes: &DifficultyAttributes) -> bool {
+        match attributes.n_accuracy_objects() {
+            Some(n_objects) => self.remaining(n_objects) == 0,
+            None => false,
+        }
+    }
+}
+
+impl ScoreState {
+    /// Convert the state into an osu!taiko score state.
+    ///
+    /// Unlike the [`From`] conversion, this fails if 50s were specified
+    /// since osu!taiko has no such hitresult and they would be dropped silently.
+    #[cfg(feature = "taiko")]
+    pub fn to_taiko_checked(&self) -> Result<TaikoScoreState, InputError> {
+        if self.n50 > 0 {
+            return Err(InputError::UnsupportedHitresult {
+                name: "n50",
+                amount: self.n50,
+            });
+        }
+
+        Ok(self.into())
+    }
+
+    /// Convert the state into an osu!ctb score state.
+    ///
+    /// Unlike the [`From`] conversion, this fails if the combo exceeds the amount of
+    /// hit fruits and droplets, i.e. `n300 + n100`. osu!ctb combo does not include
+    /// tiny droplets so a higher combo indicates that the hitresults were not mapped
+    /// onto fruits, droplets, tiny droplets (`n50`), and tiny droplet misses (`n_katu`).
+    #[cfg(feature = "fruits")]
+    pub fn to_fruits_checked(&self) -> Result<FruitsScoreState, InputError> {
+        InputError::check_combo(Some(self.max_combo), self.n300 + self.n100)?;
+
+        Ok(self.into())
+    }
 }
 
+/// Maps `n300` onto fruits, `n100` onto droplets, `n50` onto tiny droplets,
+/// and `n_katu` onto tiny droplet misses. The score is ignored.
+///
+/// See [`ScoreState::to_fruits_checked`] for a validated conversion.
 #[cfg(feature = "fruits")]
-impl From<ScoreState> for FruitsScoreState {
+impl From<&ScoreState> for FruitsScoreState {
     #[inline]
-    fn from(state: ScoreState) -> Self {
+    fn from(state: &ScoreState) -> Self {
         Self {
             max_combo: state.max_combo,
             n_fruits: state.n300,
@@ -174,10 +475,19 @@ impl From<ScoreState> for FruitsScoreState {
     }
 }
 
-#[cfg(feature = "osu")]
-impl From<ScoreState> for OsuScoreState {
+#[cfg(feature = "fruits")]
+impl From<ScoreState> for FruitsScoreState {
     #[inline]
     fn from(state: ScoreState) -> Self {
+        Self::from(&state)
+    }
+}
+
+/// Takes the combo, 300s, 100s, 50s, and misses. `n_katu` and the score are ignored.
+#[cfg(feature = "osu")]
+impl From<&ScoreState> for OsuScoreState {
+    #[inline]
+    fn from(state: &ScoreState) -> Self {
         Self {
             max_combo: state.max_combo,
             n300: state.n300,
@@ -188,10 +498,21 @@ impl From<ScoreState> for OsuScoreState {
     }
 }
 
-#[cfg(feature = "taiko")]
-impl From<ScoreState> for TaikoScoreState {
+#[cfg(feature = "osu")]
+impl From<ScoreState> for OsuScoreState {
     #[inline]
     fn from(state: ScoreState) -> Self {
+        Self::from(&state)
+    }
+}
+
+/// Takes the combo, 300s, 100s, and misses. `n50`, `n_katu`, and the score are ignored.
+///
+/// See [`ScoreState::to_taiko_checked`] for a validated conversion.
+#[cfg(feature = "taiko")]
+impl From<&ScoreState> for TaikoScoreState {
+    #[inline]
+    fn from(state: &ScoreState) -> Self {
         Self {
             max_combo: state.max_combo,
             n300: state.n300,
@@ -201,6 +522,14 @@ impl From<ScoreState> for TaikoScoreState {
     }
 }
 
+#[cfg(feature = "taiko")]
+impl From<ScoreState> for TaikoScoreState {
+    #[inline]
+    fn from(state: ScoreState) -> Self {
+        Self::from(&state)
+    }
+}
+
 /// Gradually calculate the performance attributes on maps of any mode.
 ///
 /// After each hit object you can call
@@ -227,7 +556,7 @@ impl From<ScoreState> for TaikoScoreState {
 /// # Example
 ///
 /// ```no_run
-/// use rosu_pp::{Beatmap, GradualPerformanceAttributes, ScoreState};
+/// use akatsuki_pp::{Beatmap, GradualPerformanceAttributes, ScoreState};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -289,10 +618,10 @@ impl From<ScoreState> for TaikoScoreState {
 /// state.max_combo = ...
 /// state.n300 = ...
 /// ...
-/// let final_performance = gradual_perf.process_next_n_objects(state.clone(), usize::MAX).unwrap();
+/// let final_performance = gradual_perf.process_remaining(state.clone()).unwrap();
 /// println!("PP: {}", performance.pp);
 /// # */
-/// # let _ = gradual_perf.process_next_n_objects(state.clone(), usize::MAX);
+/// # let _ = gradual_perf.process_remaining(state.clone());
 ///
 /// // Once the final performance was calculated,
 /// // attempting to process further objects will return `None`.
@@ -367,4 +696,195 @@ impl<'map> GradualPerformanceAttributes<'map> {
                 .map(PerformanceAttributes::Taiko),
         }
     }
+
+    /// Process all remaining hit objects at once and calculate the
+    /// performance attributes for the final score state.
+    ///
+    /// Suitable to finish off a play, e.g. after a fail, without having to know
+    /// how many objects are left. Returns `None` if all objects were processed already.
+    pub fn process_remaining(&mut self, state: ScoreState) -> Option<PerformanceAttributes> {
+        self.process_next_n_objects(state, usize::MAX)
+    }
+
+    /// The maximum combo that could have been achieved up to the last processed hit object.
+    ///
+    /// Returns `None` for osu!mania since the combo is irrelevant for its performance.
+    #[inline]
+    pub fn max_combo(&self) -> Option<usize> {
+        match self {
+            #[cfg(feature = "fruits")]
+            GradualPerformanceAttributes::Fruits(f) => Some(f.max_combo()),
+            #[cfg(feature = "mania")]
+            GradualPerformanceAttributes::Mania(_) => None,
+            #[cfg(feature = "osu")]
+            GradualPerformanceAttributes::Osu(o) => Some(o.max_combo()),
+            #[cfg(feature = "taiko")]
+            GradualPerformanceAttributes::Taiko(t) => Some(t.max_combo()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn state_completion() {
+        use crate::BeatmapExt;
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let attributes = map.stars(0, None);
+        let n_objects = map.hit_objects.len();
+
+        let mut state = ScoreState::max_for(&attributes);
+        assert_eq!(state.total_hits(), n_objects);
+        assert!(state.is_complete(&attributes));
+
+        state.n300 -= 3;
+        state.misses += 1;
+        assert_eq!(state.remaining(n_objects), 2);
+        assert!(!state.is_complete(&attributes));
+
+        let osu_state = OsuScoreState::from(&state);
+        assert_eq!(osu_state.total_hits(), state.total_hits());
+        assert_eq!(ScoreState::new().remaining(n_objects), n_objects);
+    }
+
+    #[cfg(all(feature = "fruits", feature = "taiko"))]
+    #[test]
+    fn checked_state_conversions() {
+        let state = ScoreState {
+            max_combo: 100,
+            n300: 80,
+            n100: 20,
+            n50: 50,
+            n_katu: 3,
+            misses: 1,
+            score: 0,
+        };
+
+        let fruits = state.to_fruits_checked().unwrap();
+        assert_eq!(fruits.n_tiny_droplets, 50);
+        assert_eq!(fruits.n_tiny_droplet_misses, 3);
+        assert_eq!(fruits, FruitsScoreState::from(&state));
+
+        let combo_with_tiny_droplets = ScoreState {
+            max_combo: 150,
+            ..state.clone()
+        };
+
+        assert_eq!(
+            combo_with_tiny_droplets.to_fruits_checked(),
+            Err(InputError::ComboTooHigh {
+                combo: 150,
+                max_combo: 100,
+            })
+        );
+
+        assert_eq!(
+            state.to_taiko_checked(),
+            Err(InputError::UnsupportedHitresult {
+                name: "n50",
+                amount: 50,
+            })
+        );
+
+        let taiko = ScoreState { n50: 0, ..state };
+        assert_eq!(taiko.to_taiko_checked(), Ok(TaikoScoreState::from(&taiko)));
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn checkpoint_matches_partial_stars() {
+        use crate::BeatmapExt;
+
+        // osu!ctb is not covered since its objects are counted differently
+        #[allow(unused_mut)]
+        let mut paths: Vec<&str> = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let mut checkpoint = DifficultyCheckpoint::new(&map, 64);
+
+            assert!(checkpoint.stars_resume(0).is_none(), "{}", path);
+
+            for passed_objects in [50, 120, 200] {
+                let additional = passed_objects - checkpoint.passed_objects();
+                let resumed = checkpoint.stars_resume(additional).unwrap().stars();
+                let expected = map.stars(64, Some(passed_objects)).stars();
+
+                assert_eq!(checkpoint.passed_objects(), passed_objects, "{}", path);
+                assert!((resumed - expected).abs() < 1e-6, "{}", path);
+            }
+
+            checkpoint.stars_resume(usize::MAX);
+            let stars = checkpoint.attributes().unwrap().stars();
+
+            assert!(
+                checkpoint.passed_objects() >= map.hit_objects.len(),
+                "{}",
+                path
+            );
+            assert!(
+                (stars - map.stars(64, None).stars()).abs() < 1e-6,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn current_strains_match_partial_strains() {
+        use crate::BeatmapExt;
+
+        // osu!ctb is not covered since its objects are counted differently
+        #[allow(unused_mut)]
+        let mut paths: Vec<&str> = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let mut gradual = GradualDifficultyAttributes::new(&map, 64);
+            let mut passed_objects = 0;
+
+            assert!(gradual.current_strains().strains.is_empty(), "{}", path);
+
+            while gradual.next().is_some() {
+                passed_objects += 1;
+
+                if passed_objects <= 3 || passed_objects % 50 == 0 {
+                    let expected = map.strains(64, Some(passed_objects));
+                    assert_eq!(gradual.current_strains(), expected, "{}", path);
+                }
+            }
+
+            assert_eq!(gradual.current_strains(), map.strains(64, None), "{}", path);
+        }
+    }
 }
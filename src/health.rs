@@ -0,0 +1,344 @@
+use crate::{util::difficulty_range, Beatmap, GameMode, Mods};
+
+/// The health increase of a GREAT in draining modes.
+const MAX_HEALTH_INCREASE: f64 = 0.05;
+
+/// Gaps between two hit objects of at least this many ms are considered breaks without drain.
+const BREAK_THRESHOLD: f64 = 5000.0;
+
+/// The lowest health of a perfect play may deviate this much from the target.
+const MIN_HEALTH_ERROR: f64 = 0.01;
+
+/// Iterations to find the drain rate.
+const MAX_DRAIN_ITERATIONS: usize = 50;
+
+/// osu!taiko health gains are normalized over this many times the amount of notes.
+const TAIKO_OBJECT_COUNT_FACTOR: f64 = 3.0;
+
+/// The osu!taiko health required to pass the map.
+const TAIKO_PASS_HEALTH: f64 = 0.5;
+
+/// The judgement of a single hit object, see [`simulate`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Judgement {
+    /// A 300, or a fruit for osu!ctb.
+    Great,
+    /// A 100, or a droplet for osu!ctb.
+    Ok,
+    /// A 50, or a tiny droplet for osu!ctb.
+    Meh,
+    /// A miss.
+    Miss,
+}
+
+/// The course of a play's health, see [`simulate`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct HealthResult {
+    /// The index of the judgement at which the play failed, if it did.
+    pub fail_index: Option<usize>,
+    /// The time in ms at which the play failed, if it did.
+    ///
+    /// Unaffected by the clock rate, i.e. the time of the map.
+    pub fail_time: Option<f64>,
+    /// The lowest health during the play, ranging from 0.0 to 1.0.
+    pub min_health: f64,
+    /// The health after the last judgement, ranging from 0.0 to 1.0.
+    pub final_health: f64,
+}
+
+impl HealthResult {
+    /// Whether the play failed.
+    #[inline]
+    pub fn failed(&self) -> bool {
+        self.fail_index.is_some()
+    }
+}
+
+/// Simulate the health of a play to estimate whether and when it failed.
+///
+/// `judgements` contains one judgement per hit object of the map in order.
+/// For osu!taiko, only notes are judged so drum rolls and swells must be left out.
+/// If there are fewer judgements than hit objects, the play is considered to end
+/// after the last judgement, e.g. for a replay that was cut off.
+///
+/// Modeled after osu!lazer's health processors:
+/// - For osu!standard, osu!ctb, and osu!mania, health starts full and drains over time.
+///   The drain rate is chosen such that a perfect play never drops below a target health
+///   which depends on the map's HP. There is no drain during breaks.
+/// - For osu!taiko, health starts empty and accumulates with each hit.
+///   A play fails if its final health is not above one half.
+///
+/// With NF the play never fails. EZ grants extra lives which restore the health,
+/// except for osu!taiko. SD fails on the first miss and PF on the first judgement
+/// other than [`Judgement::Great`].
+///
+/// Since judgements of slider ticks or droplets inside juice streams are not considered
+/// individually, the result is an estimate.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{health::{self, Judgement}, Beatmap};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+///
+/// let judgements = vec![Judgement::Great; map.hit_objects.len()];
+/// let result = health::simulate(&map, 16, &judgements); // HR
+///
+/// assert!(!result.failed());
+/// ```
+pub fn simulate(map: &Beatmap, mods: impl Mods, judgements: &[Judgement]) -> HealthResult {
+    let hp = map.attributes().mods(mods).hp;
+
+    let mut result = match map.mode {
+        GameMode::TKO => simulate_accumulating(map, hp, judgements),
+        GameMode::STD | GameMode::CTB | GameMode::MNA => {
+            simulate_draining(map, mods, hp, judgements)
+        }
+    };
+
+    let forced_fail = judgements.iter().position(|judgement| match judgement {
+        Judgement::Miss => mods.fails_on_miss(),
+        Judgement::Ok | Judgement::Meh => mods.fails_on_imperfect_hit(),
+        Judgement::Great => false,
+    });
+
+    match (forced_fail, result.fail_index) {
+        (Some(idx), Some(fail_idx)) if fail_idx <= idx => {}
+        (Some(idx), _) => {
+            result.fail_index = Some(idx);
+            result.fail_time = judged_times(map).nth(idx);
+        }
+        (None, _) => {}
+    }
+
+    if !mods.can_fail() {
+        result.fail_index = None;
+        result.fail_time = None;
+    }
+
+    result
+}
+
+/// The map times at which each judgeable hit object is judged.
+fn judged_times(map: &Beatmap) -> impl Iterator<Item = f64> + '_ {
+    map.hit_objects
+        .iter()
+        .filter(move |h| map.mode != GameMode::TKO || h.is_circle())
+        .map(move |h| map.object_end_time(h))
+}
+
+fn simulate_draining(
+    map: &Beatmap,
+    mods: impl Mods,
+    hp: f64,
+    judgements: &[Judgement],
+) -> HealthResult {
+    let clock_rate = mods.speed();
+
+    let intervals: Vec<_> = drain_intervals(map, clock_rate).collect();
+    let target_min_health = difficulty_range(hp, 0.3, 0.7, 0.95);
+    let drain_rate = drain_rate(&intervals, target_min_health);
+
+    let miss_decrease = match map.mode {
+        GameMode::STD => difficulty_range(hp, -0.2, -0.125, -0.03),
+        _ => -MAX_HEALTH_INCREASE,
+    };
+
+    let mut health: f64 = 1.0;
+    let mut min_health: f64 = 1.0;
+    let mut lives = mods.extra_lives();
+    let mut fail = None;
+
+    let judged = intervals.iter().zip(judged_times(map)).zip(judgements);
+
+    for (idx, ((drain_duration, time), judgement)) in judged.enumerate() {
+        health -= drain_duration * drain_rate;
+
+        health += match judgement {
+            Judgement::Great => MAX_HEALTH_INCREASE,
+            Judgement::Ok => MAX_HEALTH_INCREASE * 0.5,
+            Judgement::Meh => -MAX_HEALTH_INCREASE * 0.05,
+            Judgement::Miss => miss_decrease,
+        };
+
+        health = health.clamp(0.0, 1.0);
+        min_health = min_health.min(health);
+
+        if health <= 0.0 {
+            if lives > 0 {
+                lives -= 1;
+                health = 1.0;
+            } else if fail.is_none() {
+                fail = Some((idx, time));
+            }
+        }
+    }
+
+    HealthResult {
+        fail_index: fail.map(|(idx, _)| idx),
+        fail_time: fail.map(|(_, time)| time),
+        min_health,
+        final_health: health,
+    }
+}
+
+/// The time in ms, adjusted by the clock rate, that drains health before each judgement.
+fn drain_intervals(map: &Beatmap, clock_rate: f64) -> impl Iterator<Item = f64> + '_ {
+    let mut prev_time = map.hit_objects.first().map_or(0.0, |h| h.start_time);
+
+    map.hit_objects.iter().map(move |h| {
+        let end_time = map.object_end_time(h);
+
+        // Breaks start after the previous judgement and end at the next object's start
+        let gap = h.start_time - prev_time;
+        let drained = if gap >= BREAK_THRESHOLD {
+            end_time - h.start_time
+        } else {
+            end_time - prev_time
+        };
+
+        prev_time = end_time;
+
+        drained.max(0.0) / clock_rate
+    })
+}
+
+/// Find the drain rate per ms such that the lowest health of a
+/// perfect play is close to the target.
+fn drain_rate(intervals: &[f64], target_min_health: f64) -> f64 {
+    if intervals.is_empty() {
+        return 0.0;
+    }
+
+    let mut adjustment = 1.0;
+    let mut rate = 1.0;
+
+    for _ in 0..MAX_DRAIN_ITERATIONS {
+        let mut health: f64 = 1.0;
+        let mut min_health: f64 = 1.0;
+
+        for duration in intervals {
+            health -= duration * rate;
+            min_health = min_health.min(health);
+            health = (health + MAX_HEALTH_INCREASE).min(1.0);
+
+            if min_health < 0.0 {
+                break;
+            }
+        }
+
+        if (min_health - target_min_health).abs() <= MIN_HEALTH_ERROR {
+            break;
+        }
+
+        adjustment *= 2.0;
+        rate += (min_health - target_min_health).signum() / adjustment;
+    }
+
+    rate.max(0.0)
+}
+
+fn simulate_accumulating(map: &Beatmap, hp: f64, judgements: &[Judgement]) -> HealthResult {
+    let n_notes = map.hit_objects.iter().filter(|h| h.is_circle()).count();
+
+    let hit_multiplier = 1.0
+        / (TAIKO_OBJECT_COUNT_FACTOR
+            * n_notes.max(1) as f64
+            * difficulty_range(hp, 0.98, 0.75, 0.5));
+
+    let miss_multiplier = difficulty_range(hp, 0.012, 0.0075, 0.0018);
+
+    let mut health: f64 = 0.0;
+    let mut min_health: f64 = 0.0;
+
+    for judgement in judgements.iter().take(n_notes) {
+        health += match judgement {
+            Judgement::Great => 2.0 * hit_multiplier,
+            Judgement::Ok => 1.1 * hit_multiplier,
+            Judgement::Meh => 0.0,
+            Judgement::Miss => -miss_multiplier,
+        };
+
+        health = health.clamp(0.0, 1.0);
+        min_health = min_health.min(health);
+    }
+
+    let n_judged = judgements.len().min(n_notes);
+
+    let fail = (n_judged > 0 && health <= TAIKO_PASS_HEALTH).then(|| {
+        let idx = n_judged - 1;
+
+        (idx, judged_times(map).nth(idx).unwrap_or(0.0))
+    });
+
+    HealthResult {
+        fail_index: fail.map(|(idx, _)| idx),
+        fail_time: fail.map(|(_, time)| time),
+        min_health,
+        final_health: health,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn osu_health() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let n_objects = map.hit_objects.len();
+
+        let perfect = simulate(&map, 0, &vec![Judgement::Great; n_objects]);
+        assert!(!perfect.failed());
+        assert!(perfect.min_health > 0.2);
+
+        let mut judgements = vec![Judgement::Great; n_objects];
+        judgements[n_objects / 2..].fill(Judgement::Miss);
+
+        let failed = simulate(&map, 0, &judgements);
+        let fail_idx = failed.fail_index.expect("expected a fail");
+        assert!(fail_idx >= n_objects / 2);
+        assert!(failed.fail_time.is_some());
+
+        // Extra lives delay the fail
+        let easy = simulate(&map, 2, &judgements);
+        assert!(!matches!(easy.fail_index, Some(idx) if idx <= fail_idx));
+
+        assert!(!simulate(&map, 1, &judgements).failed());
+
+        let mut judgements = vec![Judgement::Great; n_objects];
+        judgements[10] = Judgement::Miss;
+        assert_eq!(simulate(&map, 32, &judgements).fail_index, Some(10));
+
+        judgements[5] = Judgement::Ok;
+        assert_eq!(simulate(&map, 16384, &judgements).fail_index, Some(5));
+    }
+
+    #[cfg(all(
+        feature = "taiko",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn taiko_health() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let n_notes = map.hit_objects.iter().filter(|h| h.is_circle()).count();
+
+        let perfect = simulate(&map, 0, &vec![Judgement::Great; n_notes]);
+        assert!(!perfect.failed());
+
+        let mut judgements = vec![Judgement::Miss; n_notes];
+        judgements[..n_notes / 4].fill(Judgement::Great);
+
+        let failed = simulate(&map, 0, &judgements);
+        assert_eq!(failed.fail_index, Some(n_notes - 1));
+    }
+}
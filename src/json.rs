@@ -0,0 +1,885 @@
+use std::{error::Error as StdError, fmt, fmt::Write};
+
+use crate::{DifficultyAttributes, PerformanceAttributes, Provenance};
+
+#[cfg(feature = "fruits")]
+use crate::fruits::{FruitsDifficultyAttributes, FruitsPerformanceAttributes};
+
+#[cfg(feature = "mania")]
+use crate::mania::{ManiaDifficultyAttributes, ManiaPerformanceAttributes};
+
+#[cfg(feature = "osu")]
+use crate::osu::{OsuDifficultyAttributes, OsuPerformanceAttributes};
+
+#[cfg(feature = "taiko")]
+use crate::taiko::{TaikoDifficultyAttributes, TaikoPerformanceAttributes};
+
+/// The version of the JSON schema that is written by
+/// [`DifficultyAttributes::to_json`] and [`PerformanceAttributes::to_json`].
+///
+/// The version is only increased if fields are removed or change their meaning.
+/// Added fields keep the version since readers ignore unknown fields.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How deep objects and arrays may be nested before the input is rejected
+/// so that malicious input can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// The error when reading attributes from JSON.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum JsonError {
+    /// The input is not valid JSON.
+    Syntax {
+        /// The byte offset at which the input became invalid.
+        position: usize,
+    },
+    /// A required field is missing or has the wrong type.
+    InvalidField {
+        /// The name of the field.
+        name: &'static str,
+    },
+    /// The input was written by a newer schema than [`SCHEMA_VERSION`].
+    UnsupportedVersion {
+        /// The schema version of the input.
+        version: u32,
+    },
+    /// The mode of the input is unknown or its feature is not enabled.
+    UnsupportedMode {
+        /// The name of the mode.
+        mode: String,
+    },
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { position } => write!(f, "invalid JSON at byte {}", position),
+            Self::InvalidField { name } => write!(f, "missing or invalid field `{}`", name),
+            Self::UnsupportedVersion { version } => write!(
+                f,
+                "schema version {} is newer than the supported version {}",
+                version, SCHEMA_VERSION
+            ),
+            Self::UnsupportedMode { mode } => write!(f, "unsupported mode `{}`", mode),
+        }
+    }
+}
+
+impl StdError for JsonError {}
+
+impl DifficultyAttributes {
+    /// Serialize the attributes as a JSON object.
+    ///
+    /// Next to the attributes' fields, the object contains the [`SCHEMA_VERSION`] as
+    /// `schema_version` and the mode as `mode`, i.e. `osu`, `taiko`, `fruits`, or `mania`.
+    /// The [`Provenance`] is stored as `crate_version` and `formula_version`.
+    /// Field names are part of the schema and do not depend on the names in this crate.
+    ///
+    /// Non-finite values are not valid JSON so they are written as `null` which
+    /// [`from_json`](DifficultyAttributes::from_json) reads as NaN. Hence, infinite
+    /// values do not survive the round-trip.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, BeatmapExt, DifficultyAttributes};
+    ///
+    /// # let map = Beatmap::default();
+    /// let attributes = map.stars(0, None);
+    /// let json = attributes.to_json();
+    ///
+    /// assert_eq!(DifficultyAttributes::from_json(&json).unwrap(), attributes);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut object = ObjectWriter::versioned();
+        write_difficulty(&mut object, self);
+
+        object.finish()
+    }
+
+    /// Deserialize attributes that were written by [`to_json`](DifficultyAttributes::to_json).
+    ///
+    /// Objects and arrays may be nested at most 64 levels deep.
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let object = Object::parse_versioned(json)?;
+
+        read_difficulty(&object)
+    }
+}
+
+impl PerformanceAttributes {
+    /// Serialize the attributes as a JSON object just like
+    /// [`DifficultyAttributes::to_json`].
+    ///
+    /// The difficulty attributes are nested in the `difficulty` field.
+    pub fn to_json(&self) -> String {
+        let mut object = ObjectWriter::versioned();
+
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => {
+                object.str("mode", "fruits");
+                object.f64("pp", attributes.pp);
+                object.object("difficulty", |o| write_fruits(o, &attributes.difficulty));
+            }
+            #[cfg(feature = "mania")]
+            Self::Mania(attributes) => {
+                object.str("mode", "mania");
+                object.f64("pp", attributes.pp);
+                object.f64("pp_acc", attributes.pp_acc);
+                object.f64("pp_strain", attributes.pp_strain);
+                object.object("difficulty", |o| write_mania(o, &attributes.difficulty));
+            }
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => {
+                object.str("mode", "osu");
+                object.f64("pp", attributes.pp);
+                object.f64("pp_acc", attributes.pp_acc);
+                object.f64("pp_aim", attributes.pp_aim);
+                object.f64("pp_flashlight", attributes.pp_flashlight);
+                object.f64("pp_speed", attributes.pp_speed);
+                object.f64("aim_strain_count", attributes.aim_strain_count);
+                object.f64("speed_strain_count", attributes.speed_strain_count);
+                object.object("difficulty", |o| write_osu(o, &attributes.difficulty));
+            }
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => {
+                object.str("mode", "taiko");
+                object.f64("pp", attributes.pp);
+                object.f64("pp_acc", attributes.pp_acc);
+                object.f64("pp_strain", attributes.pp_strain);
+                object.object("difficulty", |o| write_taiko(o, &attributes.difficulty));
+            }
+        }
+
+        object.finish()
+    }
+
+    /// Deserialize attributes that were written by [`to_json`](PerformanceAttributes::to_json).
+    ///
+    /// Objects and arrays may be nested at most 64 levels deep.
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let object = Object::parse_versioned(json)?;
+        let difficulty = object.object("difficulty")?;
+
+        match object.str("mode")? {
+            #[cfg(feature = "fruits")]
+            "fruits" => Ok(Self::Fruits(FruitsPerformanceAttributes {
+                difficulty: read_fruits(difficulty)?,
+                pp: object.f64("pp")?,
+            })),
+            #[cfg(feature = "mania")]
+            "mania" => Ok(Self::Mania(ManiaPerformanceAttributes {
+                difficulty: read_mania(difficulty)?,
+                pp: object.f64("pp")?,
+                pp_acc: object.f64("pp_acc")?,
+                pp_strain: object.f64("pp_strain")?,
+            })),
+            #[cfg(feature = "osu")]
+            "osu" => Ok(Self::Osu(OsuPerformanceAttributes {
+                difficulty: read_osu(difficulty)?,
+                pp: object.f64("pp")?,
+                pp_acc: object.f64("pp_acc")?,
+                pp_aim: object.f64("pp_aim")?,
+                pp_flashlight: object.f64("pp_flashlight")?,
+                pp_speed: object.f64("pp_speed")?,
+                aim_strain_count: object.f64("aim_strain_count")?,
+                speed_strain_count: object.f64("speed_strain_count")?,
+            })),
+            #[cfg(feature = "taiko")]
+            "taiko" => Ok(Self::Taiko(TaikoPerformanceAttributes {
+                difficulty: read_taiko(difficulty)?,
+                pp: object.f64("pp")?,
+                pp_acc: object.f64("pp_acc")?,
+                pp_strain: object.f64("pp_strain")?,
+            })),
+            mode => Err(JsonError::UnsupportedMode {
+                mode: mode.to_owned(),
+            }),
+        }
+    }
+}
+
+fn write_difficulty(object: &mut ObjectWriter, attributes: &DifficultyAttributes) {
+    match attributes {
+        #[cfg(feature = "fruits")]
+        DifficultyAttributes::Fruits(attributes) => {
+            object.str("mode", "fruits");
+            write_fruits(object, attributes);
+        }
+        #[cfg(feature = "mania")]
+        DifficultyAttributes::Mania(attributes) => {
+            object.str("mode", "mania");
+            write_mania(object, attributes);
+        }
+        #[cfg(feature = "osu")]
+        DifficultyAttributes::Osu(attributes) => {
+            object.str("mode", "osu");
+            write_osu(object, attributes);
+        }
+        #[cfg(feature = "taiko")]
+        DifficultyAttributes::Taiko(attributes) => {
+            object.str("mode", "taiko");
+            write_taiko(object, attributes);
+        }
+    }
+}
+
+fn read_difficulty(object: &Object) -> Result<DifficultyAttributes, JsonError> {
+    match object.str("mode")? {
+        #[cfg(feature = "fruits")]
+        "fruits" => read_fruits(object).map(DifficultyAttributes::Fruits),
+        #[cfg(feature = "mania")]
+        "mania" => read_mania(object).map(DifficultyAttributes::Mania),
+        #[cfg(feature = "osu")]
+        "osu" => read_osu(object).map(DifficultyAttributes::Osu),
+        #[cfg(feature = "taiko")]
+        "taiko" => read_taiko(object).map(DifficultyAttributes::Taiko),
+        mode => Err(JsonError::UnsupportedMode {
+            mode: mode.to_owned(),
+        }),
+    }
+}
+
+#[cfg(feature = "fruits")]
+fn write_fruits(object: &mut ObjectWriter, attributes: &FruitsDifficultyAttributes) {
+    object.f64("stars", attributes.stars);
+    object.f64("ar", attributes.ar);
+    object.usize("n_fruits", attributes.n_fruits);
+    object.usize("n_droplets", attributes.n_droplets);
+    object.usize("n_tiny_droplets", attributes.n_tiny_droplets);
+    write_provenance(object, &attributes.provenance);
+}
+
+#[cfg(feature = "fruits")]
+fn read_fruits(object: &Object) -> Result<FruitsDifficultyAttributes, JsonError> {
+    Ok(FruitsDifficultyAttributes {
+        stars: object.f64("stars")?,
+        ar: object.f64("ar")?,
+        n_fruits: object.usize("n_fruits")?,
+        n_droplets: object.usize("n_droplets")?,
+        n_tiny_droplets: object.usize("n_tiny_droplets")?,
+        provenance: read_provenance(object)?,
+    })
+}
+
+#[cfg(feature = "mania")]
+fn write_mania(object: &mut ObjectWriter, attributes: &ManiaDifficultyAttributes) {
+    object.f64("stars", attributes.stars);
+    object.usize("n_notes", attributes.n_notes);
+    object.usize("n_hold_notes", attributes.n_hold_notes);
+    object.f64("hold_factor", attributes.hold_factor);
+    object.f64("ln_coverage", attributes.ln_coverage);
+    write_provenance(object, &attributes.provenance);
+}
+
+#[cfg(feature = "mania")]
+fn read_mania(object: &Object) -> Result<ManiaDifficultyAttributes, JsonError> {
+    Ok(ManiaDifficultyAttributes {
+        stars: object.f64("stars")?,
+        n_notes: object.usize_or_zero("n_notes")?,
+        n_hold_notes: object.usize("n_hold_notes")?,
+        hold_factor: object.f64("hold_factor")?,
+        ln_coverage: object.f64("ln_coverage")?,
+        provenance: read_provenance(object)?,
+    })
+}
+
+#[cfg(feature = "osu")]
+fn write_osu(object: &mut ObjectWriter, attributes: &OsuDifficultyAttributes) {
+    object.f64("stars", attributes.stars);
+    object.f64("aim_strain", attributes.aim_strain);
+    object.f64("speed_strain", attributes.speed_strain);
+    object.f64("flashlight_rating", attributes.flashlight_rating);
+    object.f64("slider_factor", attributes.slider_factor);
+    object.f64(
+        "aim_difficult_strain_count",
+        attributes.aim_difficult_strain_count,
+    );
+    object.f64(
+        "speed_difficult_strain_count",
+        attributes.speed_difficult_strain_count,
+    );
+    object.f64("ar", attributes.ar);
+    object.f64("od", attributes.od);
+    object.f64("hp", attributes.hp);
+    object.f64("cs", attributes.cs);
+    object.usize("n_circles", attributes.n_circles);
+    object.usize("n_sliders", attributes.n_sliders);
+    object.usize("n_spinners", attributes.n_spinners);
+    object.usize("max_combo", attributes.max_combo);
+    write_provenance(object, &attributes.provenance);
+}
+
+#[cfg(feature = "osu")]
+fn read_osu(object: &Object) -> Result<OsuDifficultyAttributes, JsonError> {
+    Ok(OsuDifficultyAttributes {
+        stars: object.f64("stars")?,
+        aim_strain: object.f64("aim_strain")?,
+        speed_strain: object.f64("speed_strain")?,
+        flashlight_rating: object.f64("flashlight_rating")?,
+        slider_factor: object.f64("slider_factor")?,
+        aim_difficult_strain_count: object.f64("aim_difficult_strain_count")?,
+        speed_difficult_strain_count: object.f64("speed_difficult_strain_count")?,
+        ar: object.f64("ar")?,
+        od: object.f64("od")?,
+        hp: object.f64("hp")?,
+        cs: object.f64("cs")?,
+        n_circles: object.usize("n_circles")?,
+        n_sliders: object.usize("n_sliders")?,
+        n_spinners: object.usize("n_spinners")?,
+        max_combo: object.usize("max_combo")?,
+        provenance: read_provenance(object)?,
+    })
+}
+
+#[cfg(feature = "taiko")]
+fn write_taiko(object: &mut ObjectWriter, attributes: &TaikoDifficultyAttributes) {
+    object.f64("stars", attributes.stars);
+    object.usize("max_combo", attributes.max_combo);
+    object.usize("n_kats", attributes.n_kats);
+    write_provenance(object, &attributes.provenance);
+}
+
+#[cfg(feature = "taiko")]
+fn read_taiko(object: &Object) -> Result<TaikoDifficultyAttributes, JsonError> {
+    Ok(TaikoDifficultyAttributes {
+        stars: object.f64("stars")?,
+        max_combo: object.usize("max_combo")?,
+        n_kats: object.usize_or_zero("n_kats")?,
+        provenance: read_provenance(object)?,
+    })
+}
+
+fn write_provenance(object: &mut ObjectWriter, provenance: &Provenance) {
+    let (major, minor, patch) = provenance.crate_version;
+
+    object.str("crate_version", &format!("{}.{}.{}", major, minor, patch));
+    object.usize("formula_version", provenance.formula_version as usize);
+}
+
+/// Attributes that were written before the provenance was recorded are of unknown provenance.
+fn read_provenance(object: &Object) -> Result<Provenance, JsonError> {
+    if object.get("formula_version").is_none() {
+        return Ok(Provenance::UNKNOWN);
+    }
+
+    let crate_version = Provenance::parse_crate_version(object.str("crate_version")?).ok_or(
+        JsonError::InvalidField {
+            name: "crate_version",
+        },
+    )?;
+
+    let formula_version = object.usize("formula_version")?;
+
+    Ok(Provenance {
+        crate_version,
+        formula_version: formula_version.min(u32::MAX as usize) as u32,
+    })
+}
+
+/// Writes the fields of a JSON object in order.
+struct ObjectWriter {
+    buf: String,
+}
+
+impl ObjectWriter {
+    fn new() -> Self {
+        Self {
+            buf: String::from("{"),
+        }
+    }
+
+    fn versioned() -> Self {
+        let mut object = Self::new();
+        object.usize("schema_version", SCHEMA_VERSION as usize);
+
+        object
+    }
+
+    fn key(&mut self, key: &str) {
+        if self.buf.len() > 1 {
+            self.buf.push(',');
+        }
+
+        let _ = write!(self.buf, "\"{}\":", key);
+    }
+
+    /// Non-finite values are not valid JSON and are written as `null` instead.
+    fn f64(&mut self, key: &str, value: f64) {
+        self.key(key);
+
+        if value.is_finite() {
+            let _ = write!(self.buf, "{:?}", value);
+        } else {
+            self.buf.push_str("null");
+        }
+    }
+
+    fn usize(&mut self, key: &str, value: usize) {
+        self.key(key);
+        let _ = write!(self.buf, "{}", value);
+    }
+
+    /// Only used for static names that don't require escaping.
+    fn str(&mut self, key: &str, value: &str) {
+        self.key(key);
+        let _ = write!(self.buf, "\"{}\"", value);
+    }
+
+    fn object(&mut self, key: &str, f: impl FnOnce(&mut Self)) {
+        let mut object = Self::new();
+        f(&mut object);
+
+        self.key(key);
+        self.buf.push_str(&object.finish());
+    }
+
+    fn finish(mut self) -> String {
+        self.buf.push('}');
+
+        self.buf
+    }
+}
+
+enum Value {
+    Null,
+    Bool,
+    Number(f64),
+    String(String),
+    Array,
+    Object(Object),
+}
+
+/// The fields of a parsed JSON object.
+pub(crate) struct Object {
+    fields: Vec<(String, Value)>,
+}
+
+impl Object {
+    /// Parse a JSON document whose top-level value is an object.
+    pub(crate) fn parse(json: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser {
+            bytes: json.as_bytes(),
+            pos: 0,
+            depth: 0,
+        };
+
+        parser.skip_whitespace();
+
+        let object = match parser.value()? {
+            Value::Object(object) => object,
+            _ => return Err(JsonError::Syntax { position: 0 }),
+        };
+
+        parser.skip_whitespace();
+
+        if parser.pos < parser.bytes.len() {
+            return Err(JsonError::Syntax {
+                position: parser.pos,
+            });
+        }
+
+        Ok(object)
+    }
+
+    fn parse_versioned(json: &str) -> Result<Self, JsonError> {
+        let object = Self::parse(json)?;
+        let version = object.usize("schema_version")?;
+
+        if version > SCHEMA_VERSION as usize {
+            return Err(JsonError::UnsupportedVersion {
+                version: version.min(u32::MAX as usize) as u32,
+            });
+        }
+
+        Ok(object)
+    }
+
+    /// The fields whose values are numbers, in their order of appearance.
+    #[cfg(feature = "api_compat")]
+    pub(crate) fn numbers(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.fields.iter().filter_map(|(key, value)| match value {
+            Value::Number(n) => Some((key.as_str(), *n)),
+            _ => None,
+        })
+    }
+
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// `null` is read as NaN since non-finite values are written as `null`.
+    fn f64(&self, name: &'static str) -> Result<f64, JsonError> {
+        match self.get(name) {
+            Some(Value::Number(n)) => Ok(*n),
+            Some(Value::Null) => Ok(f64::NAN),
+            _ => Err(JsonError::InvalidField { name }),
+        }
+    }
+
+    fn usize(&self, name: &'static str) -> Result<usize, JsonError> {
+        match self.get(name) {
+            Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+            _ => Err(JsonError::InvalidField { name }),
+        }
+    }
+
+    /// Fields that were added without increasing the schema version are read as zero if missing.
+    fn usize_or_zero(&self, name: &'static str) -> Result<usize, JsonError> {
+        match self.get(name) {
+            Some(_) => self.usize(name),
+            None => Ok(0),
+        }
+    }
+
+    fn str(&self, name: &'static str) -> Result<&str, JsonError> {
+        match self.get(name) {
+            Some(Value::String(s)) => Ok(s),
+            _ => Err(JsonError::InvalidField { name }),
+        }
+    }
+
+    pub(crate) fn object(&self, name: &'static str) -> Result<&Object, JsonError> {
+        match self.get(name) {
+            Some(Value::Object(object)) => Ok(object),
+            _ => Err(JsonError::InvalidField { name }),
+        }
+    }
+}
+
+/// A minimal JSON parser that keeps only what's needed to read attributes.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> JsonError {
+        JsonError::Syntax { position: self.pos }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonError> {
+        self.skip_whitespace();
+
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn literal(&mut self, literal: &[u8], value: Value) -> Result<Value, JsonError> {
+        if self.bytes[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+
+            Ok(value)
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.skip_whitespace();
+
+        match self.peek().ok_or_else(|| self.error())? {
+            b'{' => self.nested(Self::object).map(Value::Object),
+            b'[' => self.nested(Self::array),
+            b'"' => self.string().map(Value::String),
+            b'n' => self.literal(b"null", Value::Null),
+            b't' => self.literal(b"true", Value::Bool),
+            b'f' => self.literal(b"false", Value::Bool),
+            b'-' | b'0'..=b'9' => self.number().map(Value::Number),
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Parse an object or array, failing if that exceeds [`MAX_DEPTH`].
+    fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, JsonError>,
+    ) -> Result<T, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error());
+        }
+
+        self.depth += 1;
+        let res = f(self);
+        self.depth -= 1;
+
+        res
+    }
+
+    fn object(&mut self) -> Result<Object, JsonError> {
+        self.expect(b'{')?;
+        let mut fields = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+
+            return Ok(Object { fields });
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+
+                    return Ok(Object { fields });
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    /// Arrays are validated but their elements are discarded.
+    fn array(&mut self) -> Result<Value, JsonError> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+
+            return Ok(Value::Array);
+        }
+
+        loop {
+            self.value()?;
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+
+                    return Ok(Value::Array);
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+
+        loop {
+            let byte = self.peek().ok_or_else(|| self.error())?;
+            self.pos += 1;
+
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = self.peek().ok_or_else(|| self.error())?;
+                    self.pos += 1;
+
+                    let unescaped = match escaped {
+                        b'"' | b'\\' | b'/' => escaped,
+                        b'b' => 0x08,
+                        b'f' => 0x0c,
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'u' => {
+                            let hex = self
+                                .bytes
+                                .get(self.pos..self.pos + 4)
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .ok_or_else(|| self.error())?;
+
+                            self.pos += 4;
+                            let c = char::from_u32(hex).unwrap_or(char::REPLACEMENT_CHARACTER);
+                            let mut buf = [0; 4];
+                            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+
+                            continue;
+                        }
+                        _ => return Err(self.error()),
+                    };
+
+                    bytes.push(unescaped);
+                }
+                _ => bytes.push(byte),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| self.error())
+    }
+
+    fn number(&mut self) -> Result<f64, JsonError> {
+        let start = self.pos;
+
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .ok_or(JsonError::Syntax { position: start })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn roundtrip() {
+        use crate::{Beatmap, BeatmapExt};
+
+        let maps = [
+            "./maps/2785319.osu",
+            "./maps/1028484.osu",
+            "./maps/2118524.osu",
+            "./maps/1974394.osu",
+        ];
+
+        for path in maps.iter() {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+
+            let difficulty = map.stars(64, None);
+            let json = difficulty.to_json();
+            assert!(json.starts_with("{\"schema_version\":1,"));
+            assert!(json.contains(&format!(
+                "\"crate_version\":\"{}\"",
+                env!("CARGO_PKG_VERSION")
+            )));
+            assert_eq!(DifficultyAttributes::from_json(&json), Ok(difficulty));
+
+            let performance = map.pp().mods(64).accuracy(97.0).calculate();
+            let json = performance.to_json();
+            assert_eq!(PerformanceAttributes::from_json(&json), Ok(performance));
+        }
+    }
+
+    #[cfg(feature = "taiko")]
+    #[test]
+    fn compatibility() {
+        let json = r#" {
+            "schema_version": 1,
+            "mode": "taiko",
+            "max_combo": 420,
+            "stars": 5.25,
+            "added_later": [1, {"nested": "é\n"}, null, true]
+        } "#;
+
+        let attributes = DifficultyAttributes::from_json(json).unwrap();
+        assert_eq!(attributes.stars(), 5.25);
+
+        match attributes {
+            DifficultyAttributes::Taiko(ref attributes) => assert_eq!(attributes.max_combo, 420),
+            #[allow(unreachable_patterns)]
+            _ => panic!("expected osu!taiko attributes"),
+        }
+
+        assert_eq!(attributes.provenance(), Provenance::UNKNOWN);
+
+        let stamped = json.replace(
+            "\"stars\": 5.25,",
+            "\"stars\": 5.25, \"crate_version\": \"0.5.0\", \"formula_version\": 1,",
+        );
+        let provenance = DifficultyAttributes::from_json(&stamped)
+            .unwrap()
+            .provenance();
+        assert_eq!(provenance.crate_version, (0, 5, 0));
+        assert_eq!(provenance.formula_version, 1);
+
+        let invalid = stamped.replace("0.5.0", "0.5");
+        assert_eq!(
+            DifficultyAttributes::from_json(&invalid),
+            Err(JsonError::InvalidField {
+                name: "crate_version"
+            })
+        );
+
+        let newer = json.replace("\"schema_version\": 1", "\"schema_version\": 2");
+        assert_eq!(
+            DifficultyAttributes::from_json(&newer),
+            Err(JsonError::UnsupportedVersion { version: 2 })
+        );
+
+        let missing = json.replace("\"stars\": 5.25,", "");
+        assert_eq!(
+            DifficultyAttributes::from_json(&missing),
+            Err(JsonError::InvalidField { name: "stars" })
+        );
+
+        let unknown = json.replace("taiko", "unknown");
+        assert!(matches!(
+            DifficultyAttributes::from_json(&unknown),
+            Err(JsonError::UnsupportedMode { .. })
+        ));
+
+        assert!(matches!(
+            DifficultyAttributes::from_json("{\"schema_version\":1,"),
+            Err(JsonError::Syntax { .. })
+        ));
+
+        // Infinite values are written as null and read back as NaN
+        let infinite = DifficultyAttributes::Taiko(crate::taiko::TaikoDifficultyAttributes {
+            stars: f64::INFINITY,
+            ..Default::default()
+        });
+        let json = infinite.to_json();
+        assert!(json.contains("\"stars\":null"));
+        assert!(DifficultyAttributes::from_json(&json)
+            .unwrap()
+            .stars()
+            .is_nan());
+    }
+
+    #[test]
+    fn nesting_depth() {
+        let nested = |depth: usize| {
+            format!(
+                "{{\"schema_version\":1,\"x\":{}{}}}",
+                "[".repeat(depth),
+                "]".repeat(depth)
+            )
+        };
+
+        // The outer object is the first level
+        assert!(Object::parse(&nested(MAX_DEPTH - 1)).is_ok());
+        assert!(matches!(
+            Object::parse(&nested(MAX_DEPTH)),
+            Err(JsonError::Syntax { .. })
+        ));
+
+        // Deeply nested input fails instead of overflowing the stack
+        assert!(matches!(
+            Object::parse(&nested(1_000_000)),
+            Err(JsonError::Syntax { .. })
+        ));
+    }
+}
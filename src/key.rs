@@ -0,0 +1,394 @@
+use std::fmt;
+
+use crate::{parse::HitObjectKind, Beatmap, Mods};
+
+const FNV_OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// The enabled mode features which determine what is parsed from a map.
+const MODE_FEATURES: u8 = cfg!(feature = "osu") as u8
+    | (cfg!(feature = "taiko") as u8) << 1
+    | (cfg!(feature = "fruits") as u8) << 2
+    | (cfg!(feature = "mania") as u8) << 3;
+
+/// Identifies a calculation, e.g. as key for caches of difficulty or performance attributes.
+///
+/// The key consists of
+/// - a fingerprint of the map's content, or its MD5 checksum,
+/// - the mods that affect the calculation, except for DT, NC, and HT,
+/// - the clock rate of the mods, which replaces DT, NC, and HT,
+/// - the overall difficulty of osu!lazer's Difficulty Adjust, if any,
+/// - the amount of passed objects, and
+/// - the [`VERSION`](CalcKey::VERSION) of the calculation.
+///
+/// Deriving the clock rate from the mods means that equal calculations have equal keys,
+/// e.g. DT and NC or HR and [`RateMods`](crate::rate::RateMods) with HR at 1.5x.
+///
+/// Keys can be stored through [`to_bytes`](CalcKey::to_bytes) or their hexadecimal
+/// [`Display`](fmt::Display). Keys of [`from_checksum`](CalcKey::from_checksum) are stable
+/// across platforms and releases with the same [`VERSION`](CalcKey::VERSION).
+///
+/// **Keys of [`new`](CalcKey::new) depend on the enabled mode features.** The parsed
+/// content of a map, e.g. whether slider control points and timing points are stored,
+/// differs between features so the feature set is part of the map's fingerprint.
+/// Builds with different mode features never share fingerprints, so use checksums
+/// for keys that are shared between them.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use akatsuki_pp::{Beatmap, BeatmapExt, CalcKey};
+///
+/// # let map = Beatmap::default();
+/// let mut cache = HashMap::new();
+///
+/// let key = CalcKey::new(&map, 64 + 16, None); // HDDT
+/// let attributes = cache.entry(key).or_insert_with(|| map.stars(64 + 16, None));
+///
+/// // NC is the same calculation as DT
+/// assert!(cache.contains_key(&CalcKey::new(&map, 512 + 64 + 16, None)));
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CalcKey {
+    map: u128,
+    mods: u32,
+    clock_rate: u64,
+    overall_difficulty: Option<u32>,
+    passed_objects: Option<u64>,
+    version: u32,
+}
+
+impl CalcKey {
+    /// The version of the calculation.
+    ///
+    /// It is increased whenever a change in any mode causes different attributes
+    /// so that keys of an older version no longer match.
+    pub const VERSION: u32 = 1;
+
+    /// The length of [`to_bytes`](CalcKey::to_bytes).
+    pub const N_BYTES: usize = 44;
+
+    /// Create a key for a calculation on the given map.
+    ///
+    /// The map is identified by its [`fingerprint`](CalcKey::fingerprint).
+    pub fn new(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize>) -> Self {
+        Self::from_checksum(Self::fingerprint(map), mods, passed_objects)
+    }
+
+    /// Create a key for a calculation on the map with the given checksum,
+    /// e.g. the MD5 of the .osu file as big-endian integer.
+    ///
+    /// The map is not hashed so this is cheaper than [`new`](CalcKey::new) if the
+    /// checksum is known anyway. Keys of both functions don't match each other.
+    pub fn from_checksum(checksum: u128, mods: impl Mods, passed_objects: Option<usize>) -> Self {
+        Self {
+            map: checksum,
+            mods: mod_bits(mods),
+            clock_rate: mods.speed().to_bits(),
+            overall_difficulty: mods.overall_difficulty().map(f32::to_bits),
+            passed_objects: passed_objects.map(|n| n as u64),
+            version: Self::VERSION,
+        }
+    }
+
+    /// Hash all content of a map that affects calculations.
+    ///
+    /// Unlike [`Hash`](std::hash::Hash), the fingerprint does not depend on the platform.
+    /// It does not depend on metadata or hitsounds either, except for osu!taiko where
+    /// the hitsound determines the note color.
+    ///
+    /// **The fingerprint depends on the enabled mode features.** Since the parsed content
+    /// differs between them, the feature set is hashed as well so that fingerprints only
+    /// match for the same set of mode features.
+    pub fn fingerprint(map: &Beatmap) -> u128 {
+        let mut hasher = Fnv::default();
+
+        hasher.write(&[MODE_FEATURES, map.mode as u8, map.version]);
+
+        for value in [map.ar, map.od, map.cs, map.hp].iter() {
+            hasher.write(&value.to_le_bytes());
+        }
+
+        #[cfg(feature = "osu")]
+        hasher.write(&map.stack_leniency.to_le_bytes());
+
+        hasher.write(&map.slider_mult.to_le_bytes());
+        hasher.write(&map.tick_rate.to_le_bytes());
+
+        hasher.write_len(map.hit_objects.len());
+
+        for h in map.hit_objects.iter() {
+            hasher.write(&h.pos.x.to_le_bytes());
+            hasher.write(&h.pos.y.to_le_bytes());
+            hasher.write(&h.start_time.to_le_bytes());
+            hasher.write(&[h.sound]);
+
+            match &h.kind {
+                HitObjectKind::Circle => hasher.write(&[0]),
+                #[cfg(feature = "sliders")]
+                HitObjectKind::Slider {
+                    pixel_len,
+                    repeats,
+                    control_points,
+                } => {
+                    hasher.write(&[1]);
+                    hasher.write(&pixel_len.to_le_bytes());
+                    hasher.write_len(*repeats);
+                    hasher.write_len(control_points.len());
+
+                    for point in control_points.iter() {
+                        hasher.write(&point.pos.x.to_le_bytes());
+                        hasher.write(&point.pos.y.to_le_bytes());
+                        hasher.write(&[point.kind.map_or(u8::MAX, |kind| kind as u8)]);
+                    }
+                }
+                #[cfg(not(feature = "sliders"))]
+                HitObjectKind::Slider {
+                    pixel_len,
+                    span_count,
+                } => {
+                    hasher.write(&[1]);
+                    hasher.write(&pixel_len.to_le_bytes());
+                    hasher.write_len(*span_count);
+                }
+                HitObjectKind::Spinner { end_time } => {
+                    hasher.write(&[2]);
+                    hasher.write(&end_time.to_le_bytes());
+                }
+                HitObjectKind::Hold { end_time } => {
+                    hasher.write(&[3]);
+                    hasher.write(&end_time.to_le_bytes());
+                }
+            }
+        }
+
+        #[cfg(feature = "sliders")]
+        {
+            hasher.write_len(map.timing_points.len());
+
+            for point in map.timing_points.iter() {
+                hasher.write(&point.time.to_le_bytes());
+                hasher.write(&point.beat_len.to_le_bytes());
+            }
+
+            hasher.write_len(map.difficulty_points.len());
+
+            for point in map.difficulty_points.iter() {
+                hasher.write(&point.time.to_le_bytes());
+                hasher.write(&point.speed_multiplier.to_le_bytes());
+            }
+        }
+
+        #[cfg(not(feature = "sliders"))]
+        hasher.write(&map.bpm.to_le_bytes());
+
+        hasher.0
+    }
+
+    /// The fingerprint or checksum of the map.
+    #[inline]
+    pub fn map(&self) -> u128 {
+        self.map
+    }
+
+    /// The bit value of the mods without DT, NC, HT, SD, and PF.
+    #[inline]
+    pub fn mods(&self) -> u32 {
+        self.mods
+    }
+
+    /// The clock rate of the mods.
+    #[inline]
+    pub fn clock_rate(&self) -> f64 {
+        f64::from_bits(self.clock_rate)
+    }
+
+    /// The overall difficulty of osu!lazer's Difficulty Adjust, if any.
+    #[inline]
+    pub fn overall_difficulty(&self) -> Option<f32> {
+        self.overall_difficulty.map(f32::from_bits)
+    }
+
+    /// The amount of passed objects.
+    #[inline]
+    pub fn passed_objects(&self) -> Option<usize> {
+        self.passed_objects.map(|n| n as usize)
+    }
+
+    /// The version of the calculation.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Whether the key was created with the current [`VERSION`](CalcKey::VERSION).
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        self.version == Self::VERSION
+    }
+
+    /// Serialize the key into bytes in little-endian order.
+    ///
+    /// The overall difficulty is stored as `u32::MAX` and passed objects
+    /// as `u64::MAX` if not specified.
+    pub fn to_bytes(&self) -> [u8; Self::N_BYTES] {
+        let mut bytes = [0; Self::N_BYTES];
+
+        bytes[..16].copy_from_slice(&self.map.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.mods.to_le_bytes());
+        bytes[20..28].copy_from_slice(&self.clock_rate.to_le_bytes());
+        bytes[28..32].copy_from_slice(&self.overall_difficulty.unwrap_or(u32::MAX).to_le_bytes());
+        bytes[32..40].copy_from_slice(&self.passed_objects.unwrap_or(u64::MAX).to_le_bytes());
+        bytes[40..].copy_from_slice(&self.version.to_le_bytes());
+
+        bytes
+    }
+
+    /// Deserialize a key from bytes of [`to_bytes`](CalcKey::to_bytes).
+    pub fn from_bytes(bytes: [u8; Self::N_BYTES]) -> Self {
+        let mut map = [0; 16];
+        let mut mods = [0; 4];
+        let mut clock_rate = [0; 8];
+        let mut overall_difficulty = [0; 4];
+        let mut passed_objects = [0; 8];
+        let mut version = [0; 4];
+
+        map.copy_from_slice(&bytes[..16]);
+        mods.copy_from_slice(&bytes[16..20]);
+        clock_rate.copy_from_slice(&bytes[20..28]);
+        overall_difficulty.copy_from_slice(&bytes[28..32]);
+        passed_objects.copy_from_slice(&bytes[32..40]);
+        version.copy_from_slice(&bytes[40..]);
+
+        let overall_difficulty = u32::from_le_bytes(overall_difficulty);
+        let passed_objects = u64::from_le_bytes(passed_objects);
+
+        Self {
+            map: u128::from_le_bytes(map),
+            mods: u32::from_le_bytes(mods),
+            clock_rate: u64::from_le_bytes(clock_rate),
+            overall_difficulty: (overall_difficulty != u32::MAX).then_some(overall_difficulty),
+            passed_objects: (passed_objects != u64::MAX).then_some(passed_objects),
+            version: u32::from_le_bytes(version),
+        }
+    }
+}
+
+impl fmt::Display for CalcKey {
+    /// The bytes of [`to_bytes`](CalcKey::to_bytes) in hexadecimal.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.to_bytes().iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The bit value of all mods except for those that only change the clock rate
+/// and SD and PF which don't affect the calculation.
+fn mod_bits(mods: impl Mods) -> u32 {
+    let flags = [
+        (mods.nf(), u32::NF),
+        (mods.ez(), u32::EZ),
+        (mods.td(), u32::TD),
+        (mods.hd(), u32::HD),
+        (mods.hr(), u32::HR),
+        (mods.rx(), u32::RX),
+        (mods.fl(), u32::FL),
+        (mods.so(), u32::SO),
+        // Blinds and Traceable have no bit value so they use unassigned bits
+        (mods.bl(), 1 << 31),
+        (mods.tc(), 1 << 30),
+    ];
+
+    flags
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .fold(0, |bits, (_, bit)| bits | bit)
+}
+
+/// 128-bit FNV-1a which, unlike std's hashers, is specified to be stable.
+struct Fnv(u128);
+
+impl Default for Fnv {
+    #[inline]
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u128;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn mods_and_clock_rate() {
+        use crate::rate::RateMods;
+
+        let map = Beatmap::default();
+
+        let dt = CalcKey::new(&map, 64 + 16, None);
+        assert_eq!(dt, CalcKey::new(&map, 512 + 64 + 16, None));
+        assert_eq!(dt, CalcKey::new(&map, RateMods::new(16, 1.5), None));
+        assert_eq!(dt.mods(), 16);
+        assert_eq!(dt.clock_rate(), 1.5);
+
+        assert_ne!(dt, CalcKey::new(&map, 16, None));
+        assert_eq!(dt, CalcKey::new(&map, 16_384 + 64 + 32 + 16, None));
+        assert_ne!(dt, CalcKey::new(&map, RateMods::new(16, 1.45), None));
+        assert_ne!(dt, CalcKey::new(&map, 64 + 16, Some(100)));
+
+        let bytes = dt.to_bytes();
+        assert_eq!(CalcKey::from_bytes(bytes), dt);
+        assert_eq!(dt.to_string().len(), 2 * CalcKey::N_BYTES);
+
+        let passed = CalcKey::from_checksum(u128::MAX, 0, Some(0));
+        assert_eq!(CalcKey::from_bytes(passed.to_bytes()), passed);
+        assert!(passed.is_current());
+
+        let da = crate::LazerMods {
+            overall_difficulty: Some(9.5),
+            ..crate::LazerMods::from(16)
+        };
+        let da_key = CalcKey::new(&map, da, None);
+        assert_ne!(da_key, CalcKey::new(&map, 16, None));
+        assert_eq!(da_key.overall_difficulty(), Some(9.5));
+        assert_eq!(CalcKey::from_bytes(da_key.to_bytes()), da_key);
+        assert_eq!(dt.overall_difficulty(), None);
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn fingerprint() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let fingerprint = CalcKey::fingerprint(&map);
+
+        let reparsed = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        assert_eq!(CalcKey::fingerprint(&reparsed), fingerprint);
+
+        let mut changed = map.clone();
+        changed.hit_objects[10].start_time += 1.0;
+        assert_ne!(CalcKey::fingerprint(&changed), fingerprint);
+
+        let mut changed = map;
+        changed.od += 0.1;
+        assert_ne!(CalcKey::fingerprint(&changed), fingerprint);
+    }
+}
@@ -7,7 +7,7 @@
 //! ## Usage
 //!
 //! ```no_run
-//! use rosu_pp::{Beatmap, BeatmapExt};
+//! use akatsuki_pp::{Beatmap, BeatmapExt};
 //!
 //! # /*
 //! // Parse the map yourself
@@ -52,7 +52,7 @@
 //! If either the `async_tokio` or `async_std` feature is enabled, beatmap parsing will be async.
 //!
 //! ```no_run
-//! use rosu_pp::{Beatmap, BeatmapExt};
+//! use akatsuki_pp::{Beatmap, BeatmapExt};
 //!
 //! # /*
 //! // Parse the map asynchronously
@@ -81,7 +81,7 @@
 //! Instead, you should use `GradualDifficultyAttributes` and `GradualPerformanceAttributes`:
 //!
 //! ```no_run
-//! use rosu_pp::{
+//! use akatsuki_pp::{
 //!     Beatmap, BeatmapExt, GradualPerformanceAttributes, ScoreState,
 //!     taiko::TaikoScoreState,
 //! };
@@ -166,6 +166,14 @@
 //! | `mania` | Enable osu!mania. |
 //! | `async_tokio` | Beatmap parsing will be async through [tokio](https://github.com/tokio-rs/tokio) |
 //! | `async_std` | Beatmap parsing will be async through [async-std](https://github.com/async-rs/async-std) |
+//! | `mmap` | Provides the `unsafe` function `Beatmap::from_path_mmap` which memory-maps the file through [memmap2](https://github.com/RazrFalcon/memmap2-rs). Not available in combination with an async feature. |
+//! | `tracing` | Emit [tracing](https://github.com/tokio-rs/tracing) spans for parsing, converting, difficulty, and performance calculation. |
+//! | `online` | Download maps by their id from osu.ppy.sh or a mirror through [reqwest](https://github.com/seanmonstar/reqwest), see `online::MapDownloader`. Custom storage can be used through `online::MapSource`. |
+//! | `timings` | Performance calculators provide `calculate_with_timings` to measure the duration of each calculation phase. |
+//! | `research` | Skill parameters such as strain decay bases and decay weights can be adjusted at runtime through `{mode}::stars_with_parameters`, see the `research` module. |
+//! | `api_compat` | Calculated difficulty attributes can be compared against the response of the osu!api v2 attributes endpoint to audit drift from osu!'s servers, see the `api_compat` module. |
+//! | `precision-f32` | Skills store their strains as `f32` instead of `f64` to halve their memory, e.g. for batch jobs that keep many calculators around. Calculations still use `f64`. Star ratings deviate by less than 1e-4 and pp by less than 1e-3, typically by about 1e-6. |
+//! | `cli` | Builds the `akatsuki-pp` binary which prints the stars and pp of a .osu file for the given mods and score, optionally as JSON. Run it e.g. via `cargo run --features cli -- map.osu --mods HDDT --acc 98.5`. |
 //!
 
 #![cfg_attr(docsrs, feature(doc_cfg), deny(broken_intra_doc_links))]
@@ -179,6 +187,15 @@
     missing_docs
 )]
 
+/// Enter a [`tracing`](https://docs.rs/tracing) span until the end of the current scope.
+/// Expands to nothing if the `tracing` feature is not enabled.
+macro_rules! trace_span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $($fields)*)?).entered();
+    };
+}
+
 #[cfg(feature = "fruits")]
 #[cfg_attr(docsrs, doc(cfg(feature = "fruits")))]
 /// Everything about osu!ctb.
@@ -202,14 +219,89 @@ pub mod taiko;
 /// Beatmap parsing and the contained types.
 pub mod parse;
 
+#[cfg(feature = "online")]
+#[cfg_attr(docsrs, doc(cfg(feature = "online")))]
+/// Downloading maps by their id.
+pub mod online;
+
+/// Mode independent analysis of a map's rhythm.
+pub mod rhythm;
+
+/// Score estimation according to osu!lazer and sorting keys for leaderboards.
+pub mod score;
+
+/// Difficulty attributes for custom clock rates.
+pub mod rate;
+
+/// Comparisons of a map's difficulty across mods.
+pub mod analysis;
+
+/// Numeric feature vectors of maps, e.g. for machine learning.
+pub mod features;
+
+/// Fast approximate star ratings to filter large map collections.
+pub mod estimate;
+
+/// Health simulation to estimate whether and when a play failed.
+pub mod health;
+
+/// Schema-versioned JSON representation of difficulty and performance attributes.
+pub mod json;
+
+/// Shared math of difficulty settings such as AR and OD as well as rounding of displayed values.
+pub mod util;
+
+#[cfg(feature = "research")]
+#[cfg_attr(docsrs, doc(cfg(feature = "research")))]
+/// Runtime parameters of the skills to experiment with difficulty calculation.
+pub mod research;
+
+#[cfg(not(feature = "research"))]
+mod research;
+
+#[cfg(feature = "api_compat")]
+#[cfg_attr(docsrs, doc(cfg(feature = "api_compat")))]
+/// Comparisons against the difficulty attributes of the osu!api v2.
+pub mod api_compat;
+
+mod preprocessed;
+pub use preprocessed::PreprocessedMap;
+
 mod gradual;
-pub use gradual::{GradualDifficultyAttributes, GradualPerformanceAttributes, ScoreState};
+pub use gradual::{
+    DifficultyCheckpoint, GradualDifficultyAttributes, GradualPerformanceAttributes, ScoreState,
+    StarDeltas,
+};
+
+mod live;
+pub use live::LivePerformance;
+
+mod key;
+pub use key::CalcKey;
+
+mod provenance;
+pub use provenance::Provenance;
+
+mod explain;
+pub use explain::{ComponentDelta, PerformanceDiff, PpComponent};
+
+mod mapset;
+pub use mapset::{Beatmapset, DifficultySpread, SetDifficulty};
 
 mod pp;
-pub use pp::{AnyPP, AttributeProvider};
+pub use pp::{AnyPP, AttributeProvider, ModeMismatchError};
+
+mod validation;
+pub use validation::InputError;
 
 mod curve;
+mod limited_queue;
 mod mods;
+mod send_sync;
+mod timings;
+
+#[cfg(feature = "timings")]
+pub use timings::CalcTimings;
 
 #[cfg(feature = "sliders")]
 pub(crate) mod control_point_iter;
@@ -229,14 +321,28 @@ pub use osu::OsuPP;
 #[cfg(feature = "taiko")]
 pub use taiko::TaikoPP;
 
-pub use mods::Mods;
-pub use parse::{Beatmap, BeatmapAttributes, GameMode, ParseError, ParseResult};
+pub use mods::{is_ranked_playable, LazerMods, Mods, ModsRanking, UnknownModError};
+pub use parse::{
+    Beatmap, BeatmapAttributes, BeatmapLength, FormatError, GameMode, ParseBuffers, ParseError,
+    ParseErrorCode, ParseLimits, ParseOptions, ParseResult, ParseWarning, RawSection,
+    UnknownModeError,
+};
 
 /// Provides some additional methods on [`Beatmap`](crate::Beatmap).
 pub trait BeatmapExt {
     /// Calculate the stars and other attributes of a beatmap which are required for pp calculation.
     fn stars(&self, mods: impl Mods, passed_objects: Option<usize>) -> DifficultyAttributes;
 
+    /// Calculate only the star rating of a beatmap.
+    ///
+    /// Suitable for showing the stars of many maps at once, e.g. in large lists,
+    /// when the other attributes are not of interest.
+    /// For osu!standard, the calculation omits everything that is only relevant
+    /// for performance calculation which makes it roughly 10-15% faster than
+    /// [`stars`](BeatmapExt::stars).
+    /// For the other modes it is just as fast.
+    fn stars_light(&self, mods: impl Mods) -> f64;
+
     /// Calculate the max pp of a beatmap.
     ///
     /// If you seek more fine-tuning you can use the [`pp`](BeatmapExt::pp) method.
@@ -252,13 +358,22 @@ pub trait BeatmapExt {
     /// instead of evaluating the final strains, they are just returned as is.
     ///
     /// Suitable to plot the difficulty of a map over time.
-    fn strains(&self, mods: impl Mods) -> Strains;
+    ///
+    /// In case of a partial play, e.g. a live play that is still ongoing,
+    /// one can specify the amount of passed objects.
+    fn strains(&self, mods: impl Mods, passed_objects: Option<usize>) -> Strains;
 
     /// Return an iterator that gives you the `DifficultyAttributes` after each hit object.
     ///
     /// Suitable to efficiently get the map's star rating after multiple different locations.
     fn gradual_difficulty(&self, mods: impl Mods) -> GradualDifficultyAttributes<'_>;
 
+    /// Prepare the map once so that its difficulty and performance
+    /// can be calculated for many mods without repeating that work.
+    ///
+    /// See [`PreprocessedMap`].
+    fn preprocess(&self) -> PreprocessedMap<'_>;
+
     /// Return a struct that gives you the `PerformanceAttributes` after every (few) hit object(s).
     ///
     /// Suitable to efficiently get a score's performance after multiple different locations,
@@ -301,6 +416,40 @@ impl BeatmapExt for Beatmap {
         }
     }
 
+    #[inline]
+    fn stars_light(&self, mods: impl Mods) -> f64 {
+        match self.mode {
+            GameMode::STD => {
+                #[cfg(not(feature = "osu"))]
+                panic!("`osu` feature is not enabled");
+
+                #[cfg(feature = "osu")]
+                osu::stars_light(self, mods)
+            }
+            GameMode::MNA => {
+                #[cfg(not(feature = "mania"))]
+                panic!("`mania` feature is not enabled");
+
+                #[cfg(feature = "mania")]
+                mania::stars(self, mods, None).stars
+            }
+            GameMode::TKO => {
+                #[cfg(not(feature = "taiko"))]
+                panic!("`taiko` feature is not enabled");
+
+                #[cfg(feature = "taiko")]
+                taiko::stars(self, mods, None).stars
+            }
+            GameMode::CTB => {
+                #[cfg(not(feature = "fruits"))]
+                panic!("`fruits` feature is not enabled");
+
+                #[cfg(feature = "fruits")]
+                fruits::stars(self, mods, None).stars
+            }
+        }
+    }
+
     #[inline]
     fn max_pp(&self, mods: u32) -> PerformanceAttributes {
         match self.mode {
@@ -341,35 +490,35 @@ impl BeatmapExt for Beatmap {
     }
 
     #[inline]
-    fn strains(&self, mods: impl Mods) -> Strains {
+    fn strains(&self, mods: impl Mods, passed_objects: Option<usize>) -> Strains {
         match self.mode {
             GameMode::STD => {
                 #[cfg(not(feature = "osu"))]
                 panic!("`osu` feature is not enabled");
 
                 #[cfg(feature = "osu")]
-                osu::strains(self, mods)
+                osu::strains(self, mods, passed_objects)
             }
             GameMode::MNA => {
                 #[cfg(not(feature = "mania"))]
                 panic!("`mania` feature is not enabled");
 
                 #[cfg(feature = "mania")]
-                mania::strains(self, mods)
+                mania::strains(self, mods, passed_objects)
             }
             GameMode::TKO => {
                 #[cfg(not(feature = "taiko"))]
                 panic!("`taiko` feature is not enabled");
 
                 #[cfg(feature = "taiko")]
-                taiko::strains(self, mods)
+                taiko::strains(self, mods, passed_objects)
             }
             GameMode::CTB => {
                 #[cfg(not(feature = "fruits"))]
                 panic!("`fruits` feature is not enabled");
 
                 #[cfg(feature = "fruits")]
-                fruits::strains(self, mods)
+                fruits::strains(self, mods, passed_objects)
             }
         }
     }
@@ -379,6 +528,11 @@ impl BeatmapExt for Beatmap {
         GradualDifficultyAttributes::new(self, mods)
     }
 
+    #[inline]
+    fn preprocess(&self) -> PreprocessedMap<'_> {
+        PreprocessedMap::new(self)
+    }
+
     #[inline]
     fn gradual_performance(&self, mods: u32) -> GradualPerformanceAttributes<'_> {
         GradualPerformanceAttributes::new(self, mods)
@@ -387,7 +541,7 @@ impl BeatmapExt for Beatmap {
 
 /// The result of calculating the strains on a map.
 /// Suitable to plot the difficulty of a map over time.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Strains {
     /// Time in ms inbetween two strains.
     pub section_length: f64,
@@ -395,8 +549,145 @@ pub struct Strains {
     pub strains: Vec<f64>,
 }
 
+impl Strains {
+    /// Resample the strains to exactly `target_points` values that cover the same
+    /// duration, e.g. to plot a graph of fixed width regardless of the map's length.
+    ///
+    /// Each new value covers an equally long interval of the original sections and
+    /// aggregates the sections within it through the given [`Pooling`].
+    /// Sections that lie only partially within an interval are weighted by their overlap.
+    /// If there are fewer sections than target points, the sections are stretched.
+    ///
+    /// Returns empty strains if there are no strains or `target_points` is zero.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Pooling, Strains};
+    ///
+    /// let strains = Strains {
+    ///     section_length: 400.0,
+    ///     strains: vec![1.0, 3.0, 2.0, 2.0],
+    /// };
+    ///
+    /// let resampled = strains.resample(2, Pooling::Max);
+    /// assert_eq!(resampled.section_length, 800.0);
+    /// assert_eq!(resampled.strains, vec![3.0, 2.0]);
+    /// ```
+    pub fn resample(&self, target_points: usize, pooling: Pooling) -> Self {
+        if self.strains.is_empty() || target_points == 0 {
+            return Self::default();
+        }
+
+        let n = self.strains.len();
+        let step = n as f64 / target_points as f64;
+
+        let strains = (0..target_points)
+            .map(|i| {
+                let start = i as f64 * step;
+                let end = ((i + 1) as f64 * step).min(n as f64);
+
+                let first = start.floor() as usize;
+                let last = (end.ceil() as usize).min(n);
+
+                let overlapping = (first..last).map(|j| {
+                    let overlap = end.min((j + 1) as f64) - start.max(j as f64);
+
+                    (self.strains[j], overlap)
+                });
+
+                match pooling {
+                    Pooling::Average => {
+                        let sum: f64 = overlapping.map(|(strain, overlap)| strain * overlap).sum();
+
+                        sum / (end - start)
+                    }
+                    Pooling::Max => overlapping
+                        .filter(|(_, overlap)| *overlap > 0.0)
+                        .map(|(strain, _)| strain)
+                        .fold(0.0, f64::max),
+                }
+            })
+            .collect();
+
+        Self {
+            section_length: self.section_length * step,
+            strains,
+        }
+    }
+}
+
+/// How [`Strains::resample`] aggregates multiple sections into one value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Pooling {
+    /// The average strain, weighted by how much of each section lies within the interval.
+    Average,
+    /// The highest strain, suitable to preserve difficulty spikes.
+    Max,
+}
+
+/// The amount of hit objects of each kind, see [`DifficultyAttributes::object_counts`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ObjectCounts {
+    /// Hit objects of osu!ctb.
+    Fruits {
+        /// The amount of fruits.
+        fruits: usize,
+        /// The amount of droplets.
+        droplets: usize,
+        /// The amount of tiny droplets.
+        tiny_droplets: usize,
+    },
+    /// Hit objects of osu!mania.
+    Mania {
+        /// The amount of regular notes.
+        notes: usize,
+        /// The amount of hold notes.
+        hold_notes: usize,
+    },
+    /// Hit objects of osu!standard.
+    Osu {
+        /// The amount of circles.
+        circles: usize,
+        /// The amount of sliders.
+        sliders: usize,
+        /// The amount of spinners.
+        spinners: usize,
+    },
+    /// Notes of osu!taiko. Drum rolls and swells are not counted.
+    Taiko {
+        /// The amount of dons, i.e. notes in the center.
+        dons: usize,
+        /// The amount of kats, i.e. notes on the rim.
+        kats: usize,
+    },
+}
+
+impl ObjectCounts {
+    /// The amount of hit objects of all kinds.
+    #[inline]
+    pub fn total(&self) -> usize {
+        match *self {
+            Self::Fruits {
+                fruits,
+                droplets,
+                tiny_droplets,
+            } => fruits + droplets + tiny_droplets,
+            Self::Mania { notes, hold_notes } => notes + hold_notes,
+            Self::Osu {
+                circles,
+                sliders,
+                spinners,
+            } => circles + sliders + spinners,
+            Self::Taiko { dons, kats } => dons + kats,
+        }
+    }
+}
+
 /// The result of a difficulty calculation based on the mode.
-#[derive(Clone, Debug)]
+///
+/// New variants may be added in the future so generic code should prefer
+/// the accessor methods over matching on the variants.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum DifficultyAttributes {
     #[cfg(feature = "fruits")]
     /// osu!ctb difficulty calculation reseult.
@@ -413,6 +704,68 @@ pub enum DifficultyAttributes {
 }
 
 impl DifficultyAttributes {
+    /// The amount of hit objects of each kind that the calculation considered.
+    ///
+    /// For converted maps these are the counts after conversion and for
+    /// partial plays only the passed objects are counted.
+    #[inline]
+    pub fn object_counts(&self) -> ObjectCounts {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => ObjectCounts::Fruits {
+                fruits: attributes.n_fruits,
+                droplets: attributes.n_droplets,
+                tiny_droplets: attributes.n_tiny_droplets,
+            },
+            #[cfg(feature = "mania")]
+            Self::Mania(attributes) => ObjectCounts::Mania {
+                notes: attributes.n_notes,
+                hold_notes: attributes.n_hold_notes,
+            },
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => ObjectCounts::Osu {
+                circles: attributes.n_circles,
+                sliders: attributes.n_sliders,
+                spinners: attributes.n_spinners,
+            },
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => ObjectCounts::Taiko {
+                dons: attributes.max_combo - attributes.n_kats,
+                kats: attributes.n_kats,
+            },
+        }
+    }
+
+    /// The mode of the attributes.
+    #[inline]
+    pub fn mode(&self) -> GameMode {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(_) => GameMode::CTB,
+            #[cfg(feature = "mania")]
+            Self::Mania(_) => GameMode::MNA,
+            #[cfg(feature = "osu")]
+            Self::Osu(_) => GameMode::STD,
+            #[cfg(feature = "taiko")]
+            Self::Taiko(_) => GameMode::TKO,
+        }
+    }
+
+    /// The release and formulas that calculated the attributes.
+    #[inline]
+    pub fn provenance(&self) -> Provenance {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => attributes.provenance,
+            #[cfg(feature = "mania")]
+            Self::Mania(attributes) => attributes.provenance,
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => attributes.provenance,
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => attributes.provenance,
+        }
+    }
+
     /// The star value.
     #[inline]
     pub fn stars(&self) -> f64 {
@@ -458,6 +811,138 @@ impl DifficultyAttributes {
             Self::Taiko(attributes) => attributes.max_combo,
         }
     }
+
+    /// The approach rate.
+    ///
+    /// This will be `None` for attributes of osu!taiko and osu!mania maps.
+    #[inline]
+    pub fn ar(&self) -> Option<f64> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => Some(attributes.ar),
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => Some(attributes.ar),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The overall difficulty.
+    ///
+    /// This will only be `Some` for attributes of osu!standard maps.
+    #[inline]
+    pub fn od(&self) -> Option<f64> {
+        match self {
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => Some(attributes.od),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The amount of hit objects that were considered for the calculation.
+    /// For osu!ctb, each fruit and droplet counts as an object.
+    ///
+    /// This will be `None` for attributes of osu!taiko and osu!mania maps.
+    #[inline]
+    pub fn n_objects(&self) -> Option<usize> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => Some(attributes.n_fruits + attributes.n_droplets),
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => {
+                Some(attributes.n_circles + attributes.n_sliders + attributes.n_spinners)
+            }
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The amount of judgements that the accuracy of the mode's performance
+    /// calculation is divided by, see e.g. [`OsuDifficultyAttributes::n_accuracy_objects`](osu::OsuDifficultyAttributes::n_accuracy_objects).
+    ///
+    /// This will be `None` for attributes of osu!mania maps since their
+    /// performance is based on the score instead of the accuracy.
+    #[inline]
+    pub fn n_accuracy_objects(&self) -> Option<usize> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => Some(attributes.n_accuracy_objects()),
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => Some(attributes.n_accuracy_objects()),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => Some(attributes.n_accuracy_objects()),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Adjust the attributes that were calculated with `old_mods` so that they
+    /// apply to `new_mods` without recalculating the difficulty.
+    ///
+    /// Useful when recalculating many scores of a map with various mods since
+    /// mods such as HD or NF do not change the difficulty.
+    ///
+    /// Returns `None` if the mods change requires a full recalculation,
+    /// see [`requires_recalculation`](DifficultyAttributes::requires_recalculation).
+    #[inline]
+    pub fn adjust_mods(&self, old_mods: impl Mods, new_mods: impl Mods) -> Option<Self> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => {
+                attributes.adjust_mods(old_mods, new_mods).map(Self::Fruits)
+            }
+            #[cfg(feature = "mania")]
+            Self::Mania(attributes) => attributes.adjust_mods(old_mods, new_mods).map(Self::Mania),
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => attributes.adjust_mods(old_mods, new_mods).map(Self::Osu),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => attributes.adjust_mods(old_mods, new_mods).map(Self::Taiko),
+        }
+    }
+
+    /// Whether the attributes that were calculated with `old_mods` can not be
+    /// adjusted to `new_mods` and the difficulty must be recalculated instead.
+    #[inline]
+    pub fn requires_recalculation(&self, old_mods: impl Mods, new_mods: impl Mods) -> bool {
+        self.adjust_mods(old_mods, new_mods).is_none()
+    }
+
+    /// Whether both attributes are of the same mode and all their fields are equal,
+    /// allowing floating point values to differ by up to `epsilon`.
+    ///
+    /// Suitable to compare attributes against snapshots or cached values.
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        match (self, other) {
+            #[cfg(feature = "fruits")]
+            (Self::Fruits(a), Self::Fruits(b)) => a.approx_eq(b, epsilon),
+            #[cfg(feature = "mania")]
+            (Self::Mania(a), Self::Mania(b)) => a.approx_eq(b, epsilon),
+            #[cfg(feature = "osu")]
+            (Self::Osu(a), Self::Osu(b)) => a.approx_eq(b, epsilon),
+            #[cfg(feature = "taiko")]
+            (Self::Taiko(a), Self::Taiko(b)) => a.approx_eq(b, epsilon),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for DifficultyAttributes {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => std::fmt::Display::fmt(attributes, f),
+            #[cfg(feature = "mania")]
+            Self::Mania(attributes) => std::fmt::Display::fmt(attributes, f),
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => std::fmt::Display::fmt(attributes, f),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => std::fmt::Display::fmt(attributes, f),
+        }
+    }
 }
 
 #[cfg(feature = "fruits")]
@@ -493,7 +978,11 @@ impl From<taiko::TaikoDifficultyAttributes> for DifficultyAttributes {
 }
 
 /// The result of a performance calculation based on the mode.
-#[derive(Clone, Debug)]
+///
+/// New variants may be added in the future so generic code should prefer
+/// the accessor methods over matching on the variants.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum PerformanceAttributes {
     #[cfg(feature = "fruits")]
     /// osu!ctb performance calculation result.
@@ -510,6 +999,21 @@ pub enum PerformanceAttributes {
 }
 
 impl PerformanceAttributes {
+    /// The mode of the attributes.
+    #[inline]
+    pub fn mode(&self) -> GameMode {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(_) => GameMode::CTB,
+            #[cfg(feature = "mania")]
+            Self::Mania(_) => GameMode::MNA,
+            #[cfg(feature = "osu")]
+            Self::Osu(_) => GameMode::STD,
+            #[cfg(feature = "taiko")]
+            Self::Taiko(_) => GameMode::TKO,
+        }
+    }
+
     /// The pp value.
     #[inline]
     pub fn pp(&self) -> f64 {
@@ -540,6 +1044,22 @@ impl PerformanceAttributes {
         }
     }
 
+    /// The release and formulas that calculated the attributes,
+    /// i.e. the provenance of the difficulty attributes.
+    #[inline]
+    pub fn provenance(&self) -> Provenance {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => attributes.difficulty.provenance,
+            #[cfg(feature = "mania")]
+            Self::Mania(attributes) => attributes.difficulty.provenance,
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => attributes.difficulty.provenance,
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => attributes.difficulty.provenance,
+        }
+    }
+
     /// Difficulty attributes that were used for the performance calculation.
     #[inline]
     pub fn difficulty_attributes(&self) -> DifficultyAttributes {
@@ -585,6 +1105,151 @@ impl PerformanceAttributes {
             Self::Taiko(t) => t.difficulty.max_combo,
         }
     }
+
+    /// The approach rate.
+    ///
+    /// This will be `None` for attributes of osu!taiko and osu!mania maps.
+    #[inline]
+    pub fn ar(&self) -> Option<f64> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => Some(f.difficulty.ar),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => Some(o.difficulty.ar),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The overall difficulty.
+    ///
+    /// This will only be `Some` for attributes of osu!standard maps.
+    #[inline]
+    pub fn od(&self) -> Option<f64> {
+        match self {
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => Some(o.difficulty.od),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The amount of hit objects that were considered for the calculation.
+    /// For osu!ctb, each fruit and droplet counts as an object.
+    ///
+    /// This will be `None` for attributes of osu!taiko and osu!mania maps.
+    #[inline]
+    pub fn n_objects(&self) -> Option<usize> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => Some(f.difficulty.n_fruits + f.difficulty.n_droplets),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => {
+                Some(o.difficulty.n_circles + o.difficulty.n_sliders + o.difficulty.n_spinners)
+            }
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The portions that make up the final pp value.
+    ///
+    /// - osu!standard: `aim`, `speed`, `acc`, and `flashlight`
+    /// - osu!taiko: `strain` and `acc`
+    /// - osu!ctb: none since its pp are not split up
+    /// - osu!mania: `strain` and `acc`
+    ///
+    /// Penalties such as the one for misses are already included in the components.
+    pub fn components(&self) -> Vec<PpComponent> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(_) => Vec::new(),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => vec![
+                PpComponent::new("strain", m.pp_strain),
+                PpComponent::new("acc", m.pp_acc),
+            ],
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => vec![
+                PpComponent::new("aim", o.pp_aim),
+                PpComponent::new("speed", o.pp_speed),
+                PpComponent::new("acc", o.pp_acc),
+                PpComponent::new("flashlight", o.pp_flashlight),
+            ],
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => vec![
+                PpComponent::new("strain", t.pp_strain),
+                PpComponent::new("acc", t.pp_acc),
+            ],
+        }
+    }
+
+    /// Explain why these attributes gave more or less pp than `other`
+    /// by comparing their [`components`](PerformanceAttributes::components).
+    ///
+    /// Returns `None` if the attributes are of different modes.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, BeatmapExt};
+    ///
+    /// # let map = Beatmap::default();
+    /// let fc = map.pp().calculate();
+    /// let choke = map.pp().misses(1).calculate();
+    ///
+    /// let diff = fc.explain_diff(&choke).unwrap();
+    /// assert_eq!(diff.delta(), fc.pp() - choke.pp());
+    ///
+    /// // e.g. "+20.00pp (aim +12.30, speed +11.80, acc +0.00, flashlight +0.00)"
+    /// println!("{}", diff);
+    /// ```
+    pub fn explain_diff(&self, other: &Self) -> Option<PerformanceDiff> {
+        if self.mode() != other.mode() {
+            return None;
+        }
+
+        Some(PerformanceDiff::new(
+            self.pp(),
+            other.pp(),
+            &self.components(),
+            &other.components(),
+        ))
+    }
+
+    /// Whether both attributes are of the same mode and all their fields are equal,
+    /// allowing floating point values to differ by up to `epsilon`.
+    ///
+    /// Suitable to compare attributes against snapshots or cached values.
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        match (self, other) {
+            #[cfg(feature = "fruits")]
+            (Self::Fruits(a), Self::Fruits(b)) => a.approx_eq(b, epsilon),
+            #[cfg(feature = "mania")]
+            (Self::Mania(a), Self::Mania(b)) => a.approx_eq(b, epsilon),
+            #[cfg(feature = "osu")]
+            (Self::Osu(a), Self::Osu(b)) => a.approx_eq(b, epsilon),
+            #[cfg(feature = "taiko")]
+            (Self::Taiko(a), Self::Taiko(b)) => a.approx_eq(b, epsilon),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for PerformanceAttributes {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(attributes) => std::fmt::Display::fmt(attributes, f),
+            #[cfg(feature = "mania")]
+            Self::Mania(attributes) => std::fmt::Display::fmt(attributes, f),
+            #[cfg(feature = "osu")]
+            Self::Osu(attributes) => std::fmt::Display::fmt(attributes, f),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(attributes) => std::fmt::Display::fmt(attributes, f),
+        }
+    }
 }
 
 impl From<PerformanceAttributes> for DifficultyAttributes {
@@ -634,18 +1299,125 @@ impl From<taiko::TaikoPerformanceAttributes> for PerformanceAttributes {
     }
 }
 
-#[cfg(any(feature = "osu", feature = "taiko"))]
+/// Apply `f` to all items, spread across the available threads.
+///
+/// The results are in the same order as the items.
+pub(crate) fn par_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Copy + Send,
+{
+    let n_threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let chunk_size = ((items.len() + n_threads - 1) / n_threads).max(1);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    timings::measure_thread(|| chunk.iter().map(f).collect::<Vec<_>>())
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                let (results, timings) = handle.join().expect("parallel calculation panicked");
+                timings::merge(timings);
+
+                results
+            })
+            .collect()
+    })
+}
+
+/// Whether the values are equal or differ by at most `epsilon`.
+#[inline]
+fn approx_eq_f64(a: f64, b: f64, epsilon: f64) -> bool {
+    a == b || (a - b).abs() <= epsilon
+}
+
+/// The type in which skills store their strain peaks and object strains.
+///
+/// With the `precision-f32` feature, strains are stored as `f32` to halve their memory.
+/// Calculations themselves are still done with `f64`.
+#[cfg(not(feature = "precision-f32"))]
+pub(crate) type StrainValue = f64;
+
+/// The type in which skills store their strain peaks and object strains.
+///
+/// With the `precision-f32` feature, strains are stored as `f32` to halve their memory.
+/// Calculations themselves are still done with `f64`.
+#[cfg(feature = "precision-f32")]
+pub(crate) type StrainValue = f32;
+
+/// Convert a stored strain into an `f64` value.
+#[inline]
+#[allow(clippy::useless_conversion)]
+fn widen_strain(strain: StrainValue) -> f64 {
+    f64::from(strain)
+}
+
+/// Round a strain to the precision in which it would be stored.
+///
+/// Used for in-progress section peaks so that they match their saved counterparts.
+#[inline]
+fn round_strain(strain: f64) -> f64 {
+    widen_strain(strain as StrainValue)
+}
+
+/// The saved strain peaks of a skill followed by the peak of its current section,
+/// i.e. the peaks that the skill would have if the current section ended now.
+#[inline]
+fn peaks_with_current(
+    strain_peaks: &[StrainValue],
+    curr_section_peak: f64,
+) -> impl Iterator<Item = f64> + '_ {
+    strain_peaks
+        .iter()
+        .copied()
+        .map(widen_strain)
+        .chain(std::iter::once(round_strain(curr_section_peak)))
+}
+
+/// Convert stored strains into `f64` values. Does not allocate without `precision-f32`.
+#[cfg(not(feature = "precision-f32"))]
+#[inline]
+fn widen_strains(strains: Vec<StrainValue>) -> Vec<f64> {
+    strains
+}
+
+/// Convert stored strains into `f64` values. Does not allocate without `precision-f32`.
+#[cfg(feature = "precision-f32")]
+#[inline]
+fn widen_strains(strains: Vec<StrainValue>) -> Vec<f64> {
+    strains.into_iter().map(widen_strain).collect()
+}
+
+/// Copy stored strains into a buffer of the same length.
 #[inline]
-fn difficulty_range(val: f64, max: f64, avg: f64, min: f64) -> f64 {
-    if val > 5.0 {
-        avg + (max - avg) * (val - 5.0) / 5.0
-    } else if val < 5.0 {
-        avg - (avg - min) * (5.0 - val) / 5.0
-    } else {
-        avg
+fn copy_strains(buf: &mut [f64], strains: &[StrainValue]) {
+    #[cfg(not(feature = "precision-f32"))]
+    buf.copy_from_slice(strains);
+
+    #[cfg(feature = "precision-f32")]
+    {
+        assert_eq!(buf.len(), strains.len());
+
+        for (value, strain) in buf.iter_mut().zip(strains) {
+            *value = widen_strain(*strain);
+        }
     }
 }
 
+/// Sort strain peaks in descending order, i.e. in the order they are weighted.
+#[inline]
+fn sort_strain_peaks(peaks: &mut [f64]) {
+    peaks.sort_unstable_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+}
+
 #[cfg(not(any(
     feature = "osu",
     feature = "taiko",
@@ -656,3 +1428,676 @@ compile_error!("At least one of the features `osu`, `taiko`, `fruits`, `mania` m
 
 #[cfg(all(feature = "async_tokio", feature = "async_std"))]
 compile_error!("Only one of the features `async_tokio` and `async_std` should be enabled");
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn osu_skill_strains() {
+        use crate::{osu, Beatmap};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let sections = osu::strains(&map, 0, None).strains.len();
+
+        let strains = osu::skill_strains(&map, 0, None);
+        assert!(strains.flashlight.is_empty());
+
+        for peaks in [&strains.aim, &strains.aim_no_sliders, &strains.speed].iter() {
+            assert_eq!(peaks.len(), sections);
+            assert!(peaks.windows(2).all(|w| w[0] >= w[1]));
+        }
+
+        let strains = osu::skill_strains(&map, 1024, None);
+        assert_eq!(strains.flashlight.len(), sections);
+    }
+
+    #[cfg(feature = "osu")]
+    #[test]
+    fn display_attributes() {
+        use crate::{
+            osu::{OsuDifficultyAttributes, OsuPerformanceAttributes},
+            PerformanceAttributes,
+        };
+
+        let difficulty = OsuDifficultyAttributes {
+            stars: 6.344,
+            aim_strain: 3.12,
+            speed_strain: 2.91,
+            max_combo: 2144,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            difficulty.to_string(),
+            "6.34★ (aim 3.1, speed 2.9) max combo 2144"
+        );
+
+        let performance = PerformanceAttributes::Osu(OsuPerformanceAttributes {
+            difficulty,
+            pp: 412.3,
+            pp_aim: 210.4,
+            pp_speed: 149.8,
+            pp_acc: 50.1,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            performance.to_string(),
+            "412pp (aim 210, speed 150, acc 50)"
+        );
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn accuracy_objects() {
+        use crate::{Beatmap, BeatmapExt};
+
+        #[cfg(feature = "osu")]
+        {
+            let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+            let attributes = map.stars(0, None);
+            assert_eq!(attributes.n_accuracy_objects(), Some(map.hit_objects.len()));
+        }
+
+        #[cfg(feature = "fruits")]
+        {
+            let map = Beatmap::from_path("./maps/2118524.osu").expect("failed to parse map");
+            let attributes = map.stars(0, None);
+            let state = crate::ScoreState::max_for(&attributes);
+            let judged = state.n300 + state.n100 + state.n50 + state.n_katu + state.misses;
+            assert_eq!(attributes.n_accuracy_objects(), Some(judged));
+        }
+
+        #[cfg(feature = "mania")]
+        {
+            let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+            assert!(map.stars(0, None).n_accuracy_objects().is_none());
+        }
+
+        #[cfg(feature = "taiko")]
+        {
+            let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+            let attributes = map.stars(0, None);
+            assert_eq!(
+                attributes.n_accuracy_objects(),
+                Some(map.n_circles as usize)
+            );
+        }
+    }
+
+    #[test]
+    fn resample_strains() {
+        use crate::{Pooling, Strains};
+
+        let strains = Strains {
+            section_length: 400.0,
+            strains: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        };
+
+        let average = strains.resample(4, Pooling::Average);
+        assert_eq!(average.strains.len(), 4);
+        assert_eq!(average.section_length, 600.0);
+        assert_eq!(
+            average.strains,
+            vec![4.0 / 3.0, 8.0 / 3.0, 13.0 / 3.0, 17.0 / 3.0]
+        );
+
+        let max = strains.resample(4, Pooling::Max);
+        assert_eq!(max.strains, vec![2.0, 3.0, 5.0, 6.0]);
+
+        let stretched = strains.resample(12, Pooling::Average);
+        assert_eq!(stretched.strains.len(), 12);
+        assert_eq!(stretched.strains[..2], [1.0, 1.0]);
+
+        assert!(strains.resample(0, Pooling::Max).strains.is_empty());
+        assert!(Strains::default()
+            .resample(600, Pooling::Max)
+            .strains
+            .is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn partial_strains() {
+        use crate::{Beatmap, BeatmapExt};
+
+        let mut paths = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+
+            let full = map.strains(0, None);
+            let partial = map.strains(0, Some(map.hit_objects.len() / 2));
+
+            assert!(!partial.strains.is_empty(), "{}", path);
+            assert!(partial.strains.len() < full.strains.len(), "{}", path);
+            assert_eq!(partial.section_length, full.section_length);
+
+            // Only the last section of the partial strains may differ
+            let n = partial.strains.len() - 1;
+            assert_eq!(partial.strains[..n], full.strains[..n], "{}", path);
+            assert_eq!(map.strains(0, Some(usize::MAX)).strains, full.strains);
+        }
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn star_deltas_sum_up() {
+        use crate::{Beatmap, BeatmapExt, GradualDifficultyAttributes};
+
+        let mut paths = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let stars = map.stars(64, None).stars();
+
+            let gradual = GradualDifficultyAttributes::new(&map, 64);
+            let len = gradual.clone().count();
+            let deltas = gradual.star_deltas();
+            assert_eq!(deltas.clone().count(), len, "{}", path);
+
+            let sum: f64 = deltas.sum();
+            assert!((sum - stars).abs() < 1e-9, "{}: {} vs {}", path, sum, stars);
+        }
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn calculate_many_matches_single() {
+        use crate::{AnyPP, Beatmap, BeatmapExt, ScoreState};
+
+        let mut paths = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let perfect = ScoreState::max_for(&map.stars(8, None));
+
+            let flawed = ScoreState {
+                max_combo: perfect.max_combo / 2,
+                n300: perfect.n300.saturating_sub(10),
+                n100: perfect.n100 + 8,
+                misses: 2,
+                score: 750_000,
+                ..perfect.clone()
+            };
+
+            let states = [perfect, flawed];
+            let many = AnyPP::new(&map).mods(8).calculate_many(&states);
+            assert_eq!(many.len(), states.len());
+
+            for (state, attributes) in states.iter().zip(many) {
+                let single = AnyPP::new(&map).mods(8).state(state.clone()).calculate();
+                assert_eq!(attributes.pp(), single.pp(), "{}", path);
+            }
+        }
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn adjust_mods() {
+        use crate::{Beatmap, BeatmapExt};
+
+        #[cfg(feature = "osu")]
+        {
+            let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+            let flashlight = crate::osu::stars(&map, 8 + 1024, None);
+
+            let adjusted = flashlight.adjust_mods(8 + 1024, 8).unwrap();
+            assert_eq!(adjusted, crate::osu::stars(&map, 8, None));
+            assert!(adjusted.adjust_mods(8, 8 + 1024).is_none());
+            assert!(adjusted.adjust_mods(8, 16).is_none());
+        }
+
+        let mut paths = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let nomod = map.stars(0, None);
+
+            let hidden = nomod.adjust_mods(0, 8 + 1).expect(path);
+            assert_eq!(hidden.stars(), map.stars(8 + 1, None).stars(), "{}", path);
+            assert!(nomod.requires_recalculation(0, 64), "{}", path);
+        }
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn osu_touch_device() {
+        use crate::{Beatmap, OsuPP};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        // TD only affects the performance, not the difficulty
+        let regular = crate::osu::stars(&map, 1024, None);
+        let touch = crate::osu::stars(&map, 1024 + 4, None);
+        assert_eq!(touch, regular);
+
+        let gradual = crate::osu::OsuGradualDifficultyAttributes::new(&map, 1024 + 4)
+            .last()
+            .unwrap();
+        assert_eq!(gradual.stars, regular.stars);
+
+        let regular = OsuPP::new(&map).mods(1024).calculate();
+        let touch = OsuPP::new(&map).mods(1024 + 4).calculate();
+        assert!(touch.pp < regular.pp);
+        assert!(touch.pp_aim < regular.pp_aim);
+        assert!(touch.pp_flashlight < regular.pp_flashlight);
+        assert_eq!(touch.pp_speed, regular.pp_speed);
+
+        // Attributes that were calculated without TD still apply to TD
+        let reused = OsuPP::new(&map)
+            .mods(1024 + 4)
+            .attributes(regular.difficulty.clone())
+            .calculate();
+        assert_eq!(reused.pp, touch.pp);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn stars_light() {
+        use crate::{Beatmap, BeatmapExt};
+
+        let mut paths = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+
+            for mods in [0, 4, 64, 1024 + 16] {
+                let stars = map.stars(mods, None).stars();
+                assert_eq!(map.stars_light(mods), stars, "{} +{}", path, mods);
+            }
+        }
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn preprocessed_map() {
+        use crate::{Beatmap, BeatmapExt};
+
+        let mut paths = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let preprocessed = map.preprocess();
+
+            for mods in [0, 16, 64, 256 + 16] {
+                let stars = map.stars(mods, Some(50));
+                assert_eq!(
+                    preprocessed.stars(mods, Some(50)),
+                    stars,
+                    "{} +{}",
+                    path,
+                    mods
+                );
+
+                let pp = map.max_pp(mods);
+                assert_eq!(preprocessed.pp(mods).calculate(), pp, "{} +{}", path, mods);
+            }
+
+            let gradual = map.gradual_difficulty(64).last();
+            assert_eq!(
+                preprocessed.gradual_difficulty(64).last(),
+                gradual,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn attribute_accessors() {
+        use crate::{Beatmap, BeatmapExt, DifficultyAttributes};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let performance = map.max_pp(16);
+        let difficulty = performance.difficulty_attributes();
+
+        let osu = match &difficulty {
+            DifficultyAttributes::Osu(attributes) => attributes.clone(),
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        };
+
+        assert_eq!(difficulty.ar(), Some(osu.ar));
+        assert_eq!(difficulty.od(), Some(osu.od));
+        assert_eq!(difficulty.n_objects(), Some(map.hit_objects.len()));
+
+        assert_eq!(performance.ar(), difficulty.ar());
+        assert_eq!(performance.od(), difficulty.od());
+        assert_eq!(performance.n_objects(), difficulty.n_objects());
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn attributes_approx_eq() {
+        use crate::{Beatmap, BeatmapExt, DifficultyAttributes};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let attributes = crate::osu::stars(&map, 0, None);
+
+        let mut noisy = attributes.clone();
+        noisy.stars += 1e-10;
+        noisy.aim_strain -= 1e-10;
+
+        assert_ne!(attributes, noisy);
+        assert!(attributes.approx_eq(&noisy, 1e-9));
+        assert!(!attributes.approx_eq(&noisy, 1e-11));
+
+        noisy.max_combo += 1;
+        assert!(!attributes.approx_eq(&noisy, 1.0));
+
+        let a = DifficultyAttributes::Osu(attributes.clone());
+        assert_eq!(a, map.stars(0, None));
+        assert!(a.approx_eq(&map.stars(0, None), 0.0));
+
+        let performance = map.max_pp(0);
+        assert_eq!(performance, map.max_pp(0));
+        assert!(performance.approx_eq(&map.max_pp(0), 1e-9));
+        assert!(!performance.approx_eq(&map.max_pp(64), 1e-9));
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn attribute_mode() {
+        use crate::{Beatmap, BeatmapExt};
+
+        let mut paths = Vec::new();
+
+        #[cfg(feature = "osu")]
+        paths.push("./maps/2785319.osu");
+
+        #[cfg(feature = "taiko")]
+        paths.push("./maps/1028484.osu");
+
+        #[cfg(feature = "fruits")]
+        paths.push("./maps/2118524.osu");
+
+        #[cfg(feature = "mania")]
+        paths.push("./maps/1974394.osu");
+
+        for path in paths {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+
+            assert_eq!(map.stars(0, None).mode(), map.mode, "{}", path);
+            assert_eq!(map.max_pp(0).mode(), map.mode, "{}", path);
+        }
+    }
+
+    #[cfg(all(
+        feature = "precision-f32",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn precision_f32_matches_f64() {
+        use crate::{Beatmap, BeatmapExt};
+
+        // Stars and pp of an SS with DT as calculated with `f64` strains
+        let mut expected = Vec::new();
+
+        #[cfg(feature = "osu")]
+        expected.push(("./maps/2785319.osu", 8.138925330230355, 726.3339402425864));
+
+        #[cfg(feature = "taiko")]
+        expected.push(("./maps/1028484.osu", 4.1929293929001705, 189.54413374646555));
+
+        #[cfg(feature = "fruits")]
+        expected.push(("./maps/2118524.osu", 4.635268921290599, 247.1846730659493));
+
+        #[cfg(feature = "mania")]
+        expected.push(("./maps/1974394.osu", 6.518026726802542, 508.5082744648102));
+
+        for (path, stars, pp) in expected {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let performance = map.pp().mods(64).calculate();
+
+            assert!((performance.stars() - stars).abs() < 1e-4, "{}", path);
+            assert!((performance.pp() - pp).abs() < 1e-3, "{}", path);
+        }
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        feature = "taiko",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn mode_specific_pp() {
+        use crate::{Beatmap, BeatmapExt, GameMode};
+
+        let osu = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let taiko = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+
+        let performance = taiko.taiko_pp().unwrap().misses(0).calculate();
+        assert_eq!(performance.pp, taiko.max_pp(0).pp());
+
+        // Converts are allowed
+        assert!(osu.taiko_pp().is_ok());
+        assert!(osu.osu_pp().is_ok());
+
+        let err = taiko.osu_pp().unwrap_err();
+        assert_eq!(err.map, GameMode::TKO);
+        assert_eq!(err.requested, GameMode::STD);
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        feature = "taiko",
+        feature = "fruits",
+        feature = "mania"
+    ))]
+    #[test]
+    fn uncalculable_maps() {
+        use crate::{
+            parse::{HitObject, HitObjectKind, Pos2},
+            Beatmap, BeatmapExt, GameMode,
+        };
+
+        for mode in [GameMode::STD, GameMode::TKO, GameMode::CTB, GameMode::MNA] {
+            let mut map = Beatmap {
+                mode,
+                od: 5.0,
+                cs: 4.0,
+                ..Default::default()
+            };
+
+            assert!(map.is_empty() && !map.is_calculable());
+
+            for i in 0..2 {
+                let stars = map.stars(0, None).stars();
+                let performance = map.pp().accuracy(95.0).misses(1).calculate();
+
+                assert_eq!(stars, 0.0, "{} with {} objects", mode, i);
+                assert_eq!(performance.pp(), 0.0, "{} with {} objects", mode, i);
+                assert_eq!(map.max_pp(64).pp(), 0.0, "{} with {} objects", mode, i);
+
+                map.hit_objects.push(HitObject {
+                    pos: Pos2::default(),
+                    start_time: 1000.0 * (i + 1) as f64,
+                    kind: HitObjectKind::Circle,
+                    sound: 0,
+                    file_idx: i,
+                });
+                map.n_circles += 1;
+            }
+
+            assert!(!map.is_empty() && map.is_calculable());
+
+            let performance = map.pp().misses(1).calculate();
+            assert!(!performance.pp().is_nan(), "{}", mode);
+
+            // Partial plays with fewer than two objects are worth nothing either
+            assert_eq!(map.pp().passed_objects(1).calculate().pp(), 0.0);
+        }
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        feature = "taiko",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn explain_diff() {
+        use crate::{Beatmap, BeatmapExt, PerformanceAttributes};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let fc = map.pp().calculate();
+        let choke = map.pp().misses(3).combo(100).calculate();
+
+        let diff = fc.explain_diff(&choke).unwrap();
+        assert!(diff.delta() > 0.0);
+        assert_eq!(diff.pp, fc.pp());
+        assert_eq!(diff.other, choke.pp());
+
+        let names: Vec<_> = diff.components.iter().map(|c| c.name).collect();
+        assert_eq!(names, ["aim", "speed", "acc", "flashlight"]);
+        assert!(diff.biggest_change().unwrap().delta() > 0.0);
+        assert!(diff.to_string().starts_with('+'));
+
+        let reverse = choke.explain_diff(&fc).unwrap();
+        assert_eq!(reverse.delta(), -diff.delta());
+
+        let taiko = PerformanceAttributes::from(map.taiko_pp().unwrap().calculate());
+        assert!(fc.explain_diff(&taiko).is_none());
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        feature = "taiko",
+        feature = "fruits",
+        feature = "mania",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn object_counts() {
+        use crate::{Beatmap, BeatmapExt, ObjectCounts};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let counts = map.stars(0, None).object_counts();
+        assert_eq!(
+            counts,
+            ObjectCounts::Osu {
+                circles: map.n_circles as usize,
+                sliders: map.n_sliders as usize,
+                spinners: map.n_spinners as usize,
+            }
+        );
+
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let attributes = map.stars(0, None);
+        let n_circles = map.hit_objects.iter().filter(|h| h.is_circle()).count();
+
+        match attributes.object_counts() {
+            ObjectCounts::Taiko { dons, kats } => {
+                assert_eq!(dons + kats, n_circles);
+                assert!(dons > 0 && kats > 0);
+            }
+            counts => panic!("expected taiko counts, got {:?}", counts),
+        }
+
+        let gradual = map.gradual_difficulty(0).last().unwrap();
+        assert_eq!(gradual.object_counts(), attributes.object_counts());
+
+        let partial = map.stars(0, Some(100)).object_counts();
+        assert_eq!(
+            partial.total(),
+            100 - (map.hit_objects[..100]
+                .iter()
+                .filter(|h| !h.is_circle())
+                .count())
+        );
+
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let counts = map.stars(0, None).object_counts();
+        assert_eq!(counts.total(), map.hit_objects.len());
+        assert!(matches!(counts, ObjectCounts::Mania { hold_notes, .. } if hold_notes > 0));
+
+        let map = Beatmap::from_path("./maps/2118524.osu").expect("failed to parse map");
+        let counts = map.stars(0, None).object_counts();
+        assert!(matches!(counts, ObjectCounts::Fruits { fruits, .. } if fruits > 0));
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(not(feature = "taiko"), allow(dead_code))]
+
 use std::cmp::Ordering;
 use std::iter::{Cycle, Skip, Take};
 use std::ops::Index;
@@ -6,6 +8,8 @@ use std::slice::Iter;
 #[derive(Clone, Debug)]
 pub(crate) struct LimitedQueue<T> {
     queue: Vec<T>,
+    // Not relying on the vec's capacity since it is not preserved when cloning
+    capacity: usize,
     start: usize,
     end: usize,
 }
@@ -17,12 +21,13 @@ impl<T> LimitedQueue<T> {
             end: capacity - 1,
             start: 0,
             queue: Vec::with_capacity(capacity),
+            capacity,
         }
     }
 
     #[inline]
     pub(crate) fn push(&mut self, elem: T) {
-        let capacity = self.queue.capacity();
+        let capacity = self.capacity;
         self.end = (self.end + 1) % capacity;
 
         if self.queue.len() == capacity {
@@ -46,13 +51,13 @@ impl<T> LimitedQueue<T> {
     #[inline]
     pub(crate) fn clear(&mut self) {
         self.start = 0;
-        self.end = self.queue.capacity() - 1;
+        self.end = self.capacity - 1;
         self.queue.clear();
     }
 
     #[inline]
     pub(crate) fn full(&self) -> bool {
-        self.queue.len() == self.queue.capacity()
+        self.queue.len() == self.capacity
     }
 
     #[inline]
@@ -86,7 +91,7 @@ impl<T> Index<usize> for LimitedQueue<T> {
 
     #[inline]
     fn index(&self, idx: usize) -> &Self::Output {
-        &self.queue[(self.start + idx) % self.queue.capacity()]
+        &self.queue[(self.start + idx) % self.capacity]
     }
 }
 
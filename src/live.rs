@@ -0,0 +1,182 @@
+use crate::{Beatmap, GradualPerformanceAttributes, PerformanceAttributes, ScoreState};
+
+/// Performance calculation in push mode, e.g. for servers that follow live plays.
+///
+/// Whereas [`GradualPerformanceAttributes`] calculates the performance right away whenever
+/// an object is processed, this calculator only records which objects have happened and
+/// what the current score state is. The skills are then brought up to date on demand, i.e.
+/// when [`performance`](LivePerformance::performance) is called, so that pushing many
+/// objects between two updates of a spectator display costs only one evaluation.
+///
+/// Objects can be pushed either by count or by the current time of the play.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{Beatmap, LivePerformance, ScoreState};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+///
+/// let mut live = LivePerformance::new(&map, 8 + 64); // HDDT
+/// let mut state = ScoreState::new();
+///
+/// // A frame arrives at 2.5 seconds into the map...
+/// state.n300 += 3;
+/// state.max_combo += 3;
+/// live.push_until(2500.0, state.clone());
+///
+/// // ... and the next one a bit later
+/// state.n100 += 1;
+/// state.max_combo += 1;
+/// live.push_until(2750.0, state.clone());
+///
+/// // The performance is only calculated once both frames are in
+/// if let Some(performance) = live.performance() {
+///     println!("PP: {}", performance.pp());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LivePerformance<'map> {
+    map: &'map Beatmap,
+    gradual: GradualPerformanceAttributes<'map>,
+    state: ScoreState,
+    n_pushed: usize,
+    n_processed: usize,
+    current: Option<PerformanceAttributes>,
+}
+
+impl<'map> LivePerformance<'map> {
+    /// Create a new live performance calculator for maps of any mode.
+    pub fn new(map: &'map Beatmap, mods: u32) -> Self {
+        Self {
+            map,
+            gradual: GradualPerformanceAttributes::new(map, mods),
+            state: ScoreState::new(),
+            n_pushed: 0,
+            n_processed: 0,
+            current: None,
+        }
+    }
+
+    /// Record that the next hit object happened and that the score is now at `state`.
+    #[inline]
+    pub fn push_object(&mut self, state: ScoreState) {
+        self.push_objects(1, state);
+    }
+
+    /// Record that the next `n` hit objects happened and that the score is now at `state`.
+    ///
+    /// Objects beyond the end of the map are ignored.
+    pub fn push_objects(&mut self, n: usize, state: ScoreState) {
+        self.n_pushed = self
+            .n_pushed
+            .saturating_add(n)
+            .min(self.map.hit_objects.len());
+        self.state = state;
+    }
+
+    /// Record that the play has reached `time`, i.e. that all hit objects starting
+    /// at or before `time` happened, and that the score is now at `state`.
+    ///
+    /// The time is that of the map, i.e. unaffected by the clock rate.
+    pub fn push_until(&mut self, time: f64, state: ScoreState) {
+        let n_passed = self
+            .map
+            .hit_objects
+            .partition_point(|h| h.start_time <= time);
+        self.n_pushed = self.n_pushed.max(n_passed);
+        self.state = state;
+    }
+
+    /// The amount of hit objects that happened so far.
+    #[inline]
+    pub fn n_pushed(&self) -> usize {
+        self.n_pushed
+    }
+
+    /// The amount of hit objects that happened but are not yet
+    /// considered by the last calculated performance.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.n_pushed - self.n_processed
+    }
+
+    /// Bring the calculation up to date with all pushed hit objects and
+    /// return the performance attributes for the latest score state.
+    ///
+    /// If no object was pushed since the last call, the previous attributes are
+    /// returned without recalculating, even if the score state changed since then.
+    /// Returns `None` if no object was pushed yet.
+    pub fn performance(&mut self) -> Option<&PerformanceAttributes> {
+        let pending = self.pending();
+
+        if pending > 0 {
+            self.current = self
+                .gradual
+                .process_next_n_objects(self.state.clone(), pending)
+                .or_else(|| self.current.take());
+            self.n_processed = self.n_pushed;
+        }
+
+        self.current.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn live_eq_gradual() {
+        use crate::BeatmapExt;
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let n_objects = map.hit_objects.len();
+        let mods = 64;
+
+        let mut live = LivePerformance::new(&map, mods);
+        assert!(live.performance().is_none());
+
+        let mut state = ScoreState::new();
+
+        for h in map.hit_objects.iter().take(100) {
+            state.n300 += 1;
+            state.max_combo += 1;
+            live.push_until(h.start_time, state.clone());
+        }
+
+        assert_eq!(live.pending(), 100);
+
+        let expected = GradualPerformanceAttributes::new(&map, mods)
+            .process_next_n_objects(state.clone(), 100)
+            .unwrap();
+
+        assert_eq!(live.performance(), Some(&expected));
+        assert_eq!(live.pending(), 0);
+
+        // Time that has already passed does not push objects again
+        live.push_until(0.0, state.clone());
+        assert_eq!(live.n_pushed(), 100);
+
+        let full_state = ScoreState::max_for(&map.stars(mods, None));
+        live.push_objects(usize::MAX, full_state.clone());
+        assert_eq!(live.n_pushed(), n_objects);
+
+        let expected = GradualPerformanceAttributes::new(&map, mods)
+            .process_next_n_objects(full_state, n_objects)
+            .unwrap();
+
+        assert_eq!(live.performance(), Some(&expected));
+
+        // Without new objects, the previous attributes are kept
+        assert_eq!(live.performance(), Some(&expected));
+    }
+}
@@ -1,15 +1,19 @@
 use std::{
-    iter::{self, Skip, Zip},
+    iter::{Skip, Zip},
     slice::Iter,
 };
 
 use crate::{
     mania::{strain::Strain, SECTION_LEN},
     parse::HitObject,
-    Beatmap, GameMode, Mods,
+    research::ManiaParameters,
+    Beatmap, Mods, Strains,
 };
 
-use super::{DifficultyHitObject, ManiaDifficultyAttributes, STAR_SCALING_FACTOR};
+use super::{
+    column_count, column_of, DifficultyHitObject, LongNotes, ManiaDifficultyAttributes,
+    DEFAULT_LN_WEIGHT, STAR_SCALING_FACTOR,
+};
 
 /// Gradually calculate the difficulty attributes of an osu!mania map.
 ///
@@ -23,7 +27,7 @@ use super::{DifficultyHitObject, ManiaDifficultyAttributes, STAR_SCALING_FACTOR}
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, mania::ManiaGradualDifficultyAttributes};
+/// use akatsuki_pp::{Beatmap, mania::ManiaGradualDifficultyAttributes};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -44,49 +48,87 @@ use super::{DifficultyHitObject, ManiaDifficultyAttributes, STAR_SCALING_FACTOR}
 #[derive(Clone, Debug)]
 pub struct ManiaGradualDifficultyAttributes<'map> {
     pub(crate) idx: usize,
+    clock_rate: f64,
     difficulty_objects: ManiaObjectIter<'map>,
     strain: Strain,
     curr_section_end: f64,
     strain_peak_buf: Vec<f64>,
+    first: Option<&'map HitObject>,
+    column_strains: Vec<f64>,
+    column_notes: Vec<usize>,
+    long_notes: LongNotes,
 }
 
 impl<'map> ManiaGradualDifficultyAttributes<'map> {
     /// Create a new difficulty attributes iterator for osu!mania maps.
     pub fn new(map: &'map Beatmap, mods: impl Mods) -> Self {
-        let rounded_cs = map.cs.round();
-
-        let columns = match map.mode {
-            GameMode::MNA => rounded_cs.max(1.0) as u8,
-            GameMode::STD => {
-                let rounded_od = map.od.round();
-
-                let n_objects = map.n_circles + map.n_sliders + map.n_spinners;
-                let slider_or_spinner_ratio = (n_objects - map.n_circles) as f32 / n_objects as f32;
-
-                if slider_or_spinner_ratio < 0.2 {
-                    7
-                } else if slider_or_spinner_ratio < 0.3 || rounded_cs >= 5.0 {
-                    6 + (rounded_od > 5.0) as u8
-                } else if slider_or_spinner_ratio > 0.6 {
-                    4 + (rounded_od > 4.0) as u8
-                } else {
-                    (rounded_od as u8 + 1).max(4).min(7)
-                }
-            }
-            other => panic!("can not calculate mania difficulty on a {:?} map", other),
-        };
+        let columns = column_count(map);
 
         let clock_rate = mods.speed();
-        let strain = Strain::new(columns);
+        let strain = Strain::new(columns, DEFAULT_LN_WEIGHT, ManiaParameters::DEFAULT.strain);
+        let column_strains = vec![0.0; columns as usize];
+        let column_notes = vec![0; columns as usize];
         let columns = columns as f32;
         let difficulty_objects = ManiaObjectIter::new(&map.hit_objects, columns, clock_rate);
 
         Self {
             idx: 0,
+            clock_rate,
             difficulty_objects,
             strain,
             curr_section_end: 0.0,
             strain_peak_buf: Vec::new(),
+            first: map.hit_objects.first(),
+            column_strains,
+            column_notes,
+            long_notes: LongNotes::default(),
+        }
+    }
+
+    /// The summed up strain of all processed notes per column, starting with the leftmost column.
+    ///
+    /// Suitable to compare the load of individual columns or hands up to the current note.
+    #[inline]
+    pub fn column_strains(&self) -> &[f64] {
+        &self.column_strains
+    }
+
+    /// The amount of processed notes per column, starting with the leftmost column.
+    #[inline]
+    pub fn column_notes(&self) -> &[usize] {
+        &self.column_notes
+    }
+
+    /// The strains of all processed notes, including the section
+    /// of the current note, as [`strains`](super::strains) would return them.
+    ///
+    /// Suitable to draw the evolving strain graph during a play
+    /// without calculating the strains from scratch.
+    pub fn current_strains(&self) -> Strains {
+        let mut strains = Vec::new();
+
+        // The first note has no strain
+        if self.idx >= 2 {
+            strains.extend(crate::peaks_with_current(
+                &self.strain.strain_peaks,
+                self.strain.curr_section_peak,
+            ));
+        }
+
+        Strains {
+            section_length: SECTION_LEN * self.clock_rate,
+            strains,
+        }
+    }
+
+    fn attributes(&self, stars: f64) -> ManiaDifficultyAttributes {
+        ManiaDifficultyAttributes {
+            stars,
+            n_notes: self.long_notes.n_notes,
+            n_hold_notes: self.long_notes.n_hold_notes,
+            hold_factor: self.strain.avg_hold_factor(),
+            ln_coverage: self.long_notes.coverage(self.column_notes.len()),
+            ..Default::default()
         }
     }
 }
@@ -98,7 +140,12 @@ impl Iterator for ManiaGradualDifficultyAttributes<'_> {
         self.idx = self.idx.saturating_add(1);
 
         if self.idx == 1 {
-            return (!self.difficulty_objects.is_empty).then(ManiaDifficultyAttributes::default);
+            // No strain for the first note
+            let first = self.first?;
+            self.column_notes[column_of(first, self.difficulty_objects.columns)] += 1;
+            self.long_notes.add(first);
+
+            return Some(self.attributes(0.0));
         }
 
         let h = self.difficulty_objects.next()?;
@@ -114,20 +161,26 @@ impl Iterator for ManiaGradualDifficultyAttributes<'_> {
         }
 
         self.strain.process(&h);
+        self.column_strains[h.column] += self.strain.current_strain();
+        self.column_notes[h.column] += 1;
+        self.long_notes.add(h.base);
 
-        let missing = self.strain.strain_peaks.len() + 1 - self.strain_peak_buf.len();
-        self.strain_peak_buf.extend(iter::repeat(0.0).take(missing));
+        self.strain_peak_buf
+            .resize(self.strain.strain_peaks.len() + 1, 0.0);
 
-        self.strain_peak_buf[..self.strain.strain_peaks.len()]
-            .copy_from_slice(&self.strain.strain_peaks);
+        crate::copy_strains(
+            &mut self.strain_peak_buf[..self.strain.strain_peaks.len()],
+            &self.strain.strain_peaks,
+        );
 
         if let Some(last) = self.strain_peak_buf.last_mut() {
-            *last = self.strain.curr_section_peak;
+            *last = crate::round_strain(self.strain.curr_section_peak);
         }
 
-        let stars = Strain::difficulty_value(&mut self.strain_peak_buf) * STAR_SCALING_FACTOR;
+        let stars = Strain::difficulty_value(&mut self.strain_peak_buf, &self.strain.params)
+            * STAR_SCALING_FACTOR;
 
-        Some(ManiaDifficultyAttributes { stars })
+        Some(self.attributes(stars))
     }
 
     #[inline]
@@ -150,19 +203,16 @@ struct ManiaObjectIter<'map> {
     hit_objects: Zip<Skip<Iter<'map, HitObject>>, Iter<'map, HitObject>>,
     columns: f32,
     clock_rate: f64,
-    is_empty: bool,
 }
 
 impl<'map> ManiaObjectIter<'map> {
     fn new(hit_objects: &'map [HitObject], columns: f32, clock_rate: f64) -> Self {
-        let is_empty = hit_objects.is_empty();
         let hit_objects = hit_objects.iter().skip(1).zip(hit_objects);
 
         Self {
             hit_objects,
             columns,
             clock_rate,
-            is_empty,
         }
     }
 }
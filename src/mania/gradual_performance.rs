@@ -19,7 +19,7 @@ use super::{ManiaGradualDifficultyAttributes, ManiaPerformanceAttributes};
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, mania::ManiaGradualPerformanceAttributes};
+/// use akatsuki_pp::{Beatmap, mania::ManiaGradualPerformanceAttributes};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -59,10 +59,10 @@ use super::{ManiaGradualDifficultyAttributes, ManiaPerformanceAttributes};
 /// // Skip to the end
 /// # /*
 /// score = ...
-/// let final_performance = gradual_perf.process_next_n_objects(score, usize::MAX).unwrap();
+/// let final_performance = gradual_perf.process_remaining(score).unwrap();
 /// println!("PP: {}", performance.pp);
 /// # */
-/// # let _ = gradual_perf.process_next_n_objects(score, usize::MAX);
+/// # let _ = gradual_perf.process_remaining(score);
 ///
 /// // Once the final performance was calculated,
 /// // attempting to process further objects will return `None`.
@@ -117,10 +117,56 @@ impl<'map> ManiaGradualPerformanceAttributes<'map> {
 
         Some(performance)
     }
+
+    /// Process all remaining hit objects at once and calculate the
+    /// performance attributes for the final score.
+    ///
+    /// Suitable to finish off a play, e.g. after a fail, without having to know
+    /// how many objects are left. Returns `None` if all objects were processed already.
+    pub fn process_remaining(&mut self, score: u32) -> Option<ManiaPerformanceAttributes> {
+        self.process_next_n_objects(score, usize::MAX)
+    }
+
+    /// The strain contribution of each column for all processed notes,
+    /// starting with the leftmost column.
+    ///
+    /// Without a state, this is the summed up strain of each column's notes.
+    /// If the play's current [`ManiaColumnState`] of each column is given,
+    /// a column's strain is scaled by the portion of its notes that were not missed.
+    /// Columns without a state are not scaled.
+    ///
+    /// Suitable to analyze the hand balance of a play while it's still ongoing.
+    pub fn column_strains(&self, state: Option<&[ManiaColumnState]>) -> Vec<f64> {
+        let strains = self.difficulty.column_strains();
+        let notes = self.difficulty.column_notes();
+        let state = state.unwrap_or_default();
+
+        strains
+            .iter()
+            .zip(notes)
+            .enumerate()
+            .map(|(i, (&strain, &n_notes))| match state.get(i) {
+                Some(column) if n_notes > 0 => {
+                    let misses = column.misses.min(n_notes);
+
+                    strain * (n_notes - misses) as f64 / n_notes as f64
+                }
+                _ => strain,
+            })
+            .collect()
+    }
+}
+
+/// The state of a single column during an osu!mania play.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManiaColumnState {
+    /// The amount of missed notes in the column.
+    pub misses: usize,
 }
 
 #[cfg(test)]
 mod tests {
+    #[allow(unused_imports)]
     use super::*;
 
     #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
@@ -198,4 +244,37 @@ mod tests {
 
         assert_eq!(regular, gradual);
     }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn column_strains() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let mods = 64;
+        let n = 200;
+
+        let mut gradual = ManiaGradualPerformanceAttributes::new(&map, mods);
+        let _ = gradual.process_next_n_objects(100_000, n);
+
+        let notes = crate::mania::note_strains(&map, mods);
+        let mut expected = vec![0.0; map.cs as usize];
+
+        for (_, column, strain) in notes.into_iter().take(n) {
+            expected[column] += strain;
+        }
+
+        let strains = gradual.column_strains(None);
+        assert_eq!(strains.len(), expected.len());
+
+        for (strain, expected) in strains.iter().zip(&expected) {
+            assert!((strain - expected).abs() < 1e-9);
+        }
+
+        assert_eq!(gradual.difficulty.column_notes().iter().sum::<usize>(), n);
+
+        let state = [ManiaColumnState { misses: usize::MAX }];
+        let scaled = gradual.column_strains(Some(&state));
+
+        assert_eq!(scaled[0], 0.0);
+        assert_eq!(scaled[1..], strains[1..]);
+    }
 }
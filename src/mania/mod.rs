@@ -3,17 +3,31 @@
 mod gradual_difficulty;
 mod gradual_performance;
 mod pp;
+mod preprocessed;
 mod strain;
 
 pub use gradual_difficulty::*;
 pub use gradual_performance::*;
 pub use pp::*;
+pub use preprocessed::ManiaPreprocessedMap;
 use strain::Strain;
+pub(crate) use strain::STRAIN_PARAMETERS;
 
-use crate::{parse::HitObject, Beatmap, GameMode, Mods, Strains};
+use std::fmt;
+
+#[cfg(feature = "research")]
+use crate::research::ParameterError;
+
+use crate::{
+    parse::{HitObject, HitObjectKind},
+    research::ManiaParameters,
+    timings::{Phase, Timer},
+    Beatmap, GameMode, Mods, Strains,
+};
 
 const SECTION_LEN: f64 = 400.0;
 const STAR_SCALING_FACTOR: f64 = 0.018;
+const DEFAULT_LN_WEIGHT: f64 = 1.0;
 
 /// Difficulty calculation for osu!mania maps.
 ///
@@ -23,33 +37,197 @@ pub fn stars(
     mods: impl Mods,
     passed_objects: Option<usize>,
 ) -> ManiaDifficultyAttributes {
-    let mut strain = calculate_strain(map, mods, passed_objects);
+    stars_with_ln_weight(map, mods, passed_objects, DEFAULT_LN_WEIGHT)
+}
 
-    ManiaDifficultyAttributes {
-        stars: Strain::difficulty_value(&mut strain.strain_peaks) * STAR_SCALING_FACTOR,
-    }
+/// Same as [`stars`] but the strain bonuses of long notes are scaled by `ln_weight`.
+///
+/// A weight of 1.0 is the regular calculation, values below nerf
+/// long notes, 0.0 removes their bonus entirely, and values above buff them.
+/// Negative values and NaN are treated as 0.0.
+pub fn stars_with_ln_weight(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    ln_weight: f64,
+) -> ManiaDifficultyAttributes {
+    calculate_stars(
+        map,
+        column_count(map),
+        mods,
+        passed_objects,
+        ln_weight,
+        &ManiaParameters::DEFAULT,
+    )
+}
+
+/// Same as [`stars`] but the skill uses the given parameters instead of the regular ones.
+///
+/// Returns an error if any parameter is outside of its valid range.
+#[cfg(feature = "research")]
+#[cfg_attr(docsrs, doc(cfg(feature = "research")))]
+pub fn stars_with_parameters(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &ManiaParameters,
+) -> Result<ManiaDifficultyAttributes, ParameterError> {
+    params.validate()?;
+
+    Ok(calculate_stars(
+        map,
+        column_count(map),
+        mods,
+        passed_objects,
+        DEFAULT_LN_WEIGHT,
+        params,
+    ))
+}
+
+fn calculate_stars(
+    map: &Beatmap,
+    columns: u8,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    ln_weight: f64,
+    params: &ManiaParameters,
+) -> ManiaDifficultyAttributes {
+    trace_span!(
+        "difficulty",
+        mode = "mania",
+        map = format_args!("{:032x}", crate::CalcKey::fingerprint(map)),
+        mods = crate::mods::legacy_bits(mods),
+        passed_objects = ?passed_objects,
+        n_objects = map.hit_objects.len(),
+    );
+
+    let mut strain = calculate_strain(map, columns, mods, passed_objects, ln_weight, params);
+
+    let timer = Timer::start();
+    let mut strain_peaks = crate::widen_strains(std::mem::take(&mut strain.strain_peaks));
+    let stars = Strain::difficulty_value(&mut strain_peaks, &params.strain) * STAR_SCALING_FACTOR;
+
+    let mut long_notes = LongNotes::default();
+    let take = passed_objects.unwrap_or(map.hit_objects.len());
+    map.hit_objects
+        .iter()
+        .take(take)
+        .for_each(|h| long_notes.add(h));
+
+    let attributes = ManiaDifficultyAttributes {
+        stars,
+        n_notes: long_notes.n_notes,
+        n_hold_notes: long_notes.n_hold_notes,
+        hold_factor: strain.avg_hold_factor(),
+        ln_coverage: long_notes.coverage(strain.column_count()),
+        ..Default::default()
+    };
+    timer.finish(Phase::Evaluation);
+
+    attributes
 }
 
 /// Essentially the same as the [`stars`] function but instead of
 /// evaluating the final strains, it just returns them as is.
 ///
 /// Suitable to plot the difficulty of a map over time.
-pub fn strains(map: &Beatmap, mods: impl Mods) -> Strains {
-    let strain = calculate_strain(map, mods, None);
+///
+/// In case of a partial play, e.g. a live play that is still ongoing,
+/// one can specify the amount of passed objects.
+pub fn strains(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize>) -> Strains {
+    let strain = calculate_strain(
+        map,
+        column_count(map),
+        mods,
+        passed_objects,
+        DEFAULT_LN_WEIGHT,
+        &ManiaParameters::DEFAULT,
+    );
 
     Strains {
         section_length: SECTION_LEN * mods.speed(),
-        strains: strain.strain_peaks,
+        strains: crate::widen_strains(strain.strain_peaks),
+    }
+}
+
+/// The strain peaks of each skill of an osu!mania map, see [`skill_strains`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ManiaSkillStrains {
+    /// Strain peaks of the strain skill.
+    pub strain: Vec<f64>,
+}
+
+/// Calculate the strain peaks of each skill of an osu!mania map.
+///
+/// The peaks of each skill are sorted in descending order and not weighted,
+/// so that the star rating can be recomputed with alternative weightings.
+///
+/// In case of a partial play, e.g. a fail, one can specify the amount of passed objects.
+pub fn skill_strains(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+) -> ManiaSkillStrains {
+    let strain = calculate_strain(
+        map,
+        column_count(map),
+        mods,
+        passed_objects,
+        DEFAULT_LN_WEIGHT,
+        &ManiaParameters::DEFAULT,
+    );
+    let mut peaks = crate::widen_strains(strain.strain_peaks);
+    crate::sort_strain_peaks(&mut peaks);
+
+    ManiaSkillStrains { strain: peaks }
+}
+
+/// Calculate the strain value of each note of an osu!mania map.
+///
+/// Returns `(time, column, strain)` for every note in the order of the map's hit objects.
+/// The time is the note's start time in the map, i.e. it is __not__ adjusted by the
+/// clock rate of speed changing mods, and the column starts at 0 on the left.
+/// Since strains build up relative to the previous note, the first note has a strain of 0.
+///
+/// The strains are the same values that are used for the star rating and thus pp,
+/// so they are suitable to give per-note difficulty feedback, e.g. in a chart editor.
+pub fn note_strains(map: &Beatmap, mods: impl Mods) -> Vec<(f64, usize, f64)> {
+    let first = match map.hit_objects.first() {
+        Some(h) => h,
+        None => return Vec::new(),
+    };
+
+    let columns = column_count(map);
+    let clock_rate = mods.speed();
+    let mut strain = Strain::new(columns, DEFAULT_LN_WEIGHT, STRAIN_PARAMETERS);
+    let columns = columns as f32;
+
+    let mut note_strains = Vec::with_capacity(map.hit_objects.len());
+    note_strains.push((first.start_time, column_of(first, columns), 0.0));
+
+    let hit_objects = map
+        .hit_objects
+        .iter()
+        .skip(1)
+        .zip(map.hit_objects.iter())
+        .map(|(base, prev)| DifficultyHitObject::new(base, prev, columns, clock_rate));
+
+    for h in hit_objects {
+        strain.process(&h);
+        note_strains.push((h.base.start_time, h.column, strain.current_strain()));
     }
+
+    note_strains
 }
 
-fn calculate_strain(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize>) -> Strain {
-    let take = passed_objects.unwrap_or_else(|| map.hit_objects.len());
+fn column_count(map: &Beatmap) -> u8 {
     let rounded_cs = map.cs.round();
 
-    let columns = match map.mode {
+    match map.mode {
         GameMode::MNA => rounded_cs.max(1.0) as u8,
         GameMode::STD => {
+            trace_span!("convert", from = "osu", to = "mania");
+
             let rounded_od = map.od.round();
 
             let n_objects = map.n_circles + map.n_sliders + map.n_spinners;
@@ -62,14 +240,32 @@ fn calculate_strain(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize
             } else if slider_or_spinner_ratio > 0.6 {
                 4 + (rounded_od > 4.0) as u8
             } else {
-                (rounded_od as u8 + 1).max(4).min(7)
+                (rounded_od as u8 + 1).clamp(4, 7)
             }
         }
         other => panic!("can not calculate mania difficulty on a {:?} map", other),
-    };
+    }
+}
+
+#[inline]
+fn column_of(h: &HitObject, columns: f32) -> usize {
+    let x_divisor = 512.0 / columns;
+
+    (h.pos.x / x_divisor).floor().min(columns - 1.0) as usize
+}
+
+fn calculate_strain(
+    map: &Beatmap,
+    columns: u8,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    ln_weight: f64,
+    params: &ManiaParameters,
+) -> Strain {
+    let take = passed_objects.unwrap_or(map.hit_objects.len());
 
     let clock_rate = mods.speed();
-    let mut strain = Strain::new(columns);
+    let mut strain = Strain::new(columns, ln_weight, params.strain);
     let columns = columns as f32;
 
     let mut hit_objects = map
@@ -106,9 +302,44 @@ fn calculate_strain(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize
     strain
 }
 
+/// Accumulates long note values of the processed hit objects.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LongNotes {
+    pub(crate) n_notes: usize,
+    pub(crate) n_hold_notes: usize,
+    hold_duration: f64,
+    start_time: Option<f64>,
+    end_time: f64,
+}
+
+impl LongNotes {
+    pub(crate) fn add(&mut self, h: &HitObject) {
+        if let HitObjectKind::Hold { end_time } = h.kind {
+            self.n_hold_notes += 1;
+            self.hold_duration += (end_time - h.start_time).max(0.0);
+        } else {
+            self.n_notes += 1;
+        }
+
+        self.start_time.get_or_insert(h.start_time);
+        self.end_time = self.end_time.max(h.end_time());
+    }
+
+    /// Portion of the playable time across all columns in which long notes are held.
+    pub(crate) fn coverage(&self, columns: usize) -> f64 {
+        let length = self.end_time - self.start_time.unwrap_or(self.end_time);
+
+        if length <= 0.0 || columns == 0 {
+            return 0.0;
+        }
+
+        (self.hold_duration / (length * columns as f64)).min(1.0)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct DifficultyHitObject<'o> {
-    base: &'o HitObject,
+    pub(crate) base: &'o HitObject,
     column: usize,
     delta: f64,
     start_time: f64,
@@ -117,12 +348,9 @@ pub(crate) struct DifficultyHitObject<'o> {
 impl<'o> DifficultyHitObject<'o> {
     #[inline]
     fn new(base: &'o HitObject, prev: &'o HitObject, columns: f32, clock_rate: f64) -> Self {
-        let x_divisor = 512.0 / columns;
-        let column = (base.pos.x / x_divisor).floor().min(columns - 1.0) as usize;
-
         Self {
             base,
-            column,
+            column: column_of(base, columns),
             delta: (base.start_time - prev.start_time) / clock_rate,
             start_time: base.start_time / clock_rate,
         }
@@ -130,10 +358,64 @@ impl<'o> DifficultyHitObject<'o> {
 }
 
 /// The result of a difficulty calculation on an osu!mania map.
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ManiaDifficultyAttributes {
     /// The final star rating.
     pub stars: f64,
+    /// The amount of regular notes, i.e. excluding hold notes.
+    pub n_notes: usize,
+    /// The amount of hold notes (long notes).
+    pub n_hold_notes: usize,
+    /// The average factor by which notes were weighted for being
+    /// pressed while a long note is held in another column.
+    ///
+    /// Ranges from 1.0 for maps without such notes up to 1.25 with the default long note weight.
+    pub hold_factor: f64,
+    /// The portion of the playable time, summed up across all columns,
+    /// in which long notes are held. Ranges from 0.0 to 1.0.
+    pub ln_coverage: f64,
+    /// The release and formulas that calculated the attributes.
+    pub provenance: crate::Provenance,
+}
+
+impl ManiaDifficultyAttributes {
+    /// Adjust the attributes that were calculated with `old_mods` so that they
+    /// apply to `new_mods` without recalculating the difficulty.
+    ///
+    /// Only the clock rate affects the difficulty of osu!mania maps so
+    /// the attributes stay the same unless it changed.
+    ///
+    /// Returns `None` if a full recalculation is required, i.e. if the clock rate changed.
+    #[inline]
+    pub fn adjust_mods(&self, old_mods: impl Mods, new_mods: impl Mods) -> Option<Self> {
+        (old_mods.speed() == new_mods.speed()).then_some(*self)
+    }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let eq = |a, b| crate::approx_eq_f64(a, b, epsilon);
+
+        eq(self.stars, other.stars)
+            && self.n_notes == other.n_notes
+            && self.n_hold_notes == other.n_hold_notes
+            && eq(self.hold_factor, other.hold_factor)
+            && eq(self.ln_coverage, other.ln_coverage)
+    }
+}
+
+impl Default for ManiaDifficultyAttributes {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            stars: 0.0,
+            n_notes: 0,
+            n_hold_notes: 0,
+            hold_factor: 1.0,
+            ln_coverage: 0.0,
+            provenance: crate::Provenance::CURRENT,
+        }
+    }
 }
 
 /// The result of a performance calculation on an osu!mania map.
@@ -161,6 +443,33 @@ impl ManiaPerformanceAttributes {
     pub fn pp(&self) -> f64 {
         self.pp
     }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let eq = |a, b| crate::approx_eq_f64(a, b, epsilon);
+
+        self.difficulty.approx_eq(&other.difficulty, epsilon)
+            && eq(self.pp, other.pp)
+            && eq(self.pp_acc, other.pp_acc)
+            && eq(self.pp_strain, other.pp_strain)
+    }
+}
+
+impl fmt::Display for ManiaDifficultyAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}★ ({} hold notes)", self.stars, self.n_hold_notes)
+    }
+}
+
+impl fmt::Display for ManiaPerformanceAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0}pp (strain {:.0}, acc {:.0})",
+            self.pp, self.pp_strain, self.pp_acc
+        )
+    }
 }
 
 impl From<ManiaPerformanceAttributes> for ManiaDifficultyAttributes {
@@ -168,3 +477,93 @@ impl From<ManiaPerformanceAttributes> for ManiaDifficultyAttributes {
         attributes.difficulty
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn long_note_attributes() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let attributes = stars(&map, 0, None);
+
+        assert_eq!(attributes.n_hold_notes, 423);
+        assert!(attributes.hold_factor > 1.0 && attributes.hold_factor <= 1.25);
+        assert!(attributes.ln_coverage > 0.0 && attributes.ln_coverage < 1.0);
+        assert_eq!(stars_with_ln_weight(&map, 0, None, 1.0), attributes);
+
+        let nerfed = stars_with_ln_weight(&map, 0, None, 0.0);
+        assert_eq!(nerfed.hold_factor, 1.0);
+        assert!(nerfed.stars < attributes.stars);
+        assert_eq!(stars_with_ln_weight(&map, 0, None, -1.0), nerfed);
+        assert_eq!(stars_with_ln_weight(&map, 0, None, f64::NAN), nerfed);
+
+        let buffed = crate::ManiaPP::new(&map).ln_weight(2.0).calculate();
+        assert!(buffed.stars() > attributes.stars);
+        assert!(buffed.pp > crate::ManiaPP::new(&map).calculate().pp);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn skill_strains_recompute_stars() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let peaks = skill_strains(&map, 0, None).strain;
+
+        assert_eq!(peaks.len(), strains(&map, 0, None).strains.len());
+        assert!(peaks.windows(2).all(|w| w[0] >= w[1]));
+
+        let (difficulty, _) = peaks.iter().fold((0.0, 1.0), |(sum, weight), strain| {
+            (sum + strain * weight, weight * 0.9)
+        });
+
+        let stars = stars(&map, 0, None).stars;
+        assert!((difficulty * STAR_SCALING_FACTOR - stars).abs() < 1e-10);
+    }
+
+    #[test]
+    fn empty_note_strains() {
+        assert!(note_strains(&Beatmap::default(), 0).is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn note_strains_match_strains() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let notes = note_strains(&map, 64);
+
+        assert_eq!(notes.len(), map.hit_objects.len());
+        assert_eq!(notes[0].2, 0.0);
+
+        let columns = map.cs as usize;
+        assert!(notes.iter().all(|(_, column, _)| *column < columns));
+
+        let times: Vec<_> = map.hit_objects.iter().map(|h| h.start_time).collect();
+        assert!(notes.iter().map(|(time, ..)| *time).eq(times));
+
+        let max_note = notes.iter().map(|(.., strain)| *strain).fold(0.0, f64::max);
+        let max_peak = strains(&map, 64, None)
+            .strains
+            .into_iter()
+            .fold(0.0, f64::max);
+        assert!((crate::round_strain(max_note) - max_peak).abs() < 1e-9);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn max_score_with_mods() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+
+        assert_eq!(max_score(&map, 0), 1_000_000);
+        assert_eq!(max_score(&map, 2 + 256), 250_000);
+        assert_eq!(max_score(&Beatmap::default(), 0), 0);
+
+        let max = crate::ManiaPP::new(&map).calculate().pp;
+        let capped = crate::ManiaPP::new(&map)
+            .mods(2)
+            .score(max_score(&map, 2))
+            .calculate();
+
+        assert!(capped.pp < max);
+    }
+}
@@ -1,12 +1,17 @@
-use super::{stars, ManiaDifficultyAttributes, ManiaPerformanceAttributes};
-use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
+use super::{
+    stars_with_ln_weight, ManiaDifficultyAttributes, ManiaPerformanceAttributes, DEFAULT_LN_WEIGHT,
+};
+use crate::{
+    timings::{Phase, Timer},
+    Beatmap, DifficultyAttributes, InputError, Mods, PerformanceAttributes,
+};
 
 /// Performance calculator on osu!mania maps.
 ///
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{ManiaPP, Beatmap};
+/// use akatsuki_pp::{ManiaPP, Beatmap};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -31,11 +36,13 @@ use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
 #[derive(Clone, Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct ManiaPP<'map> {
-    map: &'map Beatmap,
-    stars: Option<f64>,
+    pub(crate) map: &'map Beatmap,
+    attributes: Option<ManiaDifficultyAttributes>,
     mods: u32,
     pub(crate) score: Option<f64>,
     passed_objects: Option<usize>,
+    ln_weight: f64,
+    strict: bool,
 }
 
 impl<'map> ManiaPP<'map> {
@@ -44,10 +51,12 @@ impl<'map> ManiaPP<'map> {
     pub fn new(map: &'map Beatmap) -> Self {
         Self {
             map,
-            stars: None,
+            attributes: None,
             mods: 0,
             score: None,
             passed_objects: None,
+            ln_weight: DEFAULT_LN_WEIGHT,
+            strict: false,
         }
     }
 
@@ -56,8 +65,8 @@ impl<'map> ManiaPP<'map> {
     /// be sure to put them in here so that they don't have to be recalculated.
     #[inline]
     pub fn attributes(mut self, attributes: impl ManiaAttributeProvider) -> Self {
-        if let Some(stars) = attributes.attributes() {
-            self.stars = Some(stars);
+        if let Some(attributes) = attributes.attributes() {
+            self.attributes = Some(attributes);
         }
 
         self
@@ -97,19 +106,110 @@ impl<'map> ManiaPP<'map> {
         self
     }
 
+    /// Scale the strain bonuses of long notes, see [`stars_with_ln_weight`](crate::mania::stars_with_ln_weight).
+    ///
+    /// Negative values and NaN are treated as 0.0. Defaults to 1.0.
+    ///
+    /// Attributes provided through [`attributes`](ManiaPP::attributes) are used as they are,
+    /// so the weight is ignored in that case. Pass attributes that were calculated with the
+    /// same weight, e.g. by [`stars_with_ln_weight`](crate::mania::stars_with_ln_weight).
+    #[inline]
+    pub fn ln_weight(mut self, ln_weight: f64) -> Self {
+        self.ln_weight = ln_weight;
+
+        self
+    }
+
+    /// Enable or disable strict mode.
+    ///
+    /// In strict mode, input that can not stem from an actual play, e.g. incompatible mods
+    /// like DT and HT or a score above the max score, is reported as [`InputError`]
+    /// by [`try_calculate`](ManiaPP::try_calculate) and causes [`calculate`](ManiaPP::calculate)
+    /// to panic instead of silently producing meaningless values.
+    ///
+    /// Disabled by default.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+
+        self
+    }
+
+    /// Calculate all performance related values just like [`calculate`](ManiaPP::calculate)
+    /// while also measuring how much time each calculation phase took.
+    #[cfg(feature = "timings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+    pub fn calculate_with_timings(self) -> (ManiaPerformanceAttributes, crate::CalcTimings) {
+        crate::timings::measure(|| self.calculate())
+    }
+
+    /// Calculate the performance of each given score on the same map.
+    ///
+    /// The difficulty attributes are calculated only once and shared across all
+    /// scores instead of paying for them on each calculation.
+    /// All other parameters, e.g. mods or passed objects, apply to every score.
+    pub fn calculate_many(mut self, scores: &[u32]) -> Vec<ManiaPerformanceAttributes> {
+        let attributes = self.attributes.take().unwrap_or_else(|| {
+            stars_with_ln_weight(self.map, self.mods, self.passed_objects, self.ln_weight)
+        });
+        self.attributes = Some(attributes);
+
+        scores
+            .iter()
+            .map(|&score| self.clone().score(score).calculate())
+            .collect()
+    }
+
     /// Calculate all performance related values, including pp and stars.
+    ///
+    /// # Panics
+    ///
+    /// Panics in [strict](ManiaPP::strict) mode if the input is invalid.
+    #[track_caller]
     pub fn calculate(self) -> ManiaPerformanceAttributes {
-        let stars = self
-            .stars
-            .unwrap_or_else(|| stars(self.map, self.mods, self.passed_objects).stars);
+        match self.try_calculate() {
+            Ok(performance) => performance,
+            Err(err) => panic!("invalid osu!mania input: {}", err),
+        }
+    }
+
+    /// Calculate all performance related values just like [`calculate`](ManiaPP::calculate)
+    /// but in [strict](ManiaPP::strict) mode, return an error if the input is invalid.
+    ///
+    /// Outside of strict mode, this never fails.
+    pub fn try_calculate(self) -> Result<ManiaPerformanceAttributes, InputError> {
+        trace_span!(
+            "performance",
+            mode = "mania",
+            map = format_args!("{:032x}", crate::CalcKey::fingerprint(self.map)),
+            mods = self.mods,
+        );
+
+        if self.strict {
+            self.validate()?;
+        }
+
+        let attributes = self.attributes.unwrap_or_else(|| {
+            stars_with_ln_weight(self.map, self.mods, self.passed_objects, self.ln_weight)
+        });
+        let stars = attributes.stars;
+
+        // Maps with fewer than two objects have no difficulty to reward
+        if !self.map.is_calculable_until(self.passed_objects) {
+            return Ok(ManiaPerformanceAttributes {
+                difficulty: attributes,
+                ..Default::default()
+            });
+        }
+
+        let timer = Timer::start();
 
         let ez = self.mods.ez();
         let nf = self.mods.nf();
-        let ht = self.mods.ht();
 
-        let mut scaled_score = self.score.map_or(1_000_000.0, |score| {
-            score / 0.5_f64.powi(ez as i32 + nf as i32 + ht as i32)
-        });
+        let mut scaled_score = self
+            .score
+            .map_or(MAX_SCORE, |score| score / score_multiplier(self.mods));
 
         if let Some(passed_objects) = self.passed_objects {
             let percent_passed =
@@ -118,7 +218,7 @@ impl<'map> ManiaPP<'map> {
             scaled_score /= percent_passed;
         }
 
-        let mut od = 34.0 + 3.0 * (10.0 - self.map.od as f64).max(0.0).min(10.0);
+        let mut od = 34.0 + 3.0 * (10.0 - self.map.od as f64).clamp(0.0, 10.0);
         let clock_rate = self.mods.speed();
 
         let mut multiplier = 0.8;
@@ -138,12 +238,27 @@ impl<'map> ManiaPP<'map> {
         let acc_value = self.compute_accuracy_value(scaled_score, strain_value, hit_window);
 
         let pp = (strain_value.powf(1.1) + acc_value.powf(1.1)).powf(1.0 / 1.1) * multiplier;
+        timer.finish(Phase::Evaluation);
 
-        ManiaPerformanceAttributes {
-            difficulty: ManiaDifficultyAttributes { stars },
+        Ok(ManiaPerformanceAttributes {
+            difficulty: attributes,
             pp_acc: acc_value,
             pp_strain: strain_value,
             pp,
+        })
+    }
+
+    fn validate(&self) -> Result<(), InputError> {
+        InputError::check_mods(self.mods)?;
+
+        let max_score = max_score(self.map, self.mods);
+
+        match self.score {
+            Some(score) if score > max_score as f64 => Err(InputError::ScoreTooHigh {
+                score: score as u32,
+                max_score,
+            }),
+            _ => Ok(()),
         }
     }
 
@@ -179,37 +294,41 @@ impl<'map> ManiaPP<'map> {
 
 /// Abstract type to provide flexibility when passing difficulty attributes to a performance calculation.
 pub trait ManiaAttributeProvider {
-    /// Provide the star rating (only difficulty attribute for osu!mania).
-    fn attributes(self) -> Option<f64>;
+    /// Provide the actual difficulty attributes.
+    fn attributes(self) -> Option<ManiaDifficultyAttributes>;
 }
 
+/// Only provides the star rating, all other attributes keep their default value.
 impl ManiaAttributeProvider for f64 {
     #[inline]
-    fn attributes(self) -> Option<f64> {
-        Some(self)
+    fn attributes(self) -> Option<ManiaDifficultyAttributes> {
+        Some(ManiaDifficultyAttributes {
+            stars: self,
+            ..Default::default()
+        })
     }
 }
 
 impl ManiaAttributeProvider for ManiaDifficultyAttributes {
     #[inline]
-    fn attributes(self) -> Option<f64> {
-        Some(self.stars)
+    fn attributes(self) -> Option<ManiaDifficultyAttributes> {
+        Some(self)
     }
 }
 
 impl ManiaAttributeProvider for ManiaPerformanceAttributes {
     #[inline]
-    fn attributes(self) -> Option<f64> {
-        Some(self.difficulty.stars)
+    fn attributes(self) -> Option<ManiaDifficultyAttributes> {
+        Some(self.difficulty)
     }
 }
 
 impl ManiaAttributeProvider for DifficultyAttributes {
     #[inline]
-    fn attributes(self) -> Option<f64> {
+    fn attributes(self) -> Option<ManiaDifficultyAttributes> {
         #[allow(irrefutable_let_patterns)]
         if let Self::Mania(attributes) = self {
-            Some(attributes.stars)
+            Some(attributes)
         } else {
             None
         }
@@ -218,12 +337,40 @@ impl ManiaAttributeProvider for DifficultyAttributes {
 
 impl ManiaAttributeProvider for PerformanceAttributes {
     #[inline]
-    fn attributes(self) -> Option<f64> {
+    fn attributes(self) -> Option<ManiaDifficultyAttributes> {
         #[allow(irrefutable_let_patterns)]
         if let Self::Mania(attributes) = self {
-            Some(attributes.difficulty.stars)
+            Some(attributes.difficulty)
         } else {
             None
         }
     }
 }
+
+/// The ScoreV1 of a play with only perfect hits before applying mod multipliers.
+const MAX_SCORE: f64 = 1_000_000.0;
+
+/// The maximum ScoreV1 that can be achieved on an osu!mania map with the given mods.
+///
+/// EZ, NF, and HT each halve the score. Maps without hit objects have a maximum of `0`.
+///
+/// ```
+/// use akatsuki_pp::{mania, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// # let score = 0;
+/// let max_score = mania::max_score(&map, 2); // EZ
+/// let is_valid = score <= max_score;
+/// ```
+pub fn max_score(map: &Beatmap, mods: impl Mods) -> u32 {
+    if map.hit_objects.is_empty() {
+        return 0;
+    }
+
+    (MAX_SCORE * score_multiplier(mods)) as u32
+}
+
+#[inline]
+fn score_multiplier(mods: impl Mods) -> f64 {
+    0.5_f64.powi(mods.ez() as i32 + mods.nf() as i32 + mods.ht() as i32)
+}
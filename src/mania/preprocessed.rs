@@ -0,0 +1,110 @@
+use super::{
+    calculate_stars, column_count, ManiaDifficultyAttributes, ManiaGradualDifficultyAttributes,
+    ManiaPP, DEFAULT_LN_WEIGHT,
+};
+
+use crate::{research::ManiaParameters, Beatmap, Mods};
+
+/// An osu!mania map whose column count has been determined once.
+///
+/// osu!mania maps require little preparation so this type mostly exists to
+/// handle all modes uniformly through [`PreprocessedMap`](crate::PreprocessedMap).
+///
+/// ```
+/// use akatsuki_pp::{mania::ManiaPreprocessedMap, Beatmap};
+///
+/// # let map = Beatmap { mode: akatsuki_pp::GameMode::MNA, ..Default::default() };
+/// let preprocessed = ManiaPreprocessedMap::new(&map);
+///
+/// for mods in [0, 64, 256] {
+///     let stars = preprocessed.stars(mods, None).stars;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ManiaPreprocessedMap<'map> {
+    map: &'map Beatmap,
+    columns: u8,
+}
+
+impl<'map> ManiaPreprocessedMap<'map> {
+    /// Prepare the map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map is neither an osu!standard nor an osu!mania map.
+    #[inline]
+    pub fn new(map: &'map Beatmap) -> Self {
+        Self {
+            map,
+            columns: column_count(map),
+        }
+    }
+
+    /// The map that was prepared.
+    #[inline]
+    pub fn map(&self) -> &'map Beatmap {
+        self.map
+    }
+
+    /// Calculate the difficulty attributes for the given mods.
+    ///
+    /// The result is the same as the one of [`stars`](super::stars).
+    pub fn stars(
+        &self,
+        mods: impl Mods,
+        passed_objects: Option<usize>,
+    ) -> ManiaDifficultyAttributes {
+        calculate_stars(
+            self.map,
+            self.columns,
+            mods,
+            passed_objects,
+            DEFAULT_LN_WEIGHT,
+            &ManiaParameters::DEFAULT,
+        )
+    }
+
+    /// Return a performance calculator for the given mods
+    /// whose difficulty attributes are calculated on the prepared map.
+    ///
+    /// The attributes are those of the full map so for partial plays
+    /// use [`stars`](ManiaPreprocessedMap::stars) with the amount of passed objects instead.
+    #[inline]
+    pub fn pp(&self, mods: u32) -> ManiaPP<'map> {
+        ManiaPP::new(self.map)
+            .mods(mods)
+            .attributes(self.stars(mods, None))
+    }
+
+    /// Return an iterator that gives the difficulty attributes after each hit object,
+    /// see [`ManiaGradualDifficultyAttributes`].
+    #[inline]
+    pub fn gradual_difficulty(&self, mods: impl Mods) -> ManiaGradualDifficultyAttributes<'map> {
+        ManiaGradualDifficultyAttributes::new(self.map, mods)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn preprocessed_matches_regular() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let preprocessed = ManiaPreprocessedMap::new(&map);
+
+        for mods in [0, 64, 256] {
+            for passed_objects in [None, Some(1), Some(100)] {
+                let expected = super::super::stars(&map, mods, passed_objects);
+                let actual = preprocessed.stars(mods, passed_objects);
+
+                assert_eq!(actual, expected, "+{} {:?}", mods, passed_objects);
+            }
+        }
+
+        let expected = ManiaPP::new(&map).mods(64).calculate();
+        assert_eq!(preprocessed.pp(64).calculate(), expected);
+    }
+}
@@ -1,4 +1,9 @@
 use super::DifficultyHitObject;
+use crate::{
+    research::SkillParameters,
+    timings::{Phase, Timer},
+    StrainValue,
+};
 
 use std::cmp::Ordering;
 
@@ -12,21 +17,29 @@ pub(crate) struct Strain {
 
     hold_end_times: Vec<f64>,
     individual_strains: Vec<f64>,
-    pub(crate) strain_peaks: Vec<f64>,
+    pub(crate) strain_peaks: Vec<StrainValue>,
 
     prev_time: Option<f64>,
+
+    pub(crate) params: SkillParameters,
+    ln_weight: f64,
+    hold_factor_sum: f64,
+    n_processed: usize,
 }
 
 const INDIVISUAL_DECAY_BASE: f64 = 0.125;
 const OVERALL_DECAY_BASE: f64 = 0.3;
-const STRAIN_DECAY_BASE: f64 = 1.0;
 
-const SKILL_MULTIPLIER: f64 = 1.0;
-const DECAY_WEIGHT: f64 = 0.9;
+pub(crate) const STRAIN_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 1.0,
+    strain_decay_base: 1.0,
+    decay_weight: 0.9,
+    difficulty_multiplier: 1.0,
+};
 
 impl Strain {
     #[inline]
-    pub(crate) fn new(column_count: u8) -> Self {
+    pub(crate) fn new(column_count: u8, ln_weight: f64, params: SkillParameters) -> Self {
         Self {
             current_strain: 1.0,
             curr_section_peak: 1.0,
@@ -39,12 +52,38 @@ impl Strain {
             strain_peaks: Vec::with_capacity(128),
 
             prev_time: None,
+
+            params,
+            ln_weight: ln_weight.max(0.0),
+            hold_factor_sum: 0.0,
+            n_processed: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn current_strain(&self) -> f64 {
+        self.current_strain
+    }
+
+    #[inline]
+    pub(crate) fn column_count(&self) -> usize {
+        self.hold_end_times.len()
+    }
+
+    /// Average factor by which the processed notes were weighted due to held long notes.
+    #[inline]
+    pub(crate) fn avg_hold_factor(&self) -> f64 {
+        if self.n_processed == 0 {
+            1.0
+        } else {
+            self.hold_factor_sum / self.n_processed as f64
         }
     }
 
     #[inline]
     pub(crate) fn save_current_peak(&mut self) {
-        self.strain_peaks.push(self.curr_section_peak);
+        self.strain_peaks
+            .push(self.curr_section_peak as StrainValue);
     }
 
     #[inline]
@@ -60,15 +99,17 @@ impl Strain {
 
     #[inline]
     fn strain_decay(&self, ms: f64) -> f64 {
-        STRAIN_DECAY_BASE.powf(ms / 1000.0)
+        self.params.strain_decay_base.powf(ms / 1000.0)
     }
 
     #[inline]
     pub(crate) fn process(&mut self, current: &DifficultyHitObject<'_>) {
+        let timer = Timer::start();
         self.current_strain *= self.strain_decay(current.delta);
-        self.current_strain += self.strain_value_of(current) * SKILL_MULTIPLIER;
+        self.current_strain += self.strain_value_of(current) * self.params.skill_multiplier;
         self.curr_section_peak = self.current_strain.max(self.curr_section_peak);
         self.prev_time.replace(current.start_time);
+        timer.finish(Phase::Skill("strain"));
     }
 
     fn strain_value_of(&mut self, current: &DifficultyHitObject<'_>) -> f64 {
@@ -82,12 +123,12 @@ impl Strain {
 
             if end_time > hold_end_time + 1.0 {
                 if hold_end_time > current.base.start_time + 1.0 {
-                    hold_addition = 1.0;
+                    hold_addition = self.ln_weight;
                 }
             } else if (end_time - hold_end_time).abs() < 1.0 {
                 hold_addition = 0.0;
             } else if end_time < hold_end_time - 1.0 {
-                hold_factor = 1.25;
+                hold_factor = 1.0 + 0.25 * self.ln_weight;
             }
 
             self.individual_strains[col] = apply_decay(
@@ -97,6 +138,9 @@ impl Strain {
             );
         }
 
+        self.hold_factor_sum += hold_factor;
+        self.n_processed += 1;
+
         self.hold_end_times[current.column] = end_time;
         self.individual_strains[current.column] += 2.0 * hold_factor;
         self.individual_strain = self.individual_strains[current.column];
@@ -108,7 +152,7 @@ impl Strain {
     }
 
     #[inline]
-    pub(crate) fn difficulty_value(strain_peaks: &mut [f64]) -> f64 {
+    pub(crate) fn difficulty_value(strain_peaks: &mut [f64], params: &SkillParameters) -> f64 {
         let mut difficulty = 0.0;
         let mut weight = 1.0;
 
@@ -116,10 +160,10 @@ impl Strain {
 
         for &strain in strain_peaks.iter() {
             difficulty += strain * weight;
-            weight *= DECAY_WEIGHT;
+            weight *= params.decay_weight;
         }
 
-        difficulty
+        difficulty * params.difficulty_multiplier
     }
 }
 
@@ -0,0 +1,222 @@
+use std::iter::FromIterator;
+
+use crate::{AnyPP, Beatmap, PerformanceAttributes};
+
+/// Multiple difficulties of the same song, e.g. all maps of a beatmapset.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{Beatmap, Beatmapset};
+///
+/// # /*
+/// let maps: Vec<Beatmap> = ...
+/// # */
+/// # let maps = vec![Beatmap::default(), Beatmap::default()];
+/// let mapset = Beatmapset::new(maps);
+///
+/// // Stars and SS pp of all difficulties with DT
+/// let spread = mapset.spread(64);
+///
+/// for difficulty in spread.difficulties.iter() {
+///     println!(
+///         "Difficulty #{}: {:.2}★ {:.2}pp",
+///         difficulty.idx,
+///         difficulty.stars(),
+///         difficulty.pp(),
+///     );
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Beatmapset {
+    maps: Vec<Beatmap>,
+}
+
+impl Beatmapset {
+    /// Create a new set of the given difficulties.
+    #[inline]
+    pub fn new(maps: Vec<Beatmap>) -> Self {
+        Self { maps }
+    }
+
+    /// Add a difficulty to the set.
+    #[inline]
+    pub fn push(&mut self, map: Beatmap) {
+        self.maps.push(map);
+    }
+
+    /// The difficulties in the order they were added.
+    #[inline]
+    pub fn maps(&self) -> &[Beatmap] {
+        &self.maps
+    }
+
+    /// The amount of difficulties.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.maps.len()
+    }
+
+    /// Whether the set contains no difficulties.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+
+    /// Calculate the stars and SS pp of all difficulties for the given mods.
+    ///
+    /// The difficulties are calculated in parallel.
+    #[inline]
+    pub fn spread(&self, mods: u32) -> DifficultySpread {
+        self.spread_with(move |calc| calc.mods(mods))
+    }
+
+    /// Calculate the attributes of all difficulties, specifying the score
+    /// through `f` that is applied to the calculator of each difficulty.
+    ///
+    /// The difficulties are calculated in parallel.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, Beatmapset};
+    ///
+    /// # let mapset = Beatmapset::new(vec![Beatmap::default()]);
+    /// let spread = mapset.spread_with(|calc| calc.mods(8 + 16).accuracy(98.0));
+    /// ```
+    pub fn spread_with<F>(&self, f: F) -> DifficultySpread
+    where
+        F: Fn(AnyPP<'_>) -> AnyPP<'_> + Copy + Send,
+    {
+        let attributes = crate::par_map(&self.maps, move |map| f(AnyPP::new(map)).calculate());
+
+        let mut difficulties: Vec<_> = attributes
+            .into_iter()
+            .enumerate()
+            .map(|(idx, attributes)| SetDifficulty { idx, attributes })
+            .collect();
+
+        difficulties.sort_by(|a, b| a.stars().total_cmp(&b.stars()));
+
+        DifficultySpread { difficulties }
+    }
+}
+
+impl From<Vec<Beatmap>> for Beatmapset {
+    #[inline]
+    fn from(maps: Vec<Beatmap>) -> Self {
+        Self::new(maps)
+    }
+}
+
+impl FromIterator<Beatmap> for Beatmapset {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Beatmap>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// The calculated attributes of a single difficulty of a [`Beatmapset`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetDifficulty {
+    /// The index of the difficulty in [`Beatmapset::maps`].
+    pub idx: usize,
+    /// The calculated attributes.
+    pub attributes: PerformanceAttributes,
+}
+
+impl SetDifficulty {
+    /// The star value.
+    #[inline]
+    pub fn stars(&self) -> f64 {
+        self.attributes.stars()
+    }
+
+    /// The pp value.
+    #[inline]
+    pub fn pp(&self) -> f64 {
+        self.attributes.pp()
+    }
+}
+
+/// The attributes of all difficulties of a [`Beatmapset`], see [`Beatmapset::spread`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DifficultySpread {
+    /// The difficulties, sorted by their stars in ascending order.
+    pub difficulties: Vec<SetDifficulty>,
+}
+
+impl DifficultySpread {
+    /// The difficulty with the lowest stars.
+    #[inline]
+    pub fn easiest(&self) -> Option<&SetDifficulty> {
+        self.difficulties.first()
+    }
+
+    /// The difficulty with the highest stars.
+    #[inline]
+    pub fn hardest(&self) -> Option<&SetDifficulty> {
+        self.difficulties.last()
+    }
+
+    /// The lowest and highest stars across all difficulties.
+    #[inline]
+    pub fn star_range(&self) -> Option<(f64, f64)> {
+        self.easiest()
+            .zip(self.hardest())
+            .map(|(a, b)| (a.stars(), b.stars()))
+    }
+
+    /// The attributes of the difficulty at the given index in [`Beatmapset::maps`].
+    #[inline]
+    pub fn get(&self, idx: usize) -> Option<&SetDifficulty> {
+        self.difficulties
+            .iter()
+            .find(|difficulty| difficulty.idx == idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn spread() {
+        use crate::BeatmapExt;
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        // Shorter versions of the map act as easier difficulties
+        let mapset: Beatmapset = [1.0, 0.25, 0.5]
+            .iter()
+            .map(|portion| {
+                let mut map = map.clone();
+                let len = (map.hit_objects.len() as f64 * portion) as usize;
+                map.hit_objects.truncate(len);
+
+                map
+            })
+            .collect();
+
+        let spread = mapset.spread(64);
+        let indices: Vec<_> = spread.difficulties.iter().map(|d| d.idx).collect();
+        assert_eq!(indices, [1, 2, 0]);
+
+        for (idx, map) in mapset.maps().iter().enumerate() {
+            let expected = map.pp().mods(64).calculate();
+            assert_eq!(spread.get(idx).unwrap().attributes, expected);
+        }
+
+        let (min, max) = spread.star_range().unwrap();
+        assert!(min < max);
+        assert_eq!(spread.hardest().unwrap().stars(), max);
+
+        let spread = mapset.spread_with(|calc| calc.accuracy(95.0));
+        assert!(spread.hardest().unwrap().pp() < map.pp().calculate().pp());
+
+        assert_eq!(Beatmapset::default().spread(0).star_range(), None);
+    }
+}
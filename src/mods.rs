@@ -1,3 +1,23 @@
+use std::{error::Error as StdError, fmt};
+
+use crate::{validation::INCOMPATIBLE_MODS, GameMode};
+
+const NC: u32 = 1 << 9;
+const AUTOPLAY: u32 = 1 << 11;
+const AUTOPILOT: u32 = 1 << 13;
+const FADE_IN: u32 = 1 << 20;
+const RANDOM: u32 = 1 << 21;
+const CINEMA: u32 = 1 << 22;
+const TARGET: u32 = 1 << 23;
+const KEY_COOP: u32 = 1 << 25;
+const SCORE_V2: u32 = 1 << 29;
+const MIRROR: u32 = 1 << 30;
+
+/// Key mods 4K through 9K.
+const RANKED_KEYS: u32 = 0b1_1111 << 15 | 1 << 24;
+/// Key mods 1K through 3K.
+const UNRANKED_KEYS: u32 = 0b111 << 26;
+
 macro_rules! impl_mods {
     ($func_name:ident, $const_name:ident) => {
         #[inline]
@@ -7,7 +27,78 @@ macro_rules! impl_mods {
     };
 }
 
+/// Implement all methods of [`Mods`] by delegating to the mods of the given expression.
+macro_rules! delegate_mods {
+    (|$this:ident| $inner:expr) => {
+        delegate_mods!(@ $this, $inner;
+            change_speed -> bool, change_map -> bool, speed -> f64, od_ar_hp_multiplier -> f64,
+            nf -> bool, ez -> bool, td -> bool, hd -> bool, hr -> bool, dt -> bool, rx -> bool,
+            ht -> bool, fl -> bool, so -> bool, sd -> bool, pf -> bool, bl -> bool, tc -> bool,
+            overall_difficulty -> Option<f32>
+        );
+    };
+    (@ $this:ident, $inner:expr; $($func_name:ident -> $ret:ty),*) => {
+        $(
+            #[inline]
+            fn $func_name($this) -> $ret {
+                Mods::$func_name($inner)
+            }
+        )*
+    };
+}
+
 /// Abstract type to define mods.
+///
+/// Besides bit values as `u32`, it is implemented for [`LazerMods`],
+/// [`RateMods`](crate::rate::RateMods), references to mods, and `Option`s of mods
+/// with a default value, where `None` denotes nomod. Other integer types such as `u64`
+/// deliberately do not implement it so that integer literals are still inferred as `u32`,
+/// convert them through `as u32` instead.
+///
+/// The trait is not sealed so it can be implemented for custom mod types too. Since
+/// results are cached and converted based on these methods, an implementation must be
+/// consistent: [`change_speed`](Mods::change_speed) must be `true` exactly if
+/// [`speed`](Mods::speed) is not `1.0`, and [`change_map`](Mods::change_map) must be
+/// `true` if anything that alters the map's timing, positions, or attributes is enabled.
+/// The simplest way to uphold this is to delegate to `u32` or [`LazerMods`]:
+///
+/// ```
+/// use akatsuki_pp::{LazerMods, Mods};
+///
+/// #[derive(Copy, Clone)]
+/// struct MyMods {
+///     hidden: bool,
+///     double_time: bool,
+/// }
+///
+/// impl MyMods {
+///     fn bits(self) -> u32 {
+///         (self.hidden as u32 * u32::HD) | (self.double_time as u32 * u32::DT)
+///     }
+/// }
+///
+/// impl Mods for MyMods {
+///     fn change_speed(self) -> bool { self.bits().change_speed() }
+///     fn change_map(self) -> bool { self.bits().change_map() }
+///     fn speed(self) -> f64 { self.bits().speed() }
+///     fn od_ar_hp_multiplier(self) -> f64 { self.bits().od_ar_hp_multiplier() }
+///     fn nf(self) -> bool { false }
+///     fn ez(self) -> bool { false }
+///     fn td(self) -> bool { false }
+///     fn hd(self) -> bool { self.hidden }
+///     fn hr(self) -> bool { false }
+///     fn dt(self) -> bool { self.double_time }
+///     fn rx(self) -> bool { false }
+///     fn ht(self) -> bool { false }
+///     fn fl(self) -> bool { false }
+///     fn so(self) -> bool { false }
+/// }
+///
+/// let mods = MyMods { hidden: true, double_time: true };
+///
+/// assert_eq!(mods.speed(), 1.5);
+/// assert!(mods.hd() && !mods.hr());
+/// ```
 #[allow(missing_docs)]
 pub trait Mods: Copy {
     const NF: u32 = 1 << 0;
@@ -15,11 +106,13 @@ pub trait Mods: Copy {
     const TD: u32 = 1 << 2;
     const HD: u32 = 1 << 3;
     const HR: u32 = 1 << 4;
+    const SD: u32 = 1 << 5;
     const DT: u32 = 1 << 6;
     const RX: u32 = 1 << 7;
     const HT: u32 = 1 << 8;
     const FL: u32 = 1 << 10;
     const SO: u32 = 1 << 12;
+    const PF: u32 = 1 << 14;
 
     /// If the clock rate is affected by the mods.
     fn change_speed(self) -> bool;
@@ -39,6 +132,99 @@ pub trait Mods: Copy {
     fn ht(self) -> bool;
     fn fl(self) -> bool;
     fn so(self) -> bool;
+
+    /// If SD is enabled.
+    #[inline]
+    fn sd(self) -> bool {
+        false
+    }
+
+    /// If PF is enabled.
+    #[inline]
+    fn pf(self) -> bool {
+        false
+    }
+
+    /// If the osu!lazer mod Blinds is enabled. Not representable through bit values.
+    #[inline]
+    fn bl(self) -> bool {
+        false
+    }
+
+    /// If the osu!lazer mod Traceable is enabled. Not representable through bit values.
+    #[inline]
+    fn tc(self) -> bool {
+        false
+    }
+
+    /// The overall difficulty set through the osu!lazer mod Difficulty Adjust, if any.
+    /// Not representable through bit values.
+    #[inline]
+    fn overall_difficulty(self) -> Option<f32> {
+        None
+    }
+
+    /// If the player can fail at all, i.e. NF is not enabled.
+    #[inline]
+    fn can_fail(self) -> bool {
+        !self.nf()
+    }
+
+    /// The amount of additional lives, i.e. how often the player
+    /// is revived instead of failing. EZ grants two extra lives.
+    #[inline]
+    fn extra_lives(self) -> u32 {
+        if self.ez() {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// If a single miss fails the play, i.e. SD or PF is enabled.
+    #[inline]
+    fn fails_on_miss(self) -> bool {
+        self.sd() || self.pf()
+    }
+
+    /// If any hit result other than the best one fails the play, i.e. PF is enabled.
+    #[inline]
+    fn fails_on_imperfect_hit(self) -> bool {
+        self.pf()
+    }
+}
+
+/// The legacy bits of the given mods, e.g. to record them in tracing spans.
+pub(crate) fn legacy_bits(mods: impl Mods) -> u32 {
+    let flags = [
+        (mods.nf(), u32::NF),
+        (mods.ez(), u32::EZ),
+        (mods.td(), u32::TD),
+        (mods.hd(), u32::HD),
+        (mods.hr(), u32::HR),
+        (mods.sd(), u32::SD),
+        (mods.dt(), u32::DT),
+        (mods.rx(), u32::RX),
+        (mods.ht(), u32::HT),
+        (mods.fl(), u32::FL),
+        (mods.so(), u32::SO),
+        (mods.pf(), u32::PF),
+    ];
+
+    flags
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .fold(0, |bits, (_, bit)| bits | bit)
+}
+
+/// The given mods as [`LazerMods`], unlike [`legacy_bits`] keeping the lazer-only mods.
+pub(crate) fn lazer_mods(mods: impl Mods) -> LazerMods {
+    LazerMods {
+        legacy: legacy_bits(mods),
+        blinds: mods.bl(),
+        traceable: mods.tc(),
+        overall_difficulty: mods.overall_difficulty(),
+    }
 }
 
 impl Mods for u32 {
@@ -84,4 +270,379 @@ impl Mods for u32 {
     impl_mods!(ht, HT);
     impl_mods!(fl, FL);
     impl_mods!(so, SO);
+    impl_mods!(sd, SD);
+    impl_mods!(pf, PF);
+}
+
+/// Acronyms of osu!lazer mods with a legacy counterpart.
+///
+/// Variants such as NC or DC map onto the mod they are based on.
+const LEGACY_ACRONYMS: [(&str, u32); 16] = [
+    ("NF", u32::NF),
+    ("EZ", u32::EZ),
+    ("TD", u32::TD),
+    ("HD", u32::HD),
+    ("HR", u32::HR),
+    ("SD", u32::SD),
+    ("DT", u32::DT),
+    ("NC", u32::DT | NC),
+    ("RX", u32::RX),
+    ("HT", u32::HT),
+    ("DC", u32::HT),
+    ("FL", u32::FL),
+    ("SO", u32::SO),
+    ("AP", AUTOPILOT),
+    ("PF", u32::PF),
+    ("SV2", SCORE_V2),
+];
+
+/// Acronyms of osu!lazer mods that do not affect difficulty or performance.
+const NEUTRAL_ACRONYMS: [&str; 5] = ["CL", "DA", "MU", "NS", "SY"];
+
+/// Mods of an osu!lazer score.
+///
+/// Consists of the bit values of all mods with a legacy counterpart as well as
+/// the lazer-only mods that affect performance. Of the settings of lazer mods,
+/// only Difficulty Adjust's overall difficulty is considered since it alters the
+/// hit windows. Others, e.g. Hidden's "only fade approach circles", are not.
+///
+/// ```
+/// use akatsuki_pp::{LazerMods, Mods};
+///
+/// let mods = LazerMods::from_acronyms(["NC", "TC", "CL"]).unwrap();
+///
+/// assert!(mods.dt());
+/// assert!(mods.tc());
+/// assert!(!mods.hd());
+/// assert!(LazerMods::from_acronyms(["XX"]).is_err());
+///
+/// // Difficulty Adjust with an overall difficulty of 9.5
+/// let mods = LazerMods {
+///     overall_difficulty: Some(9.5),
+///     ..LazerMods::from_acronyms(["DA"]).unwrap()
+/// };
+///
+/// assert_eq!(mods.overall_difficulty(), Some(9.5));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LazerMods {
+    /// The bit values of the mods with a legacy counterpart.
+    pub legacy: u32,
+    /// If Blinds (BL) is enabled.
+    pub blinds: bool,
+    /// If Traceable (TC) is enabled.
+    pub traceable: bool,
+    /// The overall difficulty setting of Difficulty Adjust (DA), if any.
+    ///
+    /// It replaces the map's OD and, since Difficulty Adjust is incompatible
+    /// with them, HR and EZ no longer affect the OD. Speed changing mods still
+    /// shrink the hit windows.
+    pub overall_difficulty: Option<f32>,
+}
+
+impl LazerMods {
+    /// Collect the mods of the given acronyms, e.g. from a score submitted by osu!lazer.
+    ///
+    /// Acronyms are case-insensitive. Mods that do not affect difficulty
+    /// or performance such as Classic or Muted are accepted but ignored.
+    /// Difficulty Adjust is accepted too but its settings must be specified
+    /// through the respective fields.
+    pub fn from_acronyms<I, S>(acronyms: I) -> Result<Self, UnknownModError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut mods = Self::default();
+
+        for acronym in acronyms {
+            let acronym = acronym.as_ref().to_ascii_uppercase();
+
+            if let Some((_, bits)) = LEGACY_ACRONYMS.iter().find(|(a, _)| *a == acronym) {
+                mods.legacy |= bits;
+            } else if acronym == "BL" {
+                mods.blinds = true;
+            } else if acronym == "TC" {
+                mods.traceable = true;
+            } else if !NEUTRAL_ACRONYMS.contains(&acronym.as_str()) {
+                return Err(UnknownModError { acronym });
+            }
+        }
+
+        Ok(mods)
+    }
+}
+
+impl From<u32> for LazerMods {
+    #[inline]
+    fn from(legacy: u32) -> Self {
+        Self {
+            legacy,
+            ..Default::default()
+        }
+    }
+}
+
+impl Mods for LazerMods {
+    #[inline]
+    fn change_speed(self) -> bool {
+        self.legacy.change_speed()
+    }
+
+    #[inline]
+    fn change_map(self) -> bool {
+        self.legacy.change_map() || self.overall_difficulty.is_some()
+    }
+
+    #[inline]
+    fn speed(self) -> f64 {
+        self.legacy.speed()
+    }
+
+    #[inline]
+    fn od_ar_hp_multiplier(self) -> f64 {
+        self.legacy.od_ar_hp_multiplier()
+    }
+
+    #[inline]
+    fn nf(self) -> bool {
+        self.legacy.nf()
+    }
+
+    #[inline]
+    fn ez(self) -> bool {
+        self.legacy.ez()
+    }
+
+    #[inline]
+    fn td(self) -> bool {
+        self.legacy.td()
+    }
+
+    #[inline]
+    fn hd(self) -> bool {
+        self.legacy.hd()
+    }
+
+    #[inline]
+    fn hr(self) -> bool {
+        self.legacy.hr()
+    }
+
+    #[inline]
+    fn dt(self) -> bool {
+        self.legacy.dt()
+    }
+
+    #[inline]
+    fn rx(self) -> bool {
+        self.legacy.rx()
+    }
+
+    #[inline]
+    fn ht(self) -> bool {
+        self.legacy.ht()
+    }
+
+    #[inline]
+    fn fl(self) -> bool {
+        self.legacy.fl()
+    }
+
+    #[inline]
+    fn so(self) -> bool {
+        self.legacy.so()
+    }
+
+    #[inline]
+    fn sd(self) -> bool {
+        self.legacy.sd()
+    }
+
+    #[inline]
+    fn pf(self) -> bool {
+        self.legacy.pf()
+    }
+
+    #[inline]
+    fn bl(self) -> bool {
+        self.blinds
+    }
+
+    #[inline]
+    fn tc(self) -> bool {
+        self.traceable
+    }
+
+    #[inline]
+    fn overall_difficulty(self) -> Option<f32> {
+        self.overall_difficulty
+    }
+}
+
+impl<M: Mods> Mods for &M {
+    delegate_mods!(|self| *self);
+}
+
+/// `None` is treated as nomod, i.e. the default value of the mods.
+impl<M: Mods + Default> Mods for Option<M> {
+    delegate_mods!(|self| self.unwrap_or_default());
+}
+
+/// An osu!lazer mod acronym that is not known, see [`LazerMods::from_acronyms`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownModError {
+    /// The uppercase acronym.
+    pub acronym: String,
+}
+
+impl fmt::Display for UnknownModError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown mod acronym `{}`", self.acronym)
+    }
+}
+
+impl StdError for UnknownModError {}
+
+/// Whether a set of mods is eligible for the leaderboards, see [`ModsRanking::classify`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ModsRanking {
+    /// The mods are ranked.
+    Ranked,
+    /// The mods are ranked except that they include ScoreV2.
+    ScoreV2Only,
+    /// The mods can not be submitted, e.g. because they include Autoplay
+    /// or an invalid combination such as DT and HT.
+    Unranked,
+}
+
+impl ModsRanking {
+    /// Classify the mods as bit values for the given mode.
+    ///
+    /// NC implies DT and PF implies SD, even if their bits are missing.
+    /// Following Akatsuki, RX is ranked in all modes but osu!mania and AP only in osu!standard.
+    /// HD and FL can be combined in all modes but osu!mania where they are mutually
+    /// exclusive with FI.
+    ///
+    /// ```
+    /// use akatsuki_pp::{GameMode, ModsRanking};
+    ///
+    /// assert_eq!(ModsRanking::classify(8 + 1024, GameMode::STD), ModsRanking::Ranked);
+    /// assert_eq!(ModsRanking::classify(8 + 1024, GameMode::MNA), ModsRanking::Unranked);
+    /// assert_eq!(ModsRanking::classify(2048, GameMode::STD), ModsRanking::Unranked);
+    /// ```
+    pub fn classify(mods: u32, mode: GameMode) -> Self {
+        let mut mods = mods;
+
+        if mods & NC > 0 {
+            mods |= u32::DT;
+        }
+
+        if mods & u32::PF > 0 {
+            mods |= u32::SD;
+        }
+
+        let unavailable = match mode {
+            GameMode::STD => RANKED_KEYS | FADE_IN | MIRROR,
+            GameMode::TKO | GameMode::CTB => {
+                u32::TD | u32::SO | AUTOPILOT | TARGET | RANKED_KEYS | FADE_IN | MIRROR
+            }
+            GameMode::MNA => u32::TD | u32::SO | u32::RX | AUTOPILOT | TARGET,
+        };
+
+        let unranked = AUTOPLAY | CINEMA | TARGET | RANDOM | KEY_COOP | UNRANKED_KEYS;
+
+        if mods & (unavailable | unranked) > 0 {
+            return Self::Unranked;
+        }
+
+        let mut incompatible = INCOMPATIBLE_MODS
+            .iter()
+            .chain(&[(u32::RX, AUTOPILOT), (u32::SO, AUTOPILOT)]);
+
+        if incompatible.any(|(a, b)| mods & a > 0 && mods & b > 0) {
+            return Self::Unranked;
+        }
+
+        let exclusive_count = |group: u32| (mods & group).count_ones();
+
+        if exclusive_count(RANKED_KEYS) > 1
+            || (mode == GameMode::MNA && exclusive_count(u32::HD | u32::FL | FADE_IN) > 1)
+        {
+            return Self::Unranked;
+        }
+
+        if mods & SCORE_V2 > 0 {
+            Self::ScoreV2Only
+        } else {
+            Self::Ranked
+        }
+    }
+}
+
+/// Whether the mods as bit values are ranked on the given mode,
+/// see [`ModsRanking::classify`].
+#[inline]
+pub fn is_ranked_playable(mods: u32, mode: GameMode) -> bool {
+    ModsRanking::classify(mods, mode) == ModsRanking::Ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delegated_mods() {
+        let mods = u32::HD | u32::HR | u32::DT;
+
+        assert_eq!((&mods).speed(), 1.5);
+        assert!((&mods).hr());
+        assert!(Some(mods).change_map());
+        assert!(!None::<u32>.change_map());
+        assert_eq!(None::<u32>.speed(), 1.0);
+
+        let lazer = LazerMods {
+            overall_difficulty: Some(9.0),
+            ..LazerMods::from(u32::FL)
+        };
+
+        assert_eq!(Some(lazer).overall_difficulty(), Some(9.0));
+        assert!((&Some(lazer)).fl());
+        assert_eq!(None::<LazerMods>.overall_difficulty(), None);
+    }
+
+    #[test]
+    fn legacy_bits_of_mods() {
+        let mods = u32::HD | u32::HR | u32::DT | u32::PF;
+
+        assert_eq!(legacy_bits(mods), mods);
+        assert_eq!(legacy_bits(LazerMods::from(mods)), mods);
+        assert_eq!(legacy_bits(None::<u32>), 0);
+    }
+
+    #[test]
+    fn ranking() {
+        assert!(is_ranked_playable(0, GameMode::STD));
+        assert!(is_ranked_playable(8 + 16 + 64 + 1024, GameMode::STD));
+        assert!(is_ranked_playable(NC, GameMode::TKO));
+        assert!(is_ranked_playable(u32::RX + 8, GameMode::CTB));
+        assert!(is_ranked_playable(AUTOPILOT, GameMode::STD));
+        assert!(is_ranked_playable(1 << 16, GameMode::MNA));
+
+        assert!(!is_ranked_playable(NC + u32::HT, GameMode::STD));
+        assert!(!is_ranked_playable(u32::PF + u32::NF, GameMode::STD));
+        assert!(!is_ranked_playable(u32::RX, GameMode::MNA));
+        assert!(!is_ranked_playable(u32::SO, GameMode::TKO));
+        assert!(!is_ranked_playable(u32::HD + FADE_IN, GameMode::MNA));
+        assert!(!is_ranked_playable(CINEMA, GameMode::CTB));
+        assert!(!is_ranked_playable((1 << 15) + (1 << 16), GameMode::MNA));
+
+        assert_eq!(
+            ModsRanking::classify(SCORE_V2 + 8, GameMode::STD),
+            ModsRanking::ScoreV2Only
+        );
+        assert_eq!(
+            ModsRanking::classify(SCORE_V2 + AUTOPLAY, GameMode::STD),
+            ModsRanking::Unranked
+        );
+    }
 }
@@ -0,0 +1,393 @@
+use std::{
+    error::Error as StdError,
+    path::{Path, PathBuf},
+};
+
+#[cfg(any(feature = "async_tokio", feature = "async_std"))]
+use std::{future::Future, pin::Pin};
+
+use crate::{Beatmap, ParseError, ParseResult};
+
+#[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+use std::fs;
+
+#[cfg(feature = "async_tokio")]
+use tokio::fs;
+
+#[cfg(feature = "async_std")]
+use async_std::fs;
+
+const DEFAULT_BASE_URL: &str = "https://osu.ppy.sh/osu/";
+
+/// Any error that occurred while fetching a map from a [`MapSource`].
+pub type FetchError = Box<dyn StdError + Send + Sync>;
+
+/// The future returned by [`MapSource::fetch`].
+#[cfg(any(feature = "async_tokio", feature = "async_std"))]
+pub type FetchFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>, FetchError>> + Send + 'a>>;
+
+/// Provides the content of `.osu` files by their map id.
+///
+/// Implement this to download maps from a custom mirror or storage
+/// and let a [`MapDownloader`] handle the caching and parsing.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+/// # {
+/// use akatsuki_pp::online::{FetchError, MapDownloader, MapSource};
+///
+/// struct Bucket;
+///
+/// impl MapSource for Bucket {
+///     fn fetch(&self, map_id: u32) -> Result<Vec<u8>, FetchError> {
+///         // Retrieve the file from the bucket
+/// #       let _ = map_id;
+///         Ok(b"osu file format v14\n".to_vec())
+///     }
+/// }
+///
+/// let map = MapDownloader::with_source(Bucket).download(123).unwrap();
+/// # }
+/// ```
+#[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+pub trait MapSource {
+    /// Fetch the content of the `.osu` file of the given map id.
+    fn fetch(&self, map_id: u32) -> Result<Vec<u8>, FetchError>;
+}
+
+/// Provides the content of `.osu` files by their map id.
+///
+/// Implement this to download maps from a custom mirror or storage
+/// and let a [`MapDownloader`] handle the caching and parsing.
+#[cfg(any(feature = "async_tokio", feature = "async_std"))]
+pub trait MapSource {
+    /// Fetch the content of the `.osu` file of the given map id.
+    fn fetch(&self, map_id: u32) -> FetchFuture<'_>;
+}
+
+#[cfg(any(feature = "async_std", not(feature = "async_tokio")))]
+type Client = reqwest::blocking::Client;
+
+#[cfg(feature = "async_tokio")]
+type Client = reqwest::Client;
+
+/// [`MapSource`] that downloads maps through HTTP from osu.ppy.sh or a mirror.
+#[derive(Clone, Debug)]
+pub struct HttpSource {
+    base_url: String,
+    client: Client,
+}
+
+impl HttpSource {
+    /// Create a new source for osu.ppy.sh.
+    pub fn new() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            client: Client::new(),
+        }
+    }
+
+    /// Specify the url that the map id will be appended to, e.g. `https://osu.ppy.sh/osu/`.
+    #[inline]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+
+        self
+    }
+
+    #[cfg(any(feature = "async_std", not(feature = "async_tokio")))]
+    fn fetch_blocking(client: &Client, url: String) -> Result<Vec<u8>, FetchError> {
+        let bytes = client
+            .get(url)
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)?
+            .bytes()?;
+
+        Ok(bytes.to_vec())
+    }
+}
+
+impl Default for HttpSource {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+impl MapSource for HttpSource {
+    fn fetch(&self, map_id: u32) -> Result<Vec<u8>, FetchError> {
+        Self::fetch_blocking(&self.client, format!("{}{}", self.base_url, map_id))
+    }
+}
+
+#[cfg(feature = "async_tokio")]
+impl MapSource for HttpSource {
+    fn fetch(&self, map_id: u32) -> FetchFuture<'_> {
+        Box::pin(async move {
+            let bytes = self
+                .client
+                .get(format!("{}{}", self.base_url, map_id))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)?
+                .bytes()
+                .await?;
+
+            Ok(bytes.to_vec())
+        })
+    }
+}
+
+#[cfg(feature = "async_std")]
+impl MapSource for HttpSource {
+    fn fetch(&self, map_id: u32) -> FetchFuture<'_> {
+        // reqwest's async client requires a tokio runtime
+        // so the blocking client is used on a separate thread instead
+        let client = self.client.clone();
+        let url = format!("{}{}", self.base_url, map_id);
+
+        Box::pin(async_std::task::spawn_blocking(move || {
+            Self::fetch_blocking(&client, url)
+        }))
+    }
+}
+
+/// Fetches `.osu` files by their map id from a [`MapSource`] and parses them.
+///
+/// Maps are downloaded from osu.ppy.sh by default but any mirror that serves
+/// `.osu` files under `{base_url}{map_id}` can be used instead.
+/// For other kinds of storage, provide a custom [`MapSource`].
+///
+/// If a cache directory is specified, fetched files will be stored in there
+/// and later downloads of the same map id will read the file from disk instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use akatsuki_pp::online::MapDownloader;
+///
+/// # #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+/// # fn main() -> akatsuki_pp::ParseResult<()> {
+/// let downloader = MapDownloader::new()
+///     .base_url("https://my-mirror.com/osu/")
+///     .cache_dir("./maps");
+///
+/// let map = downloader.download(2785319)?;
+/// # Ok(()) }
+/// # #[cfg(any(feature = "async_tokio", feature = "async_std"))]
+/// # fn main() {}
+/// ```
+#[derive(Clone, Debug)]
+pub struct MapDownloader<S = HttpSource> {
+    source: S,
+    cache_dir: Option<PathBuf>,
+}
+
+impl MapDownloader {
+    /// Create a new downloader for osu.ppy.sh without a cache.
+    pub fn new() -> Self {
+        Self::with_source(HttpSource::new())
+    }
+
+    /// Specify the url that the map id will be appended to, e.g. `https://osu.ppy.sh/osu/`.
+    #[inline]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.source = self.source.base_url(base_url);
+
+        self
+    }
+}
+
+impl<S: MapSource> MapDownloader<S> {
+    /// Create a new downloader for the given source without a cache.
+    #[inline]
+    pub fn with_source(source: S) -> Self {
+        Self {
+            source,
+            cache_dir: None,
+        }
+    }
+
+    /// Specify a directory in which fetched files are stored as `{map_id}.osu`.
+    ///
+    /// The directory will be created if it does not exist yet.
+    #[inline]
+    pub fn cache_dir(mut self, cache_dir: impl AsRef<Path>) -> Self {
+        self.cache_dir = Some(cache_dir.as_ref().to_owned());
+
+        self
+    }
+
+    fn cache_path(&self, map_id: u32) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.osu", map_id)))
+    }
+
+    /// Fetch the map of the given id, or read it from the cache, and parse it.
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    pub fn download(&self, map_id: u32) -> ParseResult<Beatmap> {
+        trace_span!("download", map_id = map_id);
+
+        let cache_path = self.cache_path(map_id);
+
+        if let Some(path) = cache_path.as_deref().filter(|path| path.exists()) {
+            return Beatmap::from_path(path);
+        }
+
+        let bytes = self.source.fetch(map_id).map_err(ParseError::Download)?;
+        let map = Beatmap::parse(bytes.as_slice())?;
+
+        // Only cache valid maps and write them to a temporary file first
+        // so that a crash or concurrent download can't leave a truncated file
+        if let Some(path) = cache_path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+
+            let tmp_path = path.with_extension("osu.tmp");
+            fs::write(&tmp_path, &bytes)?;
+            fs::rename(tmp_path, path)?;
+        }
+
+        Ok(map)
+    }
+
+    /// Fetch the map of the given id, or read it from the cache, and parse it.
+    #[cfg(any(feature = "async_tokio", feature = "async_std"))]
+    pub async fn download(&self, map_id: u32) -> ParseResult<Beatmap> {
+        let cache_path = self.cache_path(map_id);
+
+        if let Some(path) = cache_path.as_deref() {
+            if fs::metadata(path).await.is_ok() {
+                return Beatmap::from_path(path).await;
+            }
+        }
+
+        let bytes = self
+            .source
+            .fetch(map_id)
+            .await
+            .map_err(ParseError::Download)?;
+
+        let map = Beatmap::parse(bytes.as_slice()).await?;
+
+        // Only cache valid maps and write them to a temporary file first
+        // so that a crash or concurrent download can't leave a truncated file
+        if let Some(path) = cache_path {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).await?;
+            }
+
+            let tmp_path = path.with_extension("osu.tmp");
+            fs::write(&tmp_path, &bytes).await?;
+            fs::rename(tmp_path, path).await?;
+        }
+
+        Ok(map)
+    }
+}
+
+impl Default for MapDownloader {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Beatmap {
+    /// Download the `.osu` file of the given map id from osu.ppy.sh and parse it.
+    ///
+    /// To use a mirror or cache the downloaded files, use a [`MapDownloader`] instead.
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    pub fn from_osu_id(map_id: u32) -> ParseResult<Self> {
+        MapDownloader::new().download(map_id)
+    }
+
+    /// Download the `.osu` file of the given map id from osu.ppy.sh and parse it.
+    ///
+    /// To use a mirror or cache the downloaded files, use a [`MapDownloader`] instead.
+    #[cfg(any(feature = "async_tokio", feature = "async_std"))]
+    pub async fn from_osu_id(map_id: u32) -> ParseResult<Self> {
+        MapDownloader::new().download(map_id).await
+    }
+}
+
+#[cfg(all(test, not(any(feature = "async_tokio", feature = "async_std"))))]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    struct LocalSource {
+        fetched: AtomicUsize,
+    }
+
+    impl MapSource for LocalSource {
+        fn fetch(&self, map_id: u32) -> Result<Vec<u8>, FetchError> {
+            self.fetched.fetch_add(1, Ordering::Relaxed);
+
+            if map_id == 1 {
+                return Ok(b"<html>rate limited</html>".to_vec());
+            }
+
+            Ok(fs::read(format!("./maps/{}.osu", map_id))?)
+        }
+    }
+
+    #[test]
+    fn read_from_cache() {
+        let cache_dir = std::env::temp_dir().join("akatsuki-pp-online-cache");
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::copy("./maps/2785319.osu", cache_dir.join("2785319.osu")).unwrap();
+
+        // Unreachable url so the map has to come from the cache
+        let map = MapDownloader::new()
+            .base_url("http://127.0.0.1:0/")
+            .cache_dir(&cache_dir)
+            .download(2785319)
+            .expect("failed to read cached map");
+
+        let expected = Beatmap::from_path("./maps/2785319.osu").unwrap();
+        assert_eq!(map.hit_objects.len(), expected.hit_objects.len());
+
+        let err = MapDownloader::new()
+            .base_url("http://127.0.0.1:0/")
+            .download(2785319)
+            .unwrap_err();
+
+        assert!(err.is_io());
+    }
+
+    #[test]
+    fn custom_source() {
+        let cache_dir = std::env::temp_dir().join("akatsuki-pp-custom-source-cache");
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let source = LocalSource {
+            fetched: AtomicUsize::new(0),
+        };
+
+        let downloader = MapDownloader::with_source(source).cache_dir(&cache_dir);
+
+        let first = downloader.download(1028484).expect("failed to fetch map");
+        let second = downloader
+            .download(1028484)
+            .expect("failed to read cached map");
+
+        assert_eq!(first.hit_objects.len(), second.hit_objects.len());
+        assert_eq!(downloader.source.fetched.load(Ordering::Relaxed), 1);
+        assert!(cache_dir.join("1028484.osu").exists());
+
+        let err = downloader.download(0).unwrap_err();
+        assert!(matches!(err, ParseError::Download(_)));
+
+        // Invalid content is not cached
+        assert!(downloader.download(1).is_err());
+        assert!(!cache_dir.join("1.osu").exists());
+        assert!(!cache_dir.join("1.osu.tmp").exists());
+    }
+}
@@ -0,0 +1,245 @@
+use super::{calculate_star_rating, OsuDifficultyAttributes};
+
+/// Builder to reconstruct [`OsuDifficultyAttributes`], e.g. from values that were
+/// stored in a database, without depending on all of its fields.
+///
+/// All values are expected as they were calculated, i.e. after applying mods and clock rate.
+/// Fields that are not specified take the value of an empty map, with the exception
+/// of the slider factor which defaults to `1.0`.
+///
+/// Attributes should only be reconstructed if they were calculated
+/// with the current [`OsuDifficultyAttributes::VERSION`].
+///
+/// ```
+/// use akatsuki_pp::{osu::OsuDifficultyAttributes, Beatmap, OsuPP};
+///
+/// # let map = Beatmap::default();
+/// # let (stored_version, aim, speed, od, max_combo) = (OsuDifficultyAttributes::VERSION, 3.1, 2.7, 9.0, 1200);
+/// if stored_version == OsuDifficultyAttributes::VERSION {
+///     let attributes = OsuDifficultyAttributes::builder()
+///         .aim_strain(aim)
+///         .speed_strain(speed)
+///         .od(od)
+///         .max_combo(max_combo)
+///         .build();
+///
+///     let pp = OsuPP::new(&map).attributes(attributes).calculate();
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[must_use]
+pub struct OsuDifficultyAttributesBuilder {
+    attributes: OsuDifficultyAttributes,
+    stars: Option<f64>,
+}
+
+impl OsuDifficultyAttributesBuilder {
+    /// Create a new builder.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            attributes: OsuDifficultyAttributes {
+                slider_factor: 1.0,
+                ..Default::default()
+            },
+            stars: None,
+        }
+    }
+
+    /// Specify the aim rating. The TD penalty is not included, it is
+    /// applied during performance calculation.
+    #[inline]
+    pub fn aim_strain(mut self, aim_strain: f64) -> Self {
+        self.attributes.aim_strain = aim_strain;
+
+        self
+    }
+
+    /// Specify the speed rating.
+    #[inline]
+    pub fn speed_strain(mut self, speed_strain: f64) -> Self {
+        self.attributes.speed_strain = speed_strain;
+
+        self
+    }
+
+    /// Specify the flashlight rating without the TD penalty.
+    /// Should be `0.0` for attributes that were calculated without FL.
+    #[inline]
+    pub fn flashlight_rating(mut self, flashlight_rating: f64) -> Self {
+        self.attributes.flashlight_rating = flashlight_rating;
+
+        self
+    }
+
+    /// Specify the ratio of the aim rating without sliders to the aim rating with sliders.
+    #[inline]
+    pub fn slider_factor(mut self, slider_factor: f64) -> Self {
+        self.attributes.slider_factor = slider_factor;
+
+        self
+    }
+
+    /// Specify the weighted amount of difficult aim strains.
+    #[inline]
+    pub fn aim_difficult_strain_count(mut self, count: f64) -> Self {
+        self.attributes.aim_difficult_strain_count = count;
+
+        self
+    }
+
+    /// Specify the weighted amount of difficult speed strains.
+    #[inline]
+    pub fn speed_difficult_strain_count(mut self, count: f64) -> Self {
+        self.attributes.speed_difficult_strain_count = count;
+
+        self
+    }
+
+    /// Specify the approach rate after applying mods and clock rate.
+    #[inline]
+    pub fn ar(mut self, ar: f64) -> Self {
+        self.attributes.ar = ar;
+
+        self
+    }
+
+    /// Specify the overall difficulty after applying mods and clock rate.
+    #[inline]
+    pub fn od(mut self, od: f64) -> Self {
+        self.attributes.od = od;
+
+        self
+    }
+
+    /// Specify the health drain rate after applying mods.
+    #[inline]
+    pub fn hp(mut self, hp: f64) -> Self {
+        self.attributes.hp = hp;
+
+        self
+    }
+
+    /// Specify the circle size after applying mods.
+    #[inline]
+    pub fn cs(mut self, cs: f64) -> Self {
+        self.attributes.cs = cs;
+
+        self
+    }
+
+    /// Specify the amount of circles, sliders, and spinners.
+    #[inline]
+    pub fn object_counts(mut self, n_circles: usize, n_sliders: usize, n_spinners: usize) -> Self {
+        self.attributes.n_circles = n_circles;
+        self.attributes.n_sliders = n_sliders;
+        self.attributes.n_spinners = n_spinners;
+
+        self
+    }
+
+    /// Specify the maximum combo.
+    #[inline]
+    pub fn max_combo(mut self, max_combo: usize) -> Self {
+        self.attributes.max_combo = max_combo;
+
+        self
+    }
+
+    /// Specify the star rating.
+    ///
+    /// If not specified, it will be calculated from the aim, speed, and flashlight ratings.
+    #[inline]
+    pub fn stars(mut self, stars: f64) -> Self {
+        self.stars = Some(stars);
+
+        self
+    }
+
+    /// Create the [`OsuDifficultyAttributes`].
+    pub fn build(self) -> OsuDifficultyAttributes {
+        let Self {
+            mut attributes,
+            stars,
+        } = self;
+
+        attributes.stars = stars.unwrap_or_else(|| {
+            calculate_star_rating(
+                attributes.aim_strain,
+                attributes.speed_strain,
+                attributes.flashlight_rating,
+            )
+        });
+
+        attributes
+    }
+}
+
+impl Default for OsuDifficultyAttributesBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OsuDifficultyAttributes {
+    /// The version of the difficulty calculation.
+    ///
+    /// It is increased whenever a change causes different attributes
+    /// so that stored attributes of an older version can be recalculated.
+    pub const VERSION: u32 = 1;
+
+    /// Return a builder to reconstruct attributes, see [`OsuDifficultyAttributesBuilder`].
+    #[inline]
+    pub fn builder() -> OsuDifficultyAttributesBuilder {
+        OsuDifficultyAttributesBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn rebuild_attributes() {
+        use crate::{Beatmap, OsuPP};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let attributes = super::super::stars(&map, 16 + 64, None);
+
+        let rebuilt = OsuDifficultyAttributes::builder()
+            .aim_strain(attributes.aim_strain)
+            .speed_strain(attributes.speed_strain)
+            .flashlight_rating(attributes.flashlight_rating)
+            .slider_factor(attributes.slider_factor)
+            .aim_difficult_strain_count(attributes.aim_difficult_strain_count)
+            .speed_difficult_strain_count(attributes.speed_difficult_strain_count)
+            .ar(attributes.ar)
+            .od(attributes.od)
+            .hp(attributes.hp)
+            .cs(attributes.cs)
+            .object_counts(
+                attributes.n_circles,
+                attributes.n_sliders,
+                attributes.n_spinners,
+            )
+            .max_combo(attributes.max_combo)
+            .build();
+
+        assert_eq!(rebuilt, attributes);
+
+        let expected = OsuPP::new(&map).mods(16 + 64).calculate();
+        let pp = OsuPP::new(&map)
+            .mods(16 + 64)
+            .attributes(rebuilt)
+            .calculate();
+
+        assert_eq!(pp, expected);
+
+        let default = OsuDifficultyAttributes::builder().stars(1.5).build();
+        assert_eq!(default.slider_factor, 1.0);
+        assert_eq!(default.stars, 1.5);
+    }
+}
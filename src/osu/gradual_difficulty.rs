@@ -1,7 +1,12 @@
-use std::{iter, mem, vec::IntoIter};
+use std::{mem, vec::IntoIter};
 
 use crate::{
-    curve::CurveBuffers, osu::difficulty_object::DifficultyObject, parse::Pos2, Beatmap, Mods,
+    curve::CurveBuffers,
+    osu::difficulty_object::DifficultyObject,
+    parse::Pos2,
+    rate::{RateMods, RateSchedule},
+    research::OsuParameters,
+    Beatmap, Mods, Strains,
 };
 
 use super::{
@@ -25,7 +30,7 @@ use super::{
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, osu::OsuGradualDifficultyAttributes};
+/// use akatsuki_pp::{Beatmap, osu::OsuGradualDifficultyAttributes};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -59,14 +64,71 @@ pub struct OsuGradualDifficultyAttributes {
 impl OsuGradualDifficultyAttributes {
     /// Create a new difficulty attributes iterator for osu!standard maps.
     pub fn new(map: &Beatmap, mods: impl Mods) -> Self {
+        let mut attributes = OsuDifficultyAttributes::default();
+
+        let mut params = ObjectParameters {
+            map,
+            attributes: &mut attributes,
+            slider_state: SliderState::new(map),
+            ticks: Vec::new(),
+            curve_bufs: CurveBuffers::default(),
+        };
+
+        let hit_objects_iter = map
+            .hit_objects
+            .iter()
+            .filter_map(|h| OsuObject::new(h, mods.hr(), &mut params));
+
+        let mut hit_objects = Vec::with_capacity(map.hit_objects.len());
+        hit_objects.extend(hit_objects_iter);
+
+        Self::with_objects(map, mods, hit_objects)
+    }
+
+    /// Create a new difficulty attributes iterator for osu!standard maps
+    /// whose clock rate changes over time, e.g. for lazer's Wind Up and Wind Down.
+    ///
+    /// The clock rate of `mods` is ignored. Hit windows and AR are based on the
+    /// average rate between the first and last hit object.
+    ///
+    /// ```
+    /// use akatsuki_pp::{osu::OsuGradualDifficultyAttributes, rate::RateSchedule, Beatmap};
+    ///
+    /// # let map = Beatmap::default();
+    /// let schedule = RateSchedule::ramp(0.0, 60_000.0, 1.0, 1.5).unwrap();
+    /// let mut iter = OsuGradualDifficultyAttributes::with_rate_schedule(&map, 8, &schedule);
+    ///
+    /// let final_attrs = iter.last();
+    /// ```
+    pub fn with_rate_schedule(map: &Beatmap, mods: u32, schedule: &RateSchedule) -> Self {
+        let clock_rate = match (map.hit_objects.first(), map.hit_objects.last()) {
+            (Some(first), Some(last)) => schedule.average_rate(first.start_time, last.end_time()),
+            _ => schedule.rate(0.0),
+        };
+
+        let mut this = Self::new(map, RateMods::new(mods, clock_rate));
+
+        // Times are divided by the clock rate later on so that they end up in real time
+        for h in this.hit_objects.hit_objects.as_mut_slice() {
+            h.map_times(|time| schedule.real_time(time) * clock_rate);
+        }
+
+        this
+    }
+
+    /// Create the iterator from objects that are already adjusted for HR.
+    pub(crate) fn with_objects(
+        map: &Beatmap,
+        mods: impl Mods,
+        mut hit_objects: Vec<OsuObject>,
+    ) -> Self {
         let map_attributes = map.attributes().mods(mods);
         let hit_window = difficulty_range_od(map_attributes.od) / map_attributes.clock_rate;
         let od = (80.0 - hit_window) / 6.0;
 
         let mut raw_ar = map.ar as f64;
-        let hr = mods.hr();
 
-        if hr {
+        if mods.hr() {
             raw_ar = (raw_ar * 1.4).min(10.0);
         } else if mods.ez() {
             raw_ar *= 0.5;
@@ -75,7 +137,7 @@ impl OsuGradualDifficultyAttributes {
         let time_preempt = difficulty_range_ar(raw_ar);
         let scaling_factor = ScalingFactor::new(map_attributes.cs);
 
-        let mut attributes = OsuDifficultyAttributes {
+        let attributes = OsuDifficultyAttributes {
             ar: map_attributes.ar,
             hp: map_attributes.hp,
             cs: map_attributes.cs,
@@ -83,27 +145,6 @@ impl OsuGradualDifficultyAttributes {
             ..Default::default()
         };
 
-        let mut params = ObjectParameters {
-            map,
-            attributes: &mut attributes,
-            slider_state: SliderState::new(map),
-            ticks: Vec::new(),
-            curve_bufs: CurveBuffers::default(),
-        };
-
-        let hit_objects_iter = map
-            .hit_objects
-            .iter()
-            .filter_map(|h| OsuObject::new(h, hr, &mut params));
-
-        let mut hit_objects = Vec::with_capacity(map.hit_objects.len());
-        hit_objects.extend(hit_objects_iter);
-
-        attributes.n_circles = 0;
-        attributes.n_sliders = 0;
-        attributes.n_spinners = 0;
-        attributes.max_combo = 0;
-
         let stack_threshold = time_preempt * map.stack_leniency as f64;
 
         if map.version >= 6 {
@@ -112,7 +153,14 @@ impl OsuGradualDifficultyAttributes {
             old_stacking(&mut hit_objects, stack_threshold);
         }
 
-        let skills = Skills::new(hit_window, false, scaling_factor.radius(), mods.fl());
+        let skills = Skills::new(
+            hit_window,
+            false,
+            scaling_factor.radius(),
+            mods.fl(),
+            true,
+            &OsuParameters::DEFAULT,
+        );
 
         let hit_objects = OsuObjectIter {
             hit_objects: hit_objects.into_iter(),
@@ -140,6 +188,52 @@ impl OsuGradualDifficultyAttributes {
             strain_peak_buf: Vec::new(),
         }
     }
+
+    /// The strains of all processed hit objects, including the section
+    /// of the current object, as [`strains`](super::strains) would return them.
+    ///
+    /// Suitable to draw the evolving strain graph during a play
+    /// without calculating the strains from scratch.
+    pub fn current_strains(&self) -> Strains {
+        let mut strains = Vec::new();
+
+        // The first object has no strain
+        if self.idx >= 2 {
+            let mut skills = self.skills.strain_skills().map(|skill| {
+                crate::peaks_with_current(&skill.strain_peaks, skill.curr_section_peak)
+            });
+
+            if let Some(aim) = skills.next() {
+                strains.extend(aim);
+            }
+
+            // Speed and flashlight, summed up like in `strains`
+            match (skills.next(), skills.next()) {
+                (Some(a), Some(b)) => {
+                    for ((strain, a), b) in strains.iter_mut().zip(a).zip(b) {
+                        *strain += a + b;
+                    }
+                }
+                (Some(a), None) => {
+                    for (strain, a) in strains.iter_mut().zip(a) {
+                        *strain += a;
+                    }
+                }
+                (None, _) => {}
+            }
+        }
+
+        Strains {
+            section_length: SECTION_LEN * self.clock_rate,
+            strains,
+        }
+    }
+
+    /// The maximum combo of all processed hit objects.
+    #[inline]
+    pub(crate) fn max_combo(&self) -> usize {
+        self.attributes.max_combo
+    }
 }
 
 impl Iterator for OsuGradualDifficultyAttributes {
@@ -194,27 +288,35 @@ impl Iterator for OsuGradualDifficultyAttributes {
         self.skills.process(&h);
         self.prev_prev = Some(mem::replace(&mut self.prev, curr));
 
-        let missing = self.skills.aim().strain_peaks.len() + 1 - self.strain_peak_buf.len();
-        self.strain_peak_buf.extend(iter::repeat(0.0).take(missing));
+        self.strain_peak_buf
+            .resize(self.skills.aim().strain_peaks.len() + 1, 0.0);
 
         let aim_rating = {
             let aim = self.skills.aim();
-            self.strain_peak_buf[..aim.strain_peaks.len()].copy_from_slice(&aim.strain_peaks);
+            crate::copy_strains(
+                &mut self.strain_peak_buf[..aim.strain_peaks.len()],
+                &aim.strain_peaks,
+            );
 
             if let Some(last) = self.strain_peak_buf.last_mut() {
-                *last = aim.curr_section_peak;
+                *last = crate::round_strain(aim.curr_section_peak);
             }
 
             Skill::difficulty_value(&mut self.strain_peak_buf, aim).sqrt() * DIFFICULTY_MULTIPLIER
         };
 
+        // Same as in `stars`, strains are counted w.r.t. the unmodded clock rate
+        let aim_difficult_strain_count = self.skills.aim().count_difficult_strains(1.0);
+
         let slider_factor = if aim_rating > 0.0 {
             let aim_no_sliders = self.skills.aim_no_sliders();
-            self.strain_peak_buf[..aim_no_sliders.strain_peaks.len()]
-                .copy_from_slice(&aim_no_sliders.strain_peaks);
+            crate::copy_strains(
+                &mut self.strain_peak_buf[..aim_no_sliders.strain_peaks.len()],
+                &aim_no_sliders.strain_peaks,
+            );
 
             if let Some(last) = self.strain_peak_buf.last_mut() {
-                *last = aim_no_sliders.curr_section_peak;
+                *last = crate::round_strain(aim_no_sliders.curr_section_peak);
             }
 
             let aim_rating_no_sliders =
@@ -228,24 +330,32 @@ impl Iterator for OsuGradualDifficultyAttributes {
 
         let (speed, flashlight) = self.skills.speed_flashlight();
 
-        let speed_rating = if let Some(speed) = speed {
-            self.strain_peak_buf[..speed.strain_peaks.len()].copy_from_slice(&speed.strain_peaks);
+        let (speed_rating, speed_difficult_strain_count) = if let Some(speed) = speed {
+            crate::copy_strains(
+                &mut self.strain_peak_buf[..speed.strain_peaks.len()],
+                &speed.strain_peaks,
+            );
 
             if let Some(last) = self.strain_peak_buf.last_mut() {
-                *last = speed.curr_section_peak;
+                *last = crate::round_strain(speed.curr_section_peak);
             }
 
-            Skill::difficulty_value(&mut self.strain_peak_buf, speed).sqrt() * DIFFICULTY_MULTIPLIER
+            let speed_rating = Skill::difficulty_value(&mut self.strain_peak_buf, speed).sqrt()
+                * DIFFICULTY_MULTIPLIER;
+
+            (speed_rating, speed.count_difficult_strains(1.0))
         } else {
-            0.0
+            (0.0, 0.0)
         };
 
         let flashlight_rating = if let Some(flashlight) = flashlight {
-            self.strain_peak_buf[..flashlight.strain_peaks.len()]
-                .copy_from_slice(&flashlight.strain_peaks);
+            crate::copy_strains(
+                &mut self.strain_peak_buf[..flashlight.strain_peaks.len()],
+                &flashlight.strain_peaks,
+            );
 
             if let Some(last) = self.strain_peak_buf.last_mut() {
-                *last = flashlight.curr_section_peak;
+                *last = crate::round_strain(flashlight.curr_section_peak);
             }
 
             Skill::difficulty_value(&mut self.strain_peak_buf, flashlight).sqrt()
@@ -260,6 +370,8 @@ impl Iterator for OsuGradualDifficultyAttributes {
         self.attributes.speed_strain = speed_rating;
         self.attributes.flashlight_rating = flashlight_rating;
         self.attributes.slider_factor = slider_factor;
+        self.attributes.aim_difficult_strain_count = aim_difficult_strain_count;
+        self.attributes.speed_difficult_strain_count = speed_difficult_strain_count;
         self.attributes.stars = star_rating;
 
         Some(self.attributes.clone())
@@ -333,4 +445,35 @@ mod tests {
 
         assert_eq!(regular, iter_end);
     }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn constant_rate_schedule_eq_regular() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let regular = crate::osu::stars(&map, 64, None);
+        let schedule = RateSchedule::new([(0.0, 1.5)]).unwrap();
+
+        let iter_end = OsuGradualDifficultyAttributes::with_rate_schedule(&map, 0, &schedule)
+            .last()
+            .expect("empty iter");
+
+        assert!((regular.stars - iter_end.stars).abs() < 1e-9);
+        assert!((regular.od - iter_end.od).abs() < 1e-9);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn ramping_rate_schedule_between_bounds() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let end_time = map.hit_objects.last().map_or(0.0, |h| h.start_time);
+        let schedule = RateSchedule::ramp(0.0, end_time, 1.0, 1.5).unwrap();
+
+        let stars = OsuGradualDifficultyAttributes::with_rate_schedule(&map, 0, &schedule)
+            .last()
+            .expect("empty iter")
+            .stars;
+
+        assert!(stars > crate::osu::stars(&map, 0, None).stars);
+        assert!(stars < crate::osu::stars(&map, 64, None).stars);
+    }
 }
@@ -1,6 +1,6 @@
-use crate::{Beatmap, OsuPP};
+use crate::{rate::RateSchedule, Beatmap, OsuPP};
 
-use super::{OsuGradualDifficultyAttributes, OsuPerformanceAttributes};
+use super::{OsuDifficultyAttributes, OsuGradualDifficultyAttributes, OsuPerformanceAttributes};
 
 /// Aggregation for a score's current state i.e. what was the
 /// maximum combo so far and what are the current hitresults.
@@ -26,6 +26,38 @@ impl OsuScoreState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Create the score state of a perfect play, i.e. an SS with full combo,
+    /// for the given difficulty attributes.
+    pub fn max_for(attributes: &OsuDifficultyAttributes) -> Self {
+        Self {
+            max_combo: attributes.max_combo,
+            n300: attributes.n_circles + attributes.n_sliders + attributes.n_spinners,
+            n100: 0,
+            n50: 0,
+            misses: 0,
+        }
+    }
+
+    /// The amount of hitresults, i.e. the amount of objects that were judged so far.
+    #[inline]
+    pub fn total_hits(&self) -> usize {
+        self.n300 + self.n100 + self.n50 + self.misses
+    }
+
+    /// The amount of objects that are yet to be judged out of `n_objects`.
+    #[inline]
+    pub fn remaining(&self, n_objects: usize) -> usize {
+        n_objects.saturating_sub(self.total_hits())
+    }
+
+    /// Whether all objects of the given difficulty attributes were judged.
+    ///
+    /// The objects are counted by [`OsuDifficultyAttributes::n_accuracy_objects`].
+    #[inline]
+    pub fn is_complete(&self, attributes: &OsuDifficultyAttributes) -> bool {
+        self.remaining(attributes.n_accuracy_objects()) == 0
+    }
 }
 
 /// Gradually calculate the performance attributes of an osu!standard map.
@@ -45,7 +77,7 @@ impl OsuScoreState {
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, osu::{OsuGradualPerformanceAttributes, OsuScoreState}};
+/// use akatsuki_pp::{Beatmap, osu::{OsuGradualPerformanceAttributes, OsuScoreState}};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -105,10 +137,10 @@ impl OsuScoreState {
 /// state.n100 = ...
 /// state.n50 = ...
 /// state.misses = ...
-/// let final_performance = gradual_perf.process_next_n_objects(state.clone(), usize::MAX).unwrap();
+/// let final_performance = gradual_perf.process_remaining(state.clone()).unwrap();
 /// println!("PP: {}", performance.pp);
 /// # */
-/// # let _ = gradual_perf.process_next_n_objects(state.clone(), usize::MAX);
+/// # let _ = gradual_perf.process_remaining(state.clone());
 ///
 /// // Once the final performance was calculated,
 /// // attempting to process further objects will return `None`.
@@ -132,6 +164,20 @@ impl<'map> OsuGradualPerformanceAttributes<'map> {
         }
     }
 
+    /// Create a new gradual performance calculator for osu!standard maps
+    /// whose clock rate changes over time.
+    ///
+    /// See [`OsuGradualDifficultyAttributes::with_rate_schedule`].
+    pub fn with_rate_schedule(map: &'map Beatmap, mods: u32, schedule: &RateSchedule) -> Self {
+        let difficulty = OsuGradualDifficultyAttributes::with_rate_schedule(map, mods, schedule);
+        let performance = OsuPP::new(map).mods(mods).passed_objects(0);
+
+        Self {
+            difficulty,
+            performance,
+        }
+    }
+
     /// Process the next hit object and calculate the
     /// performance attributes for the resulting score state.
     pub fn process_next_object(
@@ -165,12 +211,46 @@ impl<'map> OsuGradualPerformanceAttributes<'map> {
 
         Some(performance)
     }
+
+    /// Process all remaining hit objects at once and calculate the
+    /// performance attributes for the final score state.
+    ///
+    /// Suitable to finish off a play, e.g. after a fail, without having to know
+    /// how many objects are left. Returns `None` if all objects were processed already.
+    pub fn process_remaining(&mut self, state: OsuScoreState) -> Option<OsuPerformanceAttributes> {
+        self.process_next_n_objects(state, usize::MAX)
+    }
+
+    /// The maximum combo that could have been achieved up to the last processed hit object.
+    ///
+    /// Suitable to display a play's combo alongside the combo that was possible so far.
+    #[inline]
+    pub fn max_combo(&self) -> usize {
+        self.difficulty.max_combo()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    #[allow(unused_imports)]
     use super::*;
 
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn max_state_eq_default() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let attributes = crate::osu::stars(&map, 0, None);
+        let state = OsuScoreState::max_for(&attributes);
+
+        let any_state = crate::ScoreState::max_for(&attributes.clone().into());
+        assert_eq!(OsuScoreState::from(any_state), state);
+
+        let regular = OsuPP::new(&map).calculate();
+        let max = OsuPP::new(&map).state(state).calculate();
+
+        assert_eq!(regular, max);
+    }
+
     #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
     #[test]
     fn correct_empty() {
@@ -265,4 +345,46 @@ mod tests {
 
         assert_eq!(regular, gradual);
     }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn max_combo_progression() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let mut gradual = OsuGradualPerformanceAttributes::new(&map, 0);
+        assert_eq!(gradual.max_combo(), 0);
+
+        let state = OsuScoreState::default();
+        let _ = gradual.process_next_n_objects(state.clone(), 100);
+        let partial = crate::osu::stars(&map, 0, Some(100)).max_combo;
+        assert_eq!(gradual.max_combo(), partial);
+
+        let _ = gradual.process_next_n_objects(state, usize::MAX);
+        let full = crate::osu::stars(&map, 0, None).max_combo;
+        assert_eq!(gradual.max_combo(), full);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn process_remaining_after_partial() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let mods = 64;
+        let state = OsuScoreState::default();
+
+        let mut gradual = OsuGradualPerformanceAttributes::new(&map, mods);
+        let _ = gradual.process_next_n_objects(state.clone(), 100);
+
+        let state = OsuScoreState {
+            max_combo: 909,
+            n300: 601,
+            n100: 0,
+            n50: 0,
+            misses: 0,
+        };
+
+        let remaining = gradual.process_remaining(state.clone()).unwrap();
+        let regular = OsuPP::new(&map).mods(mods).state(state.clone()).calculate();
+
+        assert_eq!(remaining, regular);
+        assert!(gradual.process_remaining(state).is_none());
+    }
 }
@@ -1,28 +1,45 @@
 #![cfg(feature = "osu")]
 
+mod attributes_builder;
 mod difficulty_object;
 mod gradual_difficulty;
 mod gradual_performance;
+mod movement;
 mod osu_object;
 mod pp;
+mod preprocessed;
 mod scaling_factor;
 mod skill;
 mod skill_kind;
 mod slider_state;
+mod visibility;
 
-use std::mem;
+use std::{fmt, mem};
 
+pub use attributes_builder::OsuDifficultyAttributesBuilder;
 use difficulty_object::DifficultyObject;
 pub use gradual_difficulty::*;
 pub use gradual_performance::*;
+pub use movement::*;
 use osu_object::{ObjectParameters, OsuObject};
 pub use pp::*;
+pub use preprocessed::OsuPreprocessedMap;
 use scaling_factor::ScalingFactor;
 use skill::Skill;
 use skill_kind::SkillKind;
+pub(crate) use skill_kind::{AIM_PARAMETERS, FLASHLIGHT_PARAMETERS, SPEED_PARAMETERS};
 use slider_state::SliderState;
+pub use visibility::*;
 
-use crate::{curve::CurveBuffers, Beatmap, Mods, Strains};
+#[cfg(feature = "research")]
+use crate::research::ParameterError;
+
+use crate::{
+    curve::CurveBuffers,
+    research::OsuParameters,
+    timings::{Phase, Timer},
+    Beatmap, Mods, Strains,
+};
 
 use self::skill::Skills;
 
@@ -43,11 +60,56 @@ pub fn stars(
     mods: impl Mods,
     passed_objects: Option<usize>,
 ) -> OsuDifficultyAttributes {
-    let (mut skills, mut attributes) = calculate_skills(map, mods, passed_objects);
+    calculate_stars(map, mods, passed_objects, &OsuParameters::DEFAULT)
+}
+
+/// Same as [`stars`] but the skills use the given parameters instead of the regular ones.
+///
+/// Returns an error if any parameter is outside of its valid range.
+#[cfg(feature = "research")]
+#[cfg_attr(docsrs, doc(cfg(feature = "research")))]
+pub fn stars_with_parameters(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &OsuParameters,
+) -> Result<OsuDifficultyAttributes, ParameterError> {
+    params.validate()?;
+
+    Ok(calculate_stars(map, mods, passed_objects, params))
+}
+
+fn calculate_stars(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &OsuParameters,
+) -> OsuDifficultyAttributes {
+    trace_span!(
+        "difficulty",
+        mode = "osu",
+        map = format_args!("{:032x}", crate::CalcKey::fingerprint(map)),
+        mods = crate::mods::legacy_bits(mods),
+        passed_objects = ?passed_objects,
+        n_objects = map.hit_objects.len(),
+    );
+
+    let (skills, attributes) = calculate_skills(map, mods, passed_objects, true, params);
+
+    evaluate_skills(map, skills, attributes)
+}
+
+/// Evaluate the processed skills into the final attributes.
+fn evaluate_skills(
+    map: &Beatmap,
+    mut skills: Skills,
+    mut attributes: OsuDifficultyAttributes,
+) -> OsuDifficultyAttributes {
+    let timer = Timer::start();
 
     let aim_rating = {
         let aim = skills.aim();
-        let mut aim_strains = mem::take(&mut aim.strain_peaks);
+        let mut aim_strains = crate::widen_strains(mem::take(&mut aim.strain_peaks));
 
         Skill::difficulty_value(&mut aim_strains, aim).sqrt() * DIFFICULTY_MULTIPLIER
     };
@@ -55,7 +117,8 @@ pub fn stars(
     let slider_factor = if aim_rating > 0.0 {
         let aim_no_sliders = skills.aim_no_sliders();
 
-        let mut aim_strains_no_sliders = mem::take(&mut aim_no_sliders.strain_peaks);
+        let mut aim_strains_no_sliders =
+            crate::widen_strains(mem::take(&mut aim_no_sliders.strain_peaks));
         let aim_rating_no_sliders =
             Skill::difficulty_value(&mut aim_strains_no_sliders, aim_no_sliders).sqrt()
                 * DIFFICULTY_MULTIPLIER;
@@ -68,7 +131,7 @@ pub fn stars(
     let (speed, flashlight) = skills.speed_flashlight();
 
     let speed_rating = if let Some(speed) = speed {
-        let mut speed_strains = mem::take(&mut speed.strain_peaks);
+        let mut speed_strains = crate::widen_strains(mem::take(&mut speed.strain_peaks));
 
         Skill::difficulty_value(&mut speed_strains, speed).sqrt() * DIFFICULTY_MULTIPLIER
     } else {
@@ -76,14 +139,20 @@ pub fn stars(
     };
 
     let flashlight_rating = if let Some(flashlight) = flashlight {
-        let mut flashlight_strains = mem::take(&mut flashlight.strain_peaks);
+        let mut flashlight_strains = crate::widen_strains(mem::take(&mut flashlight.strain_peaks));
 
         Skill::difficulty_value(&mut flashlight_strains, flashlight).sqrt() * DIFFICULTY_MULTIPLIER
     } else {
         0.0
     };
 
-    let star_rating = calculate_star_rating(aim_rating, speed_rating, flashlight_rating);
+    // Fewer than two objects have no difficulty but the formula has a lower bound
+    let star_rating = if attributes.n_circles + attributes.n_sliders + attributes.n_spinners < 2 {
+        0.0
+    } else {
+        calculate_star_rating(aim_rating, speed_rating, flashlight_rating)
+    };
+
     let aim_difficult_strain_count = skills
         .aim()
         .count_difficult_strains(map.attributes().clock_rate);
@@ -100,10 +169,37 @@ pub fn stars(
     attributes.aim_difficult_strain_count = aim_difficult_strain_count;
     attributes.speed_difficult_strain_count = speed_difficult_strain_count;
     attributes.stars = star_rating;
+    timer.finish(Phase::Evaluation);
 
     attributes
 }
 
+/// Calculate only the star rating of a map.
+///
+/// Skips everything that is solely required for performance calculation,
+/// i.e. the aim skill without sliders and the difficult strain counts.
+/// The result is the same as the `stars` field of [`stars`].
+pub fn stars_light(map: &Beatmap, mods: impl Mods) -> f64 {
+    if !map.is_calculable() {
+        return 0.0;
+    }
+
+    let (mut skills, _) = calculate_skills(map, mods, None, false, &OsuParameters::DEFAULT);
+
+    let rating = |skill: &mut Skill| {
+        let mut strains = crate::widen_strains(mem::take(&mut skill.strain_peaks));
+
+        Skill::difficulty_value(&mut strains, skill).sqrt() * DIFFICULTY_MULTIPLIER
+    };
+
+    let aim_rating = rating(skills.aim());
+    let (speed, flashlight) = skills.speed_flashlight();
+    let speed_rating = speed.map_or(0.0, rating);
+    let flashlight_rating = flashlight.map_or(0.0, rating);
+
+    calculate_star_rating(aim_rating, speed_rating, flashlight_rating)
+}
+
 fn calculate_star_rating(aim_rating: f64, speed_rating: f64, flashlight_rating: f64) -> f64 {
     let base_aim_performance = {
         let base = 5.0 * (aim_rating / 0.0675).max(1.0) - 4.0;
@@ -137,10 +233,14 @@ fn calculate_star_rating(aim_rating: f64, speed_rating: f64, flashlight_rating:
 /// evaluating the final strains, it just returns them as is.
 ///
 /// Suitable to plot the difficulty of a map over time.
-pub fn strains(map: &Beatmap, mods: impl Mods) -> Strains {
-    let (mut skills, _) = calculate_skills(map, mods, None);
+///
+/// In case of a partial play, e.g. a live play that is still ongoing,
+/// one can specify the amount of passed objects.
+pub fn strains(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize>) -> Strains {
+    let (mut skills, _) =
+        calculate_skills(map, mods, passed_objects, true, &OsuParameters::DEFAULT);
 
-    let mut aim = mem::take(&mut skills.aim().strain_peaks);
+    let mut aim = crate::widen_strains(mem::take(&mut skills.aim().strain_peaks));
     let tuple = skills.speed_flashlight();
 
     let strains = match tuple {
@@ -150,14 +250,14 @@ pub fn strains(map: &Beatmap, mods: impl Mods) -> Strains {
                 .zip(&speed.strain_peaks)
                 .zip(&flashlight.strain_peaks)
             {
-                *aim += speed + flashlight;
+                *aim += crate::widen_strain(*speed) + crate::widen_strain(*flashlight);
             }
 
             aim
         }
         (Some(strains), None) | (None, Some(strains)) => {
             for (aim, strain) in aim.iter_mut().zip(&strains.strain_peaks) {
-                *aim += strain;
+                *aim += crate::widen_strain(*strain);
             }
 
             aim
@@ -171,36 +271,65 @@ pub fn strains(map: &Beatmap, mods: impl Mods) -> Strains {
     }
 }
 
-fn calculate_skills(
+/// The strain peaks of each skill of an osu!standard map, see [`skill_strains`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OsuSkillStrains {
+    /// Strain peaks of the aim skill.
+    pub aim: Vec<f64>,
+    /// Strain peaks of the aim skill without considering sliders.
+    pub aim_no_sliders: Vec<f64>,
+    /// Strain peaks of the speed skill.
+    pub speed: Vec<f64>,
+    /// Strain peaks of the flashlight skill.
+    ///
+    /// Empty if the mods do not include flashlight.
+    pub flashlight: Vec<f64>,
+}
+
+/// Calculate the strain peaks of each skill of an osu!standard map.
+///
+/// The peaks of each skill are sorted in descending order and
+/// neither reduced nor weighted, so that the star rating can be
+/// recomputed with alternative weightings.
+///
+/// In case of a partial play, e.g. a fail, one can specify the amount of passed objects.
+pub fn skill_strains(
     map: &Beatmap,
     mods: impl Mods,
     passed_objects: Option<usize>,
-) -> (Skills, OsuDifficultyAttributes) {
-    let take = passed_objects.unwrap_or_else(|| map.hit_objects.len());
+) -> OsuSkillStrains {
+    let (mut skills, _) =
+        calculate_skills(map, mods, passed_objects, true, &OsuParameters::DEFAULT);
 
-    let map_attributes = map.attributes().mods(mods);
-    let hit_window = difficulty_range_od(map_attributes.od) / map_attributes.clock_rate;
-    let od = (80.0 - hit_window) / 6.0;
+    let mut take_sorted = |skill: &mut Skill| {
+        let mut peaks = crate::widen_strains(mem::take(&mut skill.strain_peaks));
+        crate::sort_strain_peaks(&mut peaks);
 
-    let mut raw_ar = map.ar as f64;
-    let hr = mods.hr();
+        peaks
+    };
 
-    if hr {
-        raw_ar = (raw_ar * 1.4).min(10.0);
-    } else if mods.ez() {
-        raw_ar *= 0.5;
+    let aim = take_sorted(skills.aim());
+    let aim_no_sliders = take_sorted(skills.aim_no_sliders());
+    let (speed, flashlight) = skills.speed_flashlight();
+
+    OsuSkillStrains {
+        aim,
+        aim_no_sliders,
+        speed: speed.map_or_else(Vec::new, &mut take_sorted),
+        flashlight: flashlight.map_or_else(Vec::new, take_sorted),
     }
+}
 
-    let time_preempt = difficulty_range_ar(raw_ar);
-    let scaling_factor = ScalingFactor::new(map_attributes.cs);
+fn calculate_skills(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    no_sliders: bool,
+    skill_params: &OsuParameters,
+) -> (Skills, OsuDifficultyAttributes) {
+    let take = passed_objects.unwrap_or(map.hit_objects.len());
 
-    let mut attributes = OsuDifficultyAttributes {
-        ar: map_attributes.ar,
-        hp: map_attributes.hp,
-        cs: map_attributes.cs,
-        od,
-        ..Default::default()
-    };
+    let mut attributes = OsuDifficultyAttributes::default();
 
     let mut params = ObjectParameters {
         map,
@@ -210,31 +339,57 @@ fn calculate_skills(
         curve_bufs: CurveBuffers::default(),
     };
 
+    let timer = Timer::start();
+    let hr = mods.hr();
+
     let hit_objects_iter = map
         .hit_objects
         .iter()
         .take(take)
         .filter_map(|h| OsuObject::new(h, hr, &mut params));
 
-    let mut hit_objects = Vec::with_capacity(take);
+    let mut hit_objects = Vec::with_capacity(take.min(map.hit_objects.len()));
     hit_objects.extend(hit_objects_iter);
 
-    let stack_threshold = time_preempt * map.stack_leniency as f64;
+    timer.finish(Phase::Preprocessing);
 
-    if map.version >= 6 {
-        stacking(&mut hit_objects, stack_threshold);
-    } else {
-        old_stacking(&mut hit_objects, stack_threshold);
-    }
-
-    let mut hit_objects = hit_objects.into_iter().map(|mut h| {
-        let stack_offset = scaling_factor.stack_offset(h.stack_height);
-        h.pos += stack_offset;
+    process_objects(map, mods, hit_objects, attributes, no_sliders, skill_params)
+}
 
-        h
-    });
+/// Stack the objects and process them with the skills.
+///
+/// The objects must already be adjusted for HR and
+/// the attributes must already contain the object counts.
+fn process_objects(
+    map: &Beatmap,
+    mods: impl Mods,
+    mut hit_objects: Vec<OsuObject>,
+    mut attributes: OsuDifficultyAttributes,
+    no_sliders: bool,
+    skill_params: &OsuParameters,
+) -> (Skills, OsuDifficultyAttributes) {
+    let map_attributes = map.attributes().mods(mods);
+    let hit_window = difficulty_range_od(map_attributes.od) / map_attributes.clock_rate;
+    let scaling_factor = ScalingFactor::new(map_attributes.cs);
 
-    let mut skills = Skills::new(hit_window, false, scaling_factor.radius(), mods.fl());
+    attributes.ar = map_attributes.ar;
+    attributes.hp = map_attributes.hp;
+    attributes.cs = map_attributes.cs;
+    attributes.od = (80.0 - hit_window) / 6.0;
+
+    let timer = Timer::start();
+    stack_objects(map, mods, &mut hit_objects, &scaling_factor);
+    let mut hit_objects = hit_objects.into_iter();
+    timer.finish(Phase::Preprocessing);
+
+    let mut skills = Skills::new(
+        hit_window,
+        false,
+        scaling_factor.radius(),
+        mods.fl(),
+        no_sliders,
+        skill_params,
+    );
 
     let (mut prev, curr) = match (hit_objects.next(), hit_objects.next()) {
         (Some(prev), Some(curr)) => (prev, curr),
@@ -249,6 +404,8 @@ fn calculate_skills(
         (prev.time / map_attributes.clock_rate / SECTION_LEN).ceil() * SECTION_LEN;
 
     // Handle second object separately to remove later if-branching
+    let timer = Timer::start();
+
     let h = DifficultyObject::new(
         &curr,
         &mut prev,
@@ -257,6 +414,8 @@ fn calculate_skills(
         map_attributes.clock_rate,
     );
 
+    timer.finish(Phase::Preprocessing);
+
     let base_time = h.base.time / map_attributes.clock_rate;
 
     while base_time > curr_section_end {
@@ -269,6 +428,8 @@ fn calculate_skills(
 
     // Handle all other objects
     for curr in hit_objects {
+        let timer = Timer::start();
+
         let h = DifficultyObject::new(
             &curr,
             &mut prev,
@@ -277,6 +438,8 @@ fn calculate_skills(
             map_attributes.clock_rate,
         );
 
+        timer.finish(Phase::Preprocessing);
+
         let base_time = h.base.time / map_attributes.clock_rate;
 
         while base_time > curr_section_end {
@@ -293,6 +456,37 @@ fn calculate_skills(
     (skills, attributes)
 }
 
+/// Calculate the stack heights of the objects and offset their positions accordingly.
+///
+/// The objects must already be adjusted for HR.
+fn stack_objects(
+    map: &Beatmap,
+    mods: impl Mods,
+    hit_objects: &mut [OsuObject],
+    scaling_factor: &ScalingFactor,
+) {
+    let mut raw_ar = map.ar as f64;
+
+    if mods.hr() {
+        raw_ar = (raw_ar * 1.4).min(10.0);
+    } else if mods.ez() {
+        raw_ar *= 0.5;
+    }
+
+    let time_preempt = difficulty_range_ar(raw_ar);
+    let stack_threshold = time_preempt * map.stack_leniency as f64;
+
+    if map.version >= 6 {
+        stacking(hit_objects, stack_threshold);
+    } else {
+        old_stacking(hit_objects, stack_threshold);
+    }
+
+    for h in hit_objects.iter_mut() {
+        h.pos += scaling_factor.stack_offset(h.stack_height);
+    }
+}
+
 fn stacking(hit_objects: &mut [OsuObject], stack_threshold: f64) {
     let mut extended_start_idx = 0;
     let extended_end_idx = hit_objects.len() - 1;
@@ -434,7 +628,7 @@ fn old_stacking(hit_objects: &mut [OsuObject], stack_threshold: f64) {
 
 #[inline]
 fn difficulty_range_ar(ar: f64) -> f64 {
-    crate::difficulty_range(ar, 450.0, 1200.0, 1800.0)
+    crate::util::ar_to_ms(ar)
 }
 
 fn lerp(start: f64, end: f64, percent: f64) -> f64 {
@@ -474,6 +668,8 @@ pub struct OsuDifficultyAttributes {
     pub stars: f64,
     /// The maximum combo.
     pub max_combo: usize,
+    /// The release and formulas that calculated the attributes.
+    pub provenance: crate::Provenance,
 }
 
 impl OsuDifficultyAttributes {
@@ -482,6 +678,69 @@ impl OsuDifficultyAttributes {
     pub fn max_combo(&self) -> usize {
         self.max_combo
     }
+
+    /// The amount of judgements that the accuracy is divided by,
+    /// i.e. the amount of circles, sliders, and spinners.
+    #[inline]
+    pub fn n_accuracy_objects(&self) -> usize {
+        self.n_circles + self.n_sliders + self.n_spinners
+    }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let eq = |a, b| crate::approx_eq_f64(a, b, epsilon);
+
+        eq(self.aim_strain, other.aim_strain)
+            && eq(self.speed_strain, other.speed_strain)
+            && eq(self.flashlight_rating, other.flashlight_rating)
+            && eq(self.slider_factor, other.slider_factor)
+            && eq(
+                self.aim_difficult_strain_count,
+                other.aim_difficult_strain_count,
+            )
+            && eq(
+                self.speed_difficult_strain_count,
+                other.speed_difficult_strain_count,
+            )
+            && eq(self.ar, other.ar)
+            && eq(self.od, other.od)
+            && eq(self.hp, other.hp)
+            && eq(self.cs, other.cs)
+            && self.n_circles == other.n_circles
+            && self.n_sliders == other.n_sliders
+            && self.n_spinners == other.n_spinners
+            && eq(self.stars, other.stars)
+            && self.max_combo == other.max_combo
+    }
+
+    /// Adjust the attributes that were calculated with `old_mods` so that they
+    /// apply to `new_mods` without recalculating the difficulty.
+    ///
+    /// Mods such as HD or NF do not affect the difficulty so the attributes stay the same,
+    /// and removing FL only requires the star rating to be re-evaluated.
+    ///
+    /// Returns `None` if a full recalculation is required, i.e. if EZ, HR, or
+    /// the clock rate changed, or if FL was added.
+    pub fn adjust_mods(&self, old_mods: impl Mods, new_mods: impl Mods) -> Option<Self> {
+        if old_mods.ez() != new_mods.ez()
+            || old_mods.hr() != new_mods.hr()
+            || old_mods.speed() != new_mods.speed()
+            || (!old_mods.fl() && new_mods.fl())
+        {
+            return None;
+        }
+
+        let mut attributes = self.clone();
+
+        if old_mods.fl() && !new_mods.fl() {
+            attributes.flashlight_rating = 0.0;
+            attributes.stars =
+                calculate_star_rating(attributes.aim_strain, attributes.speed_strain, 0.0);
+        }
+
+        Some(attributes)
+    }
 }
 
 /// The result of a performance calculation on an osu!standard map.
@@ -523,6 +782,53 @@ impl OsuPerformanceAttributes {
     pub fn max_combo(&self) -> usize {
         self.difficulty.max_combo
     }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let eq = |a, b| crate::approx_eq_f64(a, b, epsilon);
+
+        self.difficulty.approx_eq(&other.difficulty, epsilon)
+            && eq(self.pp, other.pp)
+            && eq(self.pp_acc, other.pp_acc)
+            && eq(self.pp_aim, other.pp_aim)
+            && eq(self.pp_flashlight, other.pp_flashlight)
+            && eq(self.pp_speed, other.pp_speed)
+            && eq(self.aim_strain_count, other.aim_strain_count)
+            && eq(self.speed_strain_count, other.speed_strain_count)
+    }
+}
+
+impl fmt::Display for OsuDifficultyAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2}★ (aim {:.1}, speed {:.1}",
+            self.stars, self.aim_strain, self.speed_strain
+        )?;
+
+        if self.flashlight_rating > 0.0 {
+            write!(f, ", flashlight {:.1}", self.flashlight_rating)?;
+        }
+
+        write!(f, ") max combo {}", self.max_combo)
+    }
+}
+
+impl fmt::Display for OsuPerformanceAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0}pp (aim {:.0}, speed {:.0}, acc {:.0}",
+            self.pp, self.pp_aim, self.pp_speed, self.pp_acc
+        )?;
+
+        if self.pp_flashlight > 0.0 {
+            write!(f, ", flashlight {:.0}", self.pp_flashlight)?;
+        }
+
+        f.write_str(")")
+    }
 }
 
 impl From<OsuPerformanceAttributes> for OsuDifficultyAttributes {
@@ -533,5 +839,5 @@ impl From<OsuPerformanceAttributes> for OsuDifficultyAttributes {
 
 #[inline]
 fn difficulty_range_od(od: f64) -> f64 {
-    super::difficulty_range(od, 20.0, 50.0, 80.0)
+    crate::util::osu_great_hit_window(od)
 }
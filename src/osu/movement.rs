@@ -0,0 +1,142 @@
+use std::mem;
+
+use super::{
+    osu_object::{ObjectParameters, OsuObject},
+    scaling_factor::ScalingFactor,
+    slider_state::SliderState,
+    stack_objects, DifficultyObject, OsuDifficultyAttributes,
+};
+
+use crate::{curve::CurveBuffers, Beatmap, Mods};
+
+/// Calculate the movement from each hit object of an osu!standard map to the next,
+/// i.e. the geometry that the aim skill consumes.
+///
+/// Positions are stacked and adjusted for HR, and distances are scaled to a
+/// normalized circle radius of 50 osu!pixels, so that they are comparable across
+/// circle sizes. Times are adjusted by the clock rate of speed changing mods.
+///
+/// The first object has no predecessor so the result contains one movement
+/// less than there are hit objects.
+pub fn movements(map: &Beatmap, mods: impl Mods) -> Vec<OsuMovement> {
+    let map_attributes = map.attributes().mods(mods);
+    let scaling_factor = ScalingFactor::new(map_attributes.cs);
+    let clock_rate = map_attributes.clock_rate;
+
+    let mut attributes = OsuDifficultyAttributes::default();
+
+    let mut params = ObjectParameters {
+        map,
+        attributes: &mut attributes,
+        slider_state: SliderState::new(map),
+        ticks: Vec::new(),
+        curve_bufs: CurveBuffers::default(),
+    };
+
+    let mut hit_objects: Vec<_> = map
+        .hit_objects
+        .iter()
+        .filter_map(|h| OsuObject::new(h, mods.hr(), &mut params))
+        .collect();
+
+    stack_objects(map, mods, &mut hit_objects, &scaling_factor);
+
+    let mut hit_objects = hit_objects.into_iter();
+
+    let mut prev = match hit_objects.next() {
+        Some(h) => h,
+        None => return Vec::new(),
+    };
+
+    let mut prev_prev = None;
+    let mut movements = Vec::with_capacity(hit_objects.len());
+
+    for curr in hit_objects {
+        let h = DifficultyObject::new(
+            &curr,
+            &mut prev,
+            prev_prev.as_ref(),
+            &scaling_factor,
+            clock_rate,
+        );
+
+        movements.push(OsuMovement {
+            start_time: curr.time / clock_rate,
+            distance: h.jump_dist,
+            delta_time: h.delta,
+            strain_time: h.strain_time,
+            angle: h.angle,
+            travel_distance: h.travel_dist,
+            travel_time: h.travel_time,
+        });
+
+        prev_prev = Some(mem::replace(&mut prev, curr));
+    }
+
+    movements
+}
+
+/// The movement towards an osu!standard hit object, see [`movements`].
+///
+/// Movements involving a spinner have neither distances nor an angle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OsuMovement {
+    /// The start time of the object that is moved to.
+    pub start_time: f64,
+    /// The normalized distance from the cursor position at the end of
+    /// the previous object to the start of this object.
+    pub distance: f64,
+    /// The time since the start of the previous object.
+    pub delta_time: f64,
+    /// The time since the start of the previous object,
+    /// capped below at 25ms as used by the skills.
+    pub strain_time: f64,
+    /// The angle in radians between the previous movement and this one,
+    /// ranging from 0 to π.
+    ///
+    /// `None` for the first movement and movements around spinners.
+    pub angle: Option<f64>,
+    /// The normalized distance the cursor traveled while following
+    /// the previous object if it is a slider.
+    pub travel_distance: f64,
+    /// The time the cursor traveled while following the previous object if it is a slider.
+    pub travel_time: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_map() {
+        assert!(movements(&Beatmap::default(), 0).is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn movements_of_map() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        let nomod = movements(&map, 0);
+        assert_eq!(nomod.len(), map.hit_objects.len() - 1);
+        assert!(nomod[0].angle.is_none());
+        assert!(nomod.iter().skip(1).any(|m| m.angle.is_some()));
+        assert!(nomod
+            .iter()
+            .filter_map(|m| m.angle)
+            .all(|angle| (0.0..=std::f64::consts::PI).contains(&angle)));
+
+        let dt = movements(&map, 64);
+
+        for (nomod, dt) in nomod.iter().zip(dt.iter()) {
+            assert!((nomod.delta_time / 1.5 - dt.delta_time).abs() < 1e-9);
+            assert_eq!(nomod.distance, dt.distance);
+        }
+
+        // HR mirrors the positions vertically and shrinks circles,
+        // which increases normalized distances
+        let hr = movements(&map, 16);
+        let total = |movements: &[OsuMovement]| movements.iter().map(|m| m.distance).sum::<f64>();
+        assert!(total(&hr) > total(&nomod));
+    }
+}
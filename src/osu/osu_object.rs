@@ -101,8 +101,7 @@ impl OsuObject {
                 // * prior to v8, speed multipliers don't adjust for how many ticks are generated over the same distance.
                 // * this results in more (or less) ticks being generated in <v8 maps for the same time duration.
                 if map.version >= 8 {
-                    tick_dist /=
-                        (100.0 / slider_state.slider_velocity).max(10.0).min(1000.0) / 100.0;
+                    tick_dist /= (100.0 / slider_state.slider_velocity).clamp(10.0, 1000.0) / 100.0;
                 }
 
                 // Build the curve w.r.t. the control points
@@ -302,6 +301,49 @@ impl OsuObject {
         Some(obj)
     }
 
+    /// Mirror the object vertically, just like HR does.
+    pub(crate) fn flip_y(&mut self) {
+        let flip = |pos: &mut Pos2| pos.y = 384.0 - pos.y;
+
+        flip(&mut self.pos);
+
+        if let OsuObjectKind::Slider {
+            end_pos,
+            lazy_end_pos,
+            nested_objects,
+            ..
+        } = &mut self.kind
+        {
+            flip(end_pos);
+            flip(lazy_end_pos);
+
+            for nested in nested_objects.iter_mut() {
+                flip(&mut nested.pos);
+            }
+        }
+    }
+
+    /// Adjust the object's time as well as the times of its nested objects.
+    pub(crate) fn map_times(&mut self, f: impl Fn(f64) -> f64) {
+        self.time = f(self.time);
+
+        match &mut self.kind {
+            OsuObjectKind::Circle => {}
+            OsuObjectKind::Slider {
+                end_time,
+                nested_objects,
+                ..
+            } => {
+                *end_time = f(*end_time);
+
+                for nested in nested_objects.iter_mut() {
+                    nested.time = f(nested.time);
+                }
+            }
+            OsuObjectKind::Spinner { end_time } => *end_time = f(*end_time),
+        }
+    }
+
     #[inline]
     pub(crate) fn end_time(&self) -> f64 {
         match &self.kind {
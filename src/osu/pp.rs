@@ -1,12 +1,15 @@
 use super::{OsuDifficultyAttributes, OsuPerformanceAttributes, OsuScoreState};
-use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
+use crate::{
+    timings::{Phase, Timer},
+    Beatmap, DifficultyAttributes, InputError, LazerMods, Mods, PerformanceAttributes,
+};
 
 /// Performance calculator on osu!standard maps.
 ///
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{OsuPP, Beatmap};
+/// use akatsuki_pp::{OsuPP, Beatmap};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -33,9 +36,9 @@ use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
 #[derive(Clone, Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct OsuPP<'map> {
-    map: &'map Beatmap,
+    pub(crate) map: &'map Beatmap,
     attributes: Option<OsuDifficultyAttributes>,
-    mods: u32,
+    mods: LazerMods,
     acc: Option<f64>,
     pub(crate) combo: Option<usize>,
 
@@ -44,6 +47,7 @@ pub struct OsuPP<'map> {
     pub(crate) n50: Option<usize>,
     pub(crate) n_misses: usize,
     pub(crate) passed_objects: Option<usize>,
+    strict: bool,
 }
 
 impl<'map> OsuPP<'map> {
@@ -53,7 +57,7 @@ impl<'map> OsuPP<'map> {
         Self {
             map,
             attributes: None,
-            mods: 0,
+            mods: LazerMods::default(),
             acc: None,
             combo: None,
 
@@ -62,6 +66,7 @@ impl<'map> OsuPP<'map> {
             n50: None,
             n_misses: 0,
             passed_objects: None,
+            strict: false,
         }
     }
 
@@ -82,6 +87,16 @@ impl<'map> OsuPP<'map> {
     /// See [https://github.com/ppy/osu-api/wiki#mods](https://github.com/ppy/osu-api/wiki#mods)
     #[inline]
     pub fn mods(mut self, mods: u32) -> Self {
+        self.mods = LazerMods::from(mods);
+
+        self
+    }
+
+    /// Specify the mods of an osu!lazer score, including lazer-only mods.
+    ///
+    /// Traceable is rewarded just like HD. Blinds receives the same bonuses as in osu!lazer.
+    #[inline]
+    pub fn lazer_mods(mut self, mods: LazerMods) -> Self {
         self.mods = mods;
 
         self
@@ -139,6 +154,21 @@ impl<'map> OsuPP<'map> {
         self
     }
 
+    /// Enable or disable strict mode.
+    ///
+    /// In strict mode, input that can not stem from an actual play, e.g. incompatible mods
+    /// like DT and HT or more hitresults than objects, is reported as [`InputError`]
+    /// by [`try_calculate`](OsuPP::try_calculate) and causes [`calculate`](OsuPP::calculate)
+    /// to panic instead of silently producing meaningless values.
+    ///
+    /// Disabled by default.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+
+        self
+    }
+
     /// Provide parameters through an [`OsuScoreState`].
     #[inline]
     pub fn state(mut self, state: OsuScoreState) -> Self {
@@ -163,10 +193,9 @@ impl<'map> OsuPP<'map> {
     ///
     /// Be sure to set `misses` beforehand!
     /// In case of a partial play, be also sure to set `passed_objects` beforehand!
+    /// With SO, spinners are always considered as n300s.
     pub fn accuracy(mut self, acc: f64) -> Self {
-        let n_objects = self
-            .passed_objects
-            .unwrap_or_else(|| self.map.hit_objects.len());
+        let n_objects = self.passed_objects.unwrap_or(self.map.hit_objects.len());
 
         let mut acc = acc / 100.0;
 
@@ -198,13 +227,28 @@ impl<'map> OsuPP<'map> {
 
             acc = (6 * n300 + 2 * n100 + n50) as f64 / (6 * n_objects) as f64;
         } else {
-            let misses = self.n_misses.min(n_objects);
-            let target_total = (acc * n_objects as f64 * 6.0).round() as usize;
-            let delta = target_total - (n_objects - misses);
+            // With SO, spinners are completed automatically and always count as n300s
+            let n_spun_out = if self.mods.so() {
+                self.map
+                    .hit_objects
+                    .iter()
+                    .take(n_objects)
+                    .filter(|h| h.is_spinner())
+                    .count()
+            } else {
+                0
+            };
 
-            let mut n300 = delta / 5;
-            let mut n100 = (delta % 5).min(n_objects - n300 - misses);
-            let mut n50 = n_objects - n300 - n100 - misses;
+            let n_judged = n_objects - n_spun_out;
+            let misses = self.n_misses.min(n_judged);
+            let target_total = ((acc * n_objects as f64 * 6.0).round() as usize)
+                .saturating_sub(6 * n_spun_out)
+                .max(n_judged - misses);
+            let delta = target_total - (n_judged - misses);
+
+            let mut n300 = (delta / 5).min(n_judged - misses);
+            let mut n100 = (delta % 5).min(n_judged - n300 - misses);
+            let mut n50 = n_judged - n300 - n100 - misses;
 
             // Sacrifice n300s to transform n50s into n100s
             let n = n300.min(n50 / 4);
@@ -212,6 +256,8 @@ impl<'map> OsuPP<'map> {
             n100 += 5 * n;
             n50 -= 4 * n;
 
+            n300 += n_spun_out;
+
             self.n300 = Some(n300);
             self.n100 = Some(n100);
             self.n50 = Some(n50);
@@ -229,9 +275,7 @@ impl<'map> OsuPP<'map> {
         let mut n100 = self.n100;
         let mut n50 = self.n50;
 
-        let n_objects = self
-            .passed_objects
-            .unwrap_or_else(|| self.map.hit_objects.len());
+        let n_objects = self.passed_objects.unwrap_or(self.map.hit_objects.len());
 
         if let Some(acc) = self.acc {
             let n300 = n300.unwrap_or(0);
@@ -255,9 +299,7 @@ impl<'map> OsuPP<'map> {
                 effective_misses,
             }
         } else {
-            let n_objects = self
-                .passed_objects
-                .unwrap_or_else(|| self.map.hit_objects.len());
+            let n_objects = self.passed_objects.unwrap_or(self.map.hit_objects.len());
 
             let remaining = n_objects
                 .saturating_sub(n300.unwrap_or(0))
@@ -310,20 +352,99 @@ impl<'map> OsuPP<'map> {
         }
     }
 
+    /// Calculate all performance related values just like [`calculate`](OsuPP::calculate)
+    /// while also measuring how much time each calculation phase took.
+    #[cfg(feature = "timings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+    pub fn calculate_with_timings(self) -> (OsuPerformanceAttributes, crate::CalcTimings) {
+        crate::timings::measure(|| self.calculate())
+    }
+
+    /// Calculate the performance of each given score state on the same map.
+    ///
+    /// The difficulty attributes are calculated only once and shared across all
+    /// score states instead of paying for them on each calculation.
+    /// All other parameters, e.g. mods or passed objects, apply to every score state.
+    pub fn calculate_many(mut self, states: &[OsuScoreState]) -> Vec<OsuPerformanceAttributes> {
+        let attributes = self
+            .attributes
+            .take()
+            .unwrap_or_else(|| super::stars(self.map, self.mods, self.passed_objects));
+        self.attributes = Some(attributes);
+
+        states
+            .iter()
+            .map(|state| self.clone().state(state.clone()).calculate())
+            .collect()
+    }
+
     /// Calculate all performance related values, including pp and stars.
-    pub fn calculate(mut self) -> OsuPerformanceAttributes {
+    ///
+    /// # Panics
+    ///
+    /// Panics in [strict](OsuPP::strict) mode if the input is invalid.
+    #[track_caller]
+    pub fn calculate(self) -> OsuPerformanceAttributes {
+        match self.try_calculate() {
+            Ok(performance) => performance,
+            Err(err) => panic!("invalid osu!standard input: {}", err),
+        }
+    }
+
+    /// Calculate all performance related values just like [`calculate`](OsuPP::calculate)
+    /// but in [strict](OsuPP::strict) mode, return an error if the input is invalid.
+    ///
+    /// Outside of strict mode, this never fails.
+    pub fn try_calculate(mut self) -> Result<OsuPerformanceAttributes, InputError> {
+        trace_span!(
+            "performance",
+            mode = "osu",
+            map = format_args!("{:032x}", crate::CalcKey::fingerprint(self.map)),
+            mods = self.mods.legacy,
+        );
+
+        // Incompatible mods are reported before spending time on the difficulty
+        if self.strict {
+            InputError::check_mods(self.mods.legacy)?;
+        }
+
         let attributes = self
             .attributes
             .take()
             .unwrap_or_else(|| super::stars(self.map, self.mods, self.passed_objects));
 
-        self.assert_hitresults(attributes).calculate()
+        if self.strict {
+            self.validate(&attributes)?;
+        }
+
+        // Maps with fewer than two objects have no difficulty to reward
+        if !self.map.is_calculable_until(self.passed_objects) {
+            return Ok(OsuPerformanceAttributes {
+                difficulty: attributes,
+                ..Default::default()
+            });
+        }
+
+        let timer = Timer::start();
+        let performance = self.assert_hitresults(attributes).calculate();
+        timer.finish(Phase::Evaluation);
+
+        Ok(performance)
+    }
+
+    fn validate(&self, attributes: &OsuDifficultyAttributes) -> Result<(), InputError> {
+        let n_objects = attributes.n_circles + attributes.n_sliders + attributes.n_spinners;
+        let given =
+            self.n300.unwrap_or(0) + self.n100.unwrap_or(0) + self.n50.unwrap_or(0) + self.n_misses;
+
+        InputError::check_hitresults(given, n_objects)?;
+        InputError::check_combo(self.combo, attributes.max_combo)
     }
 }
 
 struct OsuPPInner {
     attributes: OsuDifficultyAttributes,
-    mods: u32,
+    mods: LazerMods,
     acc: f64,
     combo: Option<usize>,
 
@@ -359,7 +480,7 @@ impl OsuPPInner {
             let speed_crosscheck: f64 = aim_value / speed_value;
 
             if speed_crosscheck < 1.0 {
-                speed_value *= f64::max(0.1, f64::min(0.95, 0.45 * speed_crosscheck));
+                speed_value *= (0.45 * speed_crosscheck).clamp(0.1, 0.95);
             }
         }
 
@@ -434,8 +555,12 @@ impl OsuPPInner {
             aim_value *= 1.03 + (diff / 20.0);
         }
 
-        // HD bonus (this would include the Blinds mod but it's currently not representable)
-        if self.mods.hd() {
+        // BL and HD bonus, TC is treated like HD
+        if self.mods.bl() {
+            aim_value *= 1.3
+                + (self.total_hits * (0.0016 / (1.0 + 2.0 * effective_misses)) * self.acc.powi(16))
+                    * (1.0 - 0.003 * attributes.hp * attributes.hp);
+        } else if self.mods.hd() || self.mods.tc() {
             if self.mods.rx() {
                 aim_value *= 1.0 + 0.05 * (11.5 - attributes.ar);
             } else {
@@ -505,8 +630,10 @@ impl OsuPPInner {
 
         speed_value *= 1.0 + ar_factor * len_bonus; // * Buff for longer maps with high AR.
 
-        // HD bonus (this would include the Blinds mod but it's currently not representable)
-        if self.mods.hd() {
+        // BL and HD bonus, TC is treated like HD
+        if self.mods.bl() {
+            speed_value *= 1.12;
+        } else if self.mods.hd() || self.mods.tc() {
             if self.mods.rx() {
                 speed_value *= 1.0 + 0.05 * (11.5 - attributes.ar);
             } else {
@@ -543,10 +670,12 @@ impl OsuPPInner {
         let mut acc_value = 1.52163_f64.powf(attributes.od) * better_acc_percentage.powi(24) * 2.83;
 
         // Bonus for many hitcircles
-        acc_value *= ((n_circles as f64 / 1000.0).powf(0.3)).min(1.15);
+        acc_value *= ((n_circles / 1000.0).powf(0.3)).min(1.15);
 
-        // HD bonus (this would include the Blinds mod but it's currently not representable)
-        if self.mods.hd() {
+        // BL and HD bonus, TC is treated like HD
+        if self.mods.bl() {
+            acc_value *= 1.14;
+        } else if self.mods.hd() || self.mods.tc() {
             acc_value *= 1.08;
         }
 
@@ -790,3 +919,87 @@ mod test {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn spun_out() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let n_objects = map.hit_objects.len();
+
+        let regular = OsuPP::new(&map).accuracy(95.0).calculate();
+        let spun_out = OsuPP::new(&map).mods(4096).accuracy(95.0).calculate();
+
+        assert_eq!(regular.difficulty.n_spinners, 1);
+        assert!(spun_out.pp < regular.pp);
+
+        let calc = OsuPP::new(&map).mods(4096).accuracy(95.0);
+        let (n300, n100, n50) = (calc.n300.unwrap(), calc.n100.unwrap(), calc.n50.unwrap());
+        assert_eq!(n300 + n100 + n50, n_objects);
+        assert!((calc.acc.unwrap() - 0.95).abs() < 0.01);
+
+        // Spinners can not be missed with SO
+        let calc = OsuPP::new(&map).mods(4096).misses(n_objects).accuracy(0.0);
+        assert_eq!(calc.n300, Some(1));
+        assert_eq!(calc.n100, Some(0));
+        assert_eq!(calc.n50, Some(0));
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn lazer_visual_mods() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        let calculate = |acronyms: &[&str]| {
+            let mods = LazerMods::from_acronyms(acronyms).unwrap();
+
+            OsuPP::new(&map)
+                .lazer_mods(mods)
+                .accuracy(98.0)
+                .calculate()
+                .pp
+        };
+
+        let nomod = calculate(&[]);
+        let hidden = OsuPP::new(&map).mods(8).accuracy(98.0).calculate().pp;
+
+        assert_eq!(calculate(&["HD"]), hidden);
+        assert_eq!(calculate(&["TC"]), hidden);
+        assert_eq!(calculate(&["CL", "MU"]), nomod);
+        assert!(calculate(&["BL"]) > nomod);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn lazer_difficulty_adjust() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        let calculate = |legacy: u32, overall_difficulty: Option<f32>| {
+            let mods = LazerMods {
+                overall_difficulty,
+                ..LazerMods::from(legacy)
+            };
+
+            OsuPP::new(&map).lazer_mods(mods).accuracy(98.0).calculate()
+        };
+
+        let nomod = calculate(0, None);
+        let same_od = calculate(0, Some(map.od));
+        assert_eq!(same_od.pp, nomod.pp);
+
+        let harder = calculate(0, Some(10.0));
+        assert!((harder.difficulty.od - 10.0).abs() < 1e-6);
+        assert!(harder.pp_acc > nomod.pp_acc);
+
+        // The OD setting still applies to the hit windows of DT
+        let dt = calculate(64, Some(10.0));
+        let expected = crate::util::od_from_osu_great_hit_window(
+            crate::util::osu_great_hit_window(10.0) / 1.5,
+        );
+        assert!((dt.difficulty.od - expected).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,174 @@
+use super::{
+    evaluate_skills,
+    osu_object::{ObjectParameters, OsuObjectKind},
+    process_objects,
+    slider_state::SliderState,
+    OsuDifficultyAttributes, OsuGradualDifficultyAttributes, OsuObject,
+};
+
+use crate::{
+    curve::CurveBuffers,
+    research::OsuParameters,
+    timings::{Phase, Timer},
+    Beatmap, Mods, OsuPP,
+};
+
+/// An osu!standard map whose hit objects, including their slider
+/// curves and nested objects, have been prepared once.
+///
+/// Preparing hit objects, in particular flattening slider curves, takes up
+/// a large share of the difficulty calculation but does not depend on mods.
+/// When calculating the difficulty of the same map for multiple mods or clock rates,
+/// reusing an [`OsuPreprocessedMap`] avoids repeating that work each time.
+///
+/// ```
+/// use akatsuki_pp::{osu::OsuPreprocessedMap, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// let preprocessed = OsuPreprocessedMap::new(&map);
+///
+/// for mods in [0, 8, 16, 64, 16 + 64] {
+///     let stars = preprocessed.stars(mods, None).stars;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct OsuPreprocessedMap<'map> {
+    map: &'map Beatmap,
+    hit_objects: Vec<OsuObject>,
+}
+
+impl<'map> OsuPreprocessedMap<'map> {
+    /// Prepare the hit objects of the map.
+    pub fn new(map: &'map Beatmap) -> Self {
+        let mut attributes = OsuDifficultyAttributes::default();
+
+        let mut params = ObjectParameters {
+            map,
+            attributes: &mut attributes,
+            slider_state: SliderState::new(map),
+            ticks: Vec::new(),
+            curve_bufs: CurveBuffers::default(),
+        };
+
+        let timer = Timer::start();
+
+        let hit_objects = map
+            .hit_objects
+            .iter()
+            .filter_map(|h| OsuObject::new(h, false, &mut params))
+            .collect();
+
+        timer.finish(Phase::Preprocessing);
+
+        Self { map, hit_objects }
+    }
+
+    /// The map that was prepared.
+    #[inline]
+    pub fn map(&self) -> &'map Beatmap {
+        self.map
+    }
+
+    /// Calculate the difficulty attributes for the given mods.
+    ///
+    /// The result is the same as the one of [`stars`](super::stars).
+    pub fn stars(&self, mods: impl Mods, passed_objects: Option<usize>) -> OsuDifficultyAttributes {
+        let timer = Timer::start();
+        let mut attributes = OsuDifficultyAttributes::default();
+        let take = passed_objects.unwrap_or(self.hit_objects.len());
+
+        for h in self.hit_objects.iter().take(take) {
+            attributes.max_combo += 1;
+
+            match &h.kind {
+                OsuObjectKind::Circle => attributes.n_circles += 1,
+                OsuObjectKind::Slider { nested_objects, .. } => {
+                    attributes.n_sliders += 1;
+                    attributes.max_combo += nested_objects.len();
+                }
+                OsuObjectKind::Spinner { .. } => attributes.n_spinners += 1,
+            }
+        }
+
+        let hit_objects = self.hit_objects(mods, take);
+        timer.finish(Phase::Preprocessing);
+
+        let (skills, attributes) = process_objects(
+            self.map,
+            mods,
+            hit_objects,
+            attributes,
+            true,
+            &OsuParameters::DEFAULT,
+        );
+
+        evaluate_skills(self.map, skills, attributes)
+    }
+
+    /// Return a performance calculator for the given mods
+    /// whose difficulty attributes are calculated on the prepared hit objects.
+    ///
+    /// The attributes are those of the full map so for partial plays
+    /// use [`stars`](OsuPreprocessedMap::stars) with the amount of passed objects instead.
+    #[inline]
+    pub fn pp(&self, mods: u32) -> OsuPP<'map> {
+        OsuPP::new(self.map)
+            .mods(mods)
+            .attributes(self.stars(mods, None))
+    }
+
+    /// Return an iterator that gives the difficulty attributes after each hit object,
+    /// see [`OsuGradualDifficultyAttributes`].
+    #[inline]
+    pub fn gradual_difficulty(&self, mods: impl Mods) -> OsuGradualDifficultyAttributes {
+        let hit_objects = self.hit_objects(mods, self.hit_objects.len());
+
+        OsuGradualDifficultyAttributes::with_objects(self.map, mods, hit_objects)
+    }
+
+    fn hit_objects(&self, mods: impl Mods, take: usize) -> Vec<OsuObject> {
+        let hr = mods.hr();
+
+        self.hit_objects
+            .iter()
+            .take(take)
+            .map(|h| {
+                let mut h = h.clone();
+
+                if hr {
+                    h.flip_y();
+                }
+
+                h
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn preprocessed_matches_regular() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let preprocessed = OsuPreprocessedMap::new(&map);
+
+        for mods in [0, 2, 16, 64, 256, 1024, 16 + 64 + 1024] {
+            for passed_objects in [None, Some(1), Some(50), Some(100)] {
+                let expected = super::super::stars(&map, mods, passed_objects);
+                let actual = preprocessed.stars(mods, passed_objects);
+
+                assert_eq!(actual, expected, "+{} {:?}", mods, passed_objects);
+            }
+        }
+
+        let expected = OsuGradualDifficultyAttributes::new(&map, 16 + 64).last();
+        assert_eq!(preprocessed.gradual_difficulty(16 + 64).last(), expected);
+
+        let expected = OsuPP::new(&map).mods(16).calculate();
+        assert_eq!(preprocessed.pp(16).calculate(), expected);
+    }
+}
@@ -1,4 +1,9 @@
 use super::{lerp, skill_kind::calculate_speed_rhythm_bonus, DifficultyObject, SkillKind};
+use crate::{
+    research::{OsuParameters, SkillParameters},
+    timings::{Phase, Timer},
+    StrainValue,
+};
 
 use std::{cmp::Ordering, fmt};
 
@@ -13,21 +18,37 @@ pub(crate) struct Skills {
 impl Skills {
     const RX: u8 = 1 << 0;
     const FL: u8 = 1 << 1;
+    const NO_SLIDERS: u8 = 1 << 2;
+
+    /// If `no_sliders` is `false`, the aim skill without sliders is
+    /// omitted and [`Skills::aim_no_sliders`] must not be called.
+    pub(crate) fn new(
+        hit_window: f64,
+        rx: bool,
+        radius: f32,
+        fl: bool,
+        no_sliders: bool,
+        params: &OsuParameters,
+    ) -> Self {
+        let mut skills = Vec::with_capacity(1 + no_sliders as usize + !rx as usize + fl as usize);
+
+        skills.push(Skill::aim(true, params));
+
+        if no_sliders {
+            skills.push(Skill::aim(false, params));
+        }
 
-    pub(crate) fn new(hit_window: f64, rx: bool, radius: f32, fl: bool) -> Self {
-        let mut skills = Vec::with_capacity(2 + !rx as usize + fl as usize);
-
-        skills.push(Skill::aim(true));
-        skills.push(Skill::aim(false));
-        skills.push(Skill::speed(hit_window));
+        if !rx {
+            skills.push(Skill::speed(hit_window, params));
+        }
 
         if fl {
             // NOTE: Instead of having `NORMALIZED_RADIUS` as dividend, it still uses 52.0.
             let scaling_factor = 52.0 / radius as f64;
-            skills.push(Skill::flashlight(scaling_factor));
+            skills.push(Skill::flashlight(scaling_factor, params));
         }
 
-        let mask = rx as u8 * Self::RX + fl as u8 * Self::FL;
+        let mask = rx as u8 * Self::RX + fl as u8 * Self::FL + no_sliders as u8 * Self::NO_SLIDERS;
         let skills = skills.into_boxed_slice();
 
         Self { skills, mask }
@@ -54,7 +75,9 @@ impl Skills {
 
     pub(crate) fn process(&mut self, h: &DifficultyObject<'_>) {
         for skill in self.skills.iter_mut() {
+            let timer = Timer::start();
             skill.process(h);
+            timer.finish(Phase::Skill(skill.kind.name()));
         }
     }
 
@@ -63,23 +86,39 @@ impl Skills {
     }
 
     pub(crate) fn aim_no_sliders(&mut self) -> &mut Skill {
+        debug_assert!(
+            self.mask & Self::NO_SLIDERS > 0,
+            "missing aim without sliders"
+        );
+
         &mut self.skills[1]
     }
 
+    /// The skills whose peaks make up the strains of a map, i.e. all but aim without sliders.
+    pub(crate) fn strain_skills(&self) -> impl Iterator<Item = &Skill> {
+        let no_sliders = (self.mask & Self::NO_SLIDERS > 0) as usize;
+
+        self.skills[..1]
+            .iter()
+            .chain(&self.skills[1 + no_sliders..])
+    }
+
     pub(crate) fn speed_flashlight(&mut self) -> (Option<&mut Skill>, Option<&mut Skill>) {
+        let idx = 1 + (self.mask & Self::NO_SLIDERS > 0) as usize;
+
         match (self.mask & Self::RX, self.mask & Self::FL) {
             // only speed
-            (0, 0) => (Some(&mut self.skills[2]), None),
+            (0, 0) => (Some(&mut self.skills[idx]), None),
             // both speed and flashlight
             (0, _) => {
-                let (left, right) = self.skills.split_at_mut(3);
+                let (left, right) = self.skills.split_at_mut(idx + 1);
 
-                (Some(&mut left[2]), Some(&mut right[0]))
+                (Some(&mut left[idx]), Some(&mut right[0]))
             }
             // neither
             (_, 0) => (None, None),
             // only flashlight
-            (_, _) => (None, Some(&mut self.skills[2])),
+            (_, _) => (None, Some(&mut self.skills[idx])),
         }
     }
 }
@@ -90,36 +129,38 @@ pub(crate) struct Skill {
     pub(crate) curr_section_peak: f64,
 
     kind: SkillKind,
-    pub(crate) strain_peaks: Vec<f64>,
+    params: SkillParameters,
+    pub(crate) strain_peaks: Vec<StrainValue>,
 
     prev_time: Option<f64>,
 
-    pub(crate) object_strains: Vec<f64>,
+    pub(crate) object_strains: Vec<StrainValue>,
 }
 
 impl Skill {
     #[inline]
-    pub(crate) fn aim(with_sliders: bool) -> Self {
-        Self::new(SkillKind::aim(with_sliders))
+    pub(crate) fn aim(with_sliders: bool, params: &OsuParameters) -> Self {
+        Self::new(SkillKind::aim(with_sliders), params.aim)
     }
 
     #[inline]
-    pub(crate) fn flashlight(scaling_factor: f64) -> Self {
-        Self::new(SkillKind::flashlight(scaling_factor))
+    pub(crate) fn flashlight(scaling_factor: f64, params: &OsuParameters) -> Self {
+        Self::new(SkillKind::flashlight(scaling_factor), params.flashlight)
     }
 
     #[inline]
-    pub(crate) fn speed(hit_window: f64) -> Self {
-        Self::new(SkillKind::speed(hit_window))
+    pub(crate) fn speed(hit_window: f64, params: &OsuParameters) -> Self {
+        Self::new(SkillKind::speed(hit_window), params.speed)
     }
 
     #[inline]
-    fn new(kind: SkillKind) -> Self {
+    fn new(kind: SkillKind, params: SkillParameters) -> Self {
         Self {
             curr_strain: 0.0,
             curr_section_peak: 0.0,
 
             kind,
+            params,
             strain_peaks: Vec::with_capacity(128),
 
             prev_time: None,
@@ -137,7 +178,8 @@ impl Skill {
 
     #[inline]
     pub(crate) fn save_current_peak(&mut self) {
-        self.strain_peaks.push(self.curr_section_peak);
+        self.strain_peaks
+            .push(self.curr_section_peak as StrainValue);
     }
 
     #[inline]
@@ -156,9 +198,10 @@ impl Skill {
 
         let mut difficulty = 0.0;
         let mut weight = 1.0;
-        let decay_weight = this.kind.decay_weight();
+        let decay_weight = this.params.decay_weight;
+        let difficulty_multiplier = this.params.difficulty_multiplier;
 
-        let (reduced_section_count, difficulty_multiplier) = this.kind.difficulty_values();
+        let reduced_section_count = this.kind.reduced_section_count();
         let reduced_section_count_f64 = reduced_section_count as f64;
 
         strain_peaks.sort_unstable_by(|a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
@@ -183,7 +226,7 @@ impl Skill {
 
     pub(crate) fn calculate_initial_strain(&self, time: f64) -> f64 {
         let prev_time = self.prev_time.unwrap_or(0.0);
-        let decayed_strain = self.curr_strain * self.kind.strain_decay(time - prev_time);
+        let decayed_strain = self.curr_strain * self.strain_decay(time - prev_time);
 
         match &self.kind {
             SkillKind::Aim { .. } | SkillKind::Flashlight { .. } => decayed_strain,
@@ -192,12 +235,12 @@ impl Skill {
     }
 
     pub(crate) fn strain_value_at(&mut self, curr: &DifficultyObject<'_>) -> f64 {
-        self.curr_strain *= self.kind.strain_decay(curr.delta);
-        self.curr_strain += self.kind.strain_value_of(curr) * self.kind.skill_multiplier();
+        self.curr_strain *= self.strain_decay(curr.delta);
+        self.curr_strain += self.kind.strain_value_of(curr) * self.params.skill_multiplier;
 
         match &mut self.kind {
             SkillKind::Aim { .. } | SkillKind::Flashlight { .. } => {
-                self.object_strains.push(self.curr_strain);
+                self.object_strains.push(self.curr_strain as StrainValue);
 
                 self.curr_strain
             }
@@ -209,7 +252,7 @@ impl Skill {
                 *curr_rhythm = calculate_speed_rhythm_bonus(curr, history, *hit_window);
 
                 let total_strain = self.curr_strain * *curr_rhythm;
-                self.object_strains.push(total_strain);
+                self.object_strains.push(total_strain as StrainValue);
 
                 total_strain
             }
@@ -217,20 +260,30 @@ impl Skill {
     }
 
     pub(crate) fn count_difficult_strains(&mut self, clock_rate: f64) -> f64 {
-        let top_strain = self
+        let top_strain = match self
             .object_strains
-            .clone()
-            .into_iter()
+            .iter()
+            .copied()
+            .map(crate::widen_strain)
             .reduce(f64::max)
-            .unwrap();
+        {
+            Some(top_strain) if top_strain > 0.0 => top_strain,
+            _ => return 0.0,
+        };
+
         let realtime_count: f64 = self
             .object_strains
             .iter()
-            .map(|&x| f64::powf(x / top_strain, 4.0))
+            .map(|&x| f64::powf(crate::widen_strain(x) / top_strain, 4.0))
             .sum();
 
         clock_rate * realtime_count
     }
+
+    #[inline]
+    fn strain_decay(&self, ms: f64) -> f64 {
+        self.params.strain_decay_base.powf(ms / 1000.0)
+    }
 }
 
 impl fmt::Debug for Skill {
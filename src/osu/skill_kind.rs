@@ -4,7 +4,7 @@ use std::{
     fmt, iter,
 };
 
-use crate::parse::Pos2;
+use crate::{parse::Pos2, research::SkillParameters};
 
 use super::{lerp, DifficultyObject};
 
@@ -12,10 +12,12 @@ const SINGLE_SPACING_TRESHOLD: f64 = 125.0;
 
 const SPEED_BALANCING_FACTOR: f64 = 40.0;
 
-const AIM_SKILL_MULTIPLIER: f64 = 23.25;
-const AIM_STRAIN_DECAY_BASE: f64 = 0.15;
-const AIM_DECAY_WEIGHT: f64 = 0.9;
-const AIM_DIFFICULTY_MULTIPLIER: f64 = 1.06;
+pub(crate) const AIM_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 23.25,
+    strain_decay_base: 0.15,
+    decay_weight: 0.9,
+    difficulty_multiplier: 1.06,
+};
 const AIM_REDUCED_SECTION_COUNT: usize = 10;
 
 const AIM_HISTORY_LENGTH: usize = 2;
@@ -24,10 +26,12 @@ const AIM_ACUTE_ANGLE_MULTIPLIER: f64 = 2.0;
 const AIM_SLIDER_MULTIPLIER: f64 = 1.5;
 const AIM_VELOCITY_CHANGE_MULTIPLIER: f64 = 0.75;
 
-const SPEED_SKILL_MULTIPLIER: f64 = 1375.0;
-const SPEED_STRAIN_DECAY_BASE: f64 = 0.3;
-const SPEED_DECAY_WEIGHT: f64 = 0.9;
-const SPEED_DIFFICULTY_MULTIPLIER: f64 = 1.04;
+pub(crate) const SPEED_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 1375.0,
+    strain_decay_base: 0.3,
+    decay_weight: 0.9,
+    difficulty_multiplier: 1.04,
+};
 const SPEED_REDUCED_SECTION_COUNT: usize = 5;
 
 const SPEED_HISTORY_LENGTH: usize = 32;
@@ -35,10 +39,12 @@ const SPEED_RHYTHM_MULTIPLIER: f64 = 0.75;
 const SPEED_HISTORY_TIME_MAX: f64 = 5000.0; // * 5 seconds of calculate_speed_rhythm_bonus max
 const MIN_SPEED_BONUS: f64 = 75.0; // * ~200BPM
 
-const FLASHLIGHT_SKILL_MULTIPLIER: f64 = 0.15;
-const FLASHLIGHT_STRAIN_DECAY_BASE: f64 = 0.15;
-const FLASHLIGHT_DECAY_WEIGHT: f64 = 1.0;
-const FLASHLIGHT_DIFFICULTY_MULTIPLIER: f64 = 1.06;
+pub(crate) const FLASHLIGHT_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 0.15,
+    strain_decay_base: 0.15,
+    decay_weight: 1.0,
+    difficulty_multiplier: 1.06,
+};
 const FLASHLIGHT_REDUCED_SECTION_COUNT: usize = 10;
 
 const FLASHLIGHT_HISTORY_LENGTH: usize = 10;
@@ -126,6 +132,18 @@ pub(crate) enum SkillKind {
 }
 
 impl SkillKind {
+    #[cfg_attr(not(feature = "timings"), allow(dead_code))]
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Aim {
+                with_sliders: true, ..
+            } => "aim",
+            Self::Aim { .. } => "aim (no sliders)",
+            Self::Flashlight { .. } => "flashlight",
+            Self::Speed { .. } => "speed",
+        }
+    }
+
     pub(crate) fn aim(with_sliders: bool) -> Self {
         Self::Aim {
             history: VecDeque::with_capacity(AIM_HISTORY_LENGTH + 1),
@@ -408,48 +426,13 @@ impl SkillKind {
     }
 
     #[inline]
-    pub(crate) fn difficulty_values(&self) -> (usize, f64) {
-        match self {
-            Self::Aim { .. } => (AIM_REDUCED_SECTION_COUNT, AIM_DIFFICULTY_MULTIPLIER),
-            Self::Flashlight { .. } => (
-                FLASHLIGHT_REDUCED_SECTION_COUNT,
-                FLASHLIGHT_DIFFICULTY_MULTIPLIER,
-            ),
-            Self::Speed { .. } => (SPEED_REDUCED_SECTION_COUNT, SPEED_DIFFICULTY_MULTIPLIER),
-        }
-    }
-
-    #[inline]
-    pub(crate) fn skill_multiplier(&self) -> f64 {
+    pub(crate) fn reduced_section_count(&self) -> usize {
         match self {
-            SkillKind::Aim { .. } => AIM_SKILL_MULTIPLIER,
-            SkillKind::Flashlight { .. } => FLASHLIGHT_SKILL_MULTIPLIER,
-            SkillKind::Speed { .. } => SPEED_SKILL_MULTIPLIER,
+            Self::Aim { .. } => AIM_REDUCED_SECTION_COUNT,
+            Self::Flashlight { .. } => FLASHLIGHT_REDUCED_SECTION_COUNT,
+            Self::Speed { .. } => SPEED_REDUCED_SECTION_COUNT,
         }
     }
-
-    #[inline]
-    pub(crate) fn strain_decay_base(&self) -> f64 {
-        match self {
-            SkillKind::Aim { .. } => AIM_STRAIN_DECAY_BASE,
-            SkillKind::Flashlight { .. } => FLASHLIGHT_STRAIN_DECAY_BASE,
-            SkillKind::Speed { .. } => SPEED_STRAIN_DECAY_BASE,
-        }
-    }
-
-    #[inline]
-    pub(crate) fn decay_weight(&self) -> f64 {
-        match self {
-            SkillKind::Aim { .. } => AIM_DECAY_WEIGHT,
-            SkillKind::Flashlight { .. } => FLASHLIGHT_DECAY_WEIGHT,
-            SkillKind::Speed { .. } => SPEED_DECAY_WEIGHT,
-        }
-    }
-
-    #[inline]
-    pub(crate) fn strain_decay(&self, ms: f64) -> f64 {
-        self.strain_decay_base().powf(ms / 1000.0)
-    }
 }
 
 pub(crate) fn calculate_speed_rhythm_bonus(
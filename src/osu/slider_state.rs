@@ -62,24 +62,29 @@ mod test {
                 TimingPoint {
                     time: 1.0,
                     beat_len: 10.0,
+                    ..Default::default()
                 },
                 TimingPoint {
                     time: 3.0,
                     beat_len: 20.0,
+                    ..Default::default()
                 },
                 TimingPoint {
                     time: 4.0,
                     beat_len: 30.0,
+                    ..Default::default()
                 },
             ],
             difficulty_points: vec![
                 DifficultyPoint {
                     time: 2.0,
                     speed_multiplier: 15.0,
+                    ..Default::default()
                 },
                 DifficultyPoint {
                     time: 5.0,
                     speed_multiplier: 45.0,
+                    ..Default::default()
                 },
             ],
             ..Default::default()
@@ -0,0 +1,192 @@
+use super::{
+    difficulty_range_ar,
+    osu_object::{ObjectParameters, OsuObject, OsuObjectKind},
+    scaling_factor::ScalingFactor,
+    slider_state::SliderState,
+    OsuDifficultyAttributes,
+};
+
+use crate::{curve::CurveBuffers, Beatmap, Mods};
+
+const BASE_FADE_IN: f64 = 400.0;
+const PREEMPT_MIN: f64 = 450.0;
+const HD_FADE_IN_DURATION_MULTIPLIER: f64 = 0.4;
+const HD_FADE_OUT_DURATION_MULTIPLIER: f64 = 0.3;
+
+/// Calculate when each hit object of an osu!standard map appears, fades, and disappears.
+///
+/// All times are in milliseconds of the map's timeline, i.e. they are __not__
+/// adjusted by the clock rate of speed changing mods.
+/// Only HR and EZ influence the approach rate, and HD the fading.
+pub fn visibility(map: &Beatmap, mods: impl Mods) -> Vec<OsuObjectVisibility> {
+    let map_attributes = map.attributes().mods(mods);
+    let hr = mods.hr();
+
+    let mut raw_ar = map.ar as f64;
+
+    if hr {
+        raw_ar = (raw_ar * 1.4).min(10.0);
+    } else if mods.ez() {
+        raw_ar *= 0.5;
+    }
+
+    let time_preempt = difficulty_range_ar(raw_ar);
+    let radius = ScalingFactor::new(map_attributes.cs).radius() as f64;
+
+    let time_fade_in = if mods.hd() {
+        time_preempt * HD_FADE_IN_DURATION_MULTIPLIER
+    } else {
+        BASE_FADE_IN * (time_preempt / PREEMPT_MIN).min(1.0)
+    };
+
+    let mut attributes = OsuDifficultyAttributes::default();
+
+    let mut params = ObjectParameters {
+        map,
+        attributes: &mut attributes,
+        slider_state: SliderState::new(map),
+        ticks: Vec::new(),
+        curve_bufs: CurveBuffers::default(),
+    };
+
+    map.hit_objects
+        .iter()
+        .filter_map(|h| OsuObject::new(h, hr, &mut params))
+        .map(|h| {
+            let start_time = h.time;
+            let end_time = h.end_time();
+
+            let hidden = match h.kind {
+                OsuObjectKind::Spinner { .. } => None,
+                _ if !mods.hd() => None,
+                OsuObjectKind::Circle => Some(HiddenFadeOut {
+                    start: start_time - time_preempt + time_fade_in,
+                    duration: time_preempt * HD_FADE_OUT_DURATION_MULTIPLIER,
+                }),
+                OsuObjectKind::Slider { .. } => {
+                    let start = start_time - time_preempt + time_fade_in;
+
+                    Some(HiddenFadeOut {
+                        start,
+                        duration: end_time - start,
+                    })
+                }
+            };
+
+            OsuObjectVisibility {
+                start_time,
+                end_time,
+                time_preempt,
+                time_fade_in,
+                hidden,
+                radius,
+            }
+        })
+        .collect()
+}
+
+/// Timing and visibility information of an osu!standard hit object.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OsuObjectVisibility {
+    /// The time at which the object needs to be hit.
+    pub start_time: f64,
+    /// The end time of the object; same as `start_time` for circles.
+    pub end_time: f64,
+    /// The duration before `start_time` in which the object is visible.
+    pub time_preempt: f64,
+    /// The duration in which the object fades in after appearing.
+    pub time_fade_in: f64,
+    /// The fade out of the object, only present if HD is enabled.
+    pub hidden: Option<HiddenFadeOut>,
+    /// The radius of the object in osu!pixels.
+    pub radius: f64,
+}
+
+/// Fade out of a hit object due to the HD mod.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HiddenFadeOut {
+    /// The time at which the object starts fading out.
+    pub start: f64,
+    /// The duration in which the object fades out.
+    pub duration: f64,
+}
+
+impl OsuObjectVisibility {
+    /// The time at which the object appears.
+    #[inline]
+    pub fn appear_time(&self) -> f64 {
+        self.start_time - self.time_preempt
+    }
+
+    /// The scale of the approach circle relative to the object at the given time.
+    ///
+    /// Starts at 4.0 when the object appears and shrinks down to 1.0 at `start_time`.
+    #[inline]
+    pub fn approach_scale(&self, time: f64) -> f64 {
+        let remaining = (self.start_time - time) / self.time_preempt;
+
+        1.0 + 3.0 * remaining.clamp(0.0, 1.0)
+    }
+
+    /// The opacity of the object at the given time, ranging from 0.0 to 1.0.
+    ///
+    /// Objects are considered to disappear instantly at their end time.
+    pub fn opacity(&self, time: f64) -> f64 {
+        let appear_time = self.appear_time();
+
+        if time < appear_time || time > self.end_time {
+            return 0.0;
+        }
+
+        let fade_in = ((time - appear_time) / self.time_fade_in).min(1.0);
+
+        match self.hidden {
+            Some(HiddenFadeOut { start, duration }) if time > start => {
+                let fade_out = if duration > 0.0 {
+                    ((time - start) / duration).min(1.0)
+                } else {
+                    1.0
+                };
+
+                fade_in * (1.0 - fade_out)
+            }
+            _ => fade_in,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_map() {
+        let map = Beatmap::default();
+        assert!(visibility(&map, 8).is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn hidden_fade() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        let nomod = visibility(&map, 0);
+        let hidden = visibility(&map, 8);
+
+        assert_eq!(nomod.len(), hidden.len());
+        assert!(nomod.iter().all(|h| h.hidden.is_none()));
+
+        let circle = hidden
+            .iter()
+            .find(|h| h.start_time == h.end_time)
+            .expect("no circle");
+
+        let fade_out = circle.hidden.expect("no fade out");
+        assert_eq!(circle.time_fade_in, circle.time_preempt * 0.4);
+        assert_eq!(fade_out.start, circle.appear_time() + circle.time_fade_in);
+        assert_eq!(circle.opacity(fade_out.start), 1.0);
+        assert_eq!(circle.opacity(fade_out.start + fade_out.duration), 0.0);
+        assert_eq!(circle.approach_scale(circle.appear_time()), 4.0);
+        assert_eq!(circle.approach_scale(circle.start_time), 1.0);
+    }
+}
@@ -45,14 +45,14 @@ impl BeatmapAttributes {
         let multiplier = mods.od_ar_hp_multiplier();
 
         // AR
-        let mut ar = (self.ar * multiplier) as f64;
+        let mut ar = self.ar * multiplier;
         let mut ar_ms = if ar <= 5.0 {
             Self::AR0_MS - Self::AR_MS_STEP_1 * ar
         } else {
             Self::AR5_MS - Self::AR_MS_STEP_2 * (ar - 5.0)
         };
 
-        ar_ms = ar_ms.max(Self::AR10_MS).min(Self::AR0_MS);
+        ar_ms = ar_ms.clamp(Self::AR10_MS, Self::AR0_MS);
         ar_ms /= clock_rate;
 
         ar = if ar_ms > Self::AR5_MS {
@@ -62,7 +62,10 @@ impl BeatmapAttributes {
         };
 
         // OD
-        let od = (self.od * multiplier).min(10.0);
+        let od = match mods.overall_difficulty() {
+            Some(od) => od as f64,
+            None => (self.od * multiplier).min(10.0),
+        };
 
         // CS
         let mut cs = self.cs;
@@ -0,0 +1,116 @@
+use super::Beatmap;
+
+impl Beatmap {
+    /// Calculate the start times of all bar lines, in milliseconds.
+    ///
+    /// Each timing point starts a new bar line every `meter` beats until the next timing
+    /// point or, for the last one, until one bar after the end of the last hit object.
+    /// Bar lines before `0` or the first hit object, whichever is earlier, are skipped.
+    ///
+    /// Requires the `sliders` feature, i.e. `osu` or `fruits`, since timing points
+    /// are not stored otherwise.
+    ///
+    /// ```
+    /// use akatsuki_pp::Beatmap;
+    ///
+    /// # let map = Beatmap::default();
+    /// for time in map.bar_lines() {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn bar_lines(&self) -> Vec<f64> {
+        let (first, last) = match (self.hit_objects.first(), self.hit_objects.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Vec::new(),
+        };
+
+        let first_object_time = first.start_time;
+        let last_object_time = self.object_end_time(last);
+
+        let mut bar_lines = Vec::new();
+
+        for (i, point) in self.timing_points.iter().enumerate() {
+            let bar_len = point.beat_len * point.meter as f64;
+
+            let mut time = first_object_time.min(0.0).max(point.time);
+
+            let end_time = match self.timing_points.get(i + 1) {
+                Some(next) => next.time,
+                None => last_object_time + bar_len,
+            };
+
+            if point.omit_first_bar_line {
+                time += bar_len;
+            }
+
+            // A zero beat length would never reach the end
+            if bar_len <= 0.0 {
+                continue;
+            }
+
+            while end_time > time - 1e-3 {
+                let rounded = time.round();
+
+                // Prevent floating point errors from piling up
+                if (time - rounded).abs() <= 1e-7 {
+                    time = rounded;
+                }
+
+                bar_lines.push(time);
+                time += bar_len;
+            }
+        }
+
+        bar_lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{HitObject, HitObjectKind, Pos2, TimingPoint};
+
+    #[test]
+    fn bar_lines() {
+        let circle = |start_time| HitObject {
+            pos: Pos2::default(),
+            start_time,
+            kind: HitObjectKind::Circle,
+            sound: 0,
+            file_idx: 0,
+        };
+
+        let map = Beatmap {
+            hit_objects: vec![circle(1000.0), circle(5000.0)],
+            timing_points: vec![
+                TimingPoint {
+                    time: 1000.0,
+                    beat_len: 500.0,
+                    ..Default::default()
+                },
+                TimingPoint {
+                    time: 3000.0,
+                    beat_len: 250.0,
+                    meter: 3,
+                    omit_first_bar_line: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let expected = [1000.0, 3000.0, 3750.0, 4500.0, 5250.0];
+
+        assert_eq!(map.bar_lines(), expected);
+        assert!(Beatmap::default().bar_lines().is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn parsed_meter() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+
+        assert!(map.timing_points.iter().all(|point| point.meter > 0));
+        assert!(!map.bar_lines().is_empty());
+    }
+}
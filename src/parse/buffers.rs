@@ -0,0 +1,27 @@
+#[cfg(feature = "sliders")]
+use super::PathControlPoint;
+
+/// Buffers that are re-used while parsing, see [`Beatmap::parse_with`](crate::Beatmap::parse_with).
+///
+/// Parsing a map through [`Beatmap::parse`](crate::Beatmap::parse) allocates
+/// fresh buffers every time. When parsing many maps in a row, passing the same
+/// `ParseBuffers` to each call lets them keep their capacity instead.
+#[derive(Clone, Debug, Default)]
+pub struct ParseBuffers {
+    pub(crate) line: String,
+    pub(crate) sliders: SliderBuffers,
+}
+
+impl ParseBuffers {
+    /// Create new empty buffers.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SliderBuffers {
+    #[cfg(feature = "sliders")]
+    pub(crate) vertices: Vec<PathControlPoint>,
+}
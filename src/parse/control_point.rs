@@ -7,6 +7,37 @@ pub struct TimingPoint {
     pub beat_len: f64,
     /// The start time of this timing section
     pub time: f64,
+    /// The amount of beats per bar, i.e. the numerator of the time signature
+    pub meter: u32,
+    /// Whether the first bar line of this timing section is omitted
+    pub omit_first_bar_line: bool,
+    /// The sample set, `0` meaning the one of the `[General]` section,
+    /// `1` normal, `2` soft, and `3` drum
+    pub sample_set: Option<u8>,
+    /// The custom sample index, `0` meaning the default samples
+    pub sample_index: Option<i32>,
+    /// The sample volume between `0` and `100`
+    pub volume: Option<u8>,
+}
+
+impl TimingPoint {
+    /// The meter of timing points that don't specify one.
+    pub const DEFAULT_METER: u32 = 4;
+}
+
+impl Default for TimingPoint {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            beat_len: 1000.0,
+            time: 0.0,
+            meter: Self::DEFAULT_METER,
+            omit_first_bar_line: false,
+            sample_set: None,
+            sample_index: None,
+            volume: None,
+        }
+    }
 }
 
 impl PartialOrd for TimingPoint {
@@ -22,6 +53,26 @@ pub struct DifficultyPoint {
     pub time: f64,
     /// The speed multiplier until the next timing point
     pub speed_multiplier: f64,
+    /// The sample set, `0` meaning the one of the `[General]` section,
+    /// `1` normal, `2` soft, and `3` drum
+    pub sample_set: Option<u8>,
+    /// The custom sample index, `0` meaning the default samples
+    pub sample_index: Option<i32>,
+    /// The sample volume between `0` and `100`
+    pub volume: Option<u8>,
+}
+
+impl Default for DifficultyPoint {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            speed_multiplier: 1.0,
+            sample_set: None,
+            sample_index: None,
+            volume: None,
+        }
+    }
 }
 
 impl PartialOrd for DifficultyPoint {
@@ -1,4 +1,4 @@
-use super::OSU_FILE_HEADER;
+use super::{ParseLimitKind, Sections, OSU_FILE_HEADER};
 
 #[cfg(not(all(
     feature = "osu",
@@ -10,36 +10,34 @@ use super::GameMode;
 
 use std::error::Error as StdError;
 use std::fmt;
-use std::io::Error as IOError;
+use std::io::{Error as IOError, ErrorKind};
 use std::num::{ParseFloatError, ParseIntError};
 
 /// `Result<_, ParseError>`
 pub type ParseResult<T> = Result<T, ParseError>;
 
 /// Anything that could go wrong while parsing a [`Beatmap`](crate::Beatmap).
+///
+/// IO errors are kept separate from errors in the content of the file so that
+/// transient failures, e.g. a network drive timing out, can be retried while
+/// invalid maps can be rejected right away.
 #[derive(Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum ParseError {
     /// Some IO operation failed.
     IOError(IOError),
-    /// The initial data of an `.osu` file was incorrect.
-    IncorrectFileHeader,
-    /// Line in `.osu` was unexpectedly not of the form `key:value`.
-    BadLine,
-    /// Line in `.osu` that contains a slider was not in the proper format.
-    InvalidCurvePoints,
-    /// Expected a decimal number, got something else.
-    InvalidDecimalNumber,
-    /// Expected an integer, got something else.
-    InvalidInteger,
-    /// Failed to parse game mode.
-    InvalidMode,
-    /// Expected an additional field.
-    MissingField(&'static str),
-    /// Reject maps with too many repeat points.
-    TooManyRepeats,
-    /// Failed to recognized specified type for hitobjects.
-    UnknownHitObjectKind,
+    /// Failed to fetch a map from a [`MapSource`](crate::online::MapSource).
+    #[cfg(feature = "online")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "online")))]
+    Download(crate::online::FetchError),
+    /// The content of the `.osu` file is invalid.
+    Format {
+        /// The section in which the error occurred.
+        /// [`Sections::NONE`] if it occurred in the file header.
+        section: Sections,
+        /// What exactly was invalid.
+        source: FormatError,
+    },
 
     #[cfg(not(all(
         feature = "osu",
@@ -51,21 +49,93 @@ pub enum ParseError {
     UnincludedMode(GameMode),
 }
 
+impl ParseError {
+    /// Check whether the error was caused by a failing IO operation,
+    /// including downloads, rather than the content of the file.
+    #[inline]
+    pub fn is_io(&self) -> bool {
+        match self {
+            Self::IOError(_) => true,
+            #[cfg(feature = "online")]
+            Self::Download(_) => true,
+            _ => false,
+        }
+    }
+
+    /// The kind of the underlying IO error, if any.
+    #[inline]
+    pub fn io_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::IOError(err) => Some(err.kind()),
+            _ => None,
+        }
+    }
+
+    /// The section in which a format error occurred.
+    #[inline]
+    pub fn section(&self) -> Option<Sections> {
+        match self {
+            Self::Format { section, .. } => Some(*section),
+            _ => None,
+        }
+    }
+
+    /// A stable numeric code for the kind of error, e.g. for language bindings.
+    #[inline]
+    pub fn code(&self) -> ParseErrorCode {
+        match self {
+            Self::IOError(_) => ParseErrorCode::Io,
+            #[cfg(feature = "online")]
+            Self::Download(_) => ParseErrorCode::Download,
+            Self::Format { source, .. } => source.code(),
+
+            #[cfg(not(all(
+                feature = "osu",
+                feature = "taiko",
+                feature = "fruits",
+                feature = "mania"
+            )))]
+            Self::UnincludedMode(_) => ParseErrorCode::UnincludedMode,
+        }
+    }
+
+    /// The full error message, including the messages of all underlying errors,
+    /// e.g. ``invalid `[General]` section: invalid mode``.
+    pub fn message(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = StdError::source(self);
+
+        while let Some(err) = source {
+            message.push_str(": ");
+            message.push_str(&err.to_string());
+            source = err.source();
+        }
+
+        message
+    }
+
+    /// Attach the section to a format error unless it already has one.
+    pub(crate) fn in_section(mut self, new: Sections) -> Self {
+        if let Self::Format { section, .. } = &mut self {
+            if section.is_empty() {
+                *section = new;
+            }
+        }
+
+        self
+    }
+}
+
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::IOError(_) => f.write_str("IO error"),
-            Self::IncorrectFileHeader => {
-                write!(f, "expected `{}` at file begin", OSU_FILE_HEADER)
-            }
-            Self::BadLine => f.write_str("line not in `Key:Value` pattern"),
-            Self::InvalidCurvePoints => f.write_str("invalid curve point"),
-            Self::InvalidInteger => f.write_str("invalid integer"),
-            Self::InvalidDecimalNumber => f.write_str("invalid float number"),
-            Self::InvalidMode => f.write_str("invalid mode"),
-            Self::MissingField(field) => write!(f, "missing field `{}`", field),
-            Self::TooManyRepeats => f.write_str("repeat count is way too high"),
-            Self::UnknownHitObjectKind => f.write_str("unsupported hitobject kind"),
+            #[cfg(feature = "online")]
+            Self::Download(_) => f.write_str("failed to download map"),
+            Self::Format { section, .. } => match section.name() {
+                Some(name) => write!(f, "invalid `[{}]` section", name),
+                None => f.write_str("invalid file header"),
+            },
 
             #[cfg(not(all(
                 feature = "osu",
@@ -86,15 +156,9 @@ impl StdError for ParseError {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
             Self::IOError(inner) => Some(inner),
-            Self::IncorrectFileHeader => None,
-            Self::BadLine => None,
-            Self::InvalidCurvePoints => None,
-            Self::InvalidInteger => None,
-            Self::InvalidDecimalNumber => None,
-            Self::InvalidMode => None,
-            Self::MissingField(_) => None,
-            Self::TooManyRepeats => None,
-            Self::UnknownHitObjectKind => None,
+            #[cfg(feature = "online")]
+            Self::Download(inner) => Some(&**inner),
+            Self::Format { source, .. } => Some(source),
 
             #[cfg(not(all(
                 feature = "osu",
@@ -113,14 +177,220 @@ impl From<IOError> for ParseError {
     }
 }
 
+impl From<FormatError> for ParseError {
+    fn from(source: FormatError) -> Self {
+        Self::Format {
+            section: Sections::NONE,
+            source,
+        }
+    }
+}
+
 impl From<ParseIntError> for ParseError {
-    fn from(_: ParseIntError) -> Self {
-        Self::InvalidInteger
+    fn from(other: ParseIntError) -> Self {
+        FormatError::from(other).into()
     }
 }
 
 impl From<ParseFloatError> for ParseError {
-    fn from(_: ParseFloatError) -> Self {
-        Self::InvalidDecimalNumber
+    fn from(other: ParseFloatError) -> Self {
+        FormatError::from(other).into()
+    }
+}
+
+/// Stable numeric codes for the kinds of [`ParseError`].
+///
+/// The values never change between versions and all variants exist regardless of
+/// the enabled features so they can be exposed as-is through an FFI layer.
+/// Codes starting at 100 denote a [`FormatError`]. The code 108 is reserved since
+/// it denoted too many slider repeats which are now reported as
+/// [`LimitExceeded`](ParseErrorCode::LimitExceeded).
+///
+/// New codes may be added in future versions so consumers must handle unknown
+/// codes, e.g. by treating them as a generic error, instead of assuming that
+/// the current variants are exhaustive.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+#[repr(u32)]
+pub enum ParseErrorCode {
+    /// See [`ParseError::IOError`].
+    Io = 1,
+    /// Failed to fetch a map with the `online` feature.
+    Download = 2,
+    /// Tried to parse a map with a game mode whose feature was not enabled.
+    UnincludedMode = 3,
+    /// See [`FormatError::IncorrectFileHeader`].
+    IncorrectFileHeader = 100,
+    /// See [`FormatError::BadLine`].
+    BadLine = 101,
+    /// See [`FormatError::InvalidCurvePoints`].
+    InvalidCurvePoints = 102,
+    /// See [`FormatError::InvalidDecimalNumber`].
+    InvalidDecimalNumber = 103,
+    /// See [`FormatError::InvalidInteger`].
+    InvalidInteger = 104,
+    /// See [`FormatError::InvalidMode`].
+    InvalidMode = 105,
+    /// See [`FormatError::MissingField`].
+    MissingField = 106,
+    /// See [`FormatError::NonFiniteNumber`].
+    NonFiniteNumber = 107,
+    // 108 is reserved
+    /// See [`FormatError::UnknownHitObjectKind`].
+    UnknownHitObjectKind = 109,
+    /// See [`FormatError::LimitExceeded`].
+    LimitExceeded = 110,
+}
+
+impl ParseErrorCode {
+    /// Whether the code denotes a [`FormatError`], i.e. invalid content of the file.
+    #[inline]
+    pub fn is_format(self) -> bool {
+        self as u32 >= 100
+    }
+}
+
+impl From<ParseErrorCode> for u32 {
+    #[inline]
+    fn from(code: ParseErrorCode) -> Self {
+        code as u32
+    }
+}
+
+/// Invalid content of an `.osu` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FormatError {
+    /// The initial data of an `.osu` file was incorrect.
+    IncorrectFileHeader,
+    /// Line in `.osu` was unexpectedly not of the form `key:value`.
+    BadLine,
+    /// Line in `.osu` that contains a slider was not in the proper format.
+    InvalidCurvePoints,
+    /// Expected a decimal number, got something else.
+    InvalidDecimalNumber(ParseFloatError),
+    /// Expected an integer, got something else.
+    InvalidInteger(ParseIntError),
+    /// Failed to parse game mode.
+    InvalidMode,
+    /// Expected an additional field.
+    MissingField(&'static str),
+    /// A decimal number was infinite or NaN.
+    NonFiniteNumber,
+    /// Failed to recognized specified type for hitobjects.
+    UnknownHitObjectKind,
+    /// The file contains more of something than the [`ParseLimits`](super::ParseLimits) allow.
+    LimitExceeded {
+        /// What there was too much of.
+        kind: ParseLimitKind,
+        /// The limit that was exceeded.
+        limit: usize,
+    },
+}
+
+impl FormatError {
+    /// A stable numeric code for the kind of error, see [`ParseError::code`].
+    #[inline]
+    pub fn code(&self) -> ParseErrorCode {
+        match self {
+            Self::IncorrectFileHeader => ParseErrorCode::IncorrectFileHeader,
+            Self::BadLine => ParseErrorCode::BadLine,
+            Self::InvalidCurvePoints => ParseErrorCode::InvalidCurvePoints,
+            Self::InvalidDecimalNumber(_) => ParseErrorCode::InvalidDecimalNumber,
+            Self::InvalidInteger(_) => ParseErrorCode::InvalidInteger,
+            Self::InvalidMode => ParseErrorCode::InvalidMode,
+            Self::MissingField(_) => ParseErrorCode::MissingField,
+            Self::NonFiniteNumber => ParseErrorCode::NonFiniteNumber,
+            Self::UnknownHitObjectKind => ParseErrorCode::UnknownHitObjectKind,
+            Self::LimitExceeded { .. } => ParseErrorCode::LimitExceeded,
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IncorrectFileHeader => {
+                write!(f, "expected `{}` at file begin", OSU_FILE_HEADER)
+            }
+            Self::BadLine => f.write_str("line not in `Key:Value` pattern"),
+            Self::InvalidCurvePoints => f.write_str("invalid curve point"),
+            Self::InvalidInteger(_) => f.write_str("invalid integer"),
+            Self::InvalidDecimalNumber(_) => f.write_str("invalid float number"),
+            Self::InvalidMode => f.write_str("invalid mode"),
+            Self::MissingField(field) => write!(f, "missing field `{}`", field),
+            Self::NonFiniteNumber => f.write_str("float number is not finite"),
+            Self::UnknownHitObjectKind => f.write_str("unsupported hitobject kind"),
+            Self::LimitExceeded { kind, limit } => {
+                write!(f, "more than {} {}", limit, kind)
+            }
+        }
+    }
+}
+
+impl StdError for FormatError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::InvalidDecimalNumber(inner) => Some(inner),
+            Self::InvalidInteger(inner) => Some(inner),
+            Self::IncorrectFileHeader
+            | Self::BadLine
+            | Self::InvalidCurvePoints
+            | Self::InvalidMode
+            | Self::MissingField(_)
+            | Self::NonFiniteNumber
+            | Self::UnknownHitObjectKind
+            | Self::LimitExceeded { .. } => None,
+        }
+    }
+}
+
+impl From<ParseIntError> for FormatError {
+    fn from(other: ParseIntError) -> Self {
+        Self::InvalidInteger(other)
+    }
+}
+
+impl From<ParseFloatError> for FormatError {
+    fn from(other: ParseFloatError) -> Self {
+        Self::InvalidDecimalNumber(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_and_messages() {
+        let err = ParseError::from(FormatError::MissingField("x")).in_section(Sections::GENERAL);
+        assert_eq!(err.code(), ParseErrorCode::MissingField);
+        assert_eq!(u32::from(err.code()), 106);
+        assert!(err.code().is_format());
+        assert_eq!(
+            err.message(),
+            "invalid `[General]` section: missing field `x`"
+        );
+
+        let err = ParseError::from("x".parse::<i32>().unwrap_err());
+        assert_eq!(err.code(), ParseErrorCode::InvalidInteger);
+        assert!(err
+            .message()
+            .starts_with("invalid file header: invalid integer: "));
+
+        let err = ParseError::from(IOError::new(ErrorKind::NotFound, "file not found"));
+        assert_eq!(err.code(), ParseErrorCode::Io);
+        assert!(!err.code().is_format());
+        assert_eq!(err.message(), "IO error: file not found");
+
+        let err = ParseError::from(FormatError::LimitExceeded {
+            kind: ParseLimitKind::ControlPoints,
+            limit: 100,
+        })
+        .in_section(Sections::HIT_OBJECTS);
+        assert_eq!(u32::from(err.code()), 110);
+        assert_eq!(
+            err.message(),
+            "invalid `[HitObjects]` section: more than 100 slider control points"
+        );
     }
 }
@@ -14,6 +14,11 @@ pub struct HitObject {
     pub kind: HitObjectKind,
     /// The hitsound of the object. Used as color in osu!taiko.
     pub sound: u8,
+    /// The index of the object within the `[HitObjects]` section of the `.osu` file.
+    ///
+    /// Differs from the index in [`Beatmap::hit_objects`](crate::Beatmap::hit_objects)
+    /// if the objects had to be sorted.
+    pub file_idx: usize,
 }
 
 impl HitObject {
@@ -21,7 +26,7 @@ impl HitObject {
     #[inline]
     pub fn end_time(&self) -> f64 {
         match &self.kind {
-            HitObjectKind::Circle { .. } => self.start_time,
+            HitObjectKind::Circle => self.start_time,
             // incorrect, only called in mania which has no sliders though
             HitObjectKind::Slider { .. } => self.start_time,
             HitObjectKind::Spinner { end_time } => *end_time,
@@ -32,7 +37,7 @@ impl HitObject {
     /// If the object is a circle.
     #[inline]
     pub fn is_circle(&self) -> bool {
-        matches!(self.kind, HitObjectKind::Circle { .. })
+        matches!(self.kind, HitObjectKind::Circle)
     }
 
     /// If the object is a slider.
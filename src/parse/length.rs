@@ -0,0 +1,245 @@
+use super::{Beatmap, HitObject, HitObjectKind};
+
+const BASE_SCORING_DISTANCE: f64 = 100.0;
+
+/// Timestamps concerning the length of a [`Beatmap`], see [`Beatmap::length`].
+///
+/// All values are in milliseconds and adjusted by the clock rate.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BeatmapLength {
+    /// The start time of the first hit object.
+    pub first_object_time: f64,
+    /// The end time of the last hit object, including spinners.
+    pub last_object_end_time: f64,
+    /// The time from the start of the first hit object until the end of
+    /// the last hit object that is not a spinner.
+    ///
+    /// Neither the silence before the first object nor
+    /// trailing spinners are considered part of it.
+    pub playable_time: f64,
+}
+
+impl Beatmap {
+    /// Calculate the first object time, the last object end time,
+    /// and the playable time of the map for the given clock rate.
+    ///
+    /// The end time of sliders is based on their declared pixel length
+    /// instead of their actual curve so no curve has to be calculated.
+    /// If the `sliders` feature is not enabled, only the map's
+    /// [`bpm`](Beatmap::bpm) is considered for their duration.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, Mods};
+    ///
+    /// # let map = Beatmap::default();
+    /// let length = map.length(64_u32.speed());
+    /// let seconds = length.playable_time / 1000.0;
+    /// ```
+    pub fn length(&self, clock_rate: f64) -> BeatmapLength {
+        let first_object_time = match self.hit_objects.first() {
+            Some(h) => h.start_time,
+            None => return BeatmapLength::default(),
+        };
+
+        let mut last_object_end_time = first_object_time;
+        let mut last_playable_end_time = first_object_time;
+
+        for h in self.hit_objects.iter() {
+            let end_time = self.object_end_time(h);
+            last_object_end_time = last_object_end_time.max(end_time);
+
+            if !h.is_spinner() {
+                last_playable_end_time = last_playable_end_time.max(end_time);
+            }
+        }
+
+        BeatmapLength {
+            first_object_time: first_object_time / clock_rate,
+            last_object_end_time: last_object_end_time / clock_rate,
+            playable_time: (last_playable_end_time - first_object_time) / clock_rate,
+        }
+    }
+
+    /// The amount of hit objects that were passed at the given completion of
+    /// the map in percent, e.g. `63.0` for a play that failed at 63%.
+    ///
+    /// The completion is relative to the time from the start of the first hit object
+    /// until the end of the last one. An object is passed if it starts at or before
+    /// that point in time, i.e. partially passed objects are rounded down except for
+    /// the first object which is always passed. The completion is clamped between
+    /// 0 and 100, NaN is treated as 0, and the result is independent of the clock rate.
+    ///
+    /// Note that osu!ctb calculations count fruits and droplets as passed objects
+    /// instead of hit objects. [`AnyPP::completion`](crate::AnyPP::completion)
+    /// takes care of that.
+    ///
+    /// ```
+    /// use akatsuki_pp::Beatmap;
+    ///
+    /// # let map = Beatmap::default();
+    /// let passed_objects = map.passed_objects_at(63.0);
+    /// ```
+    pub fn passed_objects_at(&self, completion: f64) -> usize {
+        match self.completion_time(completion) {
+            Some(time) => self.hit_objects.partition_point(|h| h.start_time <= time),
+            None => self.hit_objects.len(),
+        }
+    }
+
+    /// The point in time until which objects are passed at the given completion,
+    /// see [`passed_objects_at`](Beatmap::passed_objects_at).
+    ///
+    /// `None` if the map is completed.
+    pub(crate) fn completion_time(&self, completion: f64) -> Option<f64> {
+        let length = self.length(1.0);
+        let duration = length.last_object_end_time - length.first_object_time;
+
+        let completion = if completion.is_nan() {
+            0.0
+        } else {
+            completion.clamp(0.0, 100.0) / 100.0
+        };
+
+        if completion >= 1.0 {
+            return None;
+        }
+
+        Some(length.first_object_time + completion * duration)
+    }
+
+    /// The end time of the object, including the duration of sliders.
+    pub(crate) fn object_end_time(&self, h: &HitObject) -> f64 {
+        let (pixel_len, span_count) = match &h.kind {
+            #[cfg(feature = "sliders")]
+            HitObjectKind::Slider {
+                pixel_len, repeats, ..
+            } => (*pixel_len, (*repeats + 1) as f64),
+            #[cfg(not(feature = "sliders"))]
+            HitObjectKind::Slider {
+                pixel_len,
+                span_count,
+            } => (*pixel_len, *span_count as f64),
+            _ => return h.end_time(),
+        };
+
+        let (beat_len, slider_velocity) = self.slider_timing(h.start_time);
+        let velocity = (BASE_SCORING_DISTANCE * self.slider_mult * slider_velocity) / beat_len;
+
+        h.start_time + span_count * pixel_len / velocity
+    }
+
+    /// The beat length and slider velocity at the given time.
+    #[cfg(feature = "sliders")]
+    fn slider_timing(&self, time: f64) -> (f64, f64) {
+        let timing_idx = self.timing_points.partition_point(|p| p.time <= time);
+        let difficulty_idx = self.difficulty_points.partition_point(|p| p.time <= time);
+
+        // Objects before the first timing point use the first one
+        let timing = self.timing_points.get(timing_idx.saturating_sub(1));
+
+        let difficulty = difficulty_idx
+            .checked_sub(1)
+            .map(|idx| &self.difficulty_points[idx]);
+
+        // A timing point resets the slider velocity of previous difficulty points
+        match (timing, difficulty) {
+            (Some(t), Some(d)) if d.time >= t.time => (t.beat_len, d.speed_multiplier),
+            (Some(t), _) => (t.beat_len, 1.0),
+            (None, Some(d)) => (1000.0, d.speed_multiplier),
+            (None, None) => (1000.0, 1.0),
+        }
+    }
+
+    /// The beat length and slider velocity at the given time.
+    #[cfg(not(feature = "sliders"))]
+    fn slider_timing(&self, _: f64) -> (f64, f64) {
+        if self.bpm > 0.0 {
+            (60_000.0 / self.bpm, 1.0)
+        } else {
+            (1000.0, 1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn map_length() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let length = map.length(1.0);
+
+        let first = map.hit_objects[0].start_time;
+        let last = map.hit_objects.last().unwrap();
+
+        assert_eq!(length.first_object_time, first);
+        assert!(length.last_object_end_time >= last.start_time);
+        assert!(length.playable_time <= length.last_object_end_time - first);
+
+        let dt = map.length(1.5);
+        assert!((dt.first_object_time - first / 1.5).abs() < 1e-9);
+        assert!((dt.playable_time - length.playable_time / 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn trailing_spinner() {
+        use crate::parse::Pos2;
+
+        let object = |start_time, kind| HitObject {
+            pos: Pos2::default(),
+            start_time,
+            kind,
+            sound: 0,
+            file_idx: 0,
+        };
+
+        let map = Beatmap {
+            hit_objects: vec![
+                object(1000.0, HitObjectKind::Circle),
+                object(2000.0, HitObjectKind::Circle),
+                object(3000.0, HitObjectKind::Spinner { end_time: 6000.0 }),
+            ],
+            ..Default::default()
+        };
+
+        let length = map.length(2.0);
+
+        assert_eq!(length.first_object_time, 500.0);
+        assert_eq!(length.last_object_end_time, 3000.0);
+        assert_eq!(length.playable_time, 500.0);
+        assert_eq!(Beatmap::default().length(1.0), BeatmapLength::default());
+    }
+
+    #[test]
+    fn passed_objects_at() {
+        let object = |start_time| HitObject {
+            pos: crate::parse::Pos2::default(),
+            start_time,
+            kind: HitObjectKind::Circle,
+            sound: 0,
+            file_idx: 0,
+        };
+
+        let map = Beatmap {
+            hit_objects: vec![
+                object(1000.0),
+                object(2000.0),
+                object(3000.0),
+                object(6000.0),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(map.passed_objects_at(-5.0), 1);
+        assert_eq!(map.passed_objects_at(0.0), 1);
+        assert_eq!(map.passed_objects_at(19.9), 1);
+        assert_eq!(map.passed_objects_at(20.0), 2);
+        assert_eq!(map.passed_objects_at(63.0), 3);
+        assert_eq!(map.passed_objects_at(100.0), 4);
+        assert_eq!(map.passed_objects_at(150.0), 4);
+        assert_eq!(map.passed_objects_at(f64::NAN), 1);
+        assert_eq!(Beatmap::default().passed_objects_at(50.0), 0);
+    }
+}
@@ -0,0 +1,81 @@
+use std::borrow::Cow;
+
+use super::ParseWarning;
+
+/// Turn the raw bytes of a `.osu` file into text that the regular parser can handle.
+///
+/// Invalid UTF-8 is replaced, NUL bytes are removed, and all line endings,
+/// i.e. `\r\n`, `\n`, and lone `\r`, become `\n`.
+pub(super) fn sanitize(bytes: &[u8], warnings: &mut Vec<ParseWarning>) -> String {
+    let content = String::from_utf8_lossy(bytes);
+
+    if let Cow::Owned(_) = content {
+        warnings.push(ParseWarning::InvalidUtf8);
+    }
+
+    let mut sanitized = String::with_capacity(content.len());
+    let mut nul_bytes = 0;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\0' => nul_bytes += 1,
+            // `\r\n` keeps only the `\n`, a lone `\r` becomes one
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' => sanitized.push('\n'),
+            _ => sanitized.push(c),
+        }
+    }
+
+    if nul_bytes > 0 {
+        warnings.push(ParseWarning::NulBytes { count: nul_bytes });
+    }
+
+    sanitized
+}
+
+/// Split off the last non-empty line, returning the preceding content and the line itself.
+pub(super) fn split_last_line(content: &str) -> Option<(&str, &str)> {
+    let trimmed = content.trim_end();
+
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let start = trimmed.rfind('\n').map_or(0, |idx| idx + 1);
+
+    Some((&content[..start], &trimmed[start..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_line_endings() {
+        let mut warnings = Vec::new();
+        let content = sanitize(b"a\r\nb\rc\nd\r\r\ne", &mut warnings);
+
+        assert_eq!(content, "a\nb\nc\nd\n\ne");
+        assert!(warnings.is_empty());
+
+        let content = sanitize(b"a\0b\xFF\n\0", &mut warnings);
+
+        assert_eq!(content, "ab\u{FFFD}\n");
+        assert_eq!(
+            warnings,
+            [
+                ParseWarning::InvalidUtf8,
+                ParseWarning::NulBytes { count: 2 }
+            ]
+        );
+    }
+
+    #[test]
+    fn last_line() {
+        assert_eq!(split_last_line("a\nb\nc"), Some(("a\nb\n", "c")));
+        assert_eq!(split_last_line("a\nb\n\n "), Some(("a\n", "b")));
+        assert_eq!(split_last_line("a"), Some(("", "a")));
+        assert_eq!(split_last_line(" \n"), None);
+    }
+}
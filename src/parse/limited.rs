@@ -0,0 +1,117 @@
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+use std::io::{BufRead, Read};
+
+#[cfg(feature = "async_tokio")]
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+#[cfg(feature = "async_std")]
+use async_std::io::{
+    prelude::{BufReadExt, ReadExt},
+    BufRead as AsyncBufRead,
+};
+
+use super::{ParseLimitKind, ParseLimits, ParseResult};
+
+/// Reads a `.osu` file line by line while respecting the
+/// [`bytes`](ParseLimits::bytes) and [`line_len`](ParseLimits::line_len) limits.
+///
+/// Each line is read through `take` so that neither the file nor a single line
+/// is ever buffered much beyond the limits, even if there's no line break at all.
+pub(super) struct LimitedReader<R> {
+    inner: R,
+    limits: ParseLimits,
+    read: usize,
+}
+
+impl<R> LimitedReader<R> {
+    pub(super) fn new(inner: R, limits: &ParseLimits) -> Self {
+        Self {
+            inner,
+            limits: *limits,
+            read: 0,
+        }
+    }
+
+    /// The amount of bytes to read at most for the next line.
+    ///
+    /// One byte more than allowed is read so that exceeding a limit can be noticed.
+    fn next_limit(&self) -> u64 {
+        let remaining = self.limits.bytes.saturating_sub(self.read);
+
+        (remaining.min(self.limits.line_len) as u64).saturating_add(1)
+    }
+
+    fn count(&mut self, n: usize) -> ParseResult<usize> {
+        self.read = self.read.saturating_add(n);
+        self.limits.check(ParseLimitKind::Bytes, self.read)?;
+        self.limits.check(ParseLimitKind::LineLength, n)?;
+
+        Ok(n)
+    }
+}
+
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+impl<R: BufRead> LimitedReader<R> {
+    pub(super) fn read_line(&mut self, buf: &mut String) -> ParseResult<usize> {
+        let limit = self.next_limit();
+        let n = (&mut self.inner).take(limit).read_line(buf)?;
+
+        self.count(n)
+    }
+}
+
+#[cfg(any(feature = "async_std", feature = "async_tokio"))]
+impl<R: AsyncBufRead + Unpin> LimitedReader<R> {
+    pub(super) async fn read_line(&mut self, buf: &mut String) -> ParseResult<usize> {
+        let limit = self.next_limit();
+        let n = (&mut self.inner).take(limit).read_line(buf).await?;
+
+        self.count(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn limits() {
+        let content = "osu file format v14\n\n[General]\nMode: 0\n";
+
+        let read_all = |limits: ParseLimits| {
+            let mut reader = LimitedReader::new(content.as_bytes(), &limits);
+            let mut buf = String::new();
+
+            loop {
+                match reader.read_line(&mut buf) {
+                    Ok(0) => return Ok(buf),
+                    Ok(_) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        };
+
+        assert_eq!(read_all(ParseLimits::UNLIMITED).unwrap(), content);
+
+        let mut limits = ParseLimits::UNLIMITED;
+        limits.bytes = content.len();
+        limits.line_len = "osu file format v14\n".len();
+        assert_eq!(read_all(limits).unwrap(), content);
+
+        limits.bytes -= 1;
+        let err = read_all(limits).unwrap_err();
+        let expected = format!("more than {} bytes", content.len() - 1);
+        assert!(err.message().contains(&expected), "{}", err.message());
+
+        limits.bytes = usize::MAX;
+        limits.line_len -= 1;
+        let err = read_all(limits).unwrap_err();
+        assert!(
+            err.message().contains("in a single line"),
+            "{}",
+            err.message()
+        );
+    }
+}
@@ -1,17 +1,45 @@
 mod attributes;
+#[cfg(feature = "sliders")]
+mod bar_lines;
+mod buffers;
 mod control_point;
 mod error;
 mod hitobject;
 mod hitsound;
+mod length;
+mod lenient;
+mod limited;
+mod mode;
+mod mods_applied;
+mod options;
 mod pos2;
+mod raw_section;
+mod slider_length;
 mod sort;
+#[cfg(feature = "sliders")]
+mod volume;
+mod warning;
 
 pub use attributes::BeatmapAttributes;
+pub use buffers::ParseBuffers;
+use buffers::SliderBuffers;
 pub use control_point::{DifficultyPoint, TimingPoint};
-pub use error::{ParseError, ParseResult};
+pub use error::{FormatError, ParseError, ParseErrorCode, ParseResult};
 pub use hitobject::{HitObject, HitObjectKind};
 pub use hitsound::HitSound;
+pub use length::BeatmapLength;
+use limited::LimitedReader;
+pub use mode::UnknownModeError;
+pub use options::{ParseLimitKind, ParseLimits, ParseOptions, Sections};
 pub use pos2::Pos2;
+pub use raw_section::RawSection;
+pub use warning::ParseWarning;
+
+#[cfg(feature = "sliders")]
+pub use options::SliderLengthPolicy;
+
+#[cfg(feature = "sliders")]
+pub use slider_length::SliderLengthMismatch;
 use sort::legacy_sort;
 
 use std::cmp::Ordering;
@@ -25,7 +53,7 @@ use std::{
 #[cfg(feature = "async_tokio")]
 use tokio::{
     fs::File,
-    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    io::{AsyncBufRead, AsyncRead, AsyncReadExt, BufReader},
 };
 
 #[cfg(not(feature = "async_std"))]
@@ -34,15 +62,24 @@ use std::path::Path;
 #[cfg(feature = "async_std")]
 use async_std::{
     fs::File,
-    io::{prelude::BufReadExt, BufReader as AsyncBufReader, Read as AsyncRead},
+    io::{
+        prelude::ReadExt, BufRead as AsyncBufRead, BufReader as AsyncBufReader, Read as AsyncRead,
+    },
     path::Path,
 };
 
 #[cfg(feature = "sliders")]
 pub use osu_fruits::*;
 
-fn sort_unstable<T: PartialOrd>(slice: &mut [T]) {
-    slice.sort_unstable_by(|p1, p2| p1.partial_cmp(p2).unwrap_or(Ordering::Equal));
+/// The minimum amount of hit objects for difficulty and performance to be calculated.
+const MIN_CALCULABLE_OBJECTS: usize = 2;
+
+/// Placeholder for parsing without a sink, i.e. collecting hit objects into the map.
+const NO_SINK: Option<&mut fn(HitObject)> = None;
+
+/// Sort by time while elements of the same time keep their relative order.
+fn sort_stable<T: PartialOrd>(slice: &mut [T]) {
+    slice.sort_by(|p1, p2| p1.partial_cmp(p2).unwrap_or(Ordering::Equal));
 }
 
 trait OptionExt<T> {
@@ -51,7 +88,7 @@ trait OptionExt<T> {
 
 impl<T> OptionExt<T> for Option<T> {
     fn next_field(self, field: &'static str) -> Result<T, ParseError> {
-        self.ok_or(ParseError::MissingField(field))
+        self.ok_or_else(|| FormatError::MissingField(field).into())
     }
 }
 
@@ -62,14 +99,14 @@ trait FloatExt: Sized {
 impl FloatExt for f64 {
     fn validate(self) -> Result<Self, ParseError> {
         self.is_finite()
-            .then(|| self)
-            .ok_or(ParseError::InvalidDecimalNumber)
+            .then_some(self)
+            .ok_or_else(|| FormatError::NonFiniteNumber.into())
     }
 }
 
 macro_rules! line_prepare {
     ($buf:ident) => {{
-        let mut line = $buf.trim_end();
+        let mut line = $buf.trim_end().trim_start_matches(BOM);
 
         if skip_line(line) {
             $buf.clear();
@@ -85,14 +122,14 @@ macro_rules! line_prepare {
 }
 
 macro_rules! section {
-    ($map:ident, $func:ident, $reader:ident, $buf:ident, $section:ident) => {{
+    ($map:ident, $func:ident, $reader:ident, $buf:ident, $section:ident, $flag:expr $(, $extra:expr)*) => {{
         #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
-        if $map.$func(&mut $reader, &mut $buf, &mut $section)? {
-            break;
-        }
+        let done = $map.$func(&mut $reader, $buf, &mut $section $(, $extra)*);
 
         #[cfg(any(feature = "async_std", feature = "async_tokio"))]
-        if $map.$func(&mut $reader, &mut $buf, &mut $section).await? {
+        let done = $map.$func(&mut $reader, $buf, &mut $section $(, $extra)*).await;
+
+        if done.map_err(|err| err.in_section($flag))? {
             break;
         }
     }};
@@ -112,6 +149,20 @@ macro_rules! read_line {
     }};
 }
 
+macro_rules! read_to_end {
+    ($reader:ident, $buf:expr) => {{
+        #[cfg(any(feature = "async_std", feature = "async_tokio"))]
+        {
+            $reader.read_to_end($buf).await
+        }
+
+        #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+        {
+            $reader.read_to_end($buf)
+        }
+    }};
+}
+
 macro_rules! parse_general_body {
     ($self:ident, $reader:ident, $buf:ident, $section:ident) => {{
         let mut mode = None;
@@ -130,21 +181,21 @@ macro_rules! parse_general_body {
                 break;
             }
 
-            let (key, value) = split_colon(&line).ok_or(ParseError::BadLine)?;
+            let (key, value) = split_colon(&line).ok_or(FormatError::BadLine)?;
 
             if key == "Mode" {
-                mode = match value {
+                mode = match leading_value(key, value, &mut $self.warnings) {
                     "0" => Some(GameMode::STD),
                     "1" => Some(GameMode::TKO),
                     "2" => Some(GameMode::CTB),
                     "3" => Some(GameMode::MNA),
-                    _ => return Err(ParseError::InvalidMode),
+                    _ => return Err(FormatError::InvalidMode.into()),
                 };
             }
 
             #[cfg(feature = "osu")]
             if key == "StackLeniency" {
-                stack_leniency = Some(value.parse()?);
+                stack_leniency = Some(leading_value(key, value, &mut $self.warnings).parse()?);
             }
 
             $buf.clear();
@@ -181,22 +232,26 @@ macro_rules! parse_general_body {
     }};
 }
 
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
 macro_rules! parse_general {
     () => {
-        fn parse_general<R: Read>(
+        fn parse_general<R: BufRead>(
             &mut self,
-            reader: &mut BufReader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
         ) -> ParseResult<bool> {
             parse_general_body!(self, reader, buf, section)
         }
     };
+}
 
-    (async $reader:ident<$inner:ident>) => {
-        async fn parse_general<R: $inner + Unpin>(
+#[cfg(any(feature = "async_std", feature = "async_tokio"))]
+macro_rules! parse_general {
+    (async $bufread:ident) => {
+        async fn parse_general<R: $bufread + Unpin>(
             &mut self,
-            reader: &mut $reader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
         ) -> ParseResult<bool> {
@@ -226,7 +281,8 @@ macro_rules! parse_difficulty_body {
                 break;
             }
 
-            let (key, value) = split_colon(&line).ok_or(ParseError::BadLine)?;
+            let (key, value) = split_colon(&line).ok_or(FormatError::BadLine)?;
+            let value = leading_value(key, value, &mut $self.warnings);
 
             match key {
                 "ApproachRate" => ar = Some(value.parse()?),
@@ -252,22 +308,26 @@ macro_rules! parse_difficulty_body {
     }};
 }
 
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
 macro_rules! parse_difficulty {
     () => {
-        fn parse_difficulty<R: Read>(
+        fn parse_difficulty<R: BufRead>(
             &mut self,
-            reader: &mut BufReader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
         ) -> ParseResult<bool> {
             parse_difficulty_body!(self, reader, buf, section)
         }
     };
+}
 
-    (async $reader:ident<$inner:ident>) => {
-        async fn parse_difficulty<R: $inner + Unpin>(
+#[cfg(any(feature = "async_std", feature = "async_tokio"))]
+macro_rules! parse_difficulty {
+    (async $bufread:ident) => {
+        async fn parse_difficulty<R: $bufread + Unpin>(
             &mut self,
-            reader: &mut $reader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
         ) -> ParseResult<bool> {
@@ -276,14 +336,16 @@ macro_rules! parse_difficulty {
     };
 }
 
+#[cfg(not(feature = "sliders"))]
 macro_rules! parse_timingpoints_body {
-    (short => $self:ident, $reader:ident, $buf:ident, $section:ident) => {{
+    ($self:ident, $reader:ident, $buf:ident, $section:ident, $limits:ident) => {{
+        let _ = $limits;
         let mut empty = true;
 
         // Only parse the first timing point to calculate the bpm
         if read_line!($reader, $buf)? != 0 {
             let line = {
-                let mut line = $buf.trim_end();
+                let mut line = $buf.trim_end().trim_start_matches(BOM);
 
                 if skip_line(line) {
                     $buf.clear();
@@ -331,14 +393,18 @@ macro_rules! parse_timingpoints_body {
 
         Ok(empty)
     }};
+}
 
-    ($self:ident, $reader:ident, $buf:ident, $section:ident) => {{
+#[cfg(feature = "sliders")]
+macro_rules! parse_timingpoints_body {
+    ($self:ident, $reader:ident, $buf:ident, $section:ident, $limits:ident) => {{
         let mut unsorted_timings = false;
         let mut unsorted_difficulties = false;
 
         let mut prev_diff = 0.0;
         let mut prev_time = 0.0;
 
+        let mut n_points = 0;
         let mut empty = true;
 
         while read_line!($reader, $buf)? != 0 {
@@ -351,6 +417,9 @@ macro_rules! parse_timingpoints_body {
                 break;
             }
 
+            n_points += 1;
+            $limits.check(ParseLimitKind::TimingPoints, n_points)?;
+
             let mut split = line.split(',');
 
             let time = split
@@ -361,11 +430,20 @@ macro_rules! parse_timingpoints_body {
                 .validate()?;
 
             let beat_len: f64 = split.next().next_field("beat len")?.trim().parse()?;
+            let meter = optional_field::<i32>(split.next())?;
+            let sample_set = optional_field::<u8>(split.next())?;
+            let sample_index = optional_field::<i32>(split.next())?;
+            let volume =
+                optional_field::<i32>(split.next())?.map(|volume| volume.clamp(0, 100) as u8);
+            let effects = optional_field::<u32>(split.nth(1))?.unwrap_or(0);
 
             if beat_len < 0.0 {
                 let point = DifficultyPoint {
                     time,
                     speed_multiplier: (-100.0 / beat_len).max(0.1).min(10.0),
+                    sample_set,
+                    sample_index,
+                    volume,
                 };
 
                 $self.difficulty_points.push(point);
@@ -376,7 +454,18 @@ macro_rules! parse_timingpoints_body {
                     prev_diff = time;
                 }
             } else {
-                $self.timing_points.push(TimingPoint { time, beat_len });
+                $self.timing_points.push(TimingPoint {
+                    time,
+                    beat_len,
+                    meter: match meter {
+                        Some(meter) if meter > 0 => meter as u32,
+                        _ => TimingPoint::DEFAULT_METER,
+                    },
+                    omit_first_bar_line: effects & OMIT_FIRST_BAR_LINE > 0,
+                    sample_set,
+                    sample_index,
+                    volume,
+                });
 
                 if time < prev_time {
                     unsorted_timings = true;
@@ -389,69 +478,63 @@ macro_rules! parse_timingpoints_body {
         }
 
         if unsorted_timings {
-            sort_unstable(&mut $self.timing_points);
+            sort_stable(&mut $self.timing_points);
         }
 
         if unsorted_difficulties {
-            sort_unstable(&mut $self.difficulty_points);
+            sort_stable(&mut $self.difficulty_points);
         }
 
         Ok(empty)
     }};
 }
 
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
 macro_rules! parse_timingpoints {
     () => {
-        fn parse_timingpoints<R: Read>(
+        fn parse_timingpoints<R: BufRead>(
             &mut self,
-            reader: &mut BufReader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
+            limits: &ParseLimits,
         ) -> ParseResult<bool> {
-            #[cfg(not(feature = "sliders"))]
-            {
-                parse_timingpoints_body!(short => self, reader, buf, section)
-            }
-
-            #[cfg(feature = "sliders")]
-            parse_timingpoints_body!(self, reader, buf, section)
+            parse_timingpoints_body!(self, reader, buf, section, limits)
         }
     };
+}
 
-    (async $reader:ident<$inner:ident>) => {
-        async fn parse_timingpoints<R: $inner + Unpin>(
+#[cfg(any(feature = "async_std", feature = "async_tokio"))]
+macro_rules! parse_timingpoints {
+    (async $bufread:ident) => {
+        async fn parse_timingpoints<R: $bufread + Unpin>(
             &mut self,
-            reader: &mut $reader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
+            limits: &ParseLimits,
         ) -> ParseResult<bool> {
-            #[cfg(not(feature = "sliders"))]
-            {
-                parse_timingpoints_body!(short => self, reader, buf, section)
-            }
-
-            #[cfg(feature = "sliders")]
-            parse_timingpoints_body!(self, reader, buf, section)
+            parse_timingpoints_body!(self, reader, buf, section, limits)
         }
     };
 }
 
 macro_rules! parse_hitobjects_body {
-    ($self:ident, $reader:ident, $buf:ident, $section:ident) => {{
-        let mut unsorted = false;
-        let mut prev_time = 0.0;
+    ($self:ident, $reader:ident, $buf:ident, $section:ident, $sliders:ident, $sink:ident, $limits:ident) => {{
+        let mut file_idx = 0;
         let mut empty = true;
 
-        // `point_split` will be of type `Vec<&str>
-        // with each element having its lifetime bound to `buf`.
-        // To circumvent this, `point_split_raw` will contain
-        // the actual `&str` elements transmuted into `usize`.
+        // Byte ranges of the control points within the current line. Unlike `&str`s,
+        // they are not bound to `buf` so the buffer can be re-used for all sliders.
         #[cfg(feature = "sliders")]
-        let mut point_split_raw: Vec<usize> = Vec::new();
+        let mut point_ranges = Vec::new();
 
         // Buffer to re-use for all sliders
         #[cfg(feature = "sliders")]
-        let mut vertices = Vec::new();
+        let vertices = &mut $sliders.vertices;
+
+        #[cfg(not(feature = "sliders"))]
+        let _ = $sliders;
 
         while read_line!($reader, $buf)? != 0 {
             let line = line_prepare!($buf);
@@ -463,11 +546,13 @@ macro_rules! parse_hitobjects_body {
                 break;
             }
 
+            $limits.check(ParseLimitKind::HitObjects, file_idx + 1)?;
+
             let mut split = line.split(',');
 
             let pos = Pos2 {
-                x: split.next().next_field("x pos")?.parse()?,
-                y: split.next().next_field("y pos")?.parse()?,
+                x: split.next().next_field("x pos")?.trim().parse()?,
+                y: split.next().next_field("y pos")?.trim().parse()?,
             };
 
             let time = split
@@ -477,12 +562,12 @@ macro_rules! parse_hitobjects_body {
                 .parse::<f64>()?
                 .validate()?;
 
-            if !$self.hit_objects.is_empty() && time < prev_time {
-                unsorted = true;
-            }
-
-            let kind: u8 = split.next().next_field("hitobject kind")?.parse()?;
-            let sound = split.next().map(str::parse).transpose()?.unwrap_or(0);
+            let kind: u8 = split.next().next_field("hitobject kind")?.trim().parse()?;
+            let sound = split
+                .next()
+                .map(|s| s.trim().parse())
+                .transpose()?
+                .unwrap_or(0);
 
             let kind = if kind & Self::CIRCLE_FLAG > 0 {
                 $self.n_circles += 1;
@@ -495,12 +580,10 @@ macro_rules! parse_hitobjects_body {
                 {
                     let mut control_points = Vec::new();
 
-                    let control_point_iter = split.next().next_field("control points")?.split('|');
-                    let mut repeats: usize = split.next().next_field("repeats")?.parse()?;
+                    let points = split.next().next_field("control points")?;
+                    let mut repeats: usize = split.next().next_field("repeats")?.trim().parse()?;
 
-                    if repeats > 9000 {
-                        return Err(ParseError::TooManyRepeats);
-                    }
+                    $limits.check(ParseLimitKind::Repeats, repeats)?;
 
                     // * osu-stable treated the first span of the slider
                     // * as a repeat, but no repeats are happening
@@ -510,37 +593,36 @@ macro_rules! parse_hitobjects_body {
                     let mut end_idx = 0;
                     let mut first = true;
 
-                    // SAFETY: `Vec<usize>` and `Vec<&str>` have the same size and layout.
-                    let point_split: &mut Vec<&str> =
-                        unsafe { std::mem::transmute(&mut point_split_raw) };
-
-                    point_split.clear();
-                    point_split.extend(control_point_iter);
+                    split_points(points, &mut point_ranges);
+                    $limits.check(ParseLimitKind::ControlPoints, point_ranges.len())?;
 
-                    #[allow(clippy::blocks_in_if_conditions)]
+                    #[allow(clippy::blocks_in_conditions)]
                     while {
                         end_idx += 1;
 
-                        end_idx < point_split.len()
+                        end_idx < point_ranges.len()
                     } {
                         // * Keep incrementing end_idx while it's not the start of a new segment
                         // * (indicated by having a type descriptor of length 1).
-                        if point_split[end_idx].len() > 1 {
+                        if point_ranges[end_idx].len() > 1 {
                             continue;
                         }
 
                         // * Multi-segmented sliders DON'T contain the end point as part of the
                         // * current segment as it's assumed to be the start of the next segment.
                         // * The start of the next segment is the index after the type descriptor.
-                        let end_point = point_split.get(end_idx + 1).copied();
+                        let end_point = point_ranges
+                            .get(end_idx + 1)
+                            .map(|range| &points[range.clone()]);
 
                         convert_points(
-                            &point_split[start_idx..end_idx],
+                            points,
+                            &point_ranges[start_idx..end_idx],
                             end_point,
                             first,
                             pos,
                             &mut control_points,
-                            &mut vertices,
+                            vertices,
                         )?;
 
                         start_idx = end_idx;
@@ -549,12 +631,13 @@ macro_rules! parse_hitobjects_body {
 
                     if end_idx > start_idx {
                         convert_points(
-                            &point_split[start_idx..end_idx],
+                            points,
+                            &point_ranges[start_idx..end_idx],
                             None,
                             first,
                             pos,
                             &mut control_points,
-                            &mut vertices,
+                            vertices,
                         )?;
                     }
 
@@ -564,6 +647,7 @@ macro_rules! parse_hitobjects_body {
                         let pixel_len = split
                             .next()
                             .next_field("pixel len")?
+                            .trim()
                             .parse::<f64>()?
                             .max(0.0)
                             .min(MAX_COORDINATE_VALUE);
@@ -578,8 +662,8 @@ macro_rules! parse_hitobjects_body {
 
                 #[cfg(not(feature = "sliders"))]
                 {
-                    let span_count = split.nth(1).next_field("repeats")?.parse()?;
-                    let pixel_len = split.next().next_field("pixel len")?.parse()?;
+                    let span_count = split.nth(1).next_field("repeats")?.trim().parse()?;
+                    let pixel_len = split.next().next_field("pixel len")?.trim().parse()?;
 
                     HitObjectKind::Slider {
                         span_count,
@@ -588,7 +672,7 @@ macro_rules! parse_hitobjects_body {
                 }
             } else if kind & Self::SPINNER_FLAG > 0 {
                 $self.n_spinners += 1;
-                let end_time = split.next().next_field("spinner endtime")?.parse()?;
+                let end_time = split.next().next_field("spinner endtime")?.trim().parse()?;
 
                 HitObjectKind::Spinner { end_time }
             } else if kind & Self::HOLD_FLAG > 0 {
@@ -596,76 +680,88 @@ macro_rules! parse_hitobjects_body {
                 let mut end = time;
 
                 if let Some(next) = split.next() {
-                    end = end.max(next.split(':').next().next_field("hold endtime")?.parse()?);
+                    end = end.max(
+                        next.split(':')
+                            .next()
+                            .next_field("hold endtime")?
+                            .trim()
+                            .parse()?,
+                    );
                 }
 
                 HitObjectKind::Hold { end_time: end }
             } else {
-                return Err(ParseError::UnknownHitObjectKind);
+                return Err(FormatError::UnknownHitObjectKind.into());
             };
 
-            $self.hit_objects.push(HitObject {
+            let h = HitObject {
                 pos,
                 start_time: time,
                 kind,
                 sound,
-            });
-
-            prev_time = time;
-            $buf.clear();
-        }
+                file_idx,
+            };
 
-        // BUG: If [General] section comes after [HitObjects] then the mode
-        // won't be set yet so mania objects won't be sorted properly
-        if $self.mode == GameMode::MNA {
-            // First a _stable_ sort by time
-            $self
-                .hit_objects
-                .sort_by(|p1, p2| p1.partial_cmp(p2).unwrap_or(Ordering::Equal));
+            match $sink {
+                Some(ref mut sink) => sink(h),
+                None => $self.hit_objects.push(h),
+            }
 
-            // Then the legacy sort for correct position order
-            legacy_sort(&mut $self.hit_objects);
-        } else if unsorted {
-            sort_unstable(&mut $self.hit_objects);
+            file_idx += 1;
+            $buf.clear();
         }
 
         Ok(empty)
     }};
 }
 
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
 macro_rules! parse_hitobjects {
     () => {
-        fn parse_hitobjects<R: Read>(
+        fn parse_hitobjects<R: BufRead, S: FnMut(HitObject)>(
             &mut self,
-            reader: &mut BufReader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
+            sliders: &mut SliderBuffers,
+            mut sink: Option<&mut S>,
+            limits: &ParseLimits,
         ) -> ParseResult<bool> {
-            parse_hitobjects_body!(self, reader, buf, section)
+            parse_hitobjects_body!(self, reader, buf, section, sliders, sink, limits)
         }
     };
+}
 
-    (async $reader:ident<$inner:ident>) => {
-        async fn parse_hitobjects<R: $inner + Unpin>(
+#[cfg(any(feature = "async_std", feature = "async_tokio"))]
+macro_rules! parse_hitobjects {
+    (async $bufread:ident) => {
+        async fn parse_hitobjects<R: $bufread + Unpin, S: FnMut(HitObject)>(
             &mut self,
-            reader: &mut $reader<R>,
+            reader: &mut LimitedReader<R>,
             buf: &mut String,
             section: &mut Section,
+            sliders: &mut SliderBuffers,
+            mut sink: Option<&mut S>,
+            limits: &ParseLimits,
         ) -> ParseResult<bool> {
-            parse_hitobjects_body!(self, reader, buf, section)
+            parse_hitobjects_body!(self, reader, buf, section, sliders, sink, limits)
         }
     };
 }
 
 macro_rules! parse_body {
-    ($reader:ident<$inner:ident>: $input:ident) => {{
-        let mut reader = $reader::new($input);
-        let mut buf = String::new();
+    ($reader:ident => $map:ident, $sections:expr, $buffers:expr, $sink:ident, $capture_raw:expr, $limits:expr) => {{
+        let limits: ParseLimits = $limits;
+        let mut reader = LimitedReader::new($reader, &limits);
+        let capture_raw: bool = $capture_raw;
+        let mut raw_open = false;
+        let ParseBuffers { line: buf, sliders } = $buffers;
+        buf.clear();
 
-        while read_line!(reader, &mut buf)? != 0 {
+        while read_line!(reader, buf)? != 0 {
             // Check for character U+FEFF specifically thanks to map id 797130
             if !buf
-                .trim_matches(|c: char| c.is_whitespace() || c == '﻿')
+                .trim_matches(|c: char| c.is_whitespace() || c == BOM)
                 .is_empty()
             {
                 break;
@@ -674,47 +770,195 @@ macro_rules! parse_body {
             buf.clear();
         }
 
-        let version = match buf.find(OSU_FILE_HEADER) {
-            Some(idx) => buf[idx + OSU_FILE_HEADER.len()..].trim_end().parse()?,
-            None => return Err(ParseError::IncorrectFileHeader),
+        $map.version = match buf.find(OSU_FILE_HEADER) {
+            Some(idx) => trim(&buf[idx + OSU_FILE_HEADER.len()..]).parse()?,
+            None => return Err(FormatError::IncorrectFileHeader.into()),
         };
 
         buf.clear();
 
-        let mut map = Beatmap {
-            version,
-            hit_objects: Vec::with_capacity(256),
-            ..Default::default()
-        };
-
+        let sections: Sections = $sections;
+        let mut remaining = sections;
         let mut section = Section::None;
 
-        loop {
-            match section {
-                Section::General => section!(map, parse_general, reader, buf, section),
-                Section::Difficulty => section!(map, parse_difficulty, reader, buf, section),
-                Section::TimingPoints => section!(map, parse_timingpoints, reader, buf, section),
-                Section::HitObjects => section!(map, parse_hitobjects, reader, buf, section),
-                Section::None => {
-                    if read_line!(reader, &mut buf)? == 0 {
+        // Sections whose processing depends on the mode are finished once the
+        // whole file is read so that the order of sections doesn't matter
+        let mut mode_dependent = Sections::NONE;
+
+        // Once all requested sections are parsed, the rest of the file can be ignored
+        // unless unknown sections are captured
+        while !remaining.is_empty() || capture_raw {
+            let parsed = match section {
+                Section::General if remaining.contains(Sections::GENERAL) => {
+                    section!($map, parse_general, reader, buf, section, Sections::GENERAL);
+
+                    Sections::GENERAL
+                }
+                Section::Difficulty if remaining.contains(Sections::DIFFICULTY) => {
+                    mode_dependent |= Sections::DIFFICULTY;
+
+                    section!(
+                        $map,
+                        parse_difficulty,
+                        reader,
+                        buf,
+                        section,
+                        Sections::DIFFICULTY
+                    );
+
+                    Sections::DIFFICULTY
+                }
+                Section::TimingPoints if remaining.contains(Sections::TIMING_POINTS) => {
+                    section!(
+                        $map,
+                        parse_timingpoints,
+                        reader,
+                        buf,
+                        section,
+                        Sections::TIMING_POINTS,
+                        &limits
+                    );
+
+                    Sections::TIMING_POINTS
+                }
+                Section::HitObjects if remaining.contains(Sections::HIT_OBJECTS) => {
+                    mode_dependent |= Sections::HIT_OBJECTS;
+
+                    section!(
+                        $map,
+                        parse_hitobjects,
+                        reader,
+                        buf,
+                        section,
+                        Sections::HIT_OBJECTS,
+                        sliders,
+                        $sink.as_deref_mut(),
+                        &limits
+                    );
+
+                    Sections::HIT_OBJECTS
+                }
+                // Skip lines until the next section header
+                _ => {
+                    if capture_raw && !raw_open {
+                        if let Section::Other(name) = &section {
+                            $map.raw_sections.push(RawSection::new(name.clone()));
+                            raw_open = true;
+                        }
+                    }
+
+                    if read_line!(reader, buf)? == 0 {
                         break;
                     }
 
-                    let line = line_prepare!(buf);
+                    let line = buf.trim_end().trim_start_matches(BOM);
 
                     if line.starts_with('[') && line.ends_with(']') {
                         section = Section::from_str(&line[1..line.len() - 1]);
+                        raw_open = false;
+                    } else if raw_open {
+                        if let Some(raw) = $map.raw_sections.last_mut() {
+                            raw.content.push_str(buf);
+                        }
                     }
 
                     buf.clear();
+
+                    continue;
                 }
-            }
+            };
+
+            remaining.remove(parsed);
+        }
+
+        if mode_dependent.contains(Sections::DIFFICULTY) {
+            $map.clamp_difficulty();
+        }
+
+        if mode_dependent.contains(Sections::HIT_OBJECTS) {
+            $map.sort_hit_objects();
+        }
+
+        $map.parsed_sections |= sections;
+
+        ParseResult::Ok(())
+    }};
+}
+
+macro_rules! parse_text {
+    ($content:expr, $options:expr) => {{
+        #[cfg(any(feature = "async_std", feature = "async_tokio"))]
+        {
+            Self::parse_buffered_with_buffers(
+                $content.as_bytes(),
+                $options,
+                &mut ParseBuffers::default(),
+            )
+            .await
+        }
+
+        #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+        {
+            Self::parse_buffered(
+                $content.as_bytes(),
+                $options,
+                &mut ParseBuffers::default(),
+                NO_SINK,
+            )
         }
+    }};
+}
+
+macro_rules! parse_lenient_body {
+    ($input:ident, $options:ident) => {{
+        let limits = $options.limits;
+
+        // Read one byte more than allowed to notice whether the limit is exceeded
+        let mut input = $input.take((limits.bytes as u64).saturating_add(1));
+        let mut bytes = Vec::new();
+        read_to_end!(input, &mut bytes)?;
+        limits.check(ParseLimitKind::Bytes, bytes.len())?;
+
+        let mut warnings = Vec::new();
+        let content = lenient::sanitize(&bytes, &mut warnings);
+
+        // Replacing invalid UTF-8 may grow the content so
+        // the size limit only applies to the original input
+        let options = ParseOptions {
+            lenient: false,
+            limits: ParseLimits {
+                bytes: usize::MAX,
+                ..limits
+            },
+            ..$options
+        };
+
+        let mut map = match parse_text!(content, options) {
+            Ok(map) => map,
+            // The last line may be cut off or followed by garbage
+            Err(err) => match lenient::split_last_line(&content) {
+                Some((rest, line)) => match parse_text!(rest, options) {
+                    Ok(map) => {
+                        warnings.push(ParseWarning::LineDropped {
+                            line: line.to_owned(),
+                        });
+
+                        map
+                    }
+                    Err(_) => return Err(err),
+                },
+                None => return Err(err),
+            },
+        };
+
+        warnings.append(&mut map.warnings);
+        map.warnings = warnings;
 
         Ok(map)
     }};
 }
 
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
 macro_rules! parse {
     () => {
         /// Parse a beatmap from a `.osu` file.
@@ -723,11 +967,185 @@ macro_rules! parse {
         /// You'll likely want to pass (a reference of) a [`File`](std::fs::File)
         /// or the file's content as a slice of bytes (`&[u8]`).
         pub fn parse<R: Read>(input: R) -> ParseResult<Self> {
-            parse_body!(BufReader<Read>: input)
+            Self::parse_with_options(input, ParseOptions::default())
+        }
+
+        /// Parse a beatmap from a `.osu` file while respecting the given [`ParseOptions`].
+        ///
+        /// Sections that are not included in the options will be skipped and
+        /// their fields remain at their default value.
+        pub fn parse_with_options<R: Read>(input: R, options: ParseOptions) -> ParseResult<Self> {
+            if options.lenient {
+                return Self::parse_lenient(input, options);
+            }
+
+            Self::parse_buffered(
+                BufReader::new(input),
+                options,
+                &mut ParseBuffers::default(),
+                NO_SINK,
+            )
+        }
+
+        /// Parse a beatmap from a `.osu` file while respecting the given [`ParseOptions`]
+        /// and measure how long parsing took.
+        ///
+        /// The duration can be filled into [`CalcTimings::parse`](crate::CalcTimings::parse)
+        /// since the calculation itself can't know when and where its map was parsed.
+        #[cfg(feature = "timings")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+        pub fn parse_with_timings<R: Read>(
+            input: R,
+            options: ParseOptions,
+        ) -> ParseResult<(Self, std::time::Duration)> {
+            let start = std::time::Instant::now();
+            let map = Self::parse_with_options(input, options)?;
+
+            Ok((map, start.elapsed()))
+        }
+
+        /// Parse a beatmap from a `.osu` file while re-using the given [`ParseBuffers`].
+        ///
+        /// When parsing many maps in a row, passing the same buffers to each call
+        /// avoids allocating new ones for every map.
+        ///
+        /// ```no_run
+        /// use akatsuki_pp::{parse::ParseBuffers, Beatmap};
+        /// use std::fs::File;
+        ///
+        /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+        /// let mut buffers = ParseBuffers::new();
+        ///
+        /// for path in ["./maps/2785319.osu", "./maps/1028484.osu"] {
+        ///     let map = Beatmap::parse_with(File::open(path)?, &mut buffers)?;
+        ///     println!("{} objects", map.hit_objects.len());
+        /// }
+        /// # Ok(()) }
+        /// ```
+        pub fn parse_with<R: Read>(input: R, buffers: &mut ParseBuffers) -> ParseResult<Self> {
+            Self::parse_buffered(
+                BufReader::new(input),
+                ParseOptions::default(),
+                buffers,
+                NO_SINK,
+            )
+        }
+
+        /// Parse a beatmap from a `.osu` file but instead of collecting its hit objects
+        /// into [`hit_objects`](Beatmap::hit_objects), pass each one to `sink` as soon as
+        /// it is parsed so that they never have to be held in memory all at once.
+        ///
+        /// The returned map contains everything except for its hit objects, i.e. counts
+        /// such as [`n_circles`](Beatmap::n_circles) are still set.
+        ///
+        /// Note that hit objects are passed in the order of the file. Since they are not
+        /// collected, they are not sorted, e.g. for osu!mania or maps with unordered objects.
+        ///
+        /// ```no_run
+        /// use akatsuki_pp::Beatmap;
+        /// use std::fs::File;
+        ///
+        /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+        /// let file = File::open("./maps/2785319.osu")?;
+        /// let mut last_time = 0.0;
+        ///
+        /// let map = Beatmap::parse_with_sink(file, |h| last_time = h.start_time)?;
+        ///
+        /// assert!(map.hit_objects.is_empty());
+        /// println!("{} circles, last one at {}ms", map.n_circles, last_time);
+        /// # Ok(()) }
+        /// ```
+        pub fn parse_with_sink<R: Read>(
+            input: R,
+            mut sink: impl FnMut(HitObject),
+        ) -> ParseResult<Self> {
+            Self::parse_buffered(
+                BufReader::new(input),
+                ParseOptions::default(),
+                &mut ParseBuffers::default(),
+                Some(&mut sink),
+            )
+        }
+
+        /// Parse a beatmap on a best-effort basis, see [`ParseOptions::lenient`].
+        fn parse_lenient<R: Read>(input: R, options: ParseOptions) -> ParseResult<Self> {
+            parse_lenient_body!(input, options)
+        }
+
+        /// Parse a beatmap from an already buffered reader.
+        ///
+        /// In particular, in-memory data such as `&[u8]` can be
+        /// passed as is without copying it into another buffer.
+        fn parse_buffered<R: BufRead, S: FnMut(HitObject)>(
+            reader: R,
+            options: ParseOptions,
+            buffers: &mut ParseBuffers,
+            mut sink: Option<&mut S>,
+        ) -> ParseResult<Self> {
+            trace_span!("parse", sections = ?options.sections);
+
+
+            let mut map = Beatmap {
+                hit_objects: Vec::with_capacity(256),
+                ..Default::default()
+            };
+
+            parse_body!(reader => map, options.sections, buffers, sink, options.raw_sections, options.limits)?;
+
+            #[cfg(feature = "sliders")]
+            map.apply_slider_length_policy(options.slider_length);
+
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(mode = ?map.mode, n_objects = map.hit_objects.len(), "parsed beatmap");
+
+            Ok(map)
+        }
+
+        /// Parse all sections of a `.osu` file that have been skipped previously,
+        /// i.e. those not contained in [`parsed_sections`](Beatmap::parsed_sections).
+        ///
+        /// The input must be the same file that the map was originally parsed from.
+        /// The sections are parsed with the default [`ParseOptions`].
+        pub fn parse_remaining<R: Read>(&mut self, input: R) -> ParseResult<()> {
+            self.parse_remaining_with_options(input, ParseOptions::default())
+        }
+
+        /// Parse all sections of a `.osu` file that have been skipped previously
+        /// while respecting the given [`ParseOptions`].
+        ///
+        /// Only skipped sections that are included in the options are parsed, and
+        /// the options' [`limits`](ParseOptions::limits) and slider length apply to
+        /// them. Raw sections are not captured and lenient parsing is not supported.
+        pub fn parse_remaining_with_options<R: Read>(
+            &mut self,
+            input: R,
+            options: ParseOptions,
+        ) -> ParseResult<()> {
+            let sections = options.sections & !self.parsed_sections;
+
+            trace_span!("parse", sections = ?sections);
+
+            let reader = BufReader::new(input);
+
+
+            let mut sink = NO_SINK;
+            parse_body!(reader => self, sections, &mut ParseBuffers::default(), sink, false, options.limits)?;
+
+            #[cfg(feature = "sliders")]
+            if sections.contains(Sections::HIT_OBJECTS) {
+                self.apply_slider_length_policy(options.slider_length);
+            }
+
+
+            Ok(())
         }
     };
+}
 
-    (async $reader:ident<$inner:ident>) => {
+#[cfg(any(feature = "async_std", feature = "async_tokio"))]
+macro_rules! parse {
+    (async $reader:ident<$inner:ident, $bufread:ident>) => {
         /// Parse a beatmap from a `.osu` file.
         ///
         /// As argument you can give anything that implements `tokio::io::AsyncRead`
@@ -735,11 +1153,187 @@ macro_rules! parse {
         /// You'll likely want to pass a `File`
         /// or the file's content as a slice of bytes (`&[u8]`).
         pub async fn parse<R: $inner + Unpin>(input: R) -> ParseResult<Self> {
-            parse_body!($reader<$inner>: input)
+            Self::parse_with_options(input, ParseOptions::default()).await
+        }
+
+        /// Parse a beatmap from a `.osu` file while respecting the given [`ParseOptions`].
+        ///
+        /// Sections that are not included in the options will be skipped and
+        /// their fields remain at their default value.
+        pub async fn parse_with_options<R: $inner + Unpin>(
+            input: R,
+            options: ParseOptions,
+        ) -> ParseResult<Self> {
+            Self::parse_buffered_with_options($reader::new(input), options).await
+        }
+
+        /// Parse a beatmap from a `.osu` file while respecting the given [`ParseOptions`]
+        /// and measure how long parsing took.
+        ///
+        /// The duration can be filled into [`CalcTimings::parse`](crate::CalcTimings::parse)
+        /// since the calculation itself can't know when and where its map was parsed.
+        #[cfg(feature = "timings")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+        pub async fn parse_with_timings<R: $inner + Unpin>(
+            input: R,
+            options: ParseOptions,
+        ) -> ParseResult<(Self, std::time::Duration)> {
+            let start = std::time::Instant::now();
+            let map = Self::parse_with_options(input, options).await?;
+
+            Ok((map, start.elapsed()))
+        }
+
+        /// Parse a beatmap from a `.osu` file through a buffer of the given capacity.
+        ///
+        /// [`parse`](Beatmap::parse) uses a buffer of 8 KiB which may be
+        /// too small for pipelines that download and parse many maps at once.
+        pub async fn from_reader_with_capacity<R: $inner + Unpin>(
+            input: R,
+            capacity: usize,
+        ) -> ParseResult<Self> {
+            Self::parse_buffered($reader::with_capacity(capacity, input)).await
+        }
+
+        /// Parse a beatmap from an already buffered reader.
+        ///
+        /// As argument you can give anything that implements `tokio::io::AsyncBufRead`
+        /// or `async_std::io::BufRead`, depending which feature you chose.
+        /// Unlike [`parse`](Beatmap::parse), the input is used as is instead of
+        /// being wrapped in another buffer, e.g. for network streams that are buffered already.
+        pub async fn parse_buffered<R: $bufread + Unpin>(input: R) -> ParseResult<Self> {
+            Self::parse_buffered_with_options(input, ParseOptions::default()).await
+        }
+
+        /// Parse a beatmap from an already buffered reader while respecting
+        /// the given [`ParseOptions`], see [`parse_buffered`](Beatmap::parse_buffered).
+        pub async fn parse_buffered_with_options<R: $bufread + Unpin>(
+            input: R,
+            options: ParseOptions,
+        ) -> ParseResult<Self> {
+            if options.lenient {
+                return Self::parse_lenient(input, options).await;
+            }
+
+            Self::parse_buffered_with_buffers(input, options, &mut ParseBuffers::default()).await
+        }
+
+        /// Parse a beatmap on a best-effort basis, see [`ParseOptions::lenient`].
+        async fn parse_lenient<R: $bufread + Unpin>(
+            input: R,
+            options: ParseOptions,
+        ) -> ParseResult<Self> {
+            parse_lenient_body!(input, options)
+        }
+
+        /// Parse a beatmap from a `.osu` file while re-using the given [`ParseBuffers`].
+        ///
+        /// When parsing many maps in a row, passing the same buffers to each call
+        /// avoids allocating new ones for every map.
+        pub async fn parse_with<R: $inner + Unpin>(
+            input: R,
+            buffers: &mut ParseBuffers,
+        ) -> ParseResult<Self> {
+            Self::parse_buffered_with_buffers($reader::new(input), ParseOptions::default(), buffers)
+                .await
+        }
+
+        async fn parse_buffered_with_buffers<R: $bufread + Unpin>(
+            input: R,
+            options: ParseOptions,
+            buffers: &mut ParseBuffers,
+        ) -> ParseResult<Self> {
+            let parse = async move {
+                let mut map = Beatmap {
+                    hit_objects: Vec::with_capacity(256),
+                    ..Default::default()
+                };
+
+
+                let mut sink = NO_SINK;
+                parse_body!(input => map, options.sections, buffers, sink, options.raw_sections, options.limits)?;
+
+                #[cfg(feature = "sliders")]
+                map.apply_slider_length_policy(options.slider_length);
+
+
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    mode = ?map.mode,
+                    n_objects = map.hit_objects.len(),
+                    "parsed beatmap"
+                );
+
+                Ok(map)
+            };
+
+            #[cfg(feature = "tracing")]
+            let parse = tracing::Instrument::instrument(
+                parse,
+                tracing::debug_span!("parse", sections = ?options.sections),
+            );
+
+            parse.await
+        }
+
+        /// Parse all sections of a `.osu` file that have been skipped previously,
+        /// i.e. those not contained in [`parsed_sections`](Beatmap::parsed_sections).
+        ///
+        /// The input must be the same file that the map was originally parsed from.
+        /// The sections are parsed with the default [`ParseOptions`].
+        pub async fn parse_remaining<R: $inner + Unpin>(&mut self, input: R) -> ParseResult<()> {
+            self.parse_remaining_with_options(input, ParseOptions::default())
+                .await
+        }
+
+        /// Parse all sections of a `.osu` file that have been skipped previously
+        /// while respecting the given [`ParseOptions`].
+        ///
+        /// Only skipped sections that are included in the options are parsed, and
+        /// the options' [`limits`](ParseOptions::limits) and slider length apply to
+        /// them. Raw sections are not captured and lenient parsing is not supported.
+        pub async fn parse_remaining_with_options<R: $inner + Unpin>(
+            &mut self,
+            input: R,
+            options: ParseOptions,
+        ) -> ParseResult<()> {
+            let sections = options.sections & !self.parsed_sections;
+
+            #[cfg(feature = "tracing")]
+            let span = tracing::debug_span!("parse", sections = ?sections);
+
+            let parse = async move {
+                let reader = $reader::new(input);
+
+
+                let mut sink = NO_SINK;
+                parse_body!(
+                    reader => self,
+                    sections,
+                    &mut ParseBuffers::default(),
+                    sink,
+                    false,
+                    options.limits
+                )?;
+
+                #[cfg(feature = "sliders")]
+                if sections.contains(Sections::HIT_OBJECTS) {
+                    self.apply_slider_length_policy(options.slider_length);
+                }
+
+
+                Ok(())
+            };
+
+            #[cfg(feature = "tracing")]
+            let parse = tracing::Instrument::instrument(parse, span);
+
+            parse.await
         }
     };
 }
 
+#[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
 macro_rules! from_path {
     () => {
         /// Pass the path to a `.osu` file.
@@ -750,8 +1344,36 @@ macro_rules! from_path {
         pub fn from_path<P: AsRef<Path>>(path: P) -> ParseResult<Self> {
             Self::parse(File::open(path)?)
         }
+
+        /// Memory-map the `.osu` file at the given path and parse it without
+        /// reading it through buffered syscalls.
+        ///
+        /// # Safety
+        ///
+        /// The file must not be modified or truncated, neither by this process nor by
+        /// any other, until this function returns. Otherwise the behavior is undefined,
+        /// e.g. a concurrently truncated file causes a `SIGBUS` on unix.
+        /// If that can't be guaranteed, use [`from_path`](Beatmap::from_path) instead.
+        #[cfg(feature = "mmap")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "mmap")))]
+        pub unsafe fn from_path_mmap<P: AsRef<Path>>(path: P) -> ParseResult<Self> {
+            let file = File::open(path)?;
+
+            // SAFETY: The caller guarantees that the file is not modified while it's mapped
+            let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+            Self::parse_buffered(
+                &mmap[..],
+                ParseOptions::default(),
+                &mut ParseBuffers::default(),
+                NO_SINK,
+            )
+        }
     };
+}
 
+#[cfg(any(feature = "async_std", feature = "async_tokio"))]
+macro_rules! from_path {
     (async $path:ident) => {
         /// Pass the path to a `.osu` file.
         ///
@@ -811,7 +1433,12 @@ pub struct Beatmap {
     pub slider_mult: f64,
     /// Amount of slider ticks per beat.
     pub tick_rate: f64,
-    /// All hitobjects of the beatmap.
+    /// All hitobjects of the beatmap, sorted by their start time.
+    ///
+    /// Simultaneous objects are in the order of the `.osu` file, see
+    /// [`HitObject::file_idx`]. osu!mania objects are an exception since they are
+    /// ordered like osu!stable does, i.e. simultaneous notes are sorted through
+    /// osu!stable's unstable sorting algorithm. Either way, the order is deterministic.
     pub hit_objects: Vec<HitObject>,
 
     #[cfg(not(feature = "sliders"))]
@@ -830,6 +1457,20 @@ pub struct Beatmap {
     /// The stack leniency that is used to calculate
     /// the stack offset for stacked positions.
     pub stack_leniency: f32,
+
+    /// Sections of the `.osu` file that are not parsed, e.g. `[Colours]` or `[Editor]`.
+    ///
+    /// Only captured if enabled through [`ParseOptions::raw_sections`].
+    pub raw_sections: Vec<RawSection>,
+
+    /// Irregularities of the `.osu` file that were fixed while parsing,
+    /// e.g. difficulty values that were out of range.
+    pub warnings: Vec<ParseWarning>,
+
+    /// The sections that have been parsed.
+    ///
+    /// Skipped sections can be parsed later on through `Beatmap::parse_remaining`.
+    pub parsed_sections: Sections,
 }
 
 pub(crate) const OSU_FILE_HEADER: &str = "osu file format v";
@@ -842,6 +1483,24 @@ impl Beatmap {
     // const COMBO_OFFSET_FLAG: u8 = (1 << 4) | (1 << 5) | (1 << 6);
     const HOLD_FLAG: u8 = 1 << 7;
 
+    /// Sort the hit objects by their start time, requires the mode to be known.
+    fn sort_hit_objects(&mut self) {
+        if self.mode == GameMode::MNA {
+            // First a _stable_ sort by time
+            sort_stable(&mut self.hit_objects);
+
+            // Then the legacy sort for correct position order
+            legacy_sort(&mut self.hit_objects);
+        } else if self
+            .hit_objects
+            .windows(2)
+            .any(|pair| pair[1].start_time < pair[0].start_time)
+        {
+            // Simultaneous objects keep their order of the file
+            sort_stable(&mut self.hit_objects);
+        }
+    }
+
     /// Extract a beatmap's attributes into their own type.
     #[inline]
     pub fn attributes(&self) -> BeatmapAttributes {
@@ -864,28 +1523,158 @@ impl Beatmap {
     pub fn bpm(&self) -> f64 {
         self.bpm
     }
+
+    /// The beats per minute of the map when played at the given clock rate,
+    /// e.g. `mods.speed()` or a custom rate.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, Mods};
+    ///
+    /// # let map = Beatmap::default();
+    /// let mods = 64; // DT
+    /// let bpm = map.bpm_with(mods.speed());
+    /// ```
+    #[inline]
+    pub fn bpm_with(&self, clock_rate: f64) -> f64 {
+        self.bpm() * clock_rate
+    }
+
+    /// The lowest and highest beats per minute across all timing points of the map.
+    ///
+    /// Without the `sliders` feature, timing points are not stored so
+    /// both values are the same as [`bpm`](Beatmap::bpm).
+    #[cfg(feature = "sliders")]
+    pub fn bpm_range(&self) -> (f64, f64) {
+        let mut bpms = self
+            .timing_points
+            .iter()
+            .filter(|point| point.beat_len > 0.0)
+            .map(|point| bpm(point.beat_len));
+
+        let first = match bpms.next() {
+            Some(bpm) => bpm,
+            None => return (self.bpm(), self.bpm()),
+        };
+
+        bpms.fold((first, first), |(min, max), bpm| {
+            (min.min(bpm), max.max(bpm))
+        })
+    }
+
+    /// The lowest and highest beats per minute across all timing points of the map.
+    ///
+    /// Without the `sliders` feature, timing points are not stored so
+    /// both values are the same as [`bpm`](Beatmap::bpm).
+    #[cfg(not(feature = "sliders"))]
+    #[inline]
+    pub fn bpm_range(&self) -> (f64, f64) {
+        (self.bpm, self.bpm)
+    }
+
+    /// The lowest and highest beats per minute of the map
+    /// when played at the given clock rate, see [`bpm_with`](Beatmap::bpm_with).
+    #[inline]
+    pub fn bpm_range_with(&self, clock_rate: f64) -> (f64, f64) {
+        let (min, max) = self.bpm_range();
+
+        (min * clock_rate, max * clock_rate)
+    }
+
+    /// Whether the map has no hit objects.
+    ///
+    /// Note that maps parsed through [`parse_with_sink`](Beatmap::parse_with_sink)
+    /// don't collect their hit objects and are thus always empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.hit_objects.is_empty()
+    }
+
+    /// Whether the map has enough hit objects for its difficulty
+    /// and performance to be calculated, i.e. at least two.
+    ///
+    /// Difficulty is based on the movement between objects so maps with fewer objects
+    /// have zeroed difficulty attributes and are worth 0pp, no matter the score.
+    #[inline]
+    pub fn is_calculable(&self) -> bool {
+        self.hit_objects.len() >= MIN_CALCULABLE_OBJECTS
+    }
+
+    /// Same as [`is_calculable`](Beatmap::is_calculable) but only considering
+    /// the given amount of passed objects, if any.
+    #[inline]
+    pub(crate) fn is_calculable_until(&self, passed_objects: Option<usize>) -> bool {
+        let n_objects = passed_objects.map_or(self.hit_objects.len(), |passed| {
+            passed.min(self.hit_objects.len())
+        });
+
+        n_objects >= MIN_CALCULABLE_OBJECTS
+    }
+
+    /// The hit object at the given index, if any.
+    #[inline]
+    pub fn object_at(&self, idx: usize) -> Option<&HitObject> {
+        self.hit_objects.get(idx)
+    }
+
+    /// The index of the first hit object that starts at or after the given time in milliseconds.
+    ///
+    /// If all hit objects start before the given time, the amount of hit objects is returned.
+    /// Since hit objects are sorted by their start time, this performs a binary search.
+    #[inline]
+    pub fn index_at_time(&self, time: f64) -> usize {
+        self.hit_objects.partition_point(|h| h.start_time < time)
+    }
+
+    /// All hit objects that start within the given range in milliseconds,
+    /// including `start_time` and excluding `end_time`.
+    ///
+    /// Times are in terms of the map's timeline, i.e. __not__
+    /// adjusted by the clock rate of speed changing mods.
+    pub fn objects_between(&self, start_time: f64, end_time: f64) -> &[HitObject] {
+        let start = self.index_at_time(start_time);
+        let end = self.index_at_time(end_time).max(start);
+
+        &self.hit_objects[start..end]
+    }
 }
 
 #[cfg(feature = "sliders")]
 mod osu_fruits {
-    use crate::ParseError;
+    use crate::{parse::FormatError, ParseError};
+
+    use std::ops::Range;
 
     use super::Pos2;
 
     pub(super) const MAX_COORDINATE_VALUE: f64 = 131_072.0;
 
+    /// Store the byte ranges of the `|`-separated control points of a slider.
+    pub(super) fn split_points(points: &str, ranges: &mut Vec<Range<usize>>) {
+        ranges.clear();
+        let mut start = 0;
+
+        for (idx, _) in points.match_indices('|') {
+            ranges.push(start..idx);
+            start = idx + 1;
+        }
+
+        ranges.push(start..points.len());
+    }
+
+    /// Convert the control points at the given byte ranges of `points`.
     pub(super) fn convert_points(
-        points: &[&str],
+        points: &str,
+        ranges: &[Range<usize>],
         end_point: Option<&str>,
         first: bool,
         offset: Pos2,
         curve_points: &mut Vec<PathControlPoint>,
         vertices: &mut Vec<PathControlPoint>,
     ) -> Result<(), ParseError> {
-        let mut path_kind = PathType::from_str(points[0]);
+        let mut path_kind = PathType::from_str(&points[ranges[0].clone()]);
 
         let read_offset = first as usize;
-        let readable_points = points.len() - 1;
+        let readable_points = ranges.len() - 1;
         let end_point_len = end_point.is_some() as usize;
 
         vertices.clear();
@@ -895,8 +1684,8 @@ mod osu_fruits {
         vertices.extend((0..read_offset).map(|_| PathControlPoint::default()));
 
         // * Parse into control points.
-        for &point in points.iter().skip(1) {
-            vertices.push(read_point(point, offset)?);
+        for range in ranges.iter().skip(1) {
+            vertices.push(read_point(&points[range.clone()], offset)?);
         }
 
         // * If an endpoint is given, add it to the end.
@@ -930,7 +1719,7 @@ mod osu_fruits {
         let mut start_idx = 0;
         let mut end_idx = 0;
 
-        #[allow(clippy::blocks_in_if_conditions)]
+        #[allow(clippy::blocks_in_conditions)]
         while {
             end_idx += 1;
 
@@ -968,7 +1757,7 @@ mod osu_fruits {
 
         match (v.next(), v.next()) {
             (Some(Ok(x)), Some(Ok(y))) => Ok(PathControlPoint::from(Pos2 { x, y } - start_pos)),
-            _ => Err(ParseError::InvalidCurvePoints),
+            _ => Err(FormatError::InvalidCurvePoints.into()),
         }
     }
 
@@ -1029,22 +1818,22 @@ impl Beatmap {
 
 #[cfg(feature = "async_tokio")]
 impl Beatmap {
-    parse!(async BufReader<AsyncRead>);
-    parse_general!(async BufReader<AsyncRead>);
-    parse_difficulty!(async BufReader<AsyncRead>);
-    parse_timingpoints!(async BufReader<AsyncRead>);
-    parse_hitobjects!(async BufReader<AsyncRead>);
+    parse!(async BufReader<AsyncRead, AsyncBufRead>);
+    parse_general!(async AsyncBufRead);
+    parse_difficulty!(async AsyncBufRead);
+    parse_timingpoints!(async AsyncBufRead);
+    parse_hitobjects!(async AsyncBufRead);
 
     from_path!(async Path);
 }
 
 #[cfg(feature = "async_std")]
 impl Beatmap {
-    parse!(async AsyncBufReader<AsyncRead>);
-    parse_general!(async AsyncBufReader<AsyncRead>);
-    parse_difficulty!(async AsyncBufReader<AsyncRead>);
-    parse_timingpoints!(async AsyncBufReader<AsyncRead>);
-    parse_hitobjects!(async AsyncBufReader<AsyncRead>);
+    parse!(async AsyncBufReader<AsyncRead, AsyncBufRead>);
+    parse_general!(async AsyncBufRead);
+    parse_difficulty!(async AsyncBufRead);
+    parse_timingpoints!(async AsyncBufRead);
+    parse_hitobjects!(async AsyncBufRead);
 
     from_path!(async Path);
 }
@@ -1057,20 +1846,66 @@ fn skip_line(line: &str) -> bool {
     line.is_empty() || line.starts_with("//") || line.starts_with(' ') || line.starts_with('_')
 }
 
+/// Byte order mark (U+FEFF) which some maps contain at the start of lines.
+const BOM: char = '\u{feff}';
+
+/// Effect flag of timing points to omit the first bar line.
+#[cfg(feature = "sliders")]
+const OMIT_FIRST_BAR_LINE: u32 = 1 << 3;
+
+/// Parse a trailing field that may be missing or empty.
+#[cfg(feature = "sliders")]
+fn optional_field<T: std::str::FromStr>(field: Option<&str>) -> Result<Option<T>, T::Err> {
+    match field.map(str::trim) {
+        Some(field) if !field.is_empty() => field.parse().map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Trim whitespace and byte order marks on both sides.
+#[inline]
+fn trim(s: &str) -> &str {
+    s.trim_matches(|c: char| c.is_whitespace() || c == BOM)
+}
+
+/// Split a `Key:Value` line, tolerating whitespace around
+/// the colon as seen in old maps, e.g. `Key : Value`.
+///
+/// Only the first colon separates key and value so that
+/// values may contain colons themselves, e.g. `Title: Foo: Bar`.
 #[inline]
 fn split_colon(line: &str) -> Option<(&str, &str)> {
-    let mut split = line.split(':');
+    let (key, value) = line.split_once(':')?;
+
+    Some((trim(key), trim(value)))
+}
+
+/// The part of a numeric value in front of another colon, e.g. `1` for `Mode: 1:2`.
+///
+/// Since [`split_colon`] keeps further colons in the value, a warning is
+/// recorded whenever something is cut off.
+fn leading_value<'v>(key: &str, value: &'v str, warnings: &mut Vec<ParseWarning>) -> &'v str {
+    match value.split_once(':') {
+        Some((leading, _)) => {
+            warnings.push(ParseWarning::ValueTruncated {
+                name: key.to_owned(),
+                value: value.to_owned(),
+            });
 
-    Some((split.next()?, split.next()?.trim()))
+            trim(leading)
+        }
+        None => value,
+    }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 enum Section {
     None,
     General,
     Difficulty,
     TimingPoints,
     HitObjects,
+    Other(String),
 }
 
 impl Section {
@@ -1081,7 +1916,7 @@ impl Section {
             "Difficulty" => Self::Difficulty,
             "TimingPoints" => Self::TimingPoints,
             "HitObjects" => Self::HitObjects,
-            _ => Self::None,
+            other => Self::Other(other.to_owned()),
         }
     }
 }
@@ -1106,6 +1941,510 @@ mod tests {
         }
     }
 
+    #[cfg(all(
+        feature = "mmap",
+        not(any(feature = "async_std", feature = "async_tokio"))
+    ))]
+    #[test]
+    fn parsing_mmap() {
+        for map_id in map_ids() {
+            let path = format!("./maps/{}.osu", map_id);
+            let map = Beatmap::from_path(&path).expect("failed to parse map");
+
+            // SAFETY: The test maps are not modified
+            let mapped = unsafe { Beatmap::from_path_mmap(&path) }.expect("failed to parse map");
+
+            assert_eq!(mapped.hit_objects, map.hit_objects);
+            assert_eq!(mapped.od, map.od);
+        }
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn parsing_sections() {
+        for map_id in map_ids() {
+            println!("map_id: {}", map_id);
+
+            let path = format!("./maps/{}.osu", map_id);
+            let full = Beatmap::from_path(&path).expect("failed to parse map");
+
+            let options = ParseOptions::new().sections(Sections::DIFFICULTY);
+            let file = File::open(&path).expect("failed to open file");
+            let mut map = Beatmap::parse_with_options(file, options).expect("failed to parse map");
+
+            assert!(map
+                .parsed_sections
+                .contains(Sections::GENERAL | Sections::DIFFICULTY));
+            assert!(!map.parsed_sections.contains(Sections::HIT_OBJECTS));
+            assert!(map.hit_objects.is_empty());
+            assert_eq!(map.mode, full.mode);
+            assert_eq!(map.od, full.od);
+
+            let file = File::open(&path).expect("failed to open file");
+            map.parse_remaining(file)
+                .expect("failed to parse remaining");
+
+            assert_eq!(map.parsed_sections, Sections::ALL);
+            assert_eq!(map.hit_objects.len(), full.hit_objects.len());
+            assert_eq!(map.n_circles, full.n_circles);
+        }
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn parsing_lenient_spacing() {
+        let content = "\u{feff}osu file format v5\n\
+            \n\
+            \u{feff}[General]\n\
+            Mode : 3\n\
+            \n\
+            [Difficulty]\n\
+            \u{feff}HPDrainRate:5\n\
+            CircleSize : 4\n\
+            OverallDifficulty :8\n\
+            SliderMultiplier: 1.4\n\
+            SliderTickRate:1\n\
+            \n\
+            [HitObjects]\n\
+            64, 192, 1000, 1, 0\n\
+            192,192,1500,128,0,2000:0:0:0:\n";
+
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+
+        assert_eq!(map.version, 5);
+        assert_eq!(map.mode, GameMode::MNA);
+        assert_eq!(map.hp, 5.0);
+        assert_eq!(map.cs, 4.0);
+        assert_eq!(map.od, 8.0);
+        assert_eq!(map.hit_objects.len(), 2);
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn parsing_errors() {
+        use std::error::Error;
+
+        let content = "osu file format v14\n\
+            \n\
+            [Difficulty]\n\
+            OverallDifficulty:abc\n";
+
+        let err = Beatmap::parse(content.as_bytes()).unwrap_err();
+
+        assert!(!err.is_io());
+        assert_eq!(err.section(), Some(Sections::DIFFICULTY));
+        assert_eq!(err.to_string(), "invalid `[Difficulty]` section");
+
+        let source = err.source().expect("missing format error");
+        assert_eq!(source.to_string(), "invalid float number");
+        assert!(source.source().is_some());
+
+        let err = Beatmap::parse(&b"not a map"[..]).unwrap_err();
+
+        assert_eq!(err.section(), Some(Sections::NONE));
+        assert!(matches!(
+            err,
+            ParseError::Format {
+                source: FormatError::IncorrectFileHeader,
+                ..
+            }
+        ));
+
+        let io = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        let err = ParseError::from(io);
+
+        assert!(err.is_io());
+        assert_eq!(err.io_kind(), Some(std::io::ErrorKind::TimedOut));
+        assert_eq!(err.section(), None);
+    }
+
+    #[test]
+    fn split_colon_first_only() {
+        assert_eq!(split_colon("Title: Foo: Bar"), Some(("Title", "Foo: Bar")));
+        assert_eq!(split_colon("Tags:a:b:c"), Some(("Tags", "a:b:c")));
+        assert_eq!(split_colon("Mode : 3"), Some(("Mode", "3")));
+        assert_eq!(split_colon("Version:"), Some(("Version", "")));
+        assert_eq!(split_colon("NoColon"), None);
+    }
+
+    #[cfg(feature = "sliders")]
+    #[test]
+    fn control_point_ranges() {
+        // Leftovers of a previous slider are cleared
+        let mut ranges = vec![0..1, 1..2];
+
+        let points = "B|1:2||30:40|P";
+        split_points(points, &mut ranges);
+        let split: Vec<_> = ranges.iter().map(|range| &points[range.clone()]).collect();
+        assert_eq!(split, points.split('|').collect::<Vec<_>>());
+
+        split_points("", &mut ranges);
+        assert_eq!(ranges.len(), 1);
+        assert!(ranges[0].is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn parse_limits() {
+        let content = std::fs::read_to_string("./maps/2785319.osu").expect("failed to read map");
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+
+        let parse = |limits: ParseLimits| {
+            let options = ParseOptions::new().limits(limits);
+
+            Beatmap::parse_with_options(content.as_bytes(), options)
+        };
+
+        let exact = ParseLimits {
+            bytes: content.len(),
+            line_len: content.lines().map(|line| line.len() + 2).max().unwrap(),
+            hit_objects: map.hit_objects.len(),
+            #[cfg(feature = "sliders")]
+            timing_points: map.timing_points.len() + map.difficulty_points.len(),
+            ..Default::default()
+        };
+
+        assert!(parse(exact).is_ok());
+
+        let err = parse(ParseLimits {
+            bytes: content.len() - 1,
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(err.code(), ParseErrorCode::LimitExceeded);
+
+        let err = parse(ParseLimits {
+            line_len: 16,
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err
+            .message()
+            .contains("more than 16 bytes in a single line"));
+
+        // The size is also limited in lenient mode, which reads the whole file at once
+        let lenient = |bytes: usize| {
+            let options = ParseOptions::new().lenient(true).limits(ParseLimits {
+                bytes,
+                ..Default::default()
+            });
+
+            Beatmap::parse_with_options(content.as_bytes(), options)
+        };
+
+        assert!(lenient(content.len()).is_ok());
+        let err = lenient(content.len() - 1).unwrap_err();
+        assert_eq!(err.code(), ParseErrorCode::LimitExceeded);
+
+        let err = parse(ParseLimits {
+            hit_objects: map.hit_objects.len() - 1,
+            ..ParseLimits::UNLIMITED
+        })
+        .unwrap_err();
+        assert_eq!(err.code(), ParseErrorCode::LimitExceeded);
+
+        #[cfg(feature = "sliders")]
+        {
+            let err = parse(ParseLimits {
+                timing_points: 1,
+                ..Default::default()
+            })
+            .unwrap_err();
+            assert_eq!(err.code(), ParseErrorCode::LimitExceeded);
+            assert!(err.message().contains("more than 1 timing points"));
+
+            let err = parse(ParseLimits {
+                control_points: 1,
+                ..Default::default()
+            })
+            .unwrap_err();
+            assert!(err.message().contains("more than 1 slider control points"));
+
+            let err = parse(ParseLimits {
+                repeats: 0,
+                ..Default::default()
+            })
+            .unwrap_err();
+            assert_eq!(err.code(), ParseErrorCode::LimitExceeded);
+            assert!(err.message().contains("more than 0 slider repeats"));
+        }
+
+        // Skipped sections are not limited
+        let options = ParseOptions::new()
+            .sections(Sections::ALL & !Sections::HIT_OBJECTS)
+            .limits(ParseLimits {
+                hit_objects: 0,
+                ..Default::default()
+            });
+        let mut partial =
+            Beatmap::parse_with_options(content.as_bytes(), options).expect("failed to parse map");
+
+        // ... unless the limits are passed when parsing them later on
+        let err = partial
+            .parse_remaining_with_options(
+                content.as_bytes(),
+                ParseOptions::new().limits(options.limits),
+            )
+            .unwrap_err();
+        assert_eq!(err.code(), ParseErrorCode::LimitExceeded);
+        assert!(!partial.parsed_sections.contains(Sections::HIT_OBJECTS));
+
+        partial
+            .parse_remaining(content.as_bytes())
+            .expect("failed to parse remaining");
+        assert_eq!(partial.hit_objects.len(), map.hit_objects.len());
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn general_after_hitobjects() {
+        let content = std::fs::read_to_string("./maps/1974394.osu").expect("failed to read map");
+
+        let start = content.find("[General]").unwrap();
+        let end = start + content[start + 1..].find("\n[").unwrap() + 1;
+        let reordered = format!(
+            "{}{}\n{}",
+            &content[..start],
+            &content[end + 1..],
+            &content[start..end]
+        );
+
+        let expected = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+        let map = Beatmap::parse(reordered.as_bytes()).expect("failed to parse reordered map");
+
+        assert_eq!(map.mode, GameMode::MNA);
+        assert_eq!(map.cs, expected.cs);
+        assert_eq!(map.hit_objects, expected.hit_objects);
+
+        // The legacy sort must have been applied
+        assert!(expected
+            .hit_objects
+            .windows(2)
+            .any(|pair| pair[1].file_idx < pair[0].file_idx));
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn bpm_with_clock_rate() {
+        let content = "osu file format v14\n\
+            \n\
+            [TimingPoints]\n\
+            0,500,4,2,0,50,1,0\n\
+            1000,-50,4,2,0,50,0,0\n\
+            2000,400,4,2,0,50,1,0\n\
+            3000,600,4,2,0,50,1,0\n\
+            \n\
+            [HitObjects]\n\
+            0,0,1000,1,0\n";
+
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+
+        assert_eq!(map.bpm(), 120.0);
+        assert_eq!(map.bpm_with(1.5), 180.0);
+        assert_eq!(map.bpm_with(0.75), 90.0);
+
+        if cfg!(feature = "sliders") {
+            assert_eq!(map.bpm_range(), (100.0, 150.0));
+            assert_eq!(map.bpm_range_with(1.5), (150.0, 225.0));
+        } else {
+            assert_eq!(map.bpm_range_with(0.75), (90.0, 90.0));
+        }
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn lenient_parsing() {
+        let content = std::fs::read_to_string("./maps/2785319.osu").expect("failed to read map");
+        let expected = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+        let lenient = ParseOptions::new().lenient(true);
+
+        // Mixed line endings, NUL bytes, and invalid UTF-8 within a comment
+        let mut damaged = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            damaged.extend_from_slice(line.as_bytes());
+
+            match i % 3 {
+                0 => damaged.extend_from_slice(b"\r\n"),
+                1 => damaged.push(b'\r'),
+                _ => damaged.extend_from_slice(b"\0\n"),
+            }
+
+            if i == 1 {
+                damaged.extend_from_slice(b"// \xFF\xFE\n");
+            }
+        }
+
+        assert!(Beatmap::parse(damaged.as_slice()).is_err());
+
+        let map = Beatmap::parse_with_options(damaged.as_slice(), lenient)
+            .expect("failed to parse damaged map");
+
+        assert_eq!(map.hit_objects, expected.hit_objects);
+        assert_eq!(map.bpm(), expected.bpm());
+        assert!(map.warnings.contains(&ParseWarning::InvalidUtf8));
+        assert!(matches!(map.warnings[1], ParseWarning::NulBytes { .. }));
+
+        // Truncated in the middle of the last hit object
+        let truncated = &content.trim_end()[..content.trim_end().len() - 30];
+        let map = Beatmap::parse_with_options(truncated.as_bytes(), lenient)
+            .expect("failed to parse truncated map");
+
+        assert_eq!(map.hit_objects.len(), expected.hit_objects.len() - 1);
+        assert!(matches!(
+            map.warnings.last(),
+            Some(ParseWarning::LineDropped { .. })
+        ));
+
+        // Trailing garbage
+        let garbage = format!("{}\n\u{1}garbage,,,\n\0\0", content);
+        let map = Beatmap::parse_with_options(garbage.as_bytes(), lenient)
+            .expect("failed to parse map with garbage");
+
+        assert_eq!(map.hit_objects, expected.hit_objects);
+
+        // Damage that is not at the end still fails
+        let broken = content.replacen("[HitObjects]\n", "[HitObjects]\ngarbage\n", 1);
+        assert!(Beatmap::parse_with_options(broken.as_bytes(), lenient).is_err());
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn sorting_simultaneous_objects() {
+        let content = "osu file format v14\n\
+            \n\
+            [General]\n\
+            Mode: 0\n\
+            \n\
+            [HitObjects]\n\
+            0,0,2000,1,0\n\
+            1,0,1000,1,0\n\
+            2,0,1000,1,0\n\
+            3,0,500,1,0\n\
+            4,0,1000,1,0\n";
+
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+
+        let file_indices: Vec<_> = map.hit_objects.iter().map(|h| h.file_idx).collect();
+        assert_eq!(file_indices, [3, 1, 2, 4, 0]);
+
+        for h in map.hit_objects.iter() {
+            assert_eq!(h.pos.x as usize, h.file_idx);
+        }
+    }
+
+    #[cfg(all(
+        feature = "taiko",
+        not(any(feature = "async_std", feature = "async_tokio"))
+    ))]
+    #[test]
+    fn parsing_colons_in_values() {
+        let content = "osu file format v14\n\
+            \n\
+            [General]\n\
+            AudioFilename: re:start.mp3\n\
+            Mode: 1\n\
+            StackLeniency: 0.5\n\
+            \n\
+            [Metadata]\n\
+            Title:Re:Zero: Starting Life\n\
+            Version:Oni: Inner\n\
+            \n\
+            [Difficulty]\n\
+            HPDrainRate:6\n\
+            CircleSize:2\n\
+            OverallDifficulty:5\n\
+            ApproachRate:10\n\
+            SliderMultiplier:1.4\n\
+            SliderTickRate:1\n\
+            \n\
+            [HitObjects]\n\
+            256,192,1000,1,0,0:0:0:0:\n";
+
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+
+        assert_eq!(map.mode, GameMode::TKO);
+        #[cfg(feature = "osu")]
+        assert_eq!(map.stack_leniency, 0.5);
+        assert_eq!(map.od, 5.0);
+        assert_eq!(map.hit_objects.len(), 1);
+
+        assert!(map.warnings.is_empty());
+
+        // Numeric values use their leading part and record a warning
+        let content = content
+            .replace("Mode: 1\n", "Mode: 1:2\n")
+            .replace("OverallDifficulty:5\n", "OverallDifficulty:5:7\n");
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+
+        assert_eq!(map.mode, GameMode::TKO);
+        assert_eq!(map.od, 5.0);
+        assert_eq!(
+            map.warnings,
+            vec![
+                ParseWarning::ValueTruncated {
+                    name: "Mode".to_owned(),
+                    value: "1:2".to_owned(),
+                },
+                ParseWarning::ValueTruncated {
+                    name: "OverallDifficulty".to_owned(),
+                    value: "5:7".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn parsing_with_buffers() {
+        let mut buffers = ParseBuffers::new();
+
+        // Parse every map twice so that the buffers are dirty from a previous map
+        for map_id in map_ids().into_iter().chain(map_ids()) {
+            let path = format!("./maps/{}.osu", map_id);
+            let file = File::open(&path).expect("failed to open map");
+            let map = Beatmap::parse_with(file, &mut buffers).expect("failed to parse map");
+            let expected = Beatmap::from_path(&path).expect("failed to parse map");
+
+            assert_eq!(map.mode, expected.mode);
+            assert_eq!(map.version, expected.version);
+            assert_eq!(map.hit_objects, expected.hit_objects);
+
+            #[cfg(feature = "sliders")]
+            {
+                assert_eq!(map.timing_points, expected.timing_points);
+                assert_eq!(map.difficulty_points, expected.difficulty_points);
+            }
+        }
+
+        assert!(buffers.line.capacity() > 0);
+    }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn parsing_with_sink() {
+        for map_id in map_ids() {
+            let path = format!("./maps/{}.osu", map_id);
+            let file = File::open(&path).expect("failed to open map");
+            let mut hit_objects = Vec::new();
+
+            let map = Beatmap::parse_with_sink(file, |h| hit_objects.push(h))
+                .expect("failed to parse map");
+            let expected = Beatmap::from_path(&path).expect("failed to parse map");
+
+            assert!(map.hit_objects.is_empty(), "{}", map_id);
+            assert_eq!(map.n_circles, expected.n_circles, "{}", map_id);
+            assert_eq!(map.n_sliders, expected.n_sliders, "{}", map_id);
+            #[cfg(feature = "sliders")]
+            assert_eq!(map.timing_points, expected.timing_points, "{}", map_id);
+            assert_eq!(hit_objects.len(), expected.hit_objects.len(), "{}", map_id);
+
+            if map.mode != GameMode::MNA {
+                assert_eq!(hit_objects, expected.hit_objects, "{}", map_id);
+            }
+        }
+    }
+
     #[cfg(feature = "async_tokio")]
     #[test]
     fn parsing_async_tokio() {
@@ -1129,6 +2468,31 @@ mod tests {
             });
     }
 
+    #[cfg(feature = "async_tokio")]
+    #[test]
+    fn parsing_async_tokio_buffered() {
+        use tokio::runtime::Builder;
+
+        Builder::new_current_thread()
+            .build()
+            .expect("could not start runtime")
+            .block_on(async {
+                for map_id in map_ids() {
+                    let path = format!("./maps/{}.osu", map_id);
+                    let bytes = std::fs::read(&path).expect("failed to read map");
+
+                    let map = Beatmap::from_path(&path).await.unwrap();
+                    let buffered = Beatmap::parse_buffered(&bytes[..]).await.unwrap();
+                    let small = Beatmap::from_reader_with_capacity(&bytes[..], 64)
+                        .await
+                        .unwrap();
+
+                    assert_eq!(buffered.hit_objects, map.hit_objects, "{}", map_id);
+                    assert_eq!(small.hit_objects, map.hit_objects, "{}", map_id);
+                }
+            });
+    }
+
     #[cfg(feature = "async_std")]
     #[test]
     fn parsing_async_std() {
@@ -1147,6 +2511,26 @@ mod tests {
         });
     }
 
+    #[cfg(feature = "async_std")]
+    #[test]
+    fn parsing_async_std_buffered() {
+        async_std::task::block_on(async {
+            for map_id in map_ids() {
+                let path = format!("./maps/{}.osu", map_id);
+                let bytes = std::fs::read(&path).expect("failed to read map");
+
+                let map = Beatmap::from_path(&path).await.unwrap();
+                let buffered = Beatmap::parse_buffered(&bytes[..]).await.unwrap();
+                let small = Beatmap::from_reader_with_capacity(&bytes[..], 64)
+                    .await
+                    .unwrap();
+
+                assert_eq!(buffered.hit_objects, map.hit_objects, "{}", map_id);
+                assert_eq!(small.hit_objects, map.hit_objects, "{}", map_id);
+            }
+        });
+    }
+
     fn map_ids() -> Vec<i32> {
         let mut map_ids = Vec::new();
 
@@ -1191,4 +2575,38 @@ mod tests {
             println!("difficulty_points: {}", map.difficulty_points.len());
         }
     }
+
+    #[cfg(not(any(feature = "async_std", feature = "async_tokio")))]
+    #[test]
+    fn objects_by_time() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let first = map.hit_objects[0].start_time;
+        let last = map.hit_objects.last().unwrap().start_time;
+
+        assert_eq!(map.index_at_time(f64::NEG_INFINITY), 0);
+        assert_eq!(map.index_at_time(first), 0);
+        assert_eq!(map.index_at_time(first + 1.0), 1);
+        assert_eq!(map.index_at_time(last + 1.0), map.hit_objects.len());
+
+        let idx = map.hit_objects.len() / 2;
+        let h = map.object_at(idx).unwrap();
+        assert_eq!(map.index_at_time(h.start_time), idx);
+        assert!(map.object_at(map.hit_objects.len()).is_none());
+
+        assert_eq!(map.objects_between(first, last + 1.0), &map.hit_objects[..]);
+        assert_eq!(map.objects_between(first, first).len(), 0);
+        assert_eq!(map.objects_between(last, first).len(), 0);
+
+        let between = map.objects_between(10_000.0, 20_000.0);
+        assert!(between
+            .iter()
+            .all(|h| h.start_time >= 10_000.0 && h.start_time < 20_000.0));
+        assert_eq!(
+            between.len(),
+            map.hit_objects
+                .iter()
+                .filter(|h| (10_000.0..20_000.0).contains(&h.start_time))
+                .count()
+        );
+    }
 }
@@ -0,0 +1,108 @@
+use std::{convert::TryFrom, error::Error as StdError, fmt, str::FromStr};
+
+use super::GameMode;
+
+impl GameMode {
+    /// The lowercase name of the mode, i.e. `osu`, `taiko`, `fruits`, or `mania`.
+    #[inline]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::STD => "osu",
+            Self::TKO => "taiko",
+            Self::CTB => "fruits",
+            Self::MNA => "mania",
+        }
+    }
+}
+
+impl fmt::Display for GameMode {
+    /// Writes the mode's [`name`](GameMode::name).
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<GameMode> for u8 {
+    #[inline]
+    fn from(mode: GameMode) -> Self {
+        mode as u8
+    }
+}
+
+impl TryFrom<u8> for GameMode {
+    type Error = UnknownModeError;
+
+    /// Convert the mode's value as used in `.osu` files and the osu!api.
+    #[inline]
+    fn try_from(mode: u8) -> Result<Self, Self::Error> {
+        match mode {
+            0 => Ok(Self::STD),
+            1 => Ok(Self::TKO),
+            2 => Ok(Self::CTB),
+            3 => Ok(Self::MNA),
+            _ => Err(UnknownModeError {
+                mode: mode.to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = UnknownModeError;
+
+    /// Parse the mode from its name or its numeric value.
+    ///
+    /// Names are case-insensitive. Next to the [`name`](GameMode::name) of each mode,
+    /// common aliases are accepted such as `std`, `catch`, or `ctb`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "0" | "osu" | "std" | "standard" => Ok(Self::STD),
+            "1" | "taiko" | "tko" => Ok(Self::TKO),
+            "2" | "fruits" | "catch" | "ctb" => Ok(Self::CTB),
+            "3" | "mania" | "mna" => Ok(Self::MNA),
+            _ => Err(UnknownModeError { mode: s.to_owned() }),
+        }
+    }
+}
+
+/// A mode that is not known, see [`GameMode`]'s conversions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownModeError {
+    /// The given mode.
+    pub mode: String,
+}
+
+impl fmt::Display for UnknownModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown mode `{}`", self.mode)
+    }
+}
+
+impl StdError for UnknownModeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_conversions() {
+        for &mode in [GameMode::STD, GameMode::TKO, GameMode::CTB, GameMode::MNA].iter() {
+            assert_eq!(GameMode::try_from(u8::from(mode)), Ok(mode));
+            assert_eq!(mode.to_string().parse(), Ok(mode));
+            assert_eq!(u8::from(mode).to_string().parse(), Ok(mode));
+        }
+
+        assert_eq!("Catch".parse(), Ok(GameMode::CTB));
+        assert_eq!(" STD ".parse(), Ok(GameMode::STD));
+        assert_eq!(GameMode::MNA.to_string(), "mania");
+
+        assert_eq!(
+            GameMode::try_from(4),
+            Err(UnknownModeError {
+                mode: "4".to_owned()
+            })
+        );
+        assert!("osu!".parse::<GameMode>().is_err());
+    }
+}
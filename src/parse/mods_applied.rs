@@ -0,0 +1,129 @@
+use super::{Beatmap, HitObjectKind};
+use crate::{
+    util::{od_from_osu_great_hit_window, osu_great_hit_window},
+    GameMode, Mods,
+};
+
+impl Beatmap {
+    /// Create a copy of the map with the given mods baked into it.
+    ///
+    /// All times are divided by the clock rate, i.e. objects, timing points, and
+    /// BPM reflect DT or HT. AR and OD are adjusted to their hit windows at that
+    /// rate, with OD being based on the osu!standard 300 hit window. HR and EZ are
+    /// applied to AR, OD, and HP and, except for osu!mania whose CS is the key count,
+    /// to CS. In osu!standard, HR also mirrors the objects vertically.
+    ///
+    /// The result is useful to export practice difficulties or for tools that can't
+    /// pass a clock rate through their pipeline. Calculating it without mods closely
+    /// approximates calculating the original map with the mods. The random offsets
+    /// that HR adds to osu!catch fruits are not applied and neither are the
+    /// [`raw_sections`](Beatmap::raw_sections) updated.
+    ///
+    /// ```
+    /// use akatsuki_pp::Beatmap;
+    ///
+    /// # let map = Beatmap::default();
+    /// // Bake HRDT into the map
+    /// let practice = map.with_mods_applied(16 + 64);
+    ///
+    /// assert!(practice.ar >= map.ar);
+    /// ```
+    pub fn with_mods_applied(&self, mods: impl Mods) -> Self {
+        let mut map = self.clone();
+        let attributes = self.attributes().mods(mods);
+        let clock_rate = attributes.clock_rate;
+
+        map.ar = attributes.ar as f32;
+        map.od =
+            od_from_osu_great_hit_window(osu_great_hit_window(attributes.od) / clock_rate) as f32;
+        map.hp = attributes.hp as f32;
+
+        if self.mode != GameMode::MNA {
+            map.cs = attributes.cs as f32;
+        }
+
+        let flip = self.mode == GameMode::STD && mods.hr();
+
+        for h in map.hit_objects.iter_mut() {
+            h.start_time /= clock_rate;
+
+            if flip {
+                h.pos.y = 384.0 - h.pos.y;
+            }
+
+            match &mut h.kind {
+                HitObjectKind::Circle => {}
+                #[cfg(feature = "sliders")]
+                HitObjectKind::Slider { control_points, .. } => {
+                    // Control points are relative to the head so they're mirrored around it
+                    if flip {
+                        for point in control_points.iter_mut() {
+                            point.pos.y = -point.pos.y;
+                        }
+                    }
+                }
+                #[cfg(not(feature = "sliders"))]
+                HitObjectKind::Slider { .. } => {}
+                HitObjectKind::Spinner { end_time } | HitObjectKind::Hold { end_time } => {
+                    *end_time /= clock_rate;
+                }
+            }
+        }
+
+        #[cfg(feature = "sliders")]
+        {
+            for point in map.timing_points.iter_mut() {
+                point.time /= clock_rate;
+                point.beat_len /= clock_rate;
+            }
+
+            for point in map.difficulty_points.iter_mut() {
+                point.time /= clock_rate;
+            }
+        }
+
+        #[cfg(not(feature = "sliders"))]
+        {
+            map.bpm *= clock_rate;
+        }
+
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn with_mods_applied() {
+        use crate::BeatmapExt;
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        let nomod = map.with_mods_applied(0);
+        assert_eq!(nomod.hit_objects, map.hit_objects);
+        assert_eq!((nomod.ar, nomod.od, nomod.cs), (map.ar, map.od, map.cs));
+
+        let dt = map.with_mods_applied(64);
+
+        for (applied, original) in dt.hit_objects.iter().zip(map.hit_objects.iter()) {
+            assert!((applied.start_time - original.start_time / 1.5).abs() < 1e-9);
+        }
+
+        let expected = map.stars(64, None);
+        assert!((dt.ar as f64 - expected.ar().unwrap()).abs() < 1e-5);
+        assert!((dt.stars(0, None).stars() - expected.stars()).abs() < 0.05);
+
+        let hr = map.with_mods_applied(16);
+        assert_eq!(hr.hit_objects[0].pos.y, 384.0 - map.hit_objects[0].pos.y);
+
+        let expected = map.stars(16, None).stars();
+        assert!((hr.stars(0, None).stars() - expected).abs() < 0.05);
+    }
+}
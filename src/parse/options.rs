@@ -0,0 +1,340 @@
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr, BitOrAssign, Not},
+};
+
+use super::FormatError;
+
+/// Set of `.osu` file sections that should be parsed.
+///
+/// Sections can be combined through `|`, e.g. `Sections::GENERAL | Sections::DIFFICULTY`.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Sections(u8);
+
+impl Sections {
+    /// No section at all.
+    pub const NONE: Self = Self(0);
+    /// The `[General]` section, containing e.g. the mode and stack leniency.
+    pub const GENERAL: Self = Self(1 << 0);
+    /// The `[Difficulty]` section, containing AR, OD, CS, HP, and slider values.
+    pub const DIFFICULTY: Self = Self(1 << 1);
+    /// The `[TimingPoints]` section.
+    pub const TIMING_POINTS: Self = Self(1 << 2);
+    /// The `[HitObjects]` section.
+    pub const HIT_OBJECTS: Self = Self(1 << 3);
+    /// All sections that are relevant for difficulty and performance calculation.
+    pub const ALL: Self =
+        Self(Self::GENERAL.0 | Self::DIFFICULTY.0 | Self::TIMING_POINTS.0 | Self::HIT_OBJECTS.0);
+
+    /// Check whether all sections of `other` are contained in `self`.
+    #[inline]
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check whether no section is contained.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Name of the section as it appears in the `.osu` file, if it's a single section.
+    pub(crate) fn name(self) -> Option<&'static str> {
+        match self {
+            Self::GENERAL => Some("General"),
+            Self::DIFFICULTY => Some("Difficulty"),
+            Self::TIMING_POINTS => Some("TimingPoints"),
+            Self::HIT_OBJECTS => Some("HitObjects"),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for Sections {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Sections {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Sections {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for Sections {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(!self.0 & Self::ALL.0)
+    }
+}
+
+/// Options to specify how a [`Beatmap`](crate::Beatmap) should be parsed.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{Beatmap, ParseOptions, parse::Sections};
+///
+/// # /*
+/// let bytes: &[u8] = ...
+/// # */
+/// # let bytes: &[u8] = b"osu file format v14\n";
+///
+/// // Only interested in the difficulty values, no need to parse hit objects
+/// let options = ParseOptions::new().sections(Sections::DIFFICULTY);
+/// # #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+/// let map = Beatmap::parse_with_options(bytes, options).unwrap();
+/// # #[cfg(any(feature = "async_tokio", feature = "async_std"))]
+/// # let map = Beatmap { parsed_sections: Sections::DIFFICULTY, ..Default::default() };
+///
+/// assert!(!map.parsed_sections.contains(Sections::HIT_OBJECTS));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub(crate) sections: Sections,
+    pub(crate) raw_sections: bool,
+    pub(crate) lenient: bool,
+    pub(crate) limits: ParseLimits,
+    #[cfg(feature = "sliders")]
+    pub(crate) slider_length: SliderLengthPolicy,
+}
+
+impl ParseOptions {
+    /// Create new options that parse all sections.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specify which sections should be parsed.
+    ///
+    /// The `[General]` section is always included since the game mode
+    /// is required to properly process the other sections.
+    #[inline]
+    pub fn sections(mut self, sections: Sections) -> Self {
+        self.sections = sections | Sections::GENERAL;
+
+        self
+    }
+
+    /// Specify whether sections that are not parsed, e.g. `[Colours]`, `[Editor]`,
+    /// or custom ones, should be kept as raw text in
+    /// [`Beatmap::raw_sections`](crate::Beatmap::raw_sections).
+    ///
+    /// Since the whole file has to be read, this prevents skipping
+    /// the rest of the file once all requested sections are parsed.
+    /// Sections that are known but excluded through [`sections`](ParseOptions::sections)
+    /// are not captured. Disabled by default.
+    #[inline]
+    pub fn raw_sections(mut self, capture: bool) -> Self {
+        self.raw_sections = capture;
+
+        self
+    }
+
+    /// Specify whether damaged files should be parsed on a best-effort basis
+    /// instead of failing, e.g. when processing files from bulk mirrors.
+    ///
+    /// In lenient mode, the whole input is read into memory first. Invalid UTF-8
+    /// is replaced, NUL bytes are removed, and lone `\r` are treated as line endings.
+    /// If parsing fails, it is retried once without the last line of the file to
+    /// handle files that were truncated mid-line or have trailing garbage.
+    /// Each such repair is recorded in [`Beatmap::warnings`](crate::Beatmap::warnings).
+    /// Disabled by default.
+    #[inline]
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+
+        self
+    }
+
+    /// Specify upper bounds on the content of the file so that public-facing
+    /// services can bound the resources that parsing a single file requires.
+    ///
+    /// Sections that are skipped and parsed later on are only limited if these
+    /// options are passed to `Beatmap::parse_remaining_with_options` as well.
+    #[inline]
+    pub fn limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+
+        self
+    }
+
+    /// Specify whether the length of sliders is taken from the
+    /// `.osu` file or calculated from their control points.
+    ///
+    /// Only applies to hit objects that are parsed with these options, i.e.
+    /// also through `Beatmap::parse_remaining_with_options` but not through
+    /// `Beatmap::parse_remaining`.
+    #[cfg(feature = "sliders")]
+    #[inline]
+    pub fn slider_length(mut self, policy: SliderLengthPolicy) -> Self {
+        self.slider_length = policy;
+
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            sections: Sections::ALL,
+            raw_sections: false,
+            lenient: false,
+            limits: ParseLimits::default(),
+            #[cfg(feature = "sliders")]
+            slider_length: SliderLengthPolicy::default(),
+        }
+    }
+}
+
+/// Upper bounds on the content of a `.osu` file, see [`ParseOptions::limits`].
+///
+/// Exceeding a limit fails with [`FormatError::LimitExceeded`]. By default,
+/// only the repeats of sliders are limited.
+///
+/// ```
+/// use akatsuki_pp::{parse::ParseLimits, ParseOptions};
+///
+/// let mut limits = ParseLimits::default();
+/// limits.bytes = 10 * 1024 * 1024;
+/// limits.hit_objects = 50_000;
+///
+/// let options = ParseOptions::new().limits(limits);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseLimits {
+    /// The maximum size of the file in bytes.
+    ///
+    /// Unlike the other limits, this one also applies to
+    /// [lenient](ParseOptions::lenient) parsing which reads the whole file first.
+    pub bytes: usize,
+    /// The maximum length of a single line in bytes, including its line break.
+    ///
+    /// In lenient mode, this applies to the lines after they've been repaired.
+    pub line_len: usize,
+    /// The maximum amount of hit objects.
+    pub hit_objects: usize,
+    /// The maximum amount of timing points, including inherited ones.
+    ///
+    /// Without the `sliders` feature, only the first timing point is parsed
+    /// so this limit does not apply.
+    pub timing_points: usize,
+    /// The maximum amount of control points of a single slider.
+    ///
+    /// Control points are only parsed with the `sliders` feature.
+    pub control_points: usize,
+    /// The maximum amount of repeats of a single slider.
+    ///
+    /// Repeats are only considered with the `sliders` feature.
+    pub repeats: usize,
+}
+
+impl ParseLimits {
+    /// No limits at all.
+    pub const UNLIMITED: Self = Self {
+        bytes: usize::MAX,
+        line_len: usize::MAX,
+        hit_objects: usize::MAX,
+        timing_points: usize::MAX,
+        control_points: usize::MAX,
+        repeats: usize::MAX,
+    };
+
+    /// Check that `count` does not exceed the limit of the given kind.
+    pub(crate) fn check(&self, kind: ParseLimitKind, count: usize) -> Result<(), FormatError> {
+        let limit = match kind {
+            ParseLimitKind::Bytes => self.bytes,
+            ParseLimitKind::LineLength => self.line_len,
+            ParseLimitKind::HitObjects => self.hit_objects,
+            ParseLimitKind::TimingPoints => self.timing_points,
+            ParseLimitKind::ControlPoints => self.control_points,
+            ParseLimitKind::Repeats => self.repeats,
+        };
+
+        if count > limit {
+            Err(FormatError::LimitExceeded { kind, limit })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for ParseLimits {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            repeats: 9000,
+            ..Self::UNLIMITED
+        }
+    }
+}
+
+/// The kind of content whose [`ParseLimits`] were exceeded.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ParseLimitKind {
+    /// See [`ParseLimits::bytes`].
+    Bytes,
+    /// See [`ParseLimits::line_len`].
+    LineLength,
+    /// See [`ParseLimits::hit_objects`].
+    HitObjects,
+    /// See [`ParseLimits::timing_points`].
+    TimingPoints,
+    /// See [`ParseLimits::control_points`].
+    ControlPoints,
+    /// See [`ParseLimits::repeats`].
+    Repeats,
+}
+
+impl fmt::Display for ParseLimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bytes => f.write_str("bytes"),
+            Self::LineLength => f.write_str("bytes in a single line"),
+            Self::HitObjects => f.write_str("hit objects"),
+            Self::TimingPoints => f.write_str("timing points"),
+            Self::ControlPoints => f.write_str("slider control points"),
+            Self::Repeats => f.write_str("slider repeats"),
+        }
+    }
+}
+
+/// Which length to use for sliders whose declared pixel length
+/// does not match the length of their path.
+///
+/// See [`Beatmap::slider_length_mismatches`](crate::Beatmap::slider_length_mismatches).
+#[cfg(feature = "sliders")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SliderLengthPolicy {
+    /// Use the pixel length declared in the `.osu` file and shorten
+    /// or extend the path accordingly, just like the game does.
+    #[default]
+    TrustFile,
+    /// Replace the declared pixel length with the
+    /// length of the path given by the control points.
+    TrustGeometry,
+}
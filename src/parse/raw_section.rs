@@ -0,0 +1,113 @@
+use super::Beatmap;
+
+/// A section of a `.osu` file that is not parsed, kept as raw text.
+///
+/// See [`ParseOptions::raw_sections`](crate::ParseOptions::raw_sections).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RawSection {
+    /// The name of the section without brackets, e.g. `Colours`.
+    pub name: String,
+    /// All lines after the section header up to the next header,
+    /// including comments, empty lines, and line endings.
+    pub content: String,
+}
+
+impl RawSection {
+    #[inline]
+    pub(super) fn new(name: String) -> Self {
+        Self {
+            name,
+            content: String::new(),
+        }
+    }
+}
+
+impl Beatmap {
+    /// The content of the raw section with the given name, if it was captured.
+    ///
+    /// ```
+    /// use akatsuki_pp::{Beatmap, ParseOptions};
+    ///
+    /// # /*
+    /// let bytes: &[u8] = ...
+    /// # */
+    /// # let bytes: &[u8] = b"osu file format v14\n\n[Colours]\nCombo1 : 255,128,0\n";
+    /// let options = ParseOptions::new().raw_sections(true);
+    /// # #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    /// let map = Beatmap::parse_with_options(bytes, options).unwrap();
+    /// # #[cfg(any(feature = "async_tokio", feature = "async_std"))]
+    /// # let map = Beatmap::default();
+    ///
+    /// if let Some(colours) = map.raw_section("Colours") {
+    ///     println!("{}", colours);
+    /// }
+    /// ```
+    pub fn raw_section(&self, name: &str) -> Option<&str> {
+        self.raw_sections
+            .iter()
+            .find(|section| section.name == name)
+            .map(|section| section.content.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn raw_sections() {
+        use crate::ParseOptions;
+
+        let content = "osu file format v14\r\n\
+            \r\n\
+            [General]\r\n\
+            Mode: 0\r\n\
+            \r\n\
+            [Editor]\r\n\
+            DistanceSpacing: 1.2\r\n\
+            \r\n\
+            [Difficulty]\r\n\
+            HPDrainRate:5\r\n\
+            CircleSize:4\r\n\
+            OverallDifficulty:8\r\n\
+            ApproachRate:9\r\n\
+            SliderMultiplier:1.4\r\n\
+            SliderTickRate:1\r\n\
+            \r\n\
+            [Colours]\r\n\
+            // comment\r\n\
+            Combo1 : 255,128,0\r\n\
+            [Empty]\r\n\
+            [HitObjects]\r\n\
+            256,192,1000,1,0\r\n\
+            [Custom]\r\n\
+            foo";
+
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+        assert!(map.raw_sections.is_empty());
+
+        let options = ParseOptions::new().raw_sections(true);
+        let map =
+            Beatmap::parse_with_options(content.as_bytes(), options).expect("failed to parse map");
+
+        let names: Vec<_> = map.raw_sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Editor", "Colours", "Empty", "Custom"]);
+
+        assert_eq!(
+            map.raw_section("Editor"),
+            Some("DistanceSpacing: 1.2\r\n\r\n")
+        );
+        assert_eq!(
+            map.raw_section("Colours"),
+            Some("// comment\r\nCombo1 : 255,128,0\r\n")
+        );
+        assert_eq!(map.raw_section("Empty"), Some(""));
+        assert_eq!(map.raw_section("Custom"), Some("foo"));
+        assert_eq!(map.raw_section("General"), None);
+
+        assert_eq!(map.hit_objects.len(), 1);
+        assert_eq!(map.od, 8.0);
+    }
+}
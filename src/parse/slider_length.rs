@@ -0,0 +1,139 @@
+#![cfg(feature = "sliders")]
+
+use super::{Beatmap, HitObjectKind, SliderLengthPolicy};
+use crate::curve::{Curve, CurveBuffers};
+
+/// A slider whose declared pixel length differs from the length of its path.
+///
+/// See [`Beatmap::slider_length_mismatches`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SliderLengthMismatch {
+    /// The index of the slider in [`Beatmap::hit_objects`].
+    pub idx: usize,
+    /// The pixel length declared in the `.osu` file.
+    pub declared: f64,
+    /// The length of the path given by the control points.
+    pub computed: f64,
+}
+
+impl Beatmap {
+    /// Find all sliders whose declared pixel length differs by more than
+    /// `tolerance` from the length of the path given by their control points.
+    ///
+    /// By default, the declared length is used and the path is shortened or extended
+    /// accordingly. Some older maps rely on that behavior while others end up with
+    /// wrong slider ticks, see [`SliderLengthPolicy`].
+    ///
+    /// ```
+    /// use akatsuki_pp::Beatmap;
+    ///
+    /// # let map = Beatmap::default();
+    /// for mismatch in map.slider_length_mismatches(1.0) {
+    ///     println!(
+    ///         "slider #{} declares {}px but its path is {}px long",
+    ///         mismatch.idx, mismatch.declared, mismatch.computed,
+    ///     );
+    /// }
+    /// ```
+    pub fn slider_length_mismatches(&self, tolerance: f64) -> Vec<SliderLengthMismatch> {
+        let mut bufs = CurveBuffers::default();
+
+        self.hit_objects
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, h)| match &h.kind {
+                HitObjectKind::Slider {
+                    pixel_len,
+                    control_points,
+                    ..
+                } => Some(SliderLengthMismatch {
+                    idx,
+                    declared: *pixel_len,
+                    computed: Curve::path_len(control_points, &mut bufs),
+                }),
+                _ => None,
+            })
+            .filter(|mismatch| (mismatch.declared - mismatch.computed).abs() > tolerance)
+            .collect()
+    }
+
+    pub(crate) fn apply_slider_length_policy(&mut self, policy: SliderLengthPolicy) {
+        if policy == SliderLengthPolicy::TrustFile {
+            return;
+        }
+
+        let mut bufs = CurveBuffers::default();
+
+        for h in self.hit_objects.iter_mut() {
+            if let HitObjectKind::Slider {
+                pixel_len,
+                control_points,
+                ..
+            } = &mut h.kind
+            {
+                *pixel_len = Curve::path_len(control_points, &mut bufs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{HitObject, PathControlPoint, PathType, Pos2};
+
+    fn slider(pixel_len: f64) -> HitObject {
+        let point = |x, y, kind| PathControlPoint {
+            pos: Pos2 { x, y },
+            kind,
+        };
+
+        HitObject {
+            pos: Pos2::default(),
+            start_time: 0.0,
+            kind: HitObjectKind::Slider {
+                repeats: 0,
+                pixel_len,
+                control_points: vec![
+                    point(0.0, 0.0, Some(PathType::Linear)),
+                    point(300.0, 0.0, None),
+                ],
+            },
+            sound: 0,
+            file_idx: 0,
+        }
+    }
+
+    #[test]
+    fn mismatched_slider_lengths() {
+        let mut map = Beatmap {
+            hit_objects: vec![slider(300.0), slider(200.0)],
+            ..Default::default()
+        };
+
+        let mismatches = map.slider_length_mismatches(1.0);
+
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].idx, 1);
+        assert_eq!(mismatches[0].declared, 200.0);
+        assert!((mismatches[0].computed - 300.0).abs() < 1e-6);
+
+        map.apply_slider_length_policy(SliderLengthPolicy::TrustFile);
+        assert_eq!(map.slider_length_mismatches(1.0).len(), 1);
+
+        map.apply_slider_length_policy(SliderLengthPolicy::TrustGeometry);
+        assert!(map.slider_length_mismatches(1e-6).is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn trust_geometry_option() {
+        use crate::ParseOptions;
+
+        let bytes = std::fs::read("./maps/2785319.osu").expect("failed to read map");
+        let options = ParseOptions::new().slider_length(SliderLengthPolicy::TrustGeometry);
+        let map = Beatmap::parse_with_options(bytes.as_slice(), options).unwrap();
+
+        assert!(map.slider_length_mismatches(1e-6).is_empty());
+    }
+}
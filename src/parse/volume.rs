@@ -0,0 +1,109 @@
+use std::cmp::Ordering;
+
+use super::Beatmap;
+
+/// The volume of samples before the first control point that specifies one.
+const DEFAULT_VOLUME: u8 = 100;
+
+impl Beatmap {
+    /// The sample volume throughout the map as a list of
+    /// `(time, volume)` pairs, sorted by time.
+    ///
+    /// Each entry marks a change of the volume which then lasts until the next entry.
+    /// Control points that don't specify a volume keep the previous one.
+    /// If timing and difficulty points share the same time, the difficulty point takes
+    /// precedence. Before the first entry, the volume is `100`.
+    ///
+    /// Requires the `sliders` feature, i.e. `osu` or `fruits`, since control points
+    /// are not stored otherwise.
+    ///
+    /// ```
+    /// use akatsuki_pp::Beatmap;
+    ///
+    /// # let map = Beatmap::default();
+    /// let timeline = map.volume_timeline();
+    ///
+    /// // The volume at 10 seconds
+    /// let idx = timeline.partition_point(|(time, _)| *time <= 10_000.0);
+    /// let volume = idx.checked_sub(1).map_or(100, |idx| timeline[idx].1);
+    /// ```
+    pub fn volume_timeline(&self) -> Vec<(f64, u8)> {
+        let timing_volumes = self.timing_points.iter().map(|p| (p.time, p.volume));
+        let difficulty_volumes = self.difficulty_points.iter().map(|p| (p.time, p.volume));
+
+        let mut volumes: Vec<(f64, u8)> = timing_volumes
+            .filter_map(|(time, volume)| Some((time, volume?)))
+            .collect();
+
+        // Appended after the timing points so that the stable sort keeps them last on ties
+        volumes.extend(difficulty_volumes.filter_map(|(time, volume)| Some((time, volume?))));
+        volumes.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let mut timeline: Vec<(f64, u8)> = Vec::with_capacity(volumes.len());
+
+        for (i, &(time, volume)) in volumes.iter().enumerate() {
+            // Only the last point at the same time is relevant
+            if volumes.get(i + 1).map_or(false, |(next, _)| *next == time) {
+                continue;
+            }
+
+            let prev_volume = timeline.last().map_or(DEFAULT_VOLUME, |(_, prev)| *prev);
+
+            if volume != prev_volume {
+                timeline.push((time, volume));
+            }
+        }
+
+        timeline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{DifficultyPoint, TimingPoint};
+
+    #[test]
+    fn volume_timeline() {
+        let timing = |time, volume| TimingPoint {
+            time,
+            volume,
+            ..Default::default()
+        };
+
+        let difficulty = |time, volume| DifficultyPoint {
+            time,
+            volume,
+            ..Default::default()
+        };
+
+        let map = Beatmap {
+            timing_points: vec![
+                timing(0.0, Some(100)),
+                timing(1000.0, Some(60)),
+                timing(3000.0, None),
+            ],
+            difficulty_points: vec![
+                difficulty(1000.0, Some(40)),
+                difficulty(2000.0, Some(40)),
+                difficulty(4000.0, Some(100)),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(map.volume_timeline(), [(1000.0, 40), (4000.0, 100)]);
+        assert!(Beatmap::default().volume_timeline().is_empty());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn parsed_volume() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+
+        assert!(map.timing_points.iter().all(|point| point.volume.is_some()));
+        assert!(map
+            .timing_points
+            .iter()
+            .all(|point| point.sample_set.is_some()));
+    }
+}
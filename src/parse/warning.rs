@@ -0,0 +1,166 @@
+use std::fmt;
+
+use super::{Beatmap, GameMode};
+
+/// A recoverable irregularity of a `.osu` file that was found while parsing.
+///
+/// See [`Beatmap::warnings`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ParseWarning {
+    /// A difficulty value was out of range and has been clamped like osu! does.
+    ValueClamped {
+        /// The name of the value's key in the `.osu` file, e.g. `CircleSize`.
+        name: &'static str,
+        /// The value in the `.osu` file.
+        value: f64,
+        /// The value after clamping.
+        clamped: f64,
+    },
+    /// A numeric value contained another colon so only the part in front of it
+    /// has been used, e.g. `1` for `Mode: 1:2`.
+    ValueTruncated {
+        /// The name of the value's key in the `.osu` file, e.g. `Mode`.
+        name: String,
+        /// The whole value in the `.osu` file.
+        value: String,
+    },
+    /// The file was not valid UTF-8 so invalid byte sequences have been replaced.
+    ///
+    /// Only checked in [lenient](crate::ParseOptions::lenient) mode.
+    InvalidUtf8,
+    /// The file contained NUL bytes which have been removed.
+    ///
+    /// Only checked in [lenient](crate::ParseOptions::lenient) mode.
+    NulBytes {
+        /// The amount of removed NUL bytes.
+        count: usize,
+    },
+    /// The last line of the file could not be parsed, e.g. because the file was
+    /// truncated or has trailing garbage, so it has been ignored.
+    ///
+    /// Only checked in [lenient](crate::ParseOptions::lenient) mode.
+    LineDropped {
+        /// The content of the ignored line.
+        line: String,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ValueClamped {
+                name,
+                value,
+                clamped,
+            } => write!(f, "{} {} was clamped to {}", name, value, clamped),
+            Self::ValueTruncated { name, value } => {
+                write!(f, "{} `{}` was truncated at its colon", name, value)
+            }
+            Self::InvalidUtf8 => f.write_str("replaced invalid UTF-8"),
+            Self::NulBytes { count } => write!(f, "removed {} NUL bytes", count),
+            Self::LineDropped { line } => write!(f, "ignored unparsable last line `{}`", line),
+        }
+    }
+}
+
+impl Beatmap {
+    /// Clamp the difficulty values into the ranges that osu! allows
+    /// and record a warning for each value that was out of range.
+    pub(super) fn clamp_difficulty(&mut self) {
+        let (min_cs, max_cs) = match self.mode {
+            GameMode::MNA => (1.0, 18.0),
+            GameMode::STD | GameMode::TKO | GameMode::CTB => (0.0, 10.0),
+        };
+
+        let warnings = &mut self.warnings;
+
+        let mut clamp_f32 = |name, value: &mut f32, min: f32, max: f32| {
+            let clamped = value.clamp(min, max);
+
+            if clamped != *value && !value.is_nan() {
+                warnings.push(ParseWarning::ValueClamped {
+                    name,
+                    value: *value as f64,
+                    clamped: clamped as f64,
+                });
+
+                *value = clamped;
+            }
+        };
+
+        clamp_f32("HPDrainRate", &mut self.hp, 0.0, 10.0);
+        clamp_f32("CircleSize", &mut self.cs, min_cs, max_cs);
+        clamp_f32("OverallDifficulty", &mut self.od, 0.0, 10.0);
+        clamp_f32("ApproachRate", &mut self.ar, 0.0, 10.0);
+
+        let mut clamp_f64 = |name, value: &mut f64, min: f64, max: f64| {
+            let clamped = value.clamp(min, max);
+
+            if clamped != *value && !value.is_nan() {
+                warnings.push(ParseWarning::ValueClamped {
+                    name,
+                    value: *value,
+                    clamped,
+                });
+
+                *value = clamped;
+            }
+        };
+
+        clamp_f64("SliderMultiplier", &mut self.slider_mult, 0.4, 3.6);
+        clamp_f64("SliderTickRate", &mut self.tick_rate, 0.5, 8.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn clamped_difficulty() {
+        let content = "osu file format v14
+
+[General]
+Mode: 3
+
+[Difficulty]
+HPDrainRate:-5
+CircleSize:112
+OverallDifficulty:8
+SliderMultiplier:1.4
+SliderTickRate:12
+";
+
+        let map = Beatmap::parse(content.as_bytes()).expect("failed to parse map");
+
+        assert_eq!(map.hp, 0.0);
+        assert_eq!(map.cs, 18.0);
+        assert_eq!(map.od, 8.0);
+        assert_eq!(map.ar, 8.0);
+        assert_eq!(map.tick_rate, 8.0);
+
+        assert_eq!(
+            map.warnings,
+            vec![
+                ParseWarning::ValueClamped {
+                    name: "HPDrainRate",
+                    value: -5.0,
+                    clamped: 0.0,
+                },
+                ParseWarning::ValueClamped {
+                    name: "CircleSize",
+                    value: 112.0,
+                    clamped: 18.0,
+                },
+                ParseWarning::ValueClamped {
+                    name: "SliderTickRate",
+                    value: 12.0,
+                    clamped: 8.0,
+                },
+            ]
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{Beatmap, DifficultyAttributes, GameMode, PerformanceAttributes, ScoreState};
+use std::{error::Error as StdError, fmt};
+
+use crate::{
+    Beatmap, DifficultyAttributes, GameMode, InputError, LazerMods, PerformanceAttributes,
+    ScoreState,
+};
 
 #[cfg(feature = "fruits")]
 use crate::fruits::{FruitsDifficultyAttributes, FruitsPP};
@@ -17,7 +22,7 @@ use crate::taiko::{TaikoDifficultyAttributes, TaikoPP};
 /// # Example
 ///
 /// ```no_run
-/// use rosu_pp::{AnyPP, Beatmap};
+/// use akatsuki_pp::{AnyPP, Beatmap};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -78,7 +83,12 @@ impl<'map> AnyPP<'map> {
 
     /// Consume the performance calculator and calculate
     /// performance attributes for the given parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics in [strict](AnyPP::strict) mode if the input is invalid.
     #[inline]
+    #[track_caller]
     pub fn calculate(self) -> PerformanceAttributes {
         match self {
             #[cfg(feature = "fruits")]
@@ -92,6 +102,80 @@ impl<'map> AnyPP<'map> {
         }
     }
 
+    /// Calculate performance attributes just like [`calculate`](AnyPP::calculate)
+    /// but in [strict](AnyPP::strict) mode, return an error if the input is invalid.
+    ///
+    /// Outside of strict mode, this never fails.
+    #[inline]
+    pub fn try_calculate(self) -> Result<PerformanceAttributes, InputError> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => f.try_calculate().map(PerformanceAttributes::Fruits),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => m.try_calculate().map(PerformanceAttributes::Mania),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => o.try_calculate().map(PerformanceAttributes::Osu),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => t.try_calculate().map(PerformanceAttributes::Taiko),
+        }
+    }
+
+    /// Calculate the performance of each given score state on the same map.
+    ///
+    /// The difficulty attributes are calculated only once and shared across all
+    /// score states instead of paying for them on each calculation.
+    /// All other parameters, e.g. mods or passed objects, apply to every score state.
+    ///
+    /// For osu!mania, only the score of each state is considered.
+    pub fn calculate_many(self, states: &[ScoreState]) -> Vec<PerformanceAttributes> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => {
+                let states: Vec<_> = states.iter().cloned().map(From::from).collect();
+
+                f.calculate_many(&states)
+                    .into_iter()
+                    .map(PerformanceAttributes::Fruits)
+                    .collect()
+            }
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => {
+                let scores: Vec<_> = states.iter().map(|state| state.score).collect();
+
+                m.calculate_many(&scores)
+                    .into_iter()
+                    .map(PerformanceAttributes::Mania)
+                    .collect()
+            }
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => {
+                let states: Vec<_> = states.iter().cloned().map(From::from).collect();
+
+                o.calculate_many(&states)
+                    .into_iter()
+                    .map(PerformanceAttributes::Osu)
+                    .collect()
+            }
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => {
+                let states: Vec<_> = states.iter().cloned().map(From::from).collect();
+
+                t.calculate_many(&states)
+                    .into_iter()
+                    .map(PerformanceAttributes::Taiko)
+                    .collect()
+            }
+        }
+    }
+
+    /// Calculate performance attributes just like [`calculate`](AnyPP::calculate)
+    /// while also measuring how much time each calculation phase took.
+    #[cfg(feature = "timings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+    pub fn calculate_with_timings(self) -> (PerformanceAttributes, crate::CalcTimings) {
+        crate::timings::measure(|| self.calculate())
+    }
+
     /// Provide the result of a previous difficulty or performance calculation.
     /// If you already calculated the attributes for the current map-mod combination,
     /// be sure to put them in here so that they don't have to be recalculated.
@@ -126,6 +210,37 @@ impl<'map> AnyPP<'map> {
         }
     }
 
+    /// Specify the mods of an osu!lazer score, including lazer-only mods.
+    ///
+    /// Only osu!standard considers the lazer-only mods, the
+    /// other modes use the [`legacy`](LazerMods::legacy) bits.
+    #[inline]
+    pub fn lazer_mods(self, mods: LazerMods) -> Self {
+        match self {
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => Self::Osu(o.lazer_mods(mods)),
+            #[allow(unreachable_patterns)]
+            _ => self.mods(mods.legacy),
+        }
+    }
+
+    /// Enable or disable strict mode, see e.g. [`OsuPP::strict`](crate::OsuPP::strict).
+    ///
+    /// Disabled by default.
+    #[inline]
+    pub fn strict(self, strict: bool) -> Self {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => Self::Fruits(f.strict(strict)),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => Self::Mania(m.strict(strict)),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => Self::Osu(o.strict(strict)),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => Self::Taiko(t.strict(strict)),
+        }
+    }
+
     /// Amount of passed objects for partial plays, e.g. a fail.
     ///
     /// If you want to calculate the performance after every few objects, instead of
@@ -145,6 +260,31 @@ impl<'map> AnyPP<'map> {
         }
     }
 
+    /// Amount of passed objects for partial plays, derived from the completion in percent,
+    /// e.g. `63.0` for a fail at 63%.
+    ///
+    /// Useful if only the completion of a play is stored but not its amount of objects.
+    /// See [`Beatmap::passed_objects_at`] for how the completion is rounded.
+    /// For osu!ctb, the fruits and droplets up to that point are counted instead of
+    /// the hit objects.
+    pub fn completion(self, completion: f64) -> Self {
+        let passed_objects = match &self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => match f.map.completion_time(completion) {
+                Some(time) => crate::fruits::passed_objects_until(f.map, time),
+                None => return self,
+            },
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => m.map.passed_objects_at(completion),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => o.map.passed_objects_at(completion),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => t.map.passed_objects_at(completion),
+        };
+
+        self.passed_objects(passed_objects)
+    }
+
     /// Provide parameters through a [`ScoreState`].
     #[inline]
     pub fn state(self, state: ScoreState) -> Self {
@@ -311,6 +451,81 @@ impl<'map> AnyPP<'map> {
     }
 }
 
+/// Mode-specific performance calculators, see [`ModeMismatchError`].
+///
+/// osu!standard maps can be calculated in any mode since they are converted.
+/// Maps of other modes can only be calculated in their own mode.
+impl Beatmap {
+    /// Returns an osu!standard performance calculator.
+    ///
+    /// Fails if the map is not an osu!standard map.
+    #[cfg(feature = "osu")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "osu")))]
+    pub fn osu_pp(&self) -> Result<OsuPP<'_>, ModeMismatchError> {
+        self.check_mode(GameMode::STD).map(OsuPP::new)
+    }
+
+    /// Returns an osu!taiko performance calculator.
+    ///
+    /// Fails if the map is neither an osu!taiko nor an osu!standard map.
+    #[cfg(feature = "taiko")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "taiko")))]
+    pub fn taiko_pp(&self) -> Result<TaikoPP<'_>, ModeMismatchError> {
+        self.check_mode(GameMode::TKO).map(TaikoPP::new)
+    }
+
+    /// Returns an osu!ctb performance calculator.
+    ///
+    /// Fails if the map is neither an osu!ctb nor an osu!standard map.
+    #[cfg(feature = "fruits")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fruits")))]
+    pub fn fruits_pp(&self) -> Result<FruitsPP<'_>, ModeMismatchError> {
+        self.check_mode(GameMode::CTB).map(FruitsPP::new)
+    }
+
+    /// Returns an osu!mania performance calculator.
+    ///
+    /// Fails if the map is neither an osu!mania nor an osu!standard map.
+    #[cfg(feature = "mania")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mania")))]
+    pub fn mania_pp(&self) -> Result<ManiaPP<'_>, ModeMismatchError> {
+        self.check_mode(GameMode::MNA).map(ManiaPP::new)
+    }
+
+    fn check_mode(&self, requested: GameMode) -> Result<&Self, ModeMismatchError> {
+        if self.mode == requested || self.mode == GameMode::STD {
+            Ok(self)
+        } else {
+            Err(ModeMismatchError {
+                map: self.mode,
+                requested,
+            })
+        }
+    }
+}
+
+/// A mode-specific calculator was requested for a map that
+/// can not be played in that mode, see e.g. [`Beatmap::taiko_pp`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ModeMismatchError {
+    /// The mode of the map.
+    pub map: GameMode,
+    /// The requested mode.
+    pub requested: GameMode,
+}
+
+impl fmt::Display for ModeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot calculate {:?} performance on a {:?} map",
+            self.requested, self.map
+        )
+    }
+}
+
+impl StdError for ModeMismatchError {}
+
 /// Abstract type to provide flexibility when passing difficulty attributes to a performance calculation.
 pub trait AttributeProvider {
     /// Provide the actual difficulty attributes.
@@ -0,0 +1,136 @@
+use crate::{AnyPP, Beatmap, DifficultyAttributes, GameMode, GradualDifficultyAttributes, Mods};
+
+#[cfg(feature = "fruits")]
+use crate::fruits::FruitsPreprocessedMap;
+
+#[cfg(feature = "mania")]
+use crate::mania::ManiaPreprocessedMap;
+
+#[cfg(feature = "osu")]
+use crate::osu::OsuPreprocessedMap;
+
+#[cfg(feature = "taiko")]
+use crate::taiko::TaikoPreprocessedMap;
+
+/// A map of any mode whose mod-independent preparation has been performed once.
+///
+/// Suitable for pipelines that calculate the difficulty or performance of the same map
+/// for many mods or clock rates, e.g. to fill a database with the attributes of
+/// each mod combination. The map is only borrowed so it must outlive this type.
+///
+/// # Example
+///
+/// ```no_run
+/// use akatsuki_pp::{Beatmap, BeatmapExt};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+/// let preprocessed = map.preprocess();
+///
+/// for mods in [0, 8, 16, 64, 8 + 64] {
+///     let stars = preprocessed.stars(mods, None).stars();
+///     let max_pp = preprocessed.pp(mods).calculate().pp();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub enum PreprocessedMap<'map> {
+    #[cfg(feature = "fruits")]
+    /// Preprocessed osu!ctb map.
+    Fruits(FruitsPreprocessedMap<'map>),
+    #[cfg(feature = "mania")]
+    /// Preprocessed osu!mania map.
+    Mania(ManiaPreprocessedMap<'map>),
+    #[cfg(feature = "osu")]
+    /// Preprocessed osu!standard map.
+    Osu(OsuPreprocessedMap<'map>),
+    #[cfg(feature = "taiko")]
+    /// Preprocessed osu!taiko map.
+    Taiko(TaikoPreprocessedMap<'map>),
+}
+
+impl<'map> PreprocessedMap<'map> {
+    /// Prepare a map of any mode.
+    pub fn new(map: &'map Beatmap) -> Self {
+        match map.mode {
+            #[cfg(feature = "fruits")]
+            GameMode::CTB => Self::Fruits(FruitsPreprocessedMap::new(map)),
+            #[cfg(feature = "mania")]
+            GameMode::MNA => Self::Mania(ManiaPreprocessedMap::new(map)),
+            #[cfg(feature = "osu")]
+            GameMode::STD => Self::Osu(OsuPreprocessedMap::new(map)),
+            #[cfg(feature = "taiko")]
+            GameMode::TKO => Self::Taiko(TaikoPreprocessedMap::new(map)),
+            #[allow(unreachable_patterns)]
+            _ => panic!("feature for mode {:?} is not enabled", map.mode),
+        }
+    }
+
+    /// The map that was prepared.
+    #[inline]
+    pub fn map(&self) -> &'map Beatmap {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => f.map(),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => m.map(),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => o.map(),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => t.map(),
+        }
+    }
+
+    /// Calculate the difficulty attributes for the given mods.
+    ///
+    /// The result is the same as the one of [`BeatmapExt::stars`](crate::BeatmapExt::stars).
+    #[inline]
+    pub fn stars(&self, mods: impl Mods, passed_objects: Option<usize>) -> DifficultyAttributes {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => DifficultyAttributes::Fruits(f.stars(mods, passed_objects)),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => DifficultyAttributes::Mania(m.stars(mods, passed_objects)),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => DifficultyAttributes::Osu(o.stars(mods, passed_objects)),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => DifficultyAttributes::Taiko(t.stars(mods, passed_objects)),
+        }
+    }
+
+    /// Return a performance calculator for the given mods
+    /// whose difficulty attributes are calculated on the prepared map.
+    ///
+    /// The attributes are those of the full map so for partial plays
+    /// use [`stars`](PreprocessedMap::stars) with the amount of passed objects instead.
+    #[inline]
+    pub fn pp(&self, mods: u32) -> AnyPP<'map> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => AnyPP::Fruits(f.pp(mods)),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => AnyPP::Mania(m.pp(mods)),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => AnyPP::Osu(o.pp(mods)),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => AnyPP::Taiko(t.pp(mods)),
+        }
+    }
+
+    /// Return an iterator that gives the difficulty attributes after each hit object,
+    /// see [`GradualDifficultyAttributes`].
+    #[inline]
+    pub fn gradual_difficulty(&self, mods: impl Mods) -> GradualDifficultyAttributes<'map> {
+        match self {
+            #[cfg(feature = "fruits")]
+            Self::Fruits(f) => GradualDifficultyAttributes::Fruits(f.gradual_difficulty(mods)),
+            #[cfg(feature = "mania")]
+            Self::Mania(m) => GradualDifficultyAttributes::Mania(m.gradual_difficulty(mods)),
+            #[cfg(feature = "osu")]
+            Self::Osu(o) => GradualDifficultyAttributes::Osu(o.gradual_difficulty(mods)),
+            #[cfg(feature = "taiko")]
+            Self::Taiko(t) => GradualDifficultyAttributes::Taiko(t.gradual_difficulty(mods)),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+
+use crate::CalcKey;
+
+/// The release and formulas that produced a set of attributes.
+///
+/// Every difficulty attribute struct carries the provenance of the calculation
+/// that created it and [`to_json`](crate::DifficultyAttributes::to_json) includes
+/// it so that stored attributes can be invalidated precisely after an upgrade:
+/// only those whose [`formula_version`](Provenance::formula_version) is outdated
+/// need to be recalculated.
+///
+/// ```
+/// use akatsuki_pp::{Beatmap, BeatmapExt, Provenance};
+///
+/// # let map = Beatmap::default();
+/// let attributes = map.stars(0, None);
+///
+/// assert_eq!(attributes.provenance(), Provenance::CURRENT);
+/// assert!(attributes.provenance().is_current());
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Provenance {
+    /// The version of this crate as major, minor, and patch version.
+    pub crate_version: (u16, u16, u16),
+    /// The version of the difficulty and performance formulas,
+    /// i.e. the [`CalcKey::VERSION`] at the time.
+    pub formula_version: u32,
+}
+
+impl Provenance {
+    /// The provenance of attributes calculated by this release.
+    pub const CURRENT: Self = Self {
+        crate_version: (
+            parse_u16(env!("CARGO_PKG_VERSION_MAJOR")),
+            parse_u16(env!("CARGO_PKG_VERSION_MINOR")),
+            parse_u16(env!("CARGO_PKG_VERSION_PATCH")),
+        ),
+        formula_version: CalcKey::VERSION,
+    };
+
+    /// The provenance of attributes that were stored without one,
+    /// i.e. by a release that did not record it yet.
+    pub const UNKNOWN: Self = Self {
+        crate_version: (0, 0, 0),
+        formula_version: 0,
+    };
+
+    /// Whether the formulas of this release produce the same attributes,
+    /// i.e. whether the attributes do not need to be recalculated.
+    #[inline]
+    pub fn is_current(&self) -> bool {
+        self.formula_version == Self::CURRENT.formula_version
+    }
+
+    /// Parse a crate version in the form `major.minor.patch`.
+    pub(crate) fn parse_crate_version(version: &str) -> Option<(u16, u16, u16)> {
+        let mut numbers = version.split('.').map(str::parse);
+
+        match (
+            numbers.next(),
+            numbers.next(),
+            numbers.next(),
+            numbers.next(),
+        ) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Some((major, minor, patch))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for Provenance {
+    #[inline]
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (major, minor, patch) = self.crate_version;
+
+        write!(
+            f,
+            "{}.{}.{} (formula version {})",
+            major, minor, patch, self.formula_version
+        )
+    }
+}
+
+const fn parse_u16(s: &str) -> u16 {
+    let bytes = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        n = n * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crate_version() {
+        let (major, minor, patch) = Provenance::CURRENT.crate_version;
+        let version = format!("{}.{}.{}", major, minor, patch);
+
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            Provenance::parse_crate_version(&version),
+            Some(Provenance::CURRENT.crate_version)
+        );
+        assert_eq!(Provenance::parse_crate_version("1.2"), None);
+        assert_eq!(Provenance::parse_crate_version("1.2.3.4"), None);
+        assert!(!Provenance::UNKNOWN.is_current());
+    }
+}
@@ -0,0 +1,481 @@
+use crate::{Beatmap, BeatmapExt, DifficultyAttributes, Mods};
+
+/// Subdivide an interval of rates at most this many times.
+const MAX_DEPTH: u32 = 4;
+
+/// Mods with a custom clock rate, e.g. for rate changes like 1.05x.
+///
+/// The clock rate replaces the one of DT or HT, all other mods are taken as is.
+///
+/// ```
+/// use akatsuki_pp::{rate::RateMods, Beatmap, BeatmapExt};
+///
+/// # let map = Beatmap::default();
+/// let mods = RateMods::new(16, 1.2); // HR at 1.2x
+/// let attributes = map.stars(mods, None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RateMods {
+    /// The bit value of the mods.
+    pub mods: u32,
+    /// The clock rate.
+    pub clock_rate: f64,
+}
+
+impl RateMods {
+    /// Create new mods with the given clock rate.
+    #[inline]
+    pub fn new(mods: u32, clock_rate: f64) -> Self {
+        Self { mods, clock_rate }
+    }
+}
+
+impl Mods for RateMods {
+    #[inline]
+    fn change_speed(self) -> bool {
+        (self.clock_rate - 1.0).abs() > f64::EPSILON
+    }
+
+    #[inline]
+    fn change_map(self) -> bool {
+        self.change_speed() || self.mods.hr() || self.mods.ez()
+    }
+
+    #[inline]
+    fn speed(self) -> f64 {
+        self.clock_rate
+    }
+
+    #[inline]
+    fn od_ar_hp_multiplier(self) -> f64 {
+        self.mods.od_ar_hp_multiplier()
+    }
+
+    #[inline]
+    fn nf(self) -> bool {
+        self.mods.nf()
+    }
+
+    #[inline]
+    fn ez(self) -> bool {
+        self.mods.ez()
+    }
+
+    #[inline]
+    fn td(self) -> bool {
+        self.mods.td()
+    }
+
+    #[inline]
+    fn hd(self) -> bool {
+        self.mods.hd()
+    }
+
+    #[inline]
+    fn hr(self) -> bool {
+        self.mods.hr()
+    }
+
+    #[inline]
+    fn dt(self) -> bool {
+        self.mods.dt()
+    }
+
+    #[inline]
+    fn rx(self) -> bool {
+        self.mods.rx()
+    }
+
+    #[inline]
+    fn ht(self) -> bool {
+        self.mods.ht()
+    }
+
+    #[inline]
+    fn fl(self) -> bool {
+        self.mods.fl()
+    }
+
+    #[inline]
+    fn so(self) -> bool {
+        self.mods.so()
+    }
+
+    #[inline]
+    fn sd(self) -> bool {
+        self.mods.sd()
+    }
+
+    #[inline]
+    fn pf(self) -> bool {
+        self.mods.pf()
+    }
+}
+
+/// A clock rate that changes over the course of a map, e.g. for lazer's Wind Up and Wind Down.
+///
+/// The rate is interpolated linearly between consecutive points and stays
+/// constant before the first and after the last point.
+///
+/// ```
+/// use akatsuki_pp::rate::RateSchedule;
+///
+/// // From 1.0x at the start to 1.5x after 30 seconds
+/// let schedule = RateSchedule::ramp(0.0, 30_000.0, 1.0, 1.5).unwrap();
+///
+/// assert_eq!(schedule.rate(15_000.0), 1.25);
+/// assert_eq!(schedule.rate(60_000.0), 1.5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateSchedule {
+    /// `(time, rate)` sorted by time
+    points: Vec<(f64, f64)>,
+}
+
+impl RateSchedule {
+    /// Create a schedule from `(time, rate)` points where the time is
+    /// in ms of the map, i.e. unaffected by the clock rate.
+    ///
+    /// Returns `None` if there are no points or any rate is not finite and positive.
+    pub fn new(points: impl IntoIterator<Item = (f64, f64)>) -> Option<Self> {
+        let mut points: Vec<_> = points.into_iter().collect();
+
+        let valid = |&(time, rate): &(f64, f64)| time.is_finite() && rate.is_finite() && rate > 0.0;
+
+        if points.is_empty() || !points.iter().all(valid) {
+            return None;
+        }
+
+        points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        Some(Self { points })
+    }
+
+    /// Create a schedule that changes linearly from `initial_rate` at `start_time`
+    /// to `final_rate` at `end_time`.
+    ///
+    /// Note that for Wind Up and Wind Down, lazer reaches the final rate
+    /// after 75% of the time between the first and last object.
+    #[inline]
+    pub fn ramp(
+        start_time: f64,
+        end_time: f64,
+        initial_rate: f64,
+        final_rate: f64,
+    ) -> Option<Self> {
+        Self::new([(start_time, initial_rate), (end_time, final_rate)])
+    }
+
+    /// The clock rate at the given time of the map.
+    pub fn rate(&self, time: f64) -> f64 {
+        let idx = self.points.partition_point(|(t, _)| *t <= time);
+
+        match (
+            idx.checked_sub(1).map(|i| self.points[i]),
+            self.points.get(idx),
+        ) {
+            (Some((t0, r0)), Some(&(t1, r1))) => r0 + (r1 - r0) * (time - t0) / (t1 - t0),
+            (Some((_, rate)), None) | (None, Some(&(_, rate))) => rate,
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// Convert a time of the map into the time that passed in real time since time 0,
+    /// i.e. the integral of `1 / rate` up to the given time.
+    pub fn real_time(&self, time: f64) -> f64 {
+        let (first_time, first_rate) = self.points[0];
+        let mut real_time = time.min(first_time) / first_rate;
+
+        for window in self.points.windows(2) {
+            let (t0, r0) = window[0];
+            let (t1, _) = window[1];
+
+            if time <= t0 {
+                return real_time;
+            }
+
+            let end = time.min(t1);
+            real_time += segment_real_time(t0, end, r0, self.rate(end));
+        }
+
+        let (last_time, last_rate) = self.points[self.points.len() - 1];
+
+        if time > last_time {
+            real_time += (time - last_time) / last_rate;
+        }
+
+        real_time
+    }
+
+    /// The constant clock rate that takes as long for the given interval of the map.
+    pub fn average_rate(&self, start_time: f64, end_time: f64) -> f64 {
+        let real_duration = self.real_time(end_time) - self.real_time(start_time);
+
+        if real_duration > 0.0 {
+            (end_time - start_time) / real_duration
+        } else {
+            self.rate(start_time)
+        }
+    }
+}
+
+/// Real time of an interval in which the rate changes linearly from `r0` to `r1`.
+fn segment_real_time(t0: f64, t1: f64, r0: f64, r1: f64) -> f64 {
+    let duration = t1 - t0;
+
+    if duration <= 0.0 {
+        return 0.0;
+    }
+
+    if (r1 - r0).abs() <= f64::EPSILON {
+        duration / r0
+    } else {
+        // Integral of 1 / (r0 + (r1 - r0) * x / duration) over x in 0..duration
+        duration * (r1 / r0).ln() / (r1 - r0)
+    }
+}
+
+/// Difficulty attributes that were interpolated between two calculated clock rates.
+#[derive(Clone, Debug)]
+pub struct InterpolatedAttributes {
+    /// The interpolated attributes.
+    pub attributes: DifficultyAttributes,
+    /// The estimated maximum deviation of the interpolated star rating
+    /// from the actual star rating. `0.0` if the rate was calculated exactly.
+    pub max_error: f64,
+}
+
+/// Difficulty attributes across a range of clock rates.
+///
+/// Instead of calculating the attributes for every single rate, e.g. for a
+/// rate change slider going from 1.01x to 1.5x in steps of 0.01, they are only
+/// calculated for a few anchor rates and linearly interpolated in between.
+///
+/// To validate the interpolation, the attributes are also calculated in the middle
+/// between two anchors. If the interpolated star rating deviates too much, the
+/// middle becomes an anchor itself and both halves are validated in turn.
+/// The deviation at the middle serves as error estimate for the whole interval.
+///
+/// Note that the estimate is not a strict bound. Since strains are grouped into sections
+/// whose length depends on the clock rate, the star rating fluctuates slightly between
+/// neighbouring rates, usually by less than 1%, which no interpolation can account for.
+///
+/// ```
+/// use akatsuki_pp::{rate::RateInterpolation, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// let rates = RateInterpolation::new(&map, 0, 1.0, 1.5, 0.05).unwrap();
+///
+/// if let Some(interpolated) = rates.attributes(1.23) {
+///     println!(
+///         "{:.2} stars (± {:.3})",
+///         interpolated.attributes.stars(),
+///         interpolated.max_error,
+///     );
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateInterpolation {
+    /// Calculated attributes sorted by their clock rate.
+    anchors: Vec<(f64, DifficultyAttributes)>,
+    /// The estimated error of the interval between two consecutive anchors.
+    errors: Vec<f64>,
+}
+
+impl RateInterpolation {
+    /// Calculate the anchors between `min_rate` and `max_rate` so that the star rating
+    /// of interpolated attributes deviates by at most `max_error`, if possible.
+    ///
+    /// Returns `None` if the rates are not positive and finite or if `min_rate > max_rate`.
+    pub fn new(
+        map: &Beatmap,
+        mods: u32,
+        min_rate: f64,
+        max_rate: f64,
+        max_error: f64,
+    ) -> Option<Self> {
+        let valid = |rate: f64| rate.is_finite() && rate > 0.0;
+
+        if !valid(min_rate) || !valid(max_rate) || min_rate > max_rate {
+            return None;
+        }
+
+        let calculate = |rate| map.stars(RateMods::new(mods, rate), None);
+
+        let mut this = Self {
+            anchors: vec![(min_rate, calculate(min_rate))],
+            errors: Vec::new(),
+        };
+
+        if max_rate > min_rate {
+            let end = calculate(max_rate);
+            this.subdivide(&calculate, max_rate, end, max_error.max(0.0), MAX_DEPTH);
+        }
+
+        Some(this)
+    }
+
+    /// Add anchors until `rate` so that the error stays below `max_error`.
+    ///
+    /// The last anchor must be the start of the interval.
+    fn subdivide(
+        &mut self,
+        calculate: &impl Fn(f64) -> DifficultyAttributes,
+        rate: f64,
+        attributes: DifficultyAttributes,
+        max_error: f64,
+        depth: u32,
+    ) {
+        let (start_rate, start) = self.anchors.last().expect("missing start anchor");
+        let start_rate = *start_rate;
+        let mid_rate = (start_rate + rate) / 2.0;
+
+        let mid = calculate(mid_rate);
+        let error = (lerp(start, &attributes, 0.5).stars() - mid.stars()).abs();
+
+        if error > max_error && depth > 0 {
+            self.subdivide(calculate, mid_rate, mid, max_error, depth - 1);
+            self.subdivide(calculate, rate, attributes, max_error, depth - 1);
+        } else {
+            self.anchors.push((mid_rate, mid));
+            self.anchors.push((rate, attributes));
+            self.errors.extend([error, error]);
+        }
+    }
+
+    /// The attributes for the given clock rate.
+    ///
+    /// Returns `None` if the rate is outside of the range that was specified on creation.
+    pub fn attributes(&self, rate: f64) -> Option<InterpolatedAttributes> {
+        let idx = self.anchors.partition_point(|(anchor, _)| *anchor < rate);
+        let (end_rate, end) = self.anchors.get(idx)?;
+
+        if (*end_rate - rate).abs() <= f64::EPSILON {
+            return Some(InterpolatedAttributes {
+                attributes: end.clone(),
+                max_error: 0.0,
+            });
+        }
+
+        let (start_rate, start) = &self.anchors[idx.checked_sub(1)?];
+        let t = (rate - start_rate) / (end_rate - start_rate);
+
+        Some(InterpolatedAttributes {
+            attributes: lerp(start, end, t),
+            max_error: self.errors[idx - 1],
+        })
+    }
+
+    /// The clock rates for which the attributes were calculated.
+    pub fn anchor_rates(&self) -> impl Iterator<Item = f64> + '_ {
+        self.anchors.iter().map(|(rate, _)| *rate)
+    }
+}
+
+fn lerp(start: &DifficultyAttributes, end: &DifficultyAttributes, t: f64) -> DifficultyAttributes {
+    let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+    match (start, end) {
+        #[cfg(feature = "fruits")]
+        (DifficultyAttributes::Fruits(a), DifficultyAttributes::Fruits(b)) => {
+            DifficultyAttributes::Fruits(crate::fruits::FruitsDifficultyAttributes {
+                stars: lerp(a.stars, b.stars),
+                ar: lerp(a.ar, b.ar),
+                ..a.clone()
+            })
+        }
+        #[cfg(feature = "mania")]
+        (DifficultyAttributes::Mania(a), DifficultyAttributes::Mania(b)) => {
+            DifficultyAttributes::Mania(crate::mania::ManiaDifficultyAttributes {
+                stars: lerp(a.stars, b.stars),
+                hold_factor: lerp(a.hold_factor, b.hold_factor),
+                ln_coverage: lerp(a.ln_coverage, b.ln_coverage),
+                ..*a
+            })
+        }
+        #[cfg(feature = "osu")]
+        (DifficultyAttributes::Osu(a), DifficultyAttributes::Osu(b)) => {
+            DifficultyAttributes::Osu(crate::osu::OsuDifficultyAttributes {
+                aim_strain: lerp(a.aim_strain, b.aim_strain),
+                speed_strain: lerp(a.speed_strain, b.speed_strain),
+                flashlight_rating: lerp(a.flashlight_rating, b.flashlight_rating),
+                slider_factor: lerp(a.slider_factor, b.slider_factor),
+                aim_difficult_strain_count: lerp(
+                    a.aim_difficult_strain_count,
+                    b.aim_difficult_strain_count,
+                ),
+                speed_difficult_strain_count: lerp(
+                    a.speed_difficult_strain_count,
+                    b.speed_difficult_strain_count,
+                ),
+                ar: lerp(a.ar, b.ar),
+                od: lerp(a.od, b.od),
+                hp: lerp(a.hp, b.hp),
+                stars: lerp(a.stars, b.stars),
+                ..a.clone()
+            })
+        }
+        #[cfg(feature = "taiko")]
+        (DifficultyAttributes::Taiko(a), DifficultyAttributes::Taiko(b)) => {
+            DifficultyAttributes::Taiko(crate::taiko::TaikoDifficultyAttributes {
+                stars: lerp(a.stars, b.stars),
+                ..*a
+            })
+        }
+        #[allow(unreachable_patterns)]
+        _ => unreachable!("all anchors are calculated on the same map"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn interpolation_within_error() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let rates = RateInterpolation::new(&map, 0, 1.0, 1.5, 0.05).unwrap();
+
+        assert!(rates.anchor_rates().count() >= 3);
+        assert!(rates.attributes(0.9).is_none());
+        assert!(rates.attributes(1.6).is_none());
+
+        let exact = rates.attributes(1.5).unwrap();
+        assert_eq!(exact.max_error, 0.0);
+        assert_eq!(exact.attributes.stars(), map.stars(64, None).stars());
+
+        for step in 0..=50 {
+            let rate = 1.0 + step as f64 * 0.01;
+            let interpolated = rates.attributes(rate).unwrap();
+            let actual = map.stars(RateMods::new(0, rate), None).stars();
+
+            // Strain sections cause small fluctuations that can not be interpolated
+            assert!((interpolated.attributes.stars() - actual).abs() <= 0.05);
+            assert!(interpolated.max_error <= 0.05);
+        }
+
+        assert!(RateInterpolation::new(&map, 0, 1.5, 1.0, 0.01).is_none());
+        assert!(RateInterpolation::new(&map, 0, 0.0, 1.0, 0.01).is_none());
+    }
+
+    #[test]
+    fn schedule_real_time() {
+        let schedule = RateSchedule::new([(2000.0, 2.0), (1000.0, 1.0)]).unwrap();
+
+        assert_eq!(schedule.rate(0.0), 1.0);
+        assert_eq!(schedule.rate(1500.0), 1.5);
+        assert_eq!(schedule.rate(3000.0), 2.0);
+
+        assert_eq!(schedule.real_time(500.0), 500.0);
+        assert!((schedule.real_time(2000.0) - (1000.0 + 1000.0 * 2.0_f64.ln())).abs() < 1e-9);
+        assert!((schedule.real_time(3000.0) - schedule.real_time(2000.0) - 500.0).abs() < 1e-9);
+
+        let constant = RateSchedule::new([(0.0, 1.5)]).unwrap();
+        assert!((constant.average_rate(100.0, 4000.0) - 1.5).abs() < 1e-9);
+
+        assert!(RateSchedule::new([]).is_none());
+        assert!(RateSchedule::new([(0.0, 0.0)]).is_none());
+    }
+}
@@ -0,0 +1,316 @@
+#[cfg(feature = "research")]
+use std::{error::Error as StdError, fmt};
+
+/// The values that determine how a skill turns strains into difficulty.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SkillParameters {
+    /// Multiplier for the strain value of each hit object.
+    ///
+    /// Must be finite and positive.
+    pub skill_multiplier: f64,
+    /// Base of the exponential decay of the current strain per second.
+    ///
+    /// Must be within `0.0..=1.0`.
+    pub strain_decay_base: f64,
+    /// Factor by which the weight of each strain peak decreases
+    /// compared to the next higher peak.
+    ///
+    /// Must be within `0.0..=1.0` and not zero.
+    pub decay_weight: f64,
+    /// Multiplier for the weighted sum of the strain peaks.
+    ///
+    /// Must be finite and positive.
+    pub difficulty_multiplier: f64,
+}
+
+#[cfg(feature = "research")]
+impl SkillParameters {
+    fn validate(&self, skill: &'static str) -> Result<(), ParameterError> {
+        let error = |parameter, value| {
+            Err(ParameterError {
+                skill,
+                parameter,
+                value,
+            })
+        };
+
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+
+        if !positive(self.skill_multiplier) {
+            error("skill multiplier", self.skill_multiplier)
+        } else if !(0.0..=1.0).contains(&self.strain_decay_base) {
+            error("strain decay base", self.strain_decay_base)
+        } else if !(self.decay_weight > 0.0 && self.decay_weight <= 1.0) {
+            error("decay weight", self.decay_weight)
+        } else if !positive(self.difficulty_multiplier) {
+            error("difficulty multiplier", self.difficulty_multiplier)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A [`SkillParameters`] value that is outside of its valid range.
+#[cfg(feature = "research")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterError {
+    /// The name of the skill, e.g. `"aim"`.
+    pub skill: &'static str,
+    /// The name of the parameter, e.g. `"decay weight"`.
+    pub parameter: &'static str,
+    /// The invalid value.
+    pub value: f64,
+}
+
+#[cfg(feature = "research")]
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {} of the {} skill: {}",
+            self.parameter, self.skill, self.value
+        )
+    }
+}
+
+#[cfg(feature = "research")]
+impl StdError for ParameterError {}
+
+/// The skill parameters of osu!standard difficulty calculation.
+///
+/// The aim parameters apply to the aim skill with and without sliders.
+#[cfg(feature = "osu")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OsuParameters {
+    /// Parameters of the aim skill.
+    pub aim: SkillParameters,
+    /// Parameters of the speed skill.
+    pub speed: SkillParameters,
+    /// Parameters of the flashlight skill.
+    pub flashlight: SkillParameters,
+}
+
+#[cfg(feature = "osu")]
+impl OsuParameters {
+    pub(crate) const DEFAULT: Self = Self {
+        aim: crate::osu::AIM_PARAMETERS,
+        speed: crate::osu::SPEED_PARAMETERS,
+        flashlight: crate::osu::FLASHLIGHT_PARAMETERS,
+    };
+
+    /// Check that all parameters are within their valid range.
+    #[cfg(feature = "research")]
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        self.aim.validate("aim")?;
+        self.speed.validate("speed")?;
+        self.flashlight.validate("flashlight")
+    }
+}
+
+/// The skill parameters of osu!taiko difficulty calculation.
+///
+/// The stamina parameters apply to the stamina skills of both hands.
+#[cfg(feature = "taiko")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TaikoParameters {
+    /// Parameters of the color skill.
+    pub color: SkillParameters,
+    /// Parameters of the rhythm skill.
+    pub rhythm: SkillParameters,
+    /// Parameters of the stamina skill.
+    pub stamina: SkillParameters,
+}
+
+#[cfg(feature = "taiko")]
+impl TaikoParameters {
+    pub(crate) const DEFAULT: Self = Self {
+        color: crate::taiko::COLOR_PARAMETERS,
+        rhythm: crate::taiko::RHYTHM_PARAMETERS,
+        stamina: crate::taiko::STAMINA_PARAMETERS,
+    };
+
+    /// Check that all parameters are within their valid range.
+    #[cfg(feature = "research")]
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        self.color.validate("color")?;
+        self.rhythm.validate("rhythm")?;
+        self.stamina.validate("stamina")
+    }
+}
+
+/// The skill parameters of osu!ctb difficulty calculation.
+#[cfg(feature = "fruits")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FruitsParameters {
+    /// Parameters of the movement skill.
+    pub movement: SkillParameters,
+}
+
+#[cfg(feature = "fruits")]
+impl FruitsParameters {
+    pub(crate) const DEFAULT: Self = Self {
+        movement: crate::fruits::MOVEMENT_PARAMETERS,
+    };
+
+    /// Check that all parameters are within their valid range.
+    #[cfg(feature = "research")]
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        self.movement.validate("movement")
+    }
+}
+
+/// The skill parameters of osu!mania difficulty calculation.
+#[cfg(feature = "mania")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ManiaParameters {
+    /// Parameters of the strain skill.
+    pub strain: SkillParameters,
+}
+
+#[cfg(feature = "mania")]
+impl ManiaParameters {
+    pub(crate) const DEFAULT: Self = Self {
+        strain: crate::mania::STRAIN_PARAMETERS,
+    };
+
+    /// Check that all parameters are within their valid range.
+    #[cfg(feature = "research")]
+    pub fn validate(&self) -> Result<(), ParameterError> {
+        self.strain.validate("strain")
+    }
+}
+
+macro_rules! impl_default {
+    ($($mode:literal: $ty:ident,)*) => {
+        $(
+            #[cfg(feature = $mode)]
+            impl Default for $ty {
+                /// The parameters that are used by regular difficulty calculation.
+                #[inline]
+                fn default() -> Self {
+                    Self::DEFAULT
+                }
+            }
+        )*
+    };
+}
+
+impl_default! {
+    "osu": OsuParameters,
+    "taiko": TaikoParameters,
+    "fruits": FruitsParameters,
+    "mania": ManiaParameters,
+}
+
+#[cfg(all(test, feature = "research"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_valid() {
+        #[cfg(feature = "osu")]
+        assert!(OsuParameters::default().validate().is_ok());
+
+        #[cfg(feature = "taiko")]
+        assert!(TaikoParameters::default().validate().is_ok());
+
+        #[cfg(feature = "fruits")]
+        assert!(FruitsParameters::default().validate().is_ok());
+
+        #[cfg(feature = "mania")]
+        assert!(ManiaParameters::default().validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        let valid = SkillParameters {
+            skill_multiplier: 1.0,
+            strain_decay_base: 0.3,
+            decay_weight: 0.9,
+            difficulty_multiplier: 1.0,
+        };
+
+        assert!(valid.validate("test").is_ok());
+
+        let invalid = [
+            SkillParameters {
+                skill_multiplier: f64::INFINITY,
+                ..valid
+            },
+            SkillParameters {
+                strain_decay_base: 1.5,
+                ..valid
+            },
+            SkillParameters {
+                decay_weight: 0.0,
+                ..valid
+            },
+            SkillParameters {
+                difficulty_multiplier: f64::NAN,
+                ..valid
+            },
+        ];
+
+        for params in invalid.iter() {
+            let err = params.validate("test").unwrap_err();
+            assert_eq!(err.skill, "test");
+        }
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn defaults_match_regular_stars() {
+        use crate::Beatmap;
+
+        #[cfg(feature = "osu")]
+        {
+            let map = Beatmap::from_path("./maps/2785319.osu").unwrap();
+            let params = OsuParameters::default();
+            let attrs = crate::osu::stars_with_parameters(&map, 8, None, &params).unwrap();
+            assert_eq!(attrs, crate::osu::stars(&map, 8, None));
+
+            let params = OsuParameters {
+                speed: SkillParameters {
+                    decay_weight: 0.95,
+                    ..params.speed
+                },
+                ..params
+            };
+            let attrs = crate::osu::stars_with_parameters(&map, 8, None, &params).unwrap();
+            assert!(attrs.speed_strain > crate::osu::stars(&map, 8, None).speed_strain);
+        }
+
+        #[cfg(feature = "taiko")]
+        {
+            let map = Beatmap::from_path("./maps/1028484.osu").unwrap();
+            let params = TaikoParameters::default();
+            let attrs = crate::taiko::stars_with_parameters(&map, 0, None, &params).unwrap();
+            assert_eq!(attrs, crate::taiko::stars(&map, 0, None));
+        }
+
+        #[cfg(feature = "fruits")]
+        {
+            let map = Beatmap::from_path("./maps/2118524.osu").unwrap();
+            let params = FruitsParameters::default();
+            let attrs = crate::fruits::stars_with_parameters(&map, 0, None, &params).unwrap();
+            assert_eq!(attrs, crate::fruits::stars(&map, 0, None));
+        }
+
+        #[cfg(feature = "mania")]
+        {
+            let map = Beatmap::from_path("./maps/1974394.osu").unwrap();
+            let params = ManiaParameters {
+                strain: SkillParameters {
+                    strain_decay_base: 2.0,
+                    ..ManiaParameters::default().strain
+                },
+            };
+            let err = crate::mania::stars_with_parameters(&map, 0, None, &params).unwrap_err();
+            assert_eq!(err.parameter, "strain decay base");
+
+            let params = ManiaParameters::default();
+            let attrs = crate::mania::stars_with_parameters(&map, 0, None, &params).unwrap();
+            assert_eq!(attrs, crate::mania::stars(&map, 0, None));
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{limited_queue::LimitedQueue, Beatmap, GameMode, Mods};
+
+use std::cmp::Ordering;
+
+const RHYTHM_STRAIN_DECAY: f64 = 0.96;
+const RHYTHM_HISTORY_MAX_LEN: usize = 8;
+
+static COMMON_RHYTHMS: [HitObjectRhythm; 9] = [
+    HitObjectRhythm {
+        id: 0,
+        ratio: 1.0,
+        difficulty: 0.0,
+    },
+    HitObjectRhythm {
+        id: 1,
+        ratio: 2.0 / 1.0,
+        difficulty: 0.3,
+    },
+    HitObjectRhythm {
+        id: 2,
+        ratio: 1.0 / 2.0,
+        difficulty: 0.5,
+    },
+    HitObjectRhythm {
+        id: 3,
+        ratio: 3.0 / 1.0,
+        difficulty: 0.3,
+    },
+    HitObjectRhythm {
+        id: 4,
+        ratio: 1.0 / 3.0,
+        difficulty: 0.35,
+    },
+    HitObjectRhythm {
+        id: 5,
+        ratio: 3.0 / 2.0,
+        difficulty: 0.6,
+    },
+    HitObjectRhythm {
+        id: 6,
+        ratio: 2.0 / 3.0,
+        difficulty: 0.4,
+    },
+    HitObjectRhythm {
+        id: 7,
+        ratio: 5.0 / 4.0,
+        difficulty: 0.5,
+    },
+    HitObjectRhythm {
+        id: 8,
+        ratio: 4.0 / 5.0,
+        difficulty: 0.7,
+    },
+];
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct HitObjectRhythm {
+    id: u8,
+    ratio: f64,
+    pub(crate) difficulty: f64,
+}
+
+impl PartialEq for HitObjectRhythm {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for HitObjectRhythm {}
+
+#[inline]
+pub(crate) fn closest_rhythm(delta_time: f64, prev_delta_time: f64) -> &'static HitObjectRhythm {
+    let ratio = delta_time / prev_delta_time;
+
+    COMMON_RHYTHMS
+        .iter()
+        .min_by(|r1, r2| {
+            (r1.ratio - ratio)
+                .abs()
+                .partial_cmp(&(r2.ratio - ratio).abs())
+                .unwrap_or(Ordering::Equal)
+        })
+        .unwrap()
+}
+
+/// Keeps track of recent rhythms to evaluate the rhythm strain of each note.
+#[derive(Clone, Debug)]
+pub(crate) struct RhythmEvaluator {
+    rhythm_history: LimitedQueue<(usize, &'static HitObjectRhythm)>, // (idx, rhythm)
+    notes_since_rhythm_change: usize,
+    current_strain: f64,
+}
+
+impl RhythmEvaluator {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            rhythm_history: LimitedQueue::new(RHYTHM_HISTORY_MAX_LEN),
+            notes_since_rhythm_change: 0,
+            current_strain: 0.0,
+        }
+    }
+
+    /// Process the note at index `idx` and return the resulting rhythm strain.
+    ///
+    /// Objects that are not considered notes, e.g. taiko drum rolls, reset the strain.
+    pub(crate) fn process(
+        &mut self,
+        idx: usize,
+        delta: f64,
+        rhythm: &'static HitObjectRhythm,
+        is_note: bool,
+    ) -> f64 {
+        if !is_note {
+            self.current_strain = 0.0;
+            self.notes_since_rhythm_change = 0;
+
+            return 0.0;
+        }
+
+        self.current_strain *= RHYTHM_STRAIN_DECAY;
+        self.notes_since_rhythm_change += 1;
+
+        if rhythm.difficulty.abs() <= f64::EPSILON {
+            return 0.0;
+        }
+
+        let mut strain = rhythm.difficulty;
+
+        self.rhythm_history.push((idx, rhythm));
+
+        let history = &self.rhythm_history;
+        let mut reps_penalty = 1.0;
+
+        for most_recent_patterns_to_compare in 2..=RHYTHM_HISTORY_MAX_LEN / 2 {
+            let iter = (0..history
+                .len()
+                .saturating_sub(most_recent_patterns_to_compare))
+                .rev();
+
+            for start in iter {
+                let different_pattern = (0..most_recent_patterns_to_compare).any(|i| {
+                    let to_compare = history.len() + i - most_recent_patterns_to_compare;
+
+                    history[start + i].1 != history[to_compare].1
+                });
+
+                if different_pattern {
+                    continue;
+                }
+
+                reps_penalty *= repetition_penalty(idx - history[start].0);
+
+                break;
+            }
+        }
+
+        let speed_penalty = if delta < 80.0 {
+            1.0
+        } else if delta < 210.0 {
+            (1.4 - 0.005 * delta).max(0.0)
+        } else {
+            self.current_strain = 0.0;
+            self.notes_since_rhythm_change = 0;
+
+            0.0
+        };
+
+        strain *= reps_penalty;
+        strain *= pattern_len_penalty(self.notes_since_rhythm_change);
+        strain *= speed_penalty;
+
+        self.notes_since_rhythm_change = 0;
+        self.current_strain += strain;
+
+        self.current_strain
+    }
+}
+
+#[inline]
+fn pattern_len_penalty(pattern_len: usize) -> f64 {
+    let pattern_len = pattern_len as f64;
+    let short_pattern_penalty = (0.15 * pattern_len).min(1.0);
+    let long_pattern_penalty = (2.5 - 0.15 * pattern_len).clamp(0.0, 1.0);
+
+    short_pattern_penalty.min(long_pattern_penalty)
+}
+
+#[inline]
+pub(crate) fn repetition_penalty(notes_since: usize) -> f64 {
+    (0.032 * notes_since as f64).min(1.0)
+}
+
+/// The rhythm of a map, see [`analyze`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RhythmAttributes {
+    /// The time between the start of each hit object and its previous one,
+    /// adjusted by the clock rate.
+    ///
+    /// Contains one value less than the map has hit objects.
+    pub deltas: Vec<f64>,
+    /// The average rhythm strain across all notes.
+    ///
+    /// A map with a perfectly steady rhythm has a complexity of 0.
+    pub complexity: f64,
+}
+
+/// Calculate the delta times between hit objects and the rhythm complexity of a map.
+///
+/// The complexity is based on the rhythm skill of osu!taiko but applies to maps of all modes.
+/// Hit objects that start at the same time, e.g. osu!mania chords, count as a single note.
+/// For osu!taiko maps, drum rolls and swells interrupt the rhythm.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{rhythm, Beatmap};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+///
+/// let mods = 64; // DT
+/// let attributes = rhythm::analyze(&map, mods);
+///
+/// assert_eq!(attributes.deltas.len(), map.hit_objects.len().saturating_sub(1));
+/// ```
+pub fn analyze(map: &Beatmap, mods: impl Mods) -> RhythmAttributes {
+    let clock_rate = mods.speed();
+
+    let deltas: Vec<_> = map
+        .hit_objects
+        .windows(2)
+        .map(|pair| (pair[1].start_time - pair[0].start_time) / clock_rate)
+        .collect();
+
+    let mut evaluator = RhythmEvaluator::new();
+    let mut prev_delta = None;
+    let mut strain_sum = 0.0;
+    let mut n_notes = 0;
+
+    let notes = map.hit_objects.iter().skip(1).zip(&deltas).enumerate();
+
+    for (i, (h, &delta)) in notes {
+        if delta <= 0.0 {
+            continue;
+        }
+
+        if let Some(prev_delta) = prev_delta {
+            let rhythm = closest_rhythm(delta, prev_delta);
+            let is_note = map.mode != GameMode::TKO || h.is_circle();
+
+            strain_sum += evaluator.process(i + 1, delta, rhythm, is_note);
+            n_notes += 1;
+        }
+
+        prev_delta = Some(delta);
+    }
+
+    let complexity = if n_notes > 0 {
+        strain_sum / n_notes as f64
+    } else {
+        0.0
+    };
+
+    RhythmAttributes { deltas, complexity }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_map() {
+        let attributes = analyze(&Beatmap::default(), 0);
+
+        assert_eq!(attributes, RhythmAttributes::default());
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn deltas_and_complexity() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+
+        let nomod = analyze(&map, 0);
+        let dt = analyze(&map, 64);
+
+        assert_eq!(nomod.deltas.len(), map.hit_objects.len() - 1);
+
+        for (nomod, dt) in nomod.deltas.iter().zip(&dt.deltas) {
+            assert!((nomod / 1.5 - dt).abs() < 1e-9);
+        }
+
+        assert!(nomod.complexity > 0.0);
+    }
+
+    #[test]
+    fn steady_rhythm() {
+        let mut evaluator = RhythmEvaluator::new();
+        let rhythm = closest_rhythm(100.0, 100.0);
+
+        for idx in 1..20 {
+            assert_eq!(evaluator.process(idx, 100.0, rhythm, true), 0.0);
+        }
+    }
+}
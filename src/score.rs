@@ -0,0 +1,526 @@
+use std::cmp::Ordering;
+
+use crate::{DifficultyAttributes, GameMode, Mods, ScoreState};
+
+const MAX_SCORE: f64 = 1_000_000.0;
+
+/// The score values of a play as calculated by osu!lazer, see [`standardised`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LazerScore {
+    /// The standardised score, capped at one million before mod multipliers.
+    pub standardised: u32,
+    /// The standardised score converted to classic scoring.
+    pub classic: u64,
+}
+
+/// Estimate the osu!lazer score of a play on a map with the given difficulty attributes.
+///
+/// The standardised score consists of a combo portion and an accuracy portion
+/// whose weights depend on the game mode, scaled by the mods' score multipliers.
+/// Just like in osu!lazer, each mod's multiplier is applied separately
+/// and depends on the game mode, e.g. `HD` and `HR` have no effect in osu!mania.
+/// Since the [`ScoreState`] does not contain the combo of every hit object,
+/// the combo portion assumes that all hits besides the maximum combo
+/// were evenly split between the misses.
+/// Bonus score, e.g. from spinners, is not considered.
+///
+/// For osu!mania, `n300` counts both 300s and MAXs, `n_katu` counts 200s,
+/// and the maximum combo is considered to be the amount of notes.
+/// If the state contains no hitresults at all, its `score` is used
+/// as an approximation of the accuracy of a full combo play instead.
+///
+/// The score state may contain a partial play, in which case the score is estimated
+/// as if the play ended after its last judged hit object.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::{score, Beatmap, BeatmapExt, ScoreState};
+///
+/// # /*
+/// let map: Beatmap = ...
+/// # */
+/// # let map = Beatmap::default();
+/// let attributes = map.stars(8 + 16, None); // HDHR
+///
+/// let state = ScoreState {
+///     max_combo: 412,
+///     n300: 320,
+///     n100: 12,
+///     misses: 1,
+///     ..Default::default()
+/// };
+///
+/// let score = score::standardised(&attributes, 8 + 16, &state);
+/// println!("{} ({} classic)", score.standardised, score.classic);
+/// ```
+pub fn standardised(
+    attributes: &DifficultyAttributes,
+    mods: impl Mods,
+    state: &ScoreState,
+) -> LazerScore {
+    let score = match attributes {
+        #[cfg(feature = "fruits")]
+        DifficultyAttributes::Fruits(attributes) => {
+            let hits = state.n300 + state.n100;
+            let judged = hits + state.n50 + state.n_katu + state.misses;
+            let max_judged = attributes.max_combo() + attributes.n_tiny_droplets;
+
+            let acc = if judged > 0 {
+                (hits + state.n50) as f64 / judged as f64
+            } else {
+                1.0
+            };
+
+            let combo = combo_progress(state.max_combo, hits, state.misses, attributes.max_combo());
+            let total = 600_000.0 * combo + 400_000.0 * acc * progress(judged, max_judged);
+
+            total * score_multiplier(GameMode::CTB, mods)
+        }
+        #[cfg(feature = "mania")]
+        DifficultyAttributes::Mania(attributes) => {
+            let hits = state.n300 + state.n_katu + state.n100 + state.n50;
+            let judged = hits + state.misses;
+            let n_objects = attributes.n_notes + attributes.n_hold_notes;
+
+            let total = if judged > 0 {
+                let acc = (6 * state.n300 + 4 * state.n_katu + 2 * state.n100 + state.n50) as f64
+                    / (6 * judged) as f64;
+
+                let combo = combo_progress(state.max_combo, hits, state.misses, n_objects);
+
+                150_000.0 * combo
+                    + 850_000.0 * acc.powf(2.0 + 2.0 * acc) * progress(judged, n_objects)
+            } else {
+                let acc = (state.score as f64 / MAX_SCORE).min(1.0);
+
+                150_000.0 + 850_000.0 * acc.powf(2.0 + 2.0 * acc)
+            };
+
+            total * score_multiplier(GameMode::MNA, mods)
+        }
+        #[cfg(feature = "osu")]
+        DifficultyAttributes::Osu(attributes) => {
+            let hits = state.n300 + state.n100 + state.n50;
+            let judged = hits + state.misses;
+            let n_objects = attributes.n_circles + attributes.n_sliders + attributes.n_spinners;
+
+            let acc = if judged > 0 {
+                (6 * state.n300 + 2 * state.n100 + state.n50) as f64 / (6 * judged) as f64
+            } else {
+                1.0
+            };
+
+            // Slider ticks and ends count towards the combo so scale the hits accordingly
+            let combo_hits = hits as f64 * attributes.max_combo as f64 / n_objects.max(1) as f64;
+            let combo = combo_progress(
+                state.max_combo,
+                combo_hits.round() as usize,
+                state.misses,
+                attributes.max_combo,
+            );
+            let total = 700_000.0 * combo + 300_000.0 * acc.powi(10) * progress(judged, n_objects);
+
+            total * score_multiplier(GameMode::STD, mods)
+        }
+        #[cfg(feature = "taiko")]
+        DifficultyAttributes::Taiko(attributes) => {
+            let hits = state.n300 + state.n100;
+            let judged = hits + state.misses;
+
+            let acc = if judged > 0 {
+                (2 * state.n300 + state.n100) as f64 / (2 * judged) as f64
+            } else {
+                1.0
+            };
+
+            let combo = combo_progress(state.max_combo, hits, state.misses, attributes.max_combo);
+            let total = 250_000.0 * combo
+                + 750_000.0 * acc.powf(3.6) * progress(judged, attributes.max_combo);
+
+            total * score_multiplier(GameMode::TKO, mods)
+        }
+    };
+
+    let standardised = score.round().max(0.0) as u32;
+    let classic = to_classic(attributes, standardised);
+
+    LazerScore {
+        standardised,
+        classic,
+    }
+}
+
+/// Estimate the portion of the maximum combo score that has been achieved.
+///
+/// The combo score of each hit grows with the square root of its combo
+/// so a run of `n` hits contributes roughly `n^1.5`.
+fn combo_progress(max_combo: usize, combo_hits: usize, misses: usize, map_max_combo: usize) -> f64 {
+    if map_max_combo == 0 {
+        return 1.0;
+    }
+
+    let max_combo = max_combo.min(map_max_combo) as f64;
+    let remaining = combo_hits.saturating_sub(max_combo as usize) as f64;
+    let runs = misses.max(1) as f64;
+
+    let achieved = max_combo.powf(1.5) + runs * (remaining / runs).powf(1.5);
+
+    (achieved / (map_max_combo as f64).powf(1.5)).min(1.0)
+}
+
+#[inline]
+fn progress(judged: usize, max: usize) -> f64 {
+    if max == 0 {
+        1.0
+    } else {
+        (judged as f64 / max as f64).min(1.0)
+    }
+}
+
+/// The score multipliers of each mod as used by a ruleset in osu!lazer.
+struct ModMultipliers {
+    nf: f64,
+    ez: f64,
+    ht: f64,
+    hd: f64,
+    hr: f64,
+    dt: f64,
+    fl: f64,
+    bl: f64,
+    so: f64,
+}
+
+impl ModMultipliers {
+    const OSU: Self = Self {
+        nf: 0.5,
+        ez: 0.5,
+        ht: 0.3,
+        hd: 1.06,
+        hr: 1.06,
+        dt: 1.12,
+        fl: 1.12,
+        bl: 1.12,
+        so: 0.9,
+    };
+
+    const TAIKO: Self = Self {
+        nf: 0.5,
+        ez: 0.5,
+        ht: 0.3,
+        hd: 1.06,
+        hr: 1.06,
+        dt: 1.12,
+        fl: 1.12,
+        bl: 1.0,
+        so: 1.0,
+    };
+
+    const FRUITS: Self = Self {
+        nf: 0.5,
+        ez: 0.5,
+        ht: 0.3,
+        hd: 1.06,
+        hr: 1.12,
+        dt: 1.12,
+        fl: 1.12,
+        bl: 1.0,
+        so: 1.0,
+    };
+
+    const MANIA: Self = Self {
+        nf: 0.5,
+        ez: 0.5,
+        ht: 0.5,
+        hd: 1.0,
+        hr: 1.0,
+        dt: 1.0,
+        fl: 1.0,
+        bl: 1.0,
+        so: 1.0,
+    };
+
+    fn new(mode: GameMode) -> Self {
+        match mode {
+            GameMode::STD => Self::OSU,
+            GameMode::TKO => Self::TAIKO,
+            GameMode::CTB => Self::FRUITS,
+            GameMode::MNA => Self::MANIA,
+        }
+    }
+}
+
+fn score_multiplier(mode: GameMode, mods: impl Mods) -> f64 {
+    let multipliers = ModMultipliers::new(mode);
+
+    [
+        (mods.nf(), multipliers.nf),
+        (mods.ez(), multipliers.ez),
+        (mods.ht(), multipliers.ht),
+        (mods.hd(), multipliers.hd),
+        (mods.hr(), multipliers.hr),
+        (mods.dt(), multipliers.dt),
+        (mods.fl(), multipliers.fl),
+        (mods.bl(), multipliers.bl),
+        (mods.so(), multipliers.so),
+    ]
+    .iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, multiplier)| multiplier)
+    .product()
+}
+
+fn to_classic(attributes: &DifficultyAttributes, standardised: u32) -> u64 {
+    let classic = match attributes {
+        #[cfg(feature = "fruits")]
+        DifficultyAttributes::Fruits(attributes) => {
+            let score = standardised as f64 / MAX_SCORE;
+            let n_objects = attributes.max_combo() as f64;
+
+            (score * n_objects).powi(2) * 21.62 + standardised as f64 / 10.0
+        }
+        #[cfg(feature = "mania")]
+        DifficultyAttributes::Mania(_) => standardised as f64,
+        #[cfg(feature = "osu")]
+        DifficultyAttributes::Osu(attributes) => {
+            let score = standardised as f64 / MAX_SCORE;
+            let n_objects =
+                (attributes.n_circles + attributes.n_sliders + attributes.n_spinners) as f64;
+
+            (n_objects * n_objects * 32.57 + 100_000.0) * score
+        }
+        #[cfg(feature = "taiko")]
+        DifficultyAttributes::Taiko(attributes) => {
+            let score = standardised as f64 / MAX_SCORE;
+            let n_objects = attributes.max_combo as f64;
+
+            (n_objects * 1109.0 + 100_000.0) * score
+        }
+    };
+
+    classic.round() as u64
+}
+
+/// A sorting key for scores on a leaderboard.
+///
+/// Scores are ordered by their pp, then by their accuracy, and lastly by their
+/// timestamp where the earlier score ranks higher. NaN values rank below everything
+/// else, including negative infinity, so that the order is total even for broken maps.
+///
+/// # Example
+///
+/// ```
+/// use akatsuki_pp::score::ScoreRank;
+///
+/// let mut scores = vec![
+///     ScoreRank::new(412.0, 98.5).timestamp(20),
+///     ScoreRank::new(f64::NAN, 100.0),
+///     ScoreRank::new(412.0, 98.5).timestamp(10),
+///     ScoreRank::new(500.0, 95.0),
+/// ];
+///
+/// // Best score first
+/// scores.sort_by(|a, b| b.cmp(a));
+///
+/// assert_eq!(scores[0].pp, 500.0);
+/// assert_eq!(scores[1].timestamp, 10);
+/// assert!(scores[3].pp.is_nan());
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ScoreRank {
+    /// The performance points.
+    pub pp: f64,
+    /// The accuracy, usually in percent.
+    pub accuracy: f64,
+    /// The time at which the score was set, e.g. a unix timestamp.
+    /// Lower values are considered earlier.
+    pub timestamp: u64,
+}
+
+impl ScoreRank {
+    /// Create a new key without timestamp.
+    #[inline]
+    pub fn new(pp: f64, accuracy: f64) -> Self {
+        Self {
+            pp,
+            accuracy,
+            timestamp: 0,
+        }
+    }
+
+    /// Specify the time at which the score was set.
+    #[inline]
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+
+        self
+    }
+}
+
+/// Compare two values such that NaN is the smallest and both zeros are equal.
+fn cmp_f64(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
+impl Ord for ScoreRank {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_f64(self.pp, other.pp)
+            .then_with(|| cmp_f64(self.accuracy, other.accuracy))
+            .then_with(|| other.timestamp.cmp(&self.timestamp))
+    }
+}
+
+impl PartialOrd for ScoreRank {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ScoreRank {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoreRank {}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn combo_progress_bounds() {
+        assert_eq!(combo_progress(0, 0, 0, 100), 0.0);
+        assert_eq!(combo_progress(100, 100, 0, 100), 1.0);
+        assert!(combo_progress(50, 99, 1, 100) < 1.0);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    use crate::{Beatmap, BeatmapExt};
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn max_standardised() {
+        for path in ["./maps/2785319.osu", "./maps/1028484.osu"] {
+            let map = Beatmap::from_path(path).expect("failed to parse map");
+            let attributes = map.stars(0, None);
+            let state = ScoreState::max_for(&attributes);
+            let score = standardised(&attributes, 0, &state);
+
+            assert_eq!(score.standardised, 1_000_000);
+        }
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn partial_lower_than_full() {
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        let attributes = map.stars(0, None);
+        let full = ScoreState::max_for(&attributes);
+
+        let state = ScoreState {
+            max_combo: full.max_combo / 2,
+            n300: full.n300 - 10,
+            n100: 9,
+            misses: 1,
+            ..Default::default()
+        };
+
+        let nomod = standardised(&attributes, 0, &state);
+        let hdhr = standardised(&attributes, 8 + 16, &state);
+
+        assert!(nomod.standardised < 1_000_000);
+        assert!(nomod.standardised < hdhr.standardised);
+        assert!(nomod.classic > 0);
+    }
+
+    #[cfg(all(
+        feature = "mania",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn mania_standardised() {
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+        let attributes = map.stars(0, None);
+
+        let max = standardised(&attributes, 0, &ScoreState::max_for(&attributes));
+        assert_eq!(max.standardised, 1_000_000);
+
+        let n_objects = match attributes {
+            DifficultyAttributes::Mania(ref attributes) => {
+                attributes.n_notes + attributes.n_hold_notes
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        };
+
+        let perfect = ScoreState {
+            max_combo: n_objects,
+            n300: n_objects,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            standardised(&attributes, 0, &perfect).standardised,
+            1_000_000
+        );
+
+        let state = ScoreState {
+            max_combo: n_objects / 2,
+            n300: n_objects - 30,
+            n_katu: 20,
+            n100: 5,
+            misses: 5,
+            ..Default::default()
+        };
+
+        let score = standardised(&attributes, 0, &state);
+        assert!(score.standardised < 1_000_000);
+        assert!(score.standardised > standardised(&attributes, 1, &state).standardised);
+
+        // The score no longer affects the result once hitresults are given
+        let with_score = ScoreState {
+            score: 1_000_000,
+            ..state
+        };
+
+        assert_eq!(standardised(&attributes, 0, &with_score), score);
+    }
+
+    #[test]
+    fn multiplier_per_mod() {
+        let nf = score_multiplier(GameMode::STD, 1);
+        let nfez = score_multiplier(GameMode::STD, 1 + 2);
+        assert!((nfez - nf * nf).abs() < f64::EPSILON);
+        assert!((nfez - 0.25).abs() < f64::EPSILON);
+
+        assert!((score_multiplier(GameMode::MNA, 8 + 16 + 1024) - 1.0).abs() < f64::EPSILON);
+        assert!((score_multiplier(GameMode::CTB, 16) - 1.12).abs() < f64::EPSILON);
+        assert!((score_multiplier(GameMode::TKO, 16) - 1.06).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn score_rank_order() {
+        let nan = ScoreRank::new(f64::NAN, 100.0);
+
+        assert!(nan < ScoreRank::new(f64::NEG_INFINITY, 0.0));
+        assert_eq!(nan, ScoreRank::new(f64::NAN, 100.0));
+        assert!(ScoreRank::new(1.0, f64::NAN) < ScoreRank::new(1.0, 0.0));
+        assert_eq!(ScoreRank::new(0.0, 90.0), ScoreRank::new(-0.0, 90.0));
+
+        assert!(ScoreRank::new(100.0, 90.0) > ScoreRank::new(99.0, 100.0));
+        assert!(ScoreRank::new(100.0, 91.0) > ScoreRank::new(100.0, 90.0));
+        assert!(
+            ScoreRank::new(100.0, 90.0).timestamp(1) > ScoreRank::new(100.0, 90.0).timestamp(2)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Compile-time guarantees that the public types can be shared across threads.
+//!
+//! If any of these assertions fail, a type gained a field that is not thread-safe,
+//! e.g. an `Rc` or a `Cell`, which would break parallel usage downstream.
+
+use crate::{
+    parse::{HitObject, Sections},
+    AnyPP, Beatmap, BeatmapAttributes, BeatmapLength, DifficultyAttributes, DifficultyCheckpoint,
+    GradualDifficultyAttributes, GradualPerformanceAttributes, ParseBuffers, ParseError,
+    ParseErrorCode, ParseLimits, ParseOptions, PerformanceAttributes, PreprocessedMap, ScoreState,
+    StarDeltas, Strains,
+};
+
+macro_rules! assert_send_sync {
+    ($($ty:ty),* $(,)?) => {
+        const _: fn() = || {
+            fn assert_send_sync<T: Send + Sync>() {}
+
+            $( assert_send_sync::<$ty>(); )*
+        };
+    };
+}
+
+assert_send_sync!(
+    Beatmap,
+    BeatmapAttributes,
+    BeatmapLength,
+    HitObject,
+    ParseBuffers,
+    ParseError,
+    ParseErrorCode,
+    ParseLimits,
+    ParseOptions,
+    crate::ParseWarning,
+    crate::RawSection,
+    Sections,
+    DifficultyAttributes,
+    PerformanceAttributes,
+    Strains,
+    ScoreState,
+    AnyPP<'static>,
+    crate::ModeMismatchError,
+    GradualDifficultyAttributes<'static>,
+    DifficultyCheckpoint<'static>,
+    PreprocessedMap<'static>,
+    GradualPerformanceAttributes<'static>,
+    crate::LivePerformance<'static>,
+    crate::CalcKey,
+    crate::Provenance,
+    crate::ObjectCounts,
+    StarDeltas<'static>,
+    crate::rhythm::RhythmAttributes,
+    crate::score::LazerScore,
+    crate::score::ScoreRank,
+    crate::rate::RateMods,
+    crate::rate::RateInterpolation,
+    crate::rate::InterpolatedAttributes,
+    crate::analysis::ModComparison,
+    crate::analysis::SectionDelta,
+    crate::analysis::MaxPerformance,
+    crate::analysis::MapPoolComparison,
+    crate::analysis::DifficultyProfile,
+    crate::analysis::ProfileValue,
+    crate::analysis::SkillRating,
+    crate::features::MapFeatures,
+    crate::estimate::EstimationError,
+    crate::health::HealthResult,
+    crate::json::JsonError,
+    crate::InputError,
+    crate::LazerMods,
+    crate::ModsRanking,
+    crate::UnknownModError,
+    crate::UnknownModeError,
+    crate::parse::ParseLimitKind,
+    crate::PerformanceDiff,
+    crate::ComponentDelta,
+    crate::PpComponent,
+    crate::Beatmapset,
+    crate::DifficultySpread,
+    crate::SetDifficulty,
+);
+
+#[cfg(feature = "online")]
+assert_send_sync!(crate::online::MapDownloader, crate::online::HttpSource);
+
+#[cfg(feature = "sliders")]
+assert_send_sync!(
+    crate::parse::SliderLengthPolicy,
+    crate::parse::SliderLengthMismatch,
+);
+
+#[cfg(feature = "research")]
+assert_send_sync!(
+    crate::research::SkillParameters,
+    crate::research::ParameterError,
+);
+
+#[cfg(feature = "api_compat")]
+assert_send_sync!(
+    crate::api_compat::ApiAttributes,
+    crate::api_compat::ApiComparison,
+    crate::api_compat::AttributeDelta,
+);
+
+#[cfg(feature = "fruits")]
+assert_send_sync!(
+    crate::FruitsPP<'static>,
+    crate::fruits::FruitsDifficultyAttributes,
+    crate::fruits::FruitsSkillStrains,
+    crate::fruits::FruitsPerformanceAttributes,
+    crate::fruits::FruitsScoreState,
+    crate::fruits::FruitsPreprocessedMap<'static>,
+    crate::fruits::FruitsGradualDifficultyAttributes<'static>,
+    crate::fruits::FruitsGradualPerformanceAttributes<'static>,
+);
+
+#[cfg(feature = "mania")]
+assert_send_sync!(
+    crate::ManiaPP<'static>,
+    crate::mania::ManiaDifficultyAttributes,
+    crate::mania::ManiaSkillStrains,
+    crate::mania::ManiaPerformanceAttributes,
+    crate::mania::ManiaPreprocessedMap<'static>,
+    crate::mania::ManiaGradualDifficultyAttributes<'static>,
+    crate::mania::ManiaGradualPerformanceAttributes<'static>,
+    crate::mania::ManiaColumnState,
+);
+
+#[cfg(feature = "osu")]
+assert_send_sync!(
+    crate::OsuPP<'static>,
+    crate::osu::OsuDifficultyAttributes,
+    crate::osu::OsuSkillStrains,
+    crate::osu::OsuPerformanceAttributes,
+    crate::osu::OsuScoreState,
+    crate::osu::OsuDifficultyAttributesBuilder,
+    crate::osu::OsuPreprocessedMap<'static>,
+    crate::osu::OsuGradualDifficultyAttributes,
+    crate::osu::OsuGradualPerformanceAttributes<'static>,
+    crate::osu::OsuObjectVisibility,
+    crate::osu::OsuMovement,
+);
+
+#[cfg(feature = "taiko")]
+assert_send_sync!(
+    crate::TaikoPP<'static>,
+    crate::taiko::TaikoDifficultyAttributes,
+    crate::taiko::TaikoSkillStrains,
+    crate::taiko::TaikoObject,
+    crate::taiko::TaikoStamina,
+    crate::taiko::TaikoStream,
+    crate::taiko::TaikoHandLoad,
+    crate::taiko::TaikoPerformanceAttributes,
+    crate::taiko::TaikoScoreState,
+    crate::taiko::TaikoPreprocessedMap<'static>,
+    crate::taiko::TaikoGradualDifficultyAttributes<'static>,
+    crate::taiko::TaikoGradualPerformanceAttributes<'static>,
+);
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use crate::{Beatmap, BeatmapExt};
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn parallel_calculation() {
+        let path = if cfg!(feature = "osu") {
+            "./maps/2785319.osu"
+        } else if cfg!(feature = "taiko") {
+            "./maps/1028484.osu"
+        } else if cfg!(feature = "fruits") {
+            "./maps/2118524.osu"
+        } else {
+            "./maps/1974394.osu"
+        };
+
+        let map = &Beatmap::from_path(path).expect("failed to parse map");
+        let mods = [0, 8, 16, 64];
+
+        let parallel: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = mods
+                .iter()
+                .map(|&mods| scope.spawn(move || map.max_pp(mods).pp()))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("thread panicked"))
+                .collect()
+        });
+
+        let sequential: Vec<_> = mods.iter().map(|&mods| map.max_pp(mods).pp()).collect();
+
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -1,5 +1,7 @@
-use super::{closest_rhythm, HitObjectRhythm};
-use crate::parse::HitObject;
+use crate::{
+    parse::HitObject,
+    rhythm::{closest_rhythm, HitObjectRhythm},
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct DifficultyObject<'o> {
@@ -21,7 +23,8 @@ impl<'o> DifficultyObject<'o> {
         clock_rate: f64,
     ) -> Self {
         let delta = (base.start_time - prev.start_time) / clock_rate;
-        let rhythm = closest_rhythm(delta, prev, prev_prev, clock_rate);
+        let prev_delta = (prev.start_time - prev_prev.start_time) / clock_rate;
+        let rhythm = closest_rhythm(delta, prev_delta);
 
         Self {
             idx,
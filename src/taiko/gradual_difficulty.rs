@@ -1,20 +1,24 @@
 use std::{
     cmp::Ordering,
-    iter::{self, Enumerate, Skip, Zip},
+    iter::{Enumerate, Skip, Zip},
     slice::Iter,
 };
 
 use crate::{
     parse::{HitObject, HitObjectKind},
+    research::TaikoParameters,
     taiko::{
         difficulty_object::DifficultyObject, norm, rescale, simple_color_penalty,
         stamina_cheese::StaminaCheeseDetector, COLOR_SKILL_MULTIPLIER, RHYTHM_SKILL_MULTIPLIER,
         SECTION_LEN, STAMINA_SKILL_MULTIPLIER,
     },
-    Beatmap, Mods,
+    Beatmap, Mods, Strains,
 };
 
-use super::{skill::Skills, TaikoDifficultyAttributes};
+use super::{
+    skill::{Skill, Skills},
+    Rim, TaikoDifficultyAttributes,
+};
 
 /// Gradually calculate the difficulty attributes of an osu!taiko map.
 ///
@@ -28,7 +32,7 @@ use super::{skill::Skills, TaikoDifficultyAttributes};
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, taiko::TaikoGradualDifficultyAttributes};
+/// use akatsuki_pp::{Beatmap, taiko::TaikoGradualDifficultyAttributes};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -49,6 +53,7 @@ use super::{skill::Skills, TaikoDifficultyAttributes};
 #[derive(Clone, Debug)]
 pub struct TaikoGradualDifficultyAttributes<'map> {
     pub(crate) idx: usize,
+    clock_rate: f64,
     difficulty_objects: TaikoObjectIter<'map>,
     cheese: Vec<bool>,
     skills: Skills,
@@ -62,12 +67,18 @@ impl<'map> TaikoGradualDifficultyAttributes<'map> {
         // True if the object at that index is stamina cheese
         let cheese = map.find_cheese();
 
-        let skills = Skills::new();
+        Self::with_cheese(map, mods, cheese)
+    }
+
+    /// Create the iterator with already detected stamina cheese.
+    pub(crate) fn with_cheese(map: &'map Beatmap, mods: impl Mods, cheese: Vec<bool>) -> Self {
+        let skills = Skills::new(&TaikoParameters::DEFAULT);
         let clock_rate = mods.speed();
         let difficulty_objects = TaikoObjectIter::new(&map.hit_objects, clock_rate);
 
         Self {
             idx: 0,
+            clock_rate,
             difficulty_objects,
             cheese,
             skills,
@@ -80,12 +91,11 @@ impl<'map> TaikoGradualDifficultyAttributes<'map> {
         let iter = self
             .skills
             .color
-            .strain_peaks
-            .iter()
-            .zip(self.skills.rhythm.strain_peaks.iter())
-            .zip(self.skills.stamina_right.strain_peaks.iter())
-            .zip(self.skills.stamina_left.strain_peaks.iter())
-            .map(|(((&color, &rhythm), &stamina_right), &stamina_left)| {
+            .peaks()
+            .zip(self.skills.rhythm.peaks())
+            .zip(self.skills.stamina_right.peaks())
+            .zip(self.skills.stamina_left.peaks())
+            .map(|(((color, rhythm), stamina_right), stamina_left)| {
                 norm(
                     2.0,
                     color * COLOR_SKILL_MULTIPLIER,
@@ -99,10 +109,10 @@ impl<'map> TaikoGradualDifficultyAttributes<'map> {
 
         let last = norm(
             2.0,
-            self.skills.color.curr_section_peak * COLOR_SKILL_MULTIPLIER,
-            self.skills.rhythm.curr_section_peak * RHYTHM_SKILL_MULTIPLIER,
-            (self.skills.stamina_right.curr_section_peak
-                + self.skills.stamina_left.curr_section_peak)
+            crate::round_strain(self.skills.color.curr_section_peak) * COLOR_SKILL_MULTIPLIER,
+            crate::round_strain(self.skills.rhythm.curr_section_peak) * RHYTHM_SKILL_MULTIPLIER,
+            (crate::round_strain(self.skills.stamina_right.curr_section_peak)
+                + crate::round_strain(self.skills.stamina_left.curr_section_peak))
                 * STAMINA_SKILL_MULTIPLIER
                 * stamina_penalty,
         );
@@ -122,6 +132,42 @@ impl<'map> TaikoGradualDifficultyAttributes<'map> {
 
         difficulty
     }
+
+    /// The strains of all processed hit objects, including the section
+    /// of the current object, as [`strains`](super::strains) would return them.
+    ///
+    /// Suitable to draw the evolving strain graph during a play
+    /// without calculating the strains from scratch.
+    pub fn current_strains(&self) -> Strains {
+        let mut strains = Vec::new();
+
+        // The first two objects have no strain
+        if self.idx >= 3 {
+            let iter = self
+                .skills
+                .color
+                .peaks_with_current()
+                .zip(self.skills.rhythm.peaks_with_current())
+                .zip(self.skills.stamina_right.peaks_with_current())
+                .zip(self.skills.stamina_left.peaks_with_current())
+                .map(|(((color, rhythm), stamina_right), stamina_left)| {
+                    color + rhythm + stamina_right + stamina_left
+                });
+
+            strains.extend(iter);
+        }
+
+        Strains {
+            section_length: SECTION_LEN * self.clock_rate,
+            strains,
+        }
+    }
+
+    /// The maximum combo of all processed hit objects.
+    #[inline]
+    pub(crate) fn max_combo(&self) -> usize {
+        self.difficulty_objects.max_combo
+    }
 }
 
 impl Iterator for TaikoGradualDifficultyAttributes<'_> {
@@ -135,12 +181,14 @@ impl Iterator for TaikoGradualDifficultyAttributes<'_> {
                 return None;
             }
 
-            self.difficulty_objects.max_combo +=
-                self.difficulty_objects.first_object.is_circle() as usize;
+            self.difficulty_objects
+                .add_counts(self.difficulty_objects.first_object);
 
             let attributes = TaikoDifficultyAttributes {
                 stars: 0.0,
                 max_combo: self.difficulty_objects.max_combo,
+                n_kats: self.difficulty_objects.n_kats,
+                ..Default::default()
             };
 
             return Some(attributes);
@@ -149,12 +197,14 @@ impl Iterator for TaikoGradualDifficultyAttributes<'_> {
                 return None;
             }
 
-            self.difficulty_objects.max_combo +=
-                self.difficulty_objects.second_object.is_circle() as usize;
+            self.difficulty_objects
+                .add_counts(self.difficulty_objects.second_object);
 
             let attributes = TaikoDifficultyAttributes {
                 stars: 0.0,
                 max_combo: self.difficulty_objects.max_combo,
+                n_kats: self.difficulty_objects.n_kats,
+                ..Default::default()
             };
 
             return Some(attributes);
@@ -175,62 +225,53 @@ impl Iterator for TaikoGradualDifficultyAttributes<'_> {
         self.skills.process(&h, &self.cheese);
 
         let len = self.skills.strain_peaks_len();
-        let missing = len + 1 - self.strain_peak_buf.len();
-        self.strain_peak_buf.extend(iter::repeat(0.0).take(missing));
+        self.strain_peak_buf.resize(len + 1, 0.0);
 
         self.skills
             .color
             .copy_strain_peaks(&mut self.strain_peak_buf[..len]);
 
         if let Some(last) = self.strain_peak_buf.last_mut() {
-            *last = self.skills.color.curr_section_peak;
+            *last = crate::round_strain(self.skills.color.curr_section_peak);
         }
 
-        let color_rating = self
-            .skills
-            .color
-            .difficulty_value(&mut self.strain_peak_buf)
-            * COLOR_SKILL_MULTIPLIER;
+        let color_rating =
+            Skill::difficulty_value(&mut self.strain_peak_buf, &TaikoParameters::DEFAULT.color)
+                * COLOR_SKILL_MULTIPLIER;
 
         self.skills
             .rhythm
             .copy_strain_peaks(&mut self.strain_peak_buf[..len]);
 
         if let Some(last) = self.strain_peak_buf.last_mut() {
-            *last = self.skills.rhythm.curr_section_peak;
+            *last = crate::round_strain(self.skills.rhythm.curr_section_peak);
         }
 
-        let rhythm_rating = self
-            .skills
-            .rhythm
-            .difficulty_value(&mut self.strain_peak_buf)
-            * RHYTHM_SKILL_MULTIPLIER;
+        let rhythm_rating =
+            Skill::difficulty_value(&mut self.strain_peak_buf, &TaikoParameters::DEFAULT.rhythm)
+                * RHYTHM_SKILL_MULTIPLIER;
 
         self.skills
             .stamina_right
             .copy_strain_peaks(&mut self.strain_peak_buf[..len]);
 
         if let Some(last) = self.strain_peak_buf.last_mut() {
-            *last = self.skills.stamina_right.curr_section_peak;
+            *last = crate::round_strain(self.skills.stamina_right.curr_section_peak);
         }
 
-        let stamina_right = self
-            .skills
-            .stamina_right
-            .difficulty_value(&mut self.strain_peak_buf);
+        let stamina_right =
+            Skill::difficulty_value(&mut self.strain_peak_buf, &TaikoParameters::DEFAULT.stamina);
 
         self.skills
             .stamina_left
             .copy_strain_peaks(&mut self.strain_peak_buf[..len]);
 
         if let Some(last) = self.strain_peak_buf.last_mut() {
-            *last = self.skills.stamina_left.curr_section_peak;
+            *last = crate::round_strain(self.skills.stamina_left.curr_section_peak);
         }
 
-        let stamina_left = self
-            .skills
-            .stamina_left
-            .difficulty_value(&mut self.strain_peak_buf);
+        let stamina_left =
+            Skill::difficulty_value(&mut self.strain_peak_buf, &TaikoParameters::DEFAULT.stamina);
 
         let mut stamina_rating = (stamina_right + stamina_left) * STAMINA_SKILL_MULTIPLIER;
 
@@ -245,6 +286,8 @@ impl Iterator for TaikoGradualDifficultyAttributes<'_> {
         let attributes = TaikoDifficultyAttributes {
             stars,
             max_combo: self.difficulty_objects.max_combo,
+            n_kats: self.difficulty_objects.n_kats,
+            ..Default::default()
         };
 
         Some(attributes)
@@ -281,7 +324,7 @@ type InnerIter<'map> = Zip<
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u8)]
 enum SimpleObject {
-    Circle,
+    Circle { rim: bool },
     Empty,
     NonCircle,
 }
@@ -289,7 +332,7 @@ enum SimpleObject {
 impl From<&HitObject> for SimpleObject {
     fn from(h: &HitObject) -> Self {
         match h.kind {
-            HitObjectKind::Circle => Self::Circle,
+            HitObjectKind::Circle => Self::Circle { rim: h.is_rim() },
             _ => Self::NonCircle,
         }
     }
@@ -301,7 +344,11 @@ impl SimpleObject {
     }
 
     fn is_circle(self) -> bool {
-        self == Self::Circle
+        matches!(self, Self::Circle { .. })
+    }
+
+    fn is_kat(self) -> bool {
+        self == Self::Circle { rim: true }
     }
 }
 
@@ -309,6 +356,7 @@ impl SimpleObject {
 struct TaikoObjectIter<'map> {
     hit_objects: InnerIter<'map>,
     max_combo: usize,
+    n_kats: usize,
     clock_rate: f64,
     first_object: SimpleObject,
     second_object: SimpleObject,
@@ -316,7 +364,7 @@ struct TaikoObjectIter<'map> {
 
 impl<'map> TaikoObjectIter<'map> {
     fn new(hit_objects: &'map [HitObject], clock_rate: f64) -> Self {
-        let first_object = hit_objects.get(0).map_or(SimpleObject::Empty, From::from);
+        let first_object = hit_objects.first().map_or(SimpleObject::Empty, From::from);
         let second_object = hit_objects.get(1).map_or(SimpleObject::Empty, From::from);
 
         let hit_objects = hit_objects
@@ -329,11 +377,17 @@ impl<'map> TaikoObjectIter<'map> {
         Self {
             hit_objects,
             max_combo: 0,
+            n_kats: 0,
             clock_rate,
             first_object,
             second_object,
         }
     }
+
+    fn add_counts(&mut self, h: SimpleObject) {
+        self.max_combo += h.is_circle() as usize;
+        self.n_kats += h.is_kat() as usize;
+    }
 }
 
 impl<'map> Iterator for TaikoObjectIter<'map> {
@@ -341,7 +395,7 @@ impl<'map> Iterator for TaikoObjectIter<'map> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let (((idx, base), prev), prev_prev) = self.hit_objects.next()?;
-        self.max_combo += base.is_circle() as usize;
+        self.add_counts(SimpleObject::from(base));
 
         Some(DifficultyObject::new(
             idx,
@@ -1,6 +1,8 @@
 use crate::{Beatmap, TaikoPP};
 
-use super::{TaikoGradualDifficultyAttributes, TaikoPerformanceAttributes};
+use super::{
+    TaikoDifficultyAttributes, TaikoGradualDifficultyAttributes, TaikoPerformanceAttributes,
+};
 
 /// Aggregation for a score's current state i.e. what was the
 /// maximum combo so far and what are the current hitresults.
@@ -24,6 +26,37 @@ impl TaikoScoreState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Create the score state of a perfect play, i.e. an SS with full combo,
+    /// for the given difficulty attributes.
+    pub fn max_for(attributes: &TaikoDifficultyAttributes) -> Self {
+        Self {
+            max_combo: attributes.max_combo,
+            n300: attributes.max_combo,
+            n100: 0,
+            misses: 0,
+        }
+    }
+
+    /// The amount of hitresults, i.e. the amount of objects that were judged so far.
+    #[inline]
+    pub fn total_hits(&self) -> usize {
+        self.n300 + self.n100 + self.misses
+    }
+
+    /// The amount of objects that are yet to be judged out of `n_objects`.
+    #[inline]
+    pub fn remaining(&self, n_objects: usize) -> usize {
+        n_objects.saturating_sub(self.total_hits())
+    }
+
+    /// Whether all objects of the given difficulty attributes were judged.
+    ///
+    /// The objects are counted by [`TaikoDifficultyAttributes::n_accuracy_objects`].
+    #[inline]
+    pub fn is_complete(&self, attributes: &TaikoDifficultyAttributes) -> bool {
+        self.remaining(attributes.n_accuracy_objects()) == 0
+    }
 }
 
 /// Gradually calculate the performance attributes of an osu!taiko map.
@@ -43,7 +76,7 @@ impl TaikoScoreState {
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{Beatmap, taiko::{TaikoGradualPerformanceAttributes, TaikoScoreState}};
+/// use akatsuki_pp::{Beatmap, taiko::{TaikoGradualPerformanceAttributes, TaikoScoreState}};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -101,10 +134,10 @@ impl TaikoScoreState {
 /// state.n300 = ...
 /// state.n100 = ...
 /// state.misses = ...
-/// let final_performance = gradual_perf.process_next_n_objects(state.clone(), usize::MAX).unwrap();
+/// let final_performance = gradual_perf.process_remaining(state.clone()).unwrap();
 /// println!("PP: {}", performance.pp);
 /// # */
-/// # let _ = gradual_perf.process_next_n_objects(state.clone(), usize::MAX);
+/// # let _ = gradual_perf.process_remaining(state.clone());
 ///
 /// // Once the final performance was calculated,
 /// // attempting to process further objects will return `None`.
@@ -161,6 +194,26 @@ impl<'map> TaikoGradualPerformanceAttributes<'map> {
 
         Some(performance)
     }
+
+    /// Process all remaining hit objects at once and calculate the
+    /// performance attributes for the final score state.
+    ///
+    /// Suitable to finish off a play, e.g. after a fail, without having to know
+    /// how many objects are left. Returns `None` if all objects were processed already.
+    pub fn process_remaining(
+        &mut self,
+        state: TaikoScoreState,
+    ) -> Option<TaikoPerformanceAttributes> {
+        self.process_next_n_objects(state, usize::MAX)
+    }
+
+    /// The maximum combo that could have been achieved up to the last processed hit object.
+    ///
+    /// Suitable to display a play's combo alongside the combo that was possible so far.
+    #[inline]
+    pub fn max_combo(&self) -> usize {
+        self.difficulty.max_combo()
+    }
 }
 
 #[cfg(test)]
@@ -168,6 +221,22 @@ mod tests {
     #[allow(unused_imports)]
     use super::*;
 
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn max_state_eq_default() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let attributes = crate::taiko::stars(&map, 0, None);
+        let state = TaikoScoreState::max_for(&attributes);
+
+        let any_state = crate::ScoreState::max_for(&attributes.into());
+        assert_eq!(TaikoScoreState::from(any_state), state);
+
+        let regular = TaikoPP::new(&map).calculate();
+        let max = TaikoPP::new(&map).state(state).calculate();
+
+        assert_eq!(regular, max);
+    }
+
     #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
     #[test]
     fn correct_empty() {
@@ -259,4 +328,21 @@ mod tests {
 
         assert_eq!(regular, gradual);
     }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn max_combo_progression() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let mut gradual = TaikoGradualPerformanceAttributes::new(&map, 0);
+        assert_eq!(gradual.max_combo(), 0);
+
+        let state = TaikoScoreState::default();
+        let _ = gradual.process_next_n_objects(state.clone(), 100);
+        let partial = crate::taiko::stars(&map, 0, Some(100)).max_combo;
+        assert_eq!(gradual.max_combo(), partial);
+
+        let _ = gradual.process_next_n_objects(state, usize::MAX);
+        let full = crate::taiko::stars(&map, 0, None).max_combo;
+        assert_eq!(gradual.max_combo(), full);
+    }
 }
@@ -3,29 +3,41 @@
 mod difficulty_object;
 mod gradual_difficulty;
 mod gradual_performance;
-mod hitobject_rhythm;
-mod limited_queue;
+mod object;
 mod pp;
+mod preprocessed;
 mod rim;
 mod skill;
 mod skill_kind;
+mod stamina;
 mod stamina_cheese;
 
 use difficulty_object::DifficultyObject;
 pub use gradual_difficulty::*;
 pub use gradual_performance::*;
-use hitobject_rhythm::{closest_rhythm, HitObjectRhythm};
-use limited_queue::LimitedQueue;
+pub use object::*;
 pub use pp::*;
+pub use preprocessed::TaikoPreprocessedMap;
 use rim::Rim;
+pub(crate) use skill::{COLOR_PARAMETERS, RHYTHM_PARAMETERS, STAMINA_PARAMETERS};
 use skill_kind::SkillKind;
+pub use stamina::{TaikoHandLoad, TaikoStamina, TaikoStream};
 use stamina_cheese::StaminaCheeseDetector;
 
-use crate::taiko::skill::Skills;
-use crate::{Beatmap, Mods, Strains};
+use crate::taiko::skill::{Skill, Skills};
+
+#[cfg(feature = "research")]
+use crate::research::ParameterError;
+
+use crate::{
+    research::TaikoParameters,
+    timings::{Phase, Timer},
+    Beatmap, Mods, Strains,
+};
 
 use std::cmp::Ordering;
 use std::f64::consts::PI;
+use std::fmt;
 
 const SECTION_LEN: f64 = 400.0;
 
@@ -41,20 +53,76 @@ pub fn stars(
     mods: impl Mods,
     passed_objects: Option<usize>,
 ) -> TaikoDifficultyAttributes {
-    let (skills, max_combo) = calculate_skills(map, mods, passed_objects);
+    calculate_stars(map, mods, passed_objects, &TaikoParameters::DEFAULT)
+}
+
+/// Same as [`stars`] but the skills use the given parameters instead of the regular ones.
+///
+/// Returns an error if any parameter is outside of its valid range.
+#[cfg(feature = "research")]
+#[cfg_attr(docsrs, doc(cfg(feature = "research")))]
+pub fn stars_with_parameters(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &TaikoParameters,
+) -> Result<TaikoDifficultyAttributes, ParameterError> {
+    params.validate()?;
+
+    Ok(calculate_stars(map, mods, passed_objects, params))
+}
+
+fn calculate_stars(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &TaikoParameters,
+) -> TaikoDifficultyAttributes {
+    trace_span!(
+        "difficulty",
+        mode = "taiko",
+        map = format_args!("{:032x}", crate::CalcKey::fingerprint(map)),
+        mods = crate::mods::legacy_bits(mods),
+        passed_objects = ?passed_objects,
+        n_objects = map.hit_objects.len(),
+    );
+
+    let (skills, max_combo) = calculate_skills(map, mods, passed_objects, params);
+
+    TaikoDifficultyAttributes {
+        n_kats: count_kats(map, passed_objects),
+        ..evaluate_skills(skills, max_combo, params)
+    }
+}
+
+/// The amount of kats among the passed hit objects.
+fn count_kats(map: &Beatmap, passed_objects: Option<usize>) -> usize {
+    map.hit_objects
+        .iter()
+        .take(passed_objects.unwrap_or(map.hit_objects.len()))
+        .filter(|h| h.is_circle() && h.is_rim())
+        .count()
+}
+
+fn evaluate_skills(
+    skills: Skills,
+    max_combo: usize,
+    params: &TaikoParameters,
+) -> TaikoDifficultyAttributes {
+    let timer = Timer::start();
     let mut buf = vec![0.0; skills.strain_peaks_len()];
 
     skills.color.copy_strain_peaks(&mut buf);
-    let color_rating = skills.color.difficulty_value(&mut buf) * COLOR_SKILL_MULTIPLIER;
+    let color_rating = Skill::difficulty_value(&mut buf, &params.color) * COLOR_SKILL_MULTIPLIER;
 
     skills.rhythm.copy_strain_peaks(&mut buf);
-    let rhythm_rating = skills.rhythm.difficulty_value(&mut buf) * RHYTHM_SKILL_MULTIPLIER;
+    let rhythm_rating = Skill::difficulty_value(&mut buf, &params.rhythm) * RHYTHM_SKILL_MULTIPLIER;
 
     skills.stamina_right.copy_strain_peaks(&mut buf);
-    let stamina_right = skills.stamina_right.difficulty_value(&mut buf);
+    let stamina_right = Skill::difficulty_value(&mut buf, &params.stamina);
 
     skills.stamina_left.copy_strain_peaks(&mut buf);
-    let stamina_left = skills.stamina_left.difficulty_value(&mut buf);
+    let stamina_left = Skill::difficulty_value(&mut buf, &params.stamina);
 
     let mut stamina_rating = (stamina_right + stamina_left) * STAMINA_SKILL_MULTIPLIER;
 
@@ -65,24 +133,31 @@ pub fn stars(
     let separate_rating = norm(1.5, color_rating, rhythm_rating, stamina_rating);
 
     let stars = rescale(1.4 * separate_rating + 0.5 * combined_rating);
+    timer.finish(Phase::Evaluation);
 
-    TaikoDifficultyAttributes { stars, max_combo }
+    TaikoDifficultyAttributes {
+        stars,
+        max_combo,
+        ..Default::default()
+    }
 }
 
 /// Essentially the same as the [`stars`] function but instead of
 /// evaluating the final strains, it just returns them as is.
 ///
 /// Suitable to plot the difficulty of a map over time.
-pub fn strains(map: &Beatmap, mods: impl Mods) -> Strains {
-    let (skills, _) = calculate_skills(map, mods, None);
+///
+/// In case of a partial play, e.g. a live play that is still ongoing,
+/// one can specify the amount of passed objects.
+pub fn strains(map: &Beatmap, mods: impl Mods, passed_objects: Option<usize>) -> Strains {
+    let (skills, _) = calculate_skills(map, mods, passed_objects, &TaikoParameters::DEFAULT);
 
     let strains = skills
         .color
-        .strain_peaks
-        .iter()
-        .zip(skills.rhythm.strain_peaks.iter())
-        .zip(skills.stamina_right.strain_peaks.iter())
-        .zip(skills.stamina_left.strain_peaks.iter())
+        .peaks()
+        .zip(skills.rhythm.peaks())
+        .zip(skills.stamina_right.peaks())
+        .zip(skills.stamina_left.peaks())
         .map(|(((color, rhythm), stamina_right), stamina_left)| {
             color + rhythm + stamina_right + stamina_left
         })
@@ -94,20 +169,74 @@ pub fn strains(map: &Beatmap, mods: impl Mods) -> Strains {
     }
 }
 
+/// The strain peaks of each skill of an osu!taiko map, see [`skill_strains`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaikoSkillStrains {
+    /// Strain peaks of the color skill.
+    pub color: Vec<f64>,
+    /// Strain peaks of the rhythm skill.
+    pub rhythm: Vec<f64>,
+    /// Strain peaks of the stamina skill for the right hand.
+    pub stamina_right: Vec<f64>,
+    /// Strain peaks of the stamina skill for the left hand.
+    pub stamina_left: Vec<f64>,
+}
+
+/// Calculate the strain peaks of each skill of an osu!taiko map.
+///
+/// The peaks of each skill are sorted in descending order and not weighted,
+/// so that the star rating can be recomputed with alternative weightings.
+///
+/// In case of a partial play, e.g. a fail, one can specify the amount of passed objects.
+pub fn skill_strains(
+    map: &Beatmap,
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+) -> TaikoSkillStrains {
+    let (skills, _) = calculate_skills(map, mods, passed_objects, &TaikoParameters::DEFAULT);
+
+    let sorted = |peaks| {
+        let mut peaks = crate::widen_strains(peaks);
+        crate::sort_strain_peaks(&mut peaks);
+
+        peaks
+    };
+
+    TaikoSkillStrains {
+        color: sorted(skills.color.strain_peaks),
+        rhythm: sorted(skills.rhythm.strain_peaks),
+        stamina_right: sorted(skills.stamina_right.strain_peaks),
+        stamina_left: sorted(skills.stamina_left.strain_peaks),
+    }
+}
+
 fn calculate_skills(
     map: &Beatmap,
     mods: impl Mods,
     passed_objects: Option<usize>,
+    params: &TaikoParameters,
 ) -> (Skills, usize) {
-    let take = passed_objects.unwrap_or_else(|| map.hit_objects.len());
-
     // True if the object at that index is stamina cheese
+    let timer = Timer::start();
     let cheese = map.find_cheese();
-    let mut skills = Skills::new();
+    timer.finish(Phase::Preprocessing);
+
+    process_objects(map, &cheese, mods, passed_objects, params)
+}
+
+fn process_objects(
+    map: &Beatmap,
+    cheese: &[bool],
+    mods: impl Mods,
+    passed_objects: Option<usize>,
+    params: &TaikoParameters,
+) -> (Skills, usize) {
+    let take = passed_objects.unwrap_or(map.hit_objects.len());
+    let mut skills = Skills::new(params);
     let clock_rate = mods.speed();
     let mut max_combo = 0;
 
-    match map.hit_objects.get(0) {
+    match map.hit_objects.first() {
         Some(h) => max_combo += h.is_circle() as usize,
         None => return (skills, max_combo),
     }
@@ -138,7 +267,7 @@ fn calculate_skills(
 
     // No strain for first object
     let mut curr_section_end = (h.start_time / SECTION_LEN).ceil() * SECTION_LEN;
-    skills.process(&h, &cheese);
+    skills.process(&h, cheese);
 
     // Handle all other objects
     for h in hit_objects {
@@ -147,7 +276,7 @@ fn calculate_skills(
             curr_section_end += SECTION_LEN;
         }
 
-        skills.process(&h, &cheese);
+        skills.process(&h, cheese);
     }
 
     skills.save_current_peak();
@@ -178,12 +307,11 @@ fn locally_combined_difficulty(peaks: &mut Vec<f64>, skills: &Skills, stamina_pe
 
     let iter = skills
         .color
-        .strain_peaks
-        .iter()
-        .zip(skills.rhythm.strain_peaks.iter())
-        .zip(skills.stamina_right.strain_peaks.iter())
-        .zip(skills.stamina_left.strain_peaks.iter())
-        .map(|(((&color, &rhythm), &stamina_right), &stamina_left)| {
+        .peaks()
+        .zip(skills.rhythm.peaks())
+        .zip(skills.stamina_right.peaks())
+        .zip(skills.stamina_left.peaks())
+        .map(|(((color, rhythm), stamina_right), stamina_left)| {
             norm(
                 2.0,
                 color * COLOR_SKILL_MULTIPLIER,
@@ -216,8 +344,12 @@ fn norm(p: f64, a: f64, b: f64, c: f64) -> f64 {
 pub struct TaikoDifficultyAttributes {
     /// The final star rating.
     pub stars: f64,
-    /// The maximum combo.
+    /// The maximum combo, i.e. the amount of notes.
     pub max_combo: usize,
+    /// The amount of kats, i.e. notes on the rim. All other notes are dons.
+    pub n_kats: usize,
+    /// The release and formulas that calculated the attributes.
+    pub provenance: crate::Provenance,
 }
 
 impl TaikoDifficultyAttributes {
@@ -226,6 +358,38 @@ impl TaikoDifficultyAttributes {
     pub fn max_combo(&self) -> usize {
         self.max_combo
     }
+
+    /// The amount of judgements that the accuracy is divided by, i.e. the amount of notes.
+    ///
+    /// Drum rolls and swells are not considered. If the second hits of big notes are
+    /// specified through [`TaikoPP::n_big_good`](crate::TaikoPP::n_big_good) or
+    /// [`TaikoPP::n_big_bad`](crate::TaikoPP::n_big_bad), each of them is judged
+    /// on top of this amount.
+    #[inline]
+    pub fn n_accuracy_objects(&self) -> usize {
+        self.max_combo
+    }
+
+    /// Adjust the attributes that were calculated with `old_mods` so that they
+    /// apply to `new_mods` without recalculating the difficulty.
+    ///
+    /// Only the clock rate affects the difficulty of osu!taiko maps so
+    /// the attributes stay the same unless it changed.
+    ///
+    /// Returns `None` if a full recalculation is required, i.e. if the clock rate changed.
+    #[inline]
+    pub fn adjust_mods(&self, old_mods: impl Mods, new_mods: impl Mods) -> Option<Self> {
+        (old_mods.speed() == new_mods.speed()).then_some(*self)
+    }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    #[inline]
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        crate::approx_eq_f64(self.stars, other.stars, epsilon)
+            && self.max_combo == other.max_combo
+            && self.n_kats == other.n_kats
+    }
 }
 
 /// The result of a performance calculation on an osu!taiko map.
@@ -259,6 +423,33 @@ impl TaikoPerformanceAttributes {
     pub fn max_combo(&self) -> usize {
         self.difficulty.max_combo
     }
+
+    /// Whether all fields are equal, allowing floating point
+    /// values to differ by up to `epsilon`.
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let eq = |a, b| crate::approx_eq_f64(a, b, epsilon);
+
+        self.difficulty.approx_eq(&other.difficulty, epsilon)
+            && eq(self.pp, other.pp)
+            && eq(self.pp_acc, other.pp_acc)
+            && eq(self.pp_strain, other.pp_strain)
+    }
+}
+
+impl fmt::Display for TaikoDifficultyAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}★ max combo {}", self.stars, self.max_combo)
+    }
+}
+
+impl fmt::Display for TaikoPerformanceAttributes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0}pp (strain {:.0}, acc {:.0})",
+            self.pp, self.pp_strain, self.pp_acc
+        )
+    }
 }
 
 impl From<TaikoPerformanceAttributes> for TaikoDifficultyAttributes {
@@ -0,0 +1,144 @@
+use super::rim::Rim;
+use crate::parse::{HitObject, HitObjectKind, HitSound};
+
+/// The color of an osu!taiko note.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum TaikoColor {
+    /// A red note, hit in the center of the drum.
+    Don,
+    /// A blue note, hit on the rim of the drum.
+    Kat,
+}
+
+impl TaikoColor {
+    /// Determine the color based on the hitsound bits,
+    /// i.e. whistles and claps are kats and everything else is a don.
+    #[inline]
+    pub fn from_sound(sound: u8) -> Self {
+        if sound.is_rim() {
+            Self::Kat
+        } else {
+            Self::Don
+        }
+    }
+}
+
+/// The type of an osu!taiko object.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TaikoObjectKind {
+    /// A note of the given color.
+    Note(TaikoColor),
+    /// A drum roll, originating from a slider.
+    DrumRoll,
+    /// A swell, originating from a spinner.
+    Swell,
+}
+
+/// A [`HitObject`] from the perspective of osu!taiko.
+///
+/// Instead of the raw [`sound`](HitObject::sound) bits, it
+/// provides the color of notes and whether the object is big.
+///
+/// ```
+/// use akatsuki_pp::{taiko::TaikoObject, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// let n_kats = map
+///     .hit_objects
+///     .iter()
+///     .map(TaikoObject::from)
+///     .filter(TaikoObject::is_kat)
+///     .count();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TaikoObject {
+    /// The start time of the object.
+    pub start_time: f64,
+    /// The type of the object.
+    pub kind: TaikoObjectKind,
+    /// Whether the object is big, i.e. it has a finish hitsound.
+    ///
+    /// Always `false` for swells.
+    pub big: bool,
+}
+
+impl TaikoObject {
+    /// The color of the object if it is a note.
+    #[inline]
+    pub fn color(&self) -> Option<TaikoColor> {
+        match self.kind {
+            TaikoObjectKind::Note(color) => Some(color),
+            TaikoObjectKind::DrumRoll | TaikoObjectKind::Swell => None,
+        }
+    }
+
+    /// If the object is a don.
+    #[inline]
+    pub fn is_don(&self) -> bool {
+        self.color() == Some(TaikoColor::Don)
+    }
+
+    /// If the object is a kat.
+    #[inline]
+    pub fn is_kat(&self) -> bool {
+        self.color() == Some(TaikoColor::Kat)
+    }
+}
+
+impl From<&HitObject> for TaikoObject {
+    #[inline]
+    fn from(h: &HitObject) -> Self {
+        let kind = match h.kind {
+            HitObjectKind::Circle => TaikoObjectKind::Note(TaikoColor::from_sound(h.sound)),
+            HitObjectKind::Slider { .. } => TaikoObjectKind::DrumRoll,
+            // osu!mania maps can not be converted to osu!taiko
+            HitObjectKind::Spinner { .. } | HitObjectKind::Hold { .. } => TaikoObjectKind::Swell,
+        };
+
+        Self {
+            start_time: h.start_time,
+            kind,
+            big: kind != TaikoObjectKind::Swell && h.sound.finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Pos2;
+
+    fn circle(sound: u8) -> HitObject {
+        HitObject {
+            pos: Pos2::default(),
+            start_time: 100.0,
+            kind: HitObjectKind::Circle,
+            sound,
+            file_idx: 0,
+        }
+    }
+
+    #[test]
+    fn note_colors() {
+        let don = TaikoObject::from(&circle(0));
+        assert_eq!(don.kind, TaikoObjectKind::Note(TaikoColor::Don));
+        assert!(don.is_don() && !don.big);
+
+        let kat = TaikoObject::from(&circle(u8::HITSOUND_CLAP));
+        assert!(kat.is_kat() && !kat.big);
+
+        let big_kat = TaikoObject::from(&circle(u8::HITSOUND_WHISTLE | u8::HITSOUND_FINISH));
+        assert!(big_kat.is_kat() && big_kat.big);
+
+        let big_don = TaikoObject::from(&circle(u8::HITSOUND_FINISH));
+        assert!(big_don.is_don() && big_don.big);
+
+        let swell = TaikoObject::from(&HitObject {
+            kind: HitObjectKind::Spinner { end_time: 500.0 },
+            ..circle(u8::HITSOUND_FINISH)
+        });
+        assert_eq!(swell.kind, TaikoObjectKind::Swell);
+        assert_eq!(swell.color(), None);
+        assert!(!swell.big);
+    }
+}
@@ -1,12 +1,17 @@
-use super::{stars, TaikoDifficultyAttributes, TaikoPerformanceAttributes, TaikoScoreState};
-use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
+use super::{
+    stars, TaikoDifficultyAttributes, TaikoObject, TaikoPerformanceAttributes, TaikoScoreState,
+};
+use crate::{
+    timings::{Phase, Timer},
+    Beatmap, DifficultyAttributes, InputError, Mods, PerformanceAttributes,
+};
 
 /// Performance calculator on osu!taiko maps.
 ///
 /// # Example
 ///
 /// ```
-/// use rosu_pp::{TaikoPP, Beatmap};
+/// use akatsuki_pp::{TaikoPP, Beatmap};
 ///
 /// # /*
 /// let map: Beatmap = ...
@@ -33,7 +38,7 @@ use crate::{Beatmap, DifficultyAttributes, Mods, PerformanceAttributes};
 #[derive(Clone, Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct TaikoPP<'map> {
-    map: &'map Beatmap,
+    pub(crate) map: &'map Beatmap,
     attributes: Option<TaikoDifficultyAttributes>,
     mods: u32,
     combo: Option<usize>,
@@ -43,6 +48,10 @@ pub struct TaikoPP<'map> {
     pub(crate) n300: Option<usize>,
     pub(crate) n100: Option<usize>,
     pub(crate) n_misses: usize,
+
+    n_big_good: Option<usize>,
+    n_big_bad: Option<usize>,
+    strict: bool,
 }
 
 impl<'map> TaikoPP<'map> {
@@ -59,6 +68,9 @@ impl<'map> TaikoPP<'map> {
             passed_objects: None,
             n300: None,
             n100: None,
+            n_big_good: None,
+            n_big_bad: None,
+            strict: false,
         }
     }
 
@@ -108,6 +120,30 @@ impl<'map> TaikoPP<'map> {
         self
     }
 
+    /// Specify the amount of big notes (finishers) that were hit with both keys.
+    ///
+    /// If this or [`n_big_bad`](TaikoPP::n_big_bad) is specified, the second hit of
+    /// each judged big note counts towards accuracy as well, i.e. a good hit counts
+    /// as an additional 300 and a bad hit as an additional 100.
+    /// The second hit of all remaining big notes counts as a miss.
+    ///
+    /// Otherwise, big notes count as a single hit just like regular notes.
+    #[inline]
+    pub fn n_big_good(mut self, n_big_good: usize) -> Self {
+        self.n_big_good.replace(n_big_good);
+
+        self
+    }
+
+    /// Specify the amount of big notes (finishers) that were hit with only one key,
+    /// see [`n_big_good`](TaikoPP::n_big_good).
+    #[inline]
+    pub fn n_big_bad(mut self, n_big_bad: usize) -> Self {
+        self.n_big_bad.replace(n_big_bad);
+
+        self
+    }
+
     /// Specify the amount of misses of the play.
     #[inline]
     pub fn misses(mut self, n_misses: usize) -> Self {
@@ -138,6 +174,21 @@ impl<'map> TaikoPP<'map> {
         self
     }
 
+    /// Enable or disable strict mode.
+    ///
+    /// In strict mode, input that can not stem from an actual play, e.g. incompatible mods
+    /// like DT and HT or more hitresults than objects, is reported as [`InputError`]
+    /// by [`try_calculate`](TaikoPP::try_calculate) and causes [`calculate`](TaikoPP::calculate)
+    /// to panic instead of silently producing meaningless values.
+    ///
+    /// Disabled by default.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+
+        self
+    }
+
     /// Provide parameters through a [`TaikoScoreState`].
     #[inline]
     pub fn state(mut self, state: TaikoScoreState) -> Self {
@@ -156,13 +207,81 @@ impl<'map> TaikoPP<'map> {
         self
     }
 
+    /// Calculate all performance related values just like [`calculate`](TaikoPP::calculate)
+    /// while also measuring how much time each calculation phase took.
+    #[cfg(feature = "timings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+    pub fn calculate_with_timings(self) -> (TaikoPerformanceAttributes, crate::CalcTimings) {
+        crate::timings::measure(|| self.calculate())
+    }
+
+    /// Calculate the performance of each given score state on the same map.
+    ///
+    /// The difficulty attributes are calculated only once and shared across all
+    /// score states instead of paying for them on each calculation.
+    /// All other parameters, e.g. mods or passed objects, apply to every score state.
+    pub fn calculate_many(mut self, states: &[TaikoScoreState]) -> Vec<TaikoPerformanceAttributes> {
+        let attributes = self
+            .attributes
+            .take()
+            .unwrap_or_else(|| stars(self.map, self.mods, self.passed_objects));
+        self.attributes = Some(attributes);
+
+        states
+            .iter()
+            .map(|state| self.clone().state(state.clone()).calculate())
+            .collect()
+    }
+
     /// Calculate all performance related values, including pp and stars.
-    pub fn calculate(mut self) -> TaikoPerformanceAttributes {
+    ///
+    /// # Panics
+    ///
+    /// Panics in [strict](TaikoPP::strict) mode if the input is invalid.
+    #[track_caller]
+    pub fn calculate(self) -> TaikoPerformanceAttributes {
+        match self.try_calculate() {
+            Ok(performance) => performance,
+            Err(err) => panic!("invalid osu!taiko input: {}", err),
+        }
+    }
+
+    /// Calculate all performance related values just like [`calculate`](TaikoPP::calculate)
+    /// but in [strict](TaikoPP::strict) mode, return an error if the input is invalid.
+    ///
+    /// Outside of strict mode, this never fails.
+    pub fn try_calculate(mut self) -> Result<TaikoPerformanceAttributes, InputError> {
+        trace_span!(
+            "performance",
+            mode = "taiko",
+            map = format_args!("{:032x}", crate::CalcKey::fingerprint(self.map)),
+            mods = self.mods,
+        );
+
+        // Incompatible mods are reported before spending time on the difficulty
+        if self.strict {
+            InputError::check_mods(self.mods)?;
+        }
+
         let attributes = self
             .attributes
             .take()
             .unwrap_or_else(|| stars(self.map, self.mods, self.passed_objects));
 
+        if self.strict {
+            self.validate(&attributes)?;
+        }
+
+        // Maps with fewer than two objects have no difficulty to reward
+        if !self.map.is_calculable_until(self.passed_objects) {
+            return Ok(TaikoPerformanceAttributes {
+                difficulty: attributes,
+                ..Default::default()
+            });
+        }
+
+        let timer = Timer::start();
+
         if self.n300.or(self.n100).is_some() {
             let total = self.map.n_circles as usize;
             let misses = self.n_misses;
@@ -183,6 +302,10 @@ impl<'map> TaikoPP<'map> {
             self.acc = (2 * n300 + n100) as f64 / (2 * (n300 + n100 + misses)) as f64;
         }
 
+        if self.n_big_good.or(self.n_big_bad).is_some() {
+            self.apply_big_hits();
+        }
+
         let inner = TaikoPPInner {
             map: self.map,
             attributes,
@@ -191,7 +314,53 @@ impl<'map> TaikoPP<'map> {
             n_misses: self.n_misses,
         };
 
-        inner.calculate()
+        let performance = inner.calculate();
+        timer.finish(Phase::Evaluation);
+
+        Ok(performance)
+    }
+
+    fn validate(&self, attributes: &TaikoDifficultyAttributes) -> Result<(), InputError> {
+        let given = self.n300.unwrap_or(0) + self.n100.unwrap_or(0) + self.n_misses;
+
+        InputError::check_hitresults(given, attributes.max_combo)?;
+        InputError::check_combo(self.combo, attributes.max_combo)
+    }
+}
+
+impl TaikoPP<'_> {
+    /// Include the second hits of big notes into the accuracy.
+    fn apply_big_hits(&mut self) {
+        let n_objects = self.passed_objects.unwrap_or(self.map.hit_objects.len());
+
+        let n_big = self
+            .map
+            .hit_objects
+            .iter()
+            .take(n_objects)
+            .map(TaikoObject::from)
+            .filter(|h| h.big && h.color().is_some())
+            .count();
+
+        let n_big_good = self.n_big_good.unwrap_or(0).min(n_big);
+        let n_big_bad = self.n_big_bad.unwrap_or(0).min(n_big - n_big_good);
+
+        let n_notes = self
+            .map
+            .hit_objects
+            .iter()
+            .take(n_objects)
+            .filter(|h| h.is_circle())
+            .count();
+
+        // The second hit of big notes that were missed or judged
+        // neither good nor bad is considered missed.
+        let numerator = self.acc * (2 * n_notes) as f64 + (2 * n_big_good + n_big_bad) as f64;
+        let denominator = 2 * (n_notes + n_big);
+
+        if denominator > 0 {
+            self.acc = numerator / denominator as f64;
+        }
     }
 }
 
@@ -276,7 +445,7 @@ impl<'map> TaikoPPInner<'map> {
 
 #[inline]
 fn difficulty_range_od(od: f64) -> f64 {
-    crate::difficulty_range(od, 20.0, 35.0, 50.0)
+    crate::util::taiko_great_hit_window(od)
 }
 
 /// Abstract type to provide flexibility when passing difficulty attributes to a performance calculation.
@@ -322,3 +491,42 @@ impl TaikoAttributeProvider for PerformanceAttributes {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn big_hits() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let attributes = stars(&map, 0, None);
+
+        let n_big = map
+            .hit_objects
+            .iter()
+            .map(TaikoObject::from)
+            .filter(|h| h.big && h.color().is_some())
+            .count();
+
+        assert!(n_big > 0);
+
+        let calc = || TaikoPP::new(&map).attributes(attributes).n300(250).n100(20);
+        let regular = calc().calculate().pp;
+
+        let all_good = calc().n_big_good(n_big).calculate().pp;
+        let some_bad = calc()
+            .n_big_good(n_big / 2)
+            .n_big_bad(n_big / 2)
+            .calculate()
+            .pp;
+
+        assert!(all_good > regular);
+        assert!(some_bad < all_good);
+
+        let perfect = TaikoPP::new(&map).attributes(attributes);
+        let with_big = perfect.clone().n_big_good(n_big).calculate().pp;
+        assert!((with_big - perfect.calculate().pp).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,123 @@
+use super::{
+    count_kats, evaluate_skills, process_objects, stamina, StaminaCheeseDetector,
+    TaikoDifficultyAttributes, TaikoGradualDifficultyAttributes, TaikoPP, TaikoStamina,
+};
+
+use crate::{
+    research::TaikoParameters,
+    timings::{Phase, Timer},
+    Beatmap, Mods,
+};
+
+/// An osu!taiko map whose stamina cheese detection has been performed once.
+///
+/// The detection does not depend on mods so when calculating the difficulty of the
+/// same map for multiple mods or clock rates, reusing a [`TaikoPreprocessedMap`]
+/// avoids repeating it each time.
+///
+/// ```
+/// use akatsuki_pp::{taiko::TaikoPreprocessedMap, Beatmap};
+///
+/// # let map = Beatmap::default();
+/// let preprocessed = TaikoPreprocessedMap::new(&map);
+///
+/// for mods in [0, 64, 256] {
+///     let stars = preprocessed.stars(mods, None).stars;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TaikoPreprocessedMap<'map> {
+    map: &'map Beatmap,
+    cheese: Vec<bool>,
+}
+
+impl<'map> TaikoPreprocessedMap<'map> {
+    /// Prepare the map.
+    pub fn new(map: &'map Beatmap) -> Self {
+        let timer = Timer::start();
+        let cheese = map.find_cheese();
+        timer.finish(Phase::Preprocessing);
+
+        Self { map, cheese }
+    }
+
+    /// The map that was prepared.
+    #[inline]
+    pub fn map(&self) -> &'map Beatmap {
+        self.map
+    }
+
+    /// Calculate the difficulty attributes for the given mods.
+    ///
+    /// The result is the same as the one of [`stars`](super::stars).
+    pub fn stars(
+        &self,
+        mods: impl Mods,
+        passed_objects: Option<usize>,
+    ) -> TaikoDifficultyAttributes {
+        let params = &TaikoParameters::DEFAULT;
+        let (skills, max_combo) =
+            process_objects(self.map, &self.cheese, mods, passed_objects, params);
+
+        TaikoDifficultyAttributes {
+            n_kats: count_kats(self.map, passed_objects),
+            ..evaluate_skills(skills, max_combo, params)
+        }
+    }
+
+    /// Return a performance calculator for the given mods
+    /// whose difficulty attributes are calculated on the prepared map.
+    ///
+    /// The attributes are those of the full map so for partial plays
+    /// use [`stars`](TaikoPreprocessedMap::stars) with the amount of passed objects instead.
+    #[inline]
+    pub fn pp(&self, mods: u32) -> TaikoPP<'map> {
+        TaikoPP::new(self.map)
+            .mods(mods)
+            .attributes(self.stars(mods, None))
+    }
+
+    /// Return an iterator that gives the difficulty attributes after each hit object,
+    /// see [`TaikoGradualDifficultyAttributes`].
+    #[inline]
+    pub fn gradual_difficulty(&self, mods: impl Mods) -> TaikoGradualDifficultyAttributes<'map> {
+        TaikoGradualDifficultyAttributes::with_cheese(self.map, mods, self.cheese.clone())
+    }
+
+    /// Analyze the stamina-related patterns of the map for the given mods,
+    /// i.e. its mono streams, bursts, and the load on each hand, see [`TaikoStamina`].
+    ///
+    /// Streams are flagged if they contain notes that were detected as stamina cheese.
+    #[inline]
+    pub fn stamina(&self, mods: impl Mods) -> TaikoStamina {
+        stamina::analyze(self.map, &self.cheese, mods)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn preprocessed_matches_regular() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let preprocessed = TaikoPreprocessedMap::new(&map);
+
+        for mods in [0, 2, 64, 256] {
+            for passed_objects in [None, Some(1), Some(100)] {
+                let expected = super::super::stars(&map, mods, passed_objects);
+                let actual = preprocessed.stars(mods, passed_objects);
+
+                assert_eq!(actual, expected, "+{} {:?}", mods, passed_objects);
+            }
+        }
+
+        let expected = TaikoGradualDifficultyAttributes::new(&map, 64).last();
+        assert_eq!(preprocessed.gradual_difficulty(64).last(), expected);
+
+        let expected = TaikoPP::new(&map).mods(64).calculate();
+        assert_eq!(preprocessed.pp(64).calculate(), expected);
+    }
+}
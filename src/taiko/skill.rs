@@ -1,17 +1,32 @@
 use super::{DifficultyObject, SkillKind};
+use crate::{
+    research::{SkillParameters, TaikoParameters},
+    timings::{Phase, Timer},
+    StrainValue,
+};
 
 use std::cmp::Ordering;
 
-const DECAY_WEIGHT: f64 = 0.9;
-
-const COLOR_SKILL_MULTIPLIER: f64 = 1.0;
-const COLOR_STRAIN_DECAY_BASE: f64 = 0.4;
-
-const RHYTHM_SKILL_MULTIPLIER: f64 = 10.0;
-const RHYTHM_STRAIN_DECAY_BASE: f64 = 0.0;
-
-const STAMINA_SKILL_MULTIPLIER: f64 = 1.0;
-const STAMINA_STRAIN_DECAY_BASE: f64 = 0.4;
+pub(crate) const COLOR_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 1.0,
+    strain_decay_base: 0.4,
+    decay_weight: 0.9,
+    difficulty_multiplier: 1.0,
+};
+
+pub(crate) const RHYTHM_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 10.0,
+    strain_decay_base: 0.0,
+    decay_weight: 0.9,
+    difficulty_multiplier: 1.0,
+};
+
+pub(crate) const STAMINA_PARAMETERS: SkillParameters = SkillParameters {
+    skill_multiplier: 1.0,
+    strain_decay_base: 0.4,
+    decay_weight: 0.9,
+    difficulty_multiplier: 1.0,
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct Skills {
@@ -22,12 +37,12 @@ pub(crate) struct Skills {
 }
 
 impl Skills {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(params: &TaikoParameters) -> Self {
         Self {
-            color: Skill::new(SkillKind::color()),
-            rhythm: Skill::new(SkillKind::rhythm()),
-            stamina_right: Skill::new(SkillKind::stamina(true)),
-            stamina_left: Skill::new(SkillKind::stamina(false)),
+            color: Skill::new(SkillKind::color(), &params.color),
+            rhythm: Skill::new(SkillKind::rhythm(), &params.rhythm),
+            stamina_right: Skill::new(SkillKind::stamina(true), &params.stamina),
+            stamina_left: Skill::new(SkillKind::stamina(false), &params.stamina),
         }
     }
 
@@ -50,10 +65,21 @@ impl Skills {
     }
 
     pub(crate) fn process(&mut self, curr: &DifficultyObject<'_>, cheese: &[bool]) {
+        let timer = Timer::start();
         self.color.process(curr, cheese);
+        timer.finish(Phase::Skill("color"));
+
+        let timer = Timer::start();
         self.rhythm.process(curr, cheese);
+        timer.finish(Phase::Skill("rhythm"));
+
+        let timer = Timer::start();
         self.stamina_right.process(curr, cheese);
+        timer.finish(Phase::Skill("stamina right"));
+
+        let timer = Timer::start();
         self.stamina_left.process(curr, cheese);
+        timer.finish(Phase::Skill("stamina left"));
     }
 
     pub(crate) fn strain_peaks_len(&self) -> usize {
@@ -67,19 +93,23 @@ pub(crate) struct Skill {
     pub(crate) curr_section_peak: f64,
 
     kind: SkillKind,
-    pub(crate) strain_peaks: Vec<f64>,
+    skill_multiplier: f64,
+    strain_decay_base: f64,
+    pub(crate) strain_peaks: Vec<StrainValue>,
 
     prev_time: Option<f64>,
 }
 
 impl Skill {
     #[inline]
-    pub(crate) fn new(kind: SkillKind) -> Self {
+    pub(crate) fn new(kind: SkillKind, params: &SkillParameters) -> Self {
         Self {
             current_strain: 1.0,
             curr_section_peak: 1.0,
 
             kind,
+            skill_multiplier: params.skill_multiplier,
+            strain_decay_base: params.strain_decay_base,
             strain_peaks: Vec::with_capacity(128),
 
             prev_time: None,
@@ -88,7 +118,8 @@ impl Skill {
 
     #[inline]
     pub(crate) fn save_current_peak(&mut self) {
-        self.strain_peaks.push(self.curr_section_peak);
+        self.strain_peaks
+            .push(self.curr_section_peak as StrainValue);
     }
 
     #[inline]
@@ -99,17 +130,29 @@ impl Skill {
     #[inline]
     pub(crate) fn process(&mut self, curr: &DifficultyObject<'_>, cheese: &[bool]) {
         self.current_strain *= self.strain_decay(curr.delta);
-        self.current_strain += self.kind.strain_value_of(curr, cheese) * self.skill_multiplier();
+        self.current_strain += self.kind.strain_value_of(curr, cheese) * self.skill_multiplier;
         self.curr_section_peak = self.curr_section_peak.max(self.current_strain);
         self.prev_time.replace(curr.start_time);
     }
 
     pub(crate) fn copy_strain_peaks(&self, buf: &mut [f64]) {
-        buf.copy_from_slice(&self.strain_peaks);
+        crate::copy_strains(buf, &self.strain_peaks);
+    }
+
+    /// Iterate over the saved strain peaks as `f64` values.
+    #[inline]
+    pub(crate) fn peaks(&self) -> impl Iterator<Item = f64> + '_ {
+        self.strain_peaks.iter().copied().map(crate::widen_strain)
+    }
+
+    /// Iterate over the saved strain peaks followed by the peak of the current section.
+    #[inline]
+    pub(crate) fn peaks_with_current(&self) -> impl Iterator<Item = f64> + '_ {
+        crate::peaks_with_current(&self.strain_peaks, self.curr_section_peak)
     }
 
     #[inline]
-    pub(crate) fn difficulty_value(&self, peaks: &mut [f64]) -> f64 {
+    pub(crate) fn difficulty_value(peaks: &mut [f64], params: &SkillParameters) -> f64 {
         let mut difficulty = 0.0;
         let mut weight = 1.0;
 
@@ -117,28 +160,10 @@ impl Skill {
 
         for &strain in peaks.iter() {
             difficulty += strain * weight;
-            weight *= DECAY_WEIGHT;
-        }
-
-        difficulty
-    }
-
-    #[inline]
-    fn skill_multiplier(&self) -> f64 {
-        match self.kind {
-            SkillKind::Color { .. } => COLOR_SKILL_MULTIPLIER,
-            SkillKind::Rhythm { .. } => RHYTHM_SKILL_MULTIPLIER,
-            SkillKind::Stamina { .. } => STAMINA_SKILL_MULTIPLIER,
+            weight *= params.decay_weight;
         }
-    }
 
-    #[inline]
-    fn strain_decay_base(&self) -> f64 {
-        match self.kind {
-            SkillKind::Color { .. } => COLOR_STRAIN_DECAY_BASE,
-            SkillKind::Rhythm { .. } => RHYTHM_STRAIN_DECAY_BASE,
-            SkillKind::Stamina { .. } => STAMINA_STRAIN_DECAY_BASE,
-        }
+        difficulty * params.difficulty_multiplier
     }
 
     #[inline]
@@ -148,6 +173,6 @@ impl Skill {
 
     #[inline]
     fn strain_decay(&self, ms: f64) -> f64 {
-        self.strain_decay_base().powf(ms / 1000.0)
+        self.strain_decay_base.powf(ms / 1000.0)
     }
 }
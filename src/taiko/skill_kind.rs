@@ -1,12 +1,14 @@
-use super::{DifficultyObject, HitObjectRhythm, LimitedQueue, Rim};
+use super::{DifficultyObject, Rim};
+use crate::{
+    limited_queue::LimitedQueue,
+    rhythm::{repetition_penalty, RhythmEvaluator},
+};
 
 use std::ops::Index;
 
-const RHYTHM_STRAIN_DECAY: f64 = 0.96;
 const MOST_RECENT_PATTERNS_TO_COMPARE: usize = 2;
 
 const MONO_HISTORY_MAX_LEN: usize = 5;
-const RHYTHM_HISTORY_MAX_LEN: usize = 8;
 const STAMINA_HISTORY_MAX_LEN: usize = 2;
 
 #[derive(Clone, Debug)]
@@ -17,9 +19,7 @@ pub(crate) enum SkillKind {
         current_mono_len: usize,
     },
     Rhythm {
-        rhythm_history: LimitedQueue<(usize, &'static HitObjectRhythm)>, // (idx, rhythm)
-        notes_since_rhythm_change: usize,
-        current_strain: f64,
+        evaluator: RhythmEvaluator,
     },
     Stamina {
         note_pair_duration_history: LimitedQueue<f64>,
@@ -41,9 +41,7 @@ impl SkillKind {
     #[inline]
     pub(crate) fn rhythm() -> Self {
         Self::Rhythm {
-            rhythm_history: LimitedQueue::new(RHYTHM_HISTORY_MAX_LEN),
-            notes_since_rhythm_change: 0,
-            current_strain: 0.0,
+            evaluator: RhythmEvaluator::new(),
         }
     }
 
@@ -141,78 +139,12 @@ impl SkillKind {
 
                 strain
             }
-            Self::Rhythm {
-                rhythm_history,
-                notes_since_rhythm_change,
-                current_strain,
-            } => {
-                let base_is_circle = current.base.is_circle();
-
-                if !base_is_circle {
-                    *current_strain = 0.0;
-                    *notes_since_rhythm_change = 0;
-
-                    return 0.0;
-                }
-
-                *current_strain *= RHYTHM_STRAIN_DECAY;
-                *notes_since_rhythm_change += 1;
-
-                if current.rhythm.difficulty.abs() <= f64::EPSILON {
-                    return 0.0;
-                }
-
-                let mut strain = current.rhythm.difficulty;
-
-                rhythm_history.push((current.idx, current.rhythm));
-
-                let mut reps_penalty = 1.0;
-
-                for most_recent_patterns_to_compare in 2..=RHYTHM_HISTORY_MAX_LEN / 2 {
-                    let iter = (0..rhythm_history
-                        .len()
-                        .saturating_sub(most_recent_patterns_to_compare))
-                        .rev();
-
-                    for start in iter {
-                        let different_pattern = (0..most_recent_patterns_to_compare).any(|i| {
-                            let to_compare =
-                                rhythm_history.len() + i - most_recent_patterns_to_compare;
-
-                            rhythm_history.index(start + i).1 != rhythm_history.index(to_compare).1
-                        });
-
-                        if different_pattern {
-                            continue;
-                        }
-
-                        reps_penalty *=
-                            repetition_penalty(current.idx - rhythm_history.index(start).0);
-
-                        break;
-                    }
-                }
-
-                let speed_penalty = if current.delta < 80.0 {
-                    1.0
-                } else if current.delta < 210.0 {
-                    (1.4 - 0.005 * current.delta).max(0.0)
-                } else {
-                    *current_strain = 0.0;
-                    *notes_since_rhythm_change = 0;
-
-                    0.0
-                };
-
-                strain *= reps_penalty;
-                strain *= pattern_len_penalty(*notes_since_rhythm_change);
-                strain *= speed_penalty;
-
-                *notes_since_rhythm_change = 0;
-                *current_strain += strain;
-
-                *current_strain
-            }
+            Self::Rhythm { evaluator } => evaluator.process(
+                current.idx,
+                current.delta,
+                current.rhythm,
+                current.base.is_circle(),
+            ),
             Self::Stamina {
                 hand,
                 note_pair_duration_history,
@@ -250,15 +182,6 @@ impl SkillKind {
     }
 }
 
-#[inline]
-fn pattern_len_penalty(pattern_len: usize) -> f64 {
-    let pattern_len = pattern_len as f64;
-    let short_pattern_penalty = (0.15 * pattern_len).min(1.0);
-    let long_pattern_penalty = (2.5 - 0.15 * pattern_len).max(0.0).min(1.0);
-
-    short_pattern_penalty.min(long_pattern_penalty)
-}
-
 #[inline]
 fn cheese_penalty(note_pair_duration: f64) -> f64 {
     if note_pair_duration > 125.0 {
@@ -281,8 +204,3 @@ fn speed_bonus(note_pair_duration: f64) -> f64 {
 
     bonus / 100_000.0
 }
-
-#[inline]
-fn repetition_penalty(notes_since: usize) -> f64 {
-    (0.032 * notes_since as f64).min(1.0)
-}
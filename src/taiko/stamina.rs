@@ -0,0 +1,333 @@
+use super::Rim;
+use crate::{parse::HitObject, Beatmap, Mods};
+
+use std::cmp::Reverse;
+
+/// Notes further apart than this, in milliseconds, interrupt mono streams and bursts.
+///
+/// Same threshold as the color skill uses to reset its mono history.
+const MAX_STREAM_INTERVAL: f64 = 1000.0;
+
+/// Relative difference up to which two intervals are considered equal within a burst.
+const BURST_INTERVAL_TOLERANCE: f64 = 0.05;
+
+/// The minimum amount of notes of a burst.
+const MIN_BURST_LEN: usize = 3;
+
+/// Stamina-related patterns of an osu!taiko map, see [`TaikoPreprocessedMap::stamina`].
+///
+/// All times and intervals are in milliseconds and already adjusted to the clock rate.
+///
+/// [`TaikoPreprocessedMap::stamina`]: super::TaikoPreprocessedMap::stamina
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaikoStamina {
+    /// Consecutive notes of the same color, sorted by length in descending order.
+    ///
+    /// Only streams of at least two notes are included.
+    pub mono_streams: Vec<TaikoStream>,
+    /// Consecutive notes with a constant interval, sorted by length in descending order.
+    ///
+    /// Only bursts of at least three notes are included.
+    pub bursts: Vec<TaikoStream>,
+    /// The load on the right hand.
+    pub right_hand: TaikoHandLoad,
+    /// The load on the left hand.
+    pub left_hand: TaikoHandLoad,
+}
+
+impl TaikoStamina {
+    /// The longest mono stream.
+    #[inline]
+    pub fn longest_mono_stream(&self) -> Option<&TaikoStream> {
+        self.mono_streams.first()
+    }
+
+    /// The highest [`bpm`](TaikoStream::bpm) across all bursts.
+    #[inline]
+    pub fn max_burst_bpm(&self) -> f64 {
+        self.bursts.iter().map(TaikoStream::bpm).fold(0.0, f64::max)
+    }
+}
+
+/// A sequence of consecutive notes of an osu!taiko map.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TaikoStream {
+    /// The index of the first note within the map's hit objects.
+    pub start_idx: usize,
+    /// The amount of notes.
+    pub len: usize,
+    /// The start time of the first note.
+    pub start_time: f64,
+    /// The start time of the last note.
+    pub end_time: f64,
+    /// The shortest interval between two consecutive notes.
+    pub min_interval: f64,
+    /// Whether any of the notes was detected as stamina cheese,
+    /// in which case the stamina skill penalizes them.
+    pub cheese: bool,
+}
+
+impl TaikoStream {
+    /// The BPM at which the stream's shortest interval would be 1/4 notes,
+    /// i.e. the BPM of an equivalent stream in osu!standard.
+    #[inline]
+    pub fn bpm(&self) -> f64 {
+        if self.min_interval > 0.0 {
+            15_000.0 / self.min_interval
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The load on a single hand when alternating every note, as the stamina skill assumes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TaikoHandLoad {
+    /// The amount of notes hit with this hand.
+    pub notes: usize,
+    /// The shortest time between two notes of this hand.
+    ///
+    /// `f64::INFINITY` if the hand has less than two notes.
+    pub min_interval: f64,
+    /// The average time between two consecutive notes of this hand.
+    ///
+    /// `f64::INFINITY` if the hand has less than two notes.
+    pub avg_interval: f64,
+}
+
+impl TaikoHandLoad {
+    fn new(map: &Beatmap, clock_rate: f64, parity: usize) -> Self {
+        let times: Vec<_> = map
+            .hit_objects
+            .iter()
+            .enumerate()
+            .skip(parity)
+            .step_by(2)
+            .filter(|(_, h)| h.is_circle())
+            .map(|(_, h)| h.start_time / clock_rate)
+            .collect();
+
+        let mut min_interval = f64::INFINITY;
+        let mut sum = 0.0;
+
+        for pair in times.windows(2) {
+            let interval = pair[1] - pair[0];
+            min_interval = min_interval.min(interval);
+            sum += interval;
+        }
+
+        let avg_interval = if times.len() >= 2 {
+            sum / (times.len() - 1) as f64
+        } else {
+            f64::INFINITY
+        };
+
+        Self {
+            notes: times.len(),
+            min_interval,
+            avg_interval,
+        }
+    }
+}
+
+pub(crate) fn analyze(map: &Beatmap, cheese: &[bool], mods: impl Mods) -> TaikoStamina {
+    let clock_rate = mods.speed();
+    let hit_objects = &map.hit_objects;
+
+    let mut mono_streams = Vec::new();
+    let mut bursts = Vec::new();
+
+    let mut mono = StreamBuilder::default();
+    let mut burst = StreamBuilder::default();
+    let mut burst_interval = 0.0;
+
+    for (idx, h) in hit_objects.iter().enumerate() {
+        let is_cheese = cheese_at(cheese, idx);
+
+        if !h.is_circle() {
+            mono.finish(2, &mut mono_streams);
+            burst.finish(MIN_BURST_LEN, &mut bursts);
+
+            continue;
+        }
+
+        let time = h.start_time / clock_rate;
+
+        let interval = match idx.checked_sub(1).map(|i| &hit_objects[i]) {
+            Some(prev) if prev.is_circle() => time - prev.start_time / clock_rate,
+            _ => {
+                mono.start(idx, time, is_cheese);
+                burst.start(idx, time, is_cheese);
+
+                continue;
+            }
+        };
+
+        if interval >= MAX_STREAM_INTERVAL {
+            mono.finish(2, &mut mono_streams);
+            mono.start(idx, time, is_cheese);
+            burst.finish(MIN_BURST_LEN, &mut bursts);
+            burst.start(idx, time, is_cheese);
+
+            continue;
+        }
+
+        if same_color(h, &hit_objects[idx - 1]) {
+            mono.push(time, interval, is_cheese);
+        } else {
+            mono.finish(2, &mut mono_streams);
+            mono.start(idx, time, is_cheese);
+        }
+
+        let constant = burst.stream.len < 2
+            || (interval - burst_interval).abs() <= BURST_INTERVAL_TOLERANCE * burst_interval;
+
+        if constant {
+            if burst.stream.len < 2 {
+                burst_interval = interval;
+            }
+
+            burst.push(time, interval, is_cheese);
+        } else {
+            burst.finish(MIN_BURST_LEN, &mut bursts);
+
+            // The previous note starts the new burst
+            let prev_time = hit_objects[idx - 1].start_time / clock_rate;
+            let prev_cheese = cheese_at(cheese, idx - 1);
+            burst.start(idx - 1, prev_time, prev_cheese);
+            burst.push(time, interval, is_cheese);
+            burst_interval = interval;
+        }
+    }
+
+    mono.finish(2, &mut mono_streams);
+    burst.finish(MIN_BURST_LEN, &mut bursts);
+
+    // Stable sorts so that equally long streams remain in chronological order
+    mono_streams.sort_by_key(|stream| Reverse(stream.len));
+    bursts.sort_by_key(|burst| Reverse(burst.len));
+
+    TaikoStamina {
+        mono_streams,
+        bursts,
+        right_hand: TaikoHandLoad::new(map, clock_rate, 1),
+        left_hand: TaikoHandLoad::new(map, clock_rate, 0),
+    }
+}
+
+#[inline]
+fn same_color(a: &HitObject, b: &HitObject) -> bool {
+    a.is_rim() == b.is_rim()
+}
+
+#[inline]
+fn cheese_at(cheese: &[bool], idx: usize) -> bool {
+    cheese.get(idx).copied().unwrap_or(false)
+}
+
+#[derive(Default)]
+struct StreamBuilder {
+    stream: TaikoStream,
+}
+
+impl StreamBuilder {
+    fn start(&mut self, idx: usize, time: f64, cheese: bool) {
+        self.stream = TaikoStream {
+            start_idx: idx,
+            len: 1,
+            start_time: time,
+            end_time: time,
+            min_interval: f64::INFINITY,
+            cheese,
+        };
+    }
+
+    fn push(&mut self, time: f64, interval: f64, cheese: bool) {
+        self.stream.len += 1;
+        self.stream.end_time = time;
+        self.stream.min_interval = self.stream.min_interval.min(interval);
+        self.stream.cheese |= cheese;
+    }
+
+    fn finish(&mut self, min_len: usize, streams: &mut Vec<TaikoStream>) {
+        if self.stream.len >= min_len {
+            streams.push(self.stream);
+        }
+
+        self.stream.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{HitObjectKind, HitSound, Pos2};
+
+    fn note(start_time: f64, kat: bool) -> HitObject {
+        HitObject {
+            pos: Pos2::default(),
+            start_time,
+            kind: HitObjectKind::Circle,
+            sound: if kat { u8::HITSOUND_CLAP } else { 0 },
+            file_idx: 0,
+        }
+    }
+
+    #[test]
+    fn streams_and_hands() {
+        let map = Beatmap {
+            hit_objects: vec![
+                // Burst of five notes at 100ms, the first four being dons
+                note(0.0, false),
+                note(100.0, false),
+                note(200.0, false),
+                note(300.0, false),
+                note(400.0, true),
+                // Interrupted by a slow note
+                note(900.0, true),
+                note(2000.0, false),
+                note(2050.0, false),
+            ],
+            ..Default::default()
+        };
+
+        let stamina = analyze(&map, &[], 0);
+
+        let mono = stamina.longest_mono_stream().unwrap();
+        assert_eq!((mono.start_idx, mono.len), (0, 4));
+        assert_eq!(mono.end_time, 300.0);
+        assert_eq!(stamina.mono_streams.len(), 3);
+
+        assert_eq!(stamina.bursts.len(), 1);
+        assert_eq!((stamina.bursts[0].start_idx, stamina.bursts[0].len), (0, 5));
+        assert_eq!(stamina.max_burst_bpm(), 150.0);
+
+        assert_eq!(stamina.left_hand.notes, 4);
+        assert_eq!(stamina.left_hand.min_interval, 200.0);
+        assert_eq!(stamina.right_hand.notes, 4);
+        assert_eq!(stamina.right_hand.min_interval, 200.0);
+
+        let dt = analyze(&map, &[], 64);
+        assert!((dt.max_burst_bpm() - 225.0).abs() < 1e-9);
+        assert!(!dt.bursts[0].cheese);
+    }
+
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn stamina_of_map() {
+        let map = Beatmap::from_path("./maps/1028484.osu").expect("failed to parse map");
+        let stamina = crate::taiko::TaikoPreprocessedMap::new(&map).stamina(0);
+
+        let n_circles = map.hit_objects.iter().filter(|h| h.is_circle()).count();
+        assert_eq!(
+            stamina.left_hand.notes + stamina.right_hand.notes,
+            n_circles
+        );
+        assert!(stamina
+            .mono_streams
+            .windows(2)
+            .all(|w| w[0].len >= w[1].len));
+        assert!(stamina.bursts.iter().all(|b| b.len >= MIN_BURST_LEN));
+        assert!(stamina.max_burst_bpm() > 0.0);
+    }
+}
@@ -0,0 +1,195 @@
+#[cfg(feature = "timings")]
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+/// Time spent in the individual phases of a calculation.
+///
+/// Retrieved through the `calculate_with_timings` method of the performance calculators.
+/// Skills are processed object by object alongside each other so their durations
+/// are accumulated over the whole map. Work that is spread across threads is
+/// included with the durations of all threads summed up.
+#[cfg(feature = "timings")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timings")))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CalcTimings {
+    /// Time spent parsing the beatmap.
+    ///
+    /// The calculation can't know when and where its map was parsed so this is zero
+    /// unless filled in by the caller, e.g. with the duration returned by
+    /// [`Beatmap::parse_with_timings`](crate::Beatmap::parse_with_timings).
+    pub parse: Duration,
+    /// Time spent converting hit objects into their difficulty representation.
+    pub preprocessing: Duration,
+    /// Time spent processing each skill, in order of their first appearance.
+    pub skills: Vec<(&'static str, Duration)>,
+    /// Time spent evaluating strains and calculating the final values.
+    pub evaluation: Duration,
+}
+
+#[cfg(feature = "timings")]
+impl CalcTimings {
+    /// Sum of all durations.
+    pub fn total(&self) -> Duration {
+        self.parse
+            + self.preprocessing
+            + self
+                .skills
+                .iter()
+                .map(|(_, duration)| *duration)
+                .sum::<Duration>()
+            + self.evaluation
+    }
+
+    fn add(&mut self, phase: Phase, duration: Duration) {
+        match phase {
+            Phase::Preprocessing => self.preprocessing += duration,
+            Phase::Skill(name) => match self.skills.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, total)) => *total += duration,
+                None => self.skills.push((name, duration)),
+            },
+            Phase::Evaluation => self.evaluation += duration,
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.parse += other.parse;
+        self.add(Phase::Preprocessing, other.preprocessing);
+
+        for (name, duration) in other.skills.iter() {
+            self.add(Phase::Skill(name), *duration);
+        }
+
+        self.add(Phase::Evaluation, other.evaluation);
+    }
+}
+
+#[cfg(feature = "timings")]
+thread_local! {
+    static TIMINGS: RefCell<CalcTimings> = RefCell::new(CalcTimings::default());
+}
+
+/// Run the calculation and collect the timings of all phases that were passed.
+///
+/// The timings are also added to those of an enclosing `measure` on the same thread.
+#[cfg(feature = "timings")]
+pub(crate) fn measure<T>(calculate: impl FnOnce() -> T) -> (T, CalcTimings) {
+    let outer = TIMINGS.with(|timings| std::mem::take(&mut *timings.borrow_mut()));
+    let value = calculate();
+
+    let timings = TIMINGS.with(|timings| {
+        let mut timings = timings.borrow_mut();
+        let inner = std::mem::replace(&mut *timings, outer);
+        timings.merge(&inner);
+
+        inner
+    });
+
+    (value, timings)
+}
+
+/// Timings that were measured on another thread, see [`measure_thread`].
+#[cfg(feature = "timings")]
+pub(crate) type ThreadTimings = CalcTimings;
+
+/// Timings that were measured on another thread, see [`measure_thread`].
+#[cfg(not(feature = "timings"))]
+pub(crate) type ThreadTimings = ();
+
+/// Run work on a spawned thread and collect its timings so that the
+/// spawning thread can [`merge`] them, otherwise they'd be lost.
+pub(crate) fn measure_thread<T>(work: impl FnOnce() -> T) -> (T, ThreadTimings) {
+    #[cfg(feature = "timings")]
+    {
+        measure(work)
+    }
+
+    #[cfg(not(feature = "timings"))]
+    {
+        (work(), ())
+    }
+}
+
+/// Add the timings of another thread to those of the current thread.
+#[cfg_attr(not(feature = "timings"), allow(unused_variables))]
+pub(crate) fn merge(other: ThreadTimings) {
+    #[cfg(feature = "timings")]
+    TIMINGS.with(|timings| timings.borrow_mut().merge(&other));
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(not(feature = "timings"), allow(dead_code))]
+pub(crate) enum Phase {
+    Preprocessing,
+    Skill(&'static str),
+    Evaluation,
+}
+
+/// Measures the duration of a phase if the `timings` feature is enabled, otherwise a no-op.
+pub(crate) struct Timer {
+    #[cfg(feature = "timings")]
+    start: Instant,
+}
+
+impl Timer {
+    #[inline]
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "timings")]
+            start: Instant::now(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn finish(self, phase: Phase) {
+        #[cfg(feature = "timings")]
+        {
+            let elapsed = self.start.elapsed();
+            TIMINGS.with(|timings| timings.borrow_mut().add(phase, elapsed));
+        }
+
+        #[cfg(not(feature = "timings"))]
+        let _ = phase;
+    }
+}
+
+#[cfg(all(test, feature = "timings", feature = "osu"))]
+mod tests {
+    #[cfg(not(any(feature = "async_tokio", feature = "async_std")))]
+    #[test]
+    fn osu_timings() {
+        use std::time::Duration;
+
+        use crate::{Beatmap, OsuPP};
+
+        let file = std::fs::File::open("./maps/2785319.osu").expect("failed to open map");
+        let (map, parse) =
+            Beatmap::parse_with_timings(file, Default::default()).expect("failed to parse map");
+        assert!(parse > Duration::ZERO);
+
+        let (attributes, timings) = OsuPP::new(&map).mods(8).calculate_with_timings();
+
+        assert_eq!(attributes.pp, OsuPP::new(&map).mods(8).calculate().pp);
+        assert_eq!(timings.parse, Duration::ZERO);
+
+        let skills: Vec<_> = timings.skills.iter().map(|(name, _)| *name).collect();
+        assert_eq!(skills, ["aim", "aim (no sliders)", "speed"]);
+
+        // Nested measurements are included in the outer one
+        let (inner, outer) = super::measure(|| {
+            let (_, inner) = OsuPP::new(&map).calculate_with_timings();
+
+            inner
+        });
+        assert_eq!(outer, inner);
+
+        // Timings of other threads are merged into the current one
+        let ((), parallel) = super::measure(|| {
+            let maps = [&map, &map];
+            crate::par_map(&maps, |map| OsuPP::new(map).calculate());
+        });
+        assert_eq!(parallel.skills.len(), 3);
+        assert!(parallel.preprocessing > Duration::ZERO);
+    }
+}
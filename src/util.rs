@@ -0,0 +1,196 @@
+const AR0_MS: f64 = 1800.0;
+const AR5_MS: f64 = 1200.0;
+const AR10_MS: f64 = 450.0;
+
+/// Map a difficulty setting such as AR or OD onto a range of values.
+///
+/// Values are interpolated linearly from `min` at 0 to `avg` at 5 and on to `max` at 10.
+/// Settings outside of `0..=10` extrapolate the respective segment.
+///
+/// ```
+/// use akatsuki_pp::util::difficulty_range;
+///
+/// assert_eq!(difficulty_range(0.0, 450.0, 1200.0, 1800.0), 1800.0);
+/// assert_eq!(difficulty_range(5.0, 450.0, 1200.0, 1800.0), 1200.0);
+/// assert_eq!(difficulty_range(10.0, 450.0, 1200.0, 1800.0), 450.0);
+/// ```
+#[inline]
+pub fn difficulty_range(val: f64, max: f64, avg: f64, min: f64) -> f64 {
+    if val > 5.0 {
+        avg + (max - avg) * (val - 5.0) / 5.0
+    } else if val < 5.0 {
+        avg - (avg - min) * (5.0 - val) / 5.0
+    } else {
+        avg
+    }
+}
+
+/// The time in ms in which a hit object is visible before it should be hit, i.e. the preempt.
+///
+/// Clock rates are not considered, divide the result by the clock rate to apply them.
+#[inline]
+pub fn ar_to_ms(ar: f64) -> f64 {
+    difficulty_range(ar, AR10_MS, AR5_MS, AR0_MS)
+}
+
+/// The approach rate for a preempt in ms, i.e. the inverse of [`ar_to_ms`].
+///
+/// Combined with [`ar_to_ms`], this gives the AR for a clock rate:
+///
+/// ```
+/// use akatsuki_pp::util::{ar_to_ms, ms_to_ar};
+///
+/// let dt_ar = ms_to_ar(ar_to_ms(9.0) / 1.5);
+/// assert!((dt_ar - 10.333).abs() < 0.001);
+/// ```
+#[inline]
+pub fn ms_to_ar(ms: f64) -> f64 {
+    if ms > AR5_MS {
+        (AR0_MS - ms) / ((AR0_MS - AR5_MS) / 5.0)
+    } else {
+        5.0 + (AR5_MS - ms) / ((AR5_MS - AR10_MS) / 5.0)
+    }
+}
+
+/// The osu!standard hit window of a 300 in ms for the given OD.
+///
+/// Clock rates are not considered, divide the result by the clock rate to apply them.
+#[inline]
+pub fn osu_great_hit_window(od: f64) -> f64 {
+    difficulty_range(od, 20.0, 50.0, 80.0)
+}
+
+/// The osu!standard hit window of a 100 in ms for the given OD.
+///
+/// Clock rates are not considered, divide the result by the clock rate to apply them.
+#[inline]
+pub fn osu_ok_hit_window(od: f64) -> f64 {
+    difficulty_range(od, 60.0, 100.0, 140.0)
+}
+
+/// The osu!standard hit window of a 50 in ms for the given OD.
+///
+/// Clock rates are not considered, divide the result by the clock rate to apply them.
+#[inline]
+pub fn osu_meh_hit_window(od: f64) -> f64 {
+    difficulty_range(od, 100.0, 150.0, 200.0)
+}
+
+/// The OD for an osu!standard hit window of a 300 in ms, i.e. the inverse of [`osu_great_hit_window`].
+///
+/// ```
+/// use akatsuki_pp::util::{od_from_osu_great_hit_window, osu_great_hit_window};
+///
+/// let dt_od = od_from_osu_great_hit_window(osu_great_hit_window(9.0) / 1.5);
+/// assert!((dt_od - 10.444).abs() < 0.001);
+/// ```
+#[inline]
+pub fn od_from_osu_great_hit_window(hit_window: f64) -> f64 {
+    (80.0 - hit_window) / 6.0
+}
+
+/// The osu!taiko hit window of a GREAT in ms for the given OD.
+///
+/// Clock rates are not considered, divide the result by the clock rate to apply them.
+#[inline]
+pub fn taiko_great_hit_window(od: f64) -> f64 {
+    difficulty_range(od, 20.0, 35.0, 50.0)
+}
+
+/// Round a star rating to two decimals the way osu!web displays it.
+///
+/// Ties are rounded away from zero based on the exact value of the float, as
+/// `Intl.NumberFormat` does, rather than to the nearest even digit. Hence `2.125`
+/// becomes `2.13` while `1.005`, which is slightly less as a float, becomes `1.0`.
+///
+/// ```
+/// use akatsuki_pp::util::round_stars;
+///
+/// assert_eq!(round_stars(5.4449), 5.44);
+/// assert_eq!(round_stars(2.125), 2.13);
+/// assert_eq!(round_stars(1.005), 1.0);
+/// ```
+#[inline]
+pub fn round_stars(stars: f64) -> f64 {
+    round_half_away(stars, 2)
+}
+
+/// Round a performance point value to an integer the way osu!web displays it,
+/// i.e. ties are rounded away from zero.
+///
+/// ```
+/// use akatsuki_pp::util::round_pp;
+///
+/// assert_eq!(round_pp(727.5), 728.0);
+/// assert_eq!(round_pp(727.4999), 727.0);
+/// ```
+#[inline]
+pub fn round_pp(pp: f64) -> f64 {
+    round_half_away(pp, 0)
+}
+
+/// Round to the given amount of decimals with ties away from zero,
+/// deciding ties by the exact value rather than the rounded product.
+fn round_half_away(value: f64, decimals: i32) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+
+    let factor = 10_f64.powi(decimals);
+    let abs = value.abs();
+    let lower = (abs * factor).floor();
+
+    // The fused multiply-add rounds only once so its sign is exact
+    let rounded = if abs.mul_add(factor, -(lower + 0.5)) >= 0.0 {
+        lower + 1.0
+    } else {
+        lower
+    };
+
+    (rounded / factor).copysign(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ar_roundtrip() {
+        for i in 0..=110 {
+            let ar = i as f64 / 10.0;
+            assert!((ms_to_ar(ar_to_ms(ar)) - ar).abs() < 1e-9, "AR{}", ar);
+        }
+
+        let attributes = crate::BeatmapAttributes::new(9.0, 8.0, 4.0, 5.0).mods(64);
+        assert!((ms_to_ar(ar_to_ms(9.0) / 1.5) - attributes.ar).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hit_windows() {
+        for i in 0..=100 {
+            let od = i as f64 / 10.0;
+
+            assert!((osu_great_hit_window(od) - (80.0 - 6.0 * od)).abs() < 1e-9);
+            assert!((osu_ok_hit_window(od) - (140.0 - 8.0 * od)).abs() < 1e-9);
+            assert!((osu_meh_hit_window(od) - (200.0 - 10.0 * od)).abs() < 1e-9);
+            assert!((od_from_osu_great_hit_window(osu_great_hit_window(od)) - od).abs() < 1e-9);
+        }
+
+        assert_eq!(taiko_great_hit_window(0.0), 50.0);
+        assert_eq!(taiko_great_hit_window(10.0), 20.0);
+    }
+
+    #[test]
+    fn display_rounding() {
+        assert_eq!(round_stars(0.0), 0.0);
+        assert_eq!(round_stars(6.995), 7.0);
+        assert_eq!(round_stars(0.125), 0.13);
+        assert_eq!(round_stars(0.285), 0.28);
+        assert_eq!(round_stars(-2.125), -2.13);
+        assert!(round_stars(f64::NAN).is_nan());
+
+        assert_eq!(round_pp(0.5), 1.0);
+        assert_eq!(round_pp(2.5), 3.0);
+        assert_eq!(round_pp(1234.49), 1234.0);
+    }
+}
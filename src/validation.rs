@@ -0,0 +1,262 @@
+use std::{error::Error as StdError, fmt};
+
+use crate::Mods;
+
+/// Pairs of mods that can not be enabled at the same time.
+pub(crate) const INCOMPATIBLE_MODS: [(u32, u32); 4] = [
+    (u32::DT, u32::HT),
+    (u32::HR, u32::EZ),
+    (u32::NF, u32::SD),
+    (u32::NF, u32::PF),
+];
+
+/// Input of a performance calculation that can not stem from an actual play.
+///
+/// Only reported if the calculator is in strict mode,
+/// see e.g. [`OsuPP::strict`](crate::OsuPP::strict).
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum InputError {
+    /// Two mods were specified that can not be enabled at the same time, e.g. DT and HT.
+    IncompatibleMods {
+        /// The bit value of the first mod.
+        a: u32,
+        /// The bit value of the second mod.
+        b: u32,
+    },
+    /// More hitresults were specified than there are objects.
+    TooManyHitresults {
+        /// The amount of specified hitresults.
+        given: usize,
+        /// The amount of objects.
+        max: usize,
+    },
+    /// The specified combo is higher than the max combo.
+    ComboTooHigh {
+        /// The specified combo.
+        combo: usize,
+        /// The max combo.
+        max_combo: usize,
+    },
+    /// A hitresult was specified that does not exist in the mode, e.g. 50s in osu!taiko.
+    UnsupportedHitresult {
+        /// The name of the hitresult.
+        name: &'static str,
+        /// The specified amount.
+        amount: usize,
+    },
+    /// The specified score is higher than the max score.
+    ScoreTooHigh {
+        /// The specified score.
+        score: u32,
+        /// The max score.
+        max_score: u32,
+    },
+}
+
+impl InputError {
+    /// Check that the mods do not contain any incompatible pair.
+    pub(crate) fn check_mods(mods: u32) -> Result<(), Self> {
+        match INCOMPATIBLE_MODS
+            .iter()
+            .find(|(a, b)| mods & a > 0 && mods & b > 0)
+        {
+            Some(&(a, b)) => Err(Self::IncompatibleMods { a, b }),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that at most `max` hitresults were given.
+    #[cfg(any(feature = "osu", feature = "taiko", feature = "fruits"))]
+    pub(crate) fn check_hitresults(given: usize, max: usize) -> Result<(), Self> {
+        if given > max {
+            Err(Self::TooManyHitresults { given, max })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check that the combo, if specified, does not exceed the max combo.
+    #[cfg(any(feature = "osu", feature = "taiko", feature = "fruits"))]
+    pub(crate) fn check_combo(combo: Option<usize>, max_combo: usize) -> Result<(), Self> {
+        match combo {
+            Some(combo) if combo > max_combo => Err(Self::ComboTooHigh { combo, max_combo }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn acronym(mod_bit: u32) -> &'static str {
+    match mod_bit {
+        u32::NF => "NF",
+        u32::EZ => "EZ",
+        u32::HR => "HR",
+        u32::SD => "SD",
+        u32::DT => "DT",
+        u32::HT => "HT",
+        u32::PF => "PF",
+        _ => "??",
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IncompatibleMods { a, b } => {
+                write!(
+                    f,
+                    "mods {} and {} are incompatible",
+                    acronym(*a),
+                    acronym(*b)
+                )
+            }
+            Self::TooManyHitresults { given, max } => {
+                write!(
+                    f,
+                    "{} hitresults were given but there are only {} objects",
+                    given, max
+                )
+            }
+            Self::ComboTooHigh { combo, max_combo } => {
+                write!(f, "combo {} exceeds the max combo of {}", combo, max_combo)
+            }
+            Self::UnsupportedHitresult { name, amount } => {
+                write!(
+                    f,
+                    "{} {} were given but the mode has no such hitresult",
+                    amount, name
+                )
+            }
+            Self::ScoreTooHigh { score, max_score } => {
+                write!(f, "score {} exceeds the max score of {}", score, max_score)
+            }
+        }
+    }
+}
+
+impl StdError for InputError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incompatible_mods() {
+        assert_eq!(InputError::check_mods(8 + 16 + 64), Ok(()));
+
+        let err = InputError::check_mods(64 + 256).unwrap_err();
+        assert_eq!(err, InputError::IncompatibleMods { a: 64, b: 256 });
+        assert_eq!(err.to_string(), "mods DT and HT are incompatible");
+
+        assert!(InputError::check_mods(1 + 32).is_err());
+        assert!(InputError::check_mods(2 + 16).is_err());
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn strict_osu() {
+        use crate::{Beatmap, OsuPP};
+
+        let map = Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+
+        let lenient = OsuPP::new(&map).mods(64 + 256).try_calculate();
+        assert!(lenient.is_ok());
+
+        let strict = OsuPP::new(&map).mods(64 + 256).strict(true).try_calculate();
+        assert_eq!(
+            strict.unwrap_err(),
+            InputError::IncompatibleMods { a: 64, b: 256 }
+        );
+
+        let n_objects = map.hit_objects.len();
+        let strict = OsuPP::new(&map)
+            .n300(n_objects)
+            .misses(1)
+            .strict(true)
+            .try_calculate();
+        assert_eq!(
+            strict.unwrap_err(),
+            InputError::TooManyHitresults {
+                given: n_objects + 1,
+                max: n_objects,
+            }
+        );
+
+        // The amount of objects is taken from the attributes, e.g. of a partial play
+        let partial = crate::osu::stars(&map, 0, Some(100));
+        let strict = OsuPP::new(&map)
+            .attributes(partial)
+            .n300(101)
+            .strict(true)
+            .try_calculate();
+        assert_eq!(
+            strict.unwrap_err(),
+            InputError::TooManyHitresults {
+                given: 101,
+                max: 100,
+            }
+        );
+
+        let max_combo = crate::osu::stars(&map, 0, None).max_combo;
+        let strict = OsuPP::new(&map)
+            .combo(max_combo + 1)
+            .strict(true)
+            .try_calculate();
+        assert!(matches!(strict, Err(InputError::ComboTooHigh { .. })));
+
+        let valid = OsuPP::new(&map)
+            .mods(8)
+            .combo(max_combo)
+            .strict(true)
+            .calculate();
+        assert_eq!(valid, OsuPP::new(&map).mods(8).combo(max_combo).calculate());
+    }
+
+    #[cfg(all(
+        feature = "osu",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    #[should_panic(expected = "mods HR and EZ are incompatible")]
+    fn strict_panics() {
+        let map = crate::Beatmap::from_path("./maps/2785319.osu").expect("failed to parse map");
+        crate::AnyPP::new(&map)
+            .mods(2 + 16)
+            .strict(true)
+            .calculate();
+    }
+
+    #[cfg(all(
+        feature = "mania",
+        not(any(feature = "async_tokio", feature = "async_std"))
+    ))]
+    #[test]
+    fn strict_mania() {
+        use crate::{Beatmap, ManiaPP};
+
+        let map = Beatmap::from_path("./maps/1974394.osu").expect("failed to parse map");
+
+        let strict = ManiaPP::new(&map)
+            .mods(2)
+            .score(600_000)
+            .strict(true)
+            .try_calculate();
+        assert_eq!(
+            strict.unwrap_err(),
+            InputError::ScoreTooHigh {
+                score: 600_000,
+                max_score: 500_000,
+            }
+        );
+
+        assert!(ManiaPP::new(&map)
+            .mods(2)
+            .score(500_000)
+            .strict(true)
+            .try_calculate()
+            .is_ok());
+    }
+}